@@ -43,6 +43,29 @@ fn main() {
 
     println!("cargo:rerun-if-env-changed=PHP_CONFIG");
     println!("cargo:rerun-if-env-changed=POX_STATIC");
+
+    embed_git_sha();
+}
+
+/// Expose the commit being built as `POX_GIT_SHA`, honoring an explicit override
+/// for builds outside a git checkout (e.g. Docker)
+fn embed_git_sha() {
+    let sha = env::var("POX_GIT_SHA").ok().filter(|sha| !sha.is_empty()).or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short=12", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|sha| sha.trim().to_string())
+    });
+
+    println!("cargo:rustc-env=POX_GIT_SHA={}", sha.as_deref().unwrap_or("unknown"));
+    println!("cargo:rerun-if-env-changed=POX_GIT_SHA");
+    if Path::new("../../.git/HEAD").exists() {
+        println!("cargo:rerun-if-changed=../../.git/HEAD");
+        println!("cargo:rerun-if-changed=../../.git/refs/heads");
+    }
 }
 
 fn get_php_config(php_config: &str, arg: &str) -> String {
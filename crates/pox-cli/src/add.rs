@@ -3,13 +3,12 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
 
 use pox_pm::{
     ComposerBuilder,
     config::Config,
-    installer::Installer,
-    json::{ComposerJson, ComposerLock},
+    installer::{Installer, ProcessLock},
+    json::{ComposerJson, ComposerLock, JsonEditor},
 };
 use crate::pm::platform::PlatformInfo;
 
@@ -23,8 +22,8 @@ pub struct AddArgs {
     #[arg(long)]
     pub dev: bool,
 
-    /// Prefer source installation
-    #[arg(long)]
+    /// Prefer source installation, falling back to dist for packages without a source
+    #[arg(long, conflicts_with = "prefer_dist")]
     pub prefer_source: bool,
 
     /// Prefer dist installation
@@ -51,15 +50,26 @@ pub struct AddArgs {
     #[arg(short = 'o', long)]
     pub optimize_autoloader: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// When the requirements can't be resolved, look for the root constraints to relax to
+    /// make them installable (solves again for every candidate, so it can be slow)
+    #[arg(long)]
+    pub explain_fix: bool,
 }
 
 pub async fn execute(args: AddArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
+    // Load config
+    let config = Config::build(Some(&working_dir), true)?;
+
+    // Like install and update, hold the vendor lock while composer.json and composer.lock are read
+    let _vendor_lock = if args.dry_run {
+        None
+    } else {
+        Some(ProcessLock::acquire_async(working_dir.join(&config.vendor_dir), true).await?)
+    };
+
     // Load composer.json
     let json_path = working_dir.join("composer.json");
     let composer_json: ComposerJson = if json_path.exists() {
@@ -80,12 +90,14 @@ pub async fn execute(args: AddArgs) -> Result<i32> {
         None
     };
 
-    // Load config
-    let config = Config::build(Some(&working_dir), true)?;
+    let sort_packages = config.sort_packages;
 
     // Detect platform
     let platform = PlatformInfo::detect();
 
+    // The builder merges files included through extra.merge-plugin; only the root file is written back
+    let mut editor = JsonEditor::load(&json_path)?;
+
     // Create Composer using builder
     let mut builder = ComposerBuilder::new(working_dir.clone())
         .with_config(config)
@@ -119,34 +131,35 @@ pub async fn execute(args: AddArgs) -> Result<i32> {
         );
 
         if args.dev {
+            editor.add_link("require-dev", &name, &constraint, sort_packages)?;
             composer.composer_json.require_dev.insert(name, constraint);
         } else {
+            editor.add_link("require", &name, &constraint, sort_packages)?;
             composer.composer_json.require.insert(name, constraint);
         }
     }
 
     // Write updated composer.json
     if !args.dry_run {
-        let content = serde_json::to_string_pretty(&composer.composer_json)
-            .context("Failed to serialize composer.json")?;
-        std::fs::write(&json_path, content)
+        editor.save(&json_path)
             .context("Failed to write composer.json")?;
     }
 
     // Run update
     if !args.no_update {
         // Run Installer
-        let installer = Installer::new(composer);
+        let installer = Installer::new(composer)
+            .explain_fix(args.explain_fix);
 
         let new_packages: Vec<String> = args.packages.iter()
             .map(|spec| parse_package_spec(spec).0)
             .collect();
 
-        installer.update(
+        Ok(installer.update(
             args.optimize_autoloader,
             false,
             Some(new_packages),
-        ).await
+        ).await?)
     } else {
         println!("{} Packages added to composer.json", style("Success:").green().bold());
         Ok(0)
@@ -3,18 +3,19 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use pox_pm::{
     ComposerBuilder,
     Repository,
-    config::Config,
+    config::{AuthConfig, Config},
     downloader::{DownloadConfig, DownloadManager},
-    http::HttpClient,
+    http::{HttpClient, HttpClientConfig},
     installer::Installer,
-    json::ComposerJson,
+    json::{ComposerJson, ComposerLock},
     repository::ComposerRepository,
+    scripts::run_event_script,
     Package,
 };
 use pox_semver::VersionParser;
@@ -39,8 +40,8 @@ pub struct CreateProjectArgs {
     #[arg(short = 's', long)]
     pub stability: Option<String>,
 
-    /// Prefer source installation (git clone)
-    #[arg(long)]
+    /// Prefer source installation (git clone), falling back to dist for packages without a source
+    #[arg(long, conflicts_with = "prefer_dist")]
     pub prefer_source: bool,
 
     /// Prefer dist installation (zip download)
@@ -122,7 +123,7 @@ fn parse_package_spec(package: &str) -> (String, Option<String>) {
     }
 }
 
-fn find_best_version(
+pub(crate) fn find_best_version(
     packages: &[Arc<Package>],
     version_constraint: Option<&str>,
     stability: &str,
@@ -244,12 +245,19 @@ pub async fn execute(args: CreateProjectArgs) -> Result<i32> {
     );
 
     let config = Config::build(None::<&std::path::Path>, true)?;
+    let auth = AuthConfig::build(None::<&std::path::Path>)?;
+    let http_client = Arc::new(
+        HttpClient::with_config(HttpClientConfig::from_config(&config))
+            .context("Failed to create HTTP client")?
+            .with_auth(auth),
+    );
 
-    let repo = if let Some(cache_dir) = &config.cache_dir {
+    let mut repo = if let Some(cache_dir) = &config.cache_dir {
         ComposerRepository::packagist_with_cache(cache_dir.join("repo"))
     } else {
         ComposerRepository::packagist()
     };
+    repo.set_http_client(http_client.clone());
 
     let packages = repo.find_packages(&package_name).await;
 
@@ -285,12 +293,17 @@ pub async fn execute(args: CreateProjectArgs) -> Result<i32> {
 
     std::fs::create_dir_all(&target_dir)?;
 
-    let http_client = Arc::new(HttpClient::new().context("Failed to create HTTP client")?);
     let download_config = DownloadConfig {
         prefer_source: args.prefer_source,
         prefer_dist: args.prefer_dist || !args.prefer_source,
+        package_preferences: if args.prefer_source || args.prefer_dist {
+            Vec::new()
+        } else {
+            config.preferred_install_packages.clone()
+        },
         cache_dir: config.cache_dir.clone().unwrap_or_else(|| PathBuf::from(".composer/cache")),
         vendor_dir: target_dir.clone(),
+        ..Default::default()
     };
     let download_manager = DownloadManager::new(http_client, download_config);
 
@@ -338,15 +351,6 @@ pub async fn execute(args: CreateProjectArgs) -> Result<i32> {
         }
     }
 
-    if args.no_install {
-        println!(
-            "{} Skipping installation. Run 'pox install' in {} to install dependencies.",
-            style("Info:").cyan(),
-            directory
-        );
-        return Ok(0);
-    }
-
     let composer_json_path = target_dir.join("composer.json");
     if !composer_json_path.exists() {
         println!(
@@ -356,21 +360,69 @@ pub async fn execute(args: CreateProjectArgs) -> Result<i32> {
         return Ok(0);
     }
 
-    println!("{} Installing dependencies...", style("Info:").cyan());
-
     let json_content = std::fs::read_to_string(&composer_json_path)?;
     let composer_json: ComposerJson = serde_json::from_str(&json_content)?;
 
-    let project_config = Config::build(Some(&target_dir), true)?;
+    if !args.no_scripts {
+        let exit_code = run_event_script("post-root-package-install", &composer_json, &target_dir, args.quiet)?;
+        if exit_code != 0 {
+            return Ok(exit_code);
+        }
+    }
+
+    let result = if args.no_install {
+        println!(
+            "{} Skipping installation. Run 'pox install' in {} to install dependencies.",
+            style("Info:").cyan(),
+            directory
+        );
+        Ok(0)
+    } else {
+        install_dependencies(&args, &target_dir, composer_json.clone()).await
+    };
+    if !matches!(result, Ok(0)) {
+        return result;
+    }
+
+    if !args.no_scripts {
+        let exit_code = run_event_script("post-create-project-cmd", &composer_json, &target_dir, args.quiet)?;
+        if exit_code != 0 {
+            return Ok(exit_code);
+        }
+    }
+
+    println!(
+        "\n{} Project {} successfully created in {}",
+        style("Success:").green().bold(),
+        style(&package_name).white().bold(),
+        style(&directory).cyan()
+    );
+
+    result
+}
+
+/// Install the dependencies of the created project, from its composer.lock when it ships one
+async fn install_dependencies(args: &CreateProjectArgs, target_dir: &Path, composer_json: ComposerJson) -> Result<i32> {
+    println!("{} Installing dependencies...", style("Info:").cyan());
+
+    let project_config = Config::build(Some(target_dir), true)?;
 
     let lock_path = target_dir.join("composer.lock");
-    let has_lock = lock_path.exists();
+    let lock = if lock_path.exists() {
+        let content = std::fs::read_to_string(&lock_path)
+            .context("Failed to read composer.lock")?;
+        Some(serde_json::from_str::<ComposerLock>(&content).context("Failed to parse composer.lock")?)
+    } else {
+        None
+    };
+    let has_lock = lock.is_some();
 
     let platform = PlatformInfo::detect();
 
-    let mut builder = ComposerBuilder::new(target_dir.clone())
+    let mut builder = ComposerBuilder::new(target_dir.to_path_buf())
         .with_config(project_config)
         .with_composer_json(composer_json)
+        .with_composer_lock(lock)
         .with_platform_packages(platform.to_packages())
         .no_dev(args.no_dev);
 
@@ -392,27 +444,16 @@ pub async fn execute(args: CreateProjectArgs) -> Result<i32> {
     };
 
     if result.is_ok() && !args.no_audit {
-        let audit_args = crate::pm::audit::AuditArgs {
-            no_dev: args.no_dev,
-            format: args.audit_format.clone(),
-            locked: false,
-            abandoned: Some("report".to_string()),
-            working_dir: target_dir.clone(),
-        };
-
-        if let Err(e) = crate::pm::audit::execute(audit_args).await {
-            eprintln!("Warning: Audit failed: {}", e);
-        }
+        crate::pm::audit::audit_after_install(
+            target_dir.to_path_buf(),
+            args.no_dev,
+            args.audit_format.clone(),
+            false,
+            "low".to_string(),
+        ).await;
     }
 
-    println!(
-        "\n{} Project {} successfully created in {}",
-        style("Success:").green().bold(),
-        style(&package_name).white().bold(),
-        style(&directory).cyan()
-    );
-
-    result
+    Ok(result?)
 }
 
 #[cfg(test)]
@@ -5,6 +5,7 @@ use clap::Args;
 use console::style;
 use dialoguer::{Confirm, Input};
 use regex::Regex;
+use pox_pm::{is_platform_package, json::JsonEditor};
 use pox_spdx::SpdxLicenses;
 use std::io::IsTerminal;
 use std::path::PathBuf;
@@ -56,13 +57,13 @@ pub struct InitArgs {
     #[arg(long, action = clap::ArgAction::Append)]
     pub repository: Vec<String>,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
-
     /// Non-interactive mode (use defaults and provided options)
     #[arg(long, short = 'n')]
     pub no_interaction: bool,
+
+    /// Overwrite an existing composer.json
+    #[arg(long)]
+    pub force: bool,
 }
 
 /// Git configuration values
@@ -133,6 +134,15 @@ fn validate_package_name(name: &str) -> Result<(), String> {
     }
 }
 
+/// Validate the package name of a `--require`/`--require-dev` entry, allowing platform packages
+fn validate_requirement_name(name: &str) -> Result<(), String> {
+    if is_platform_package(&name.to_lowercase()) {
+        return Ok(());
+    }
+    validate_package_name(&name.to_lowercase())
+        .map_err(|_| format!("The requirement '{}' is invalid, expected a package name like vendor/package", name))
+}
+
 /// Validate author format: Name or Name <email>
 fn validate_author(author: &str) -> Result<(), String> {
     if author.is_empty() {
@@ -269,22 +279,24 @@ fn add_vendor_to_gitignore(gitignore_path: &PathBuf) -> Result<()> {
 }
 
 pub async fn execute(args: InitArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
 
-    if json_path.exists() {
+    if json_path.exists() && !args.force {
         eprintln!(
-            "{} composer.json already exists in {}",
+            "{} composer.json already exists in {}, use --force to overwrite it",
             style("Error:").red().bold(),
             working_dir.display()
         );
         return Ok(1);
     }
 
+    for spec in args.require.iter().chain(&args.require_dev) {
+        validate_requirement_name(&parse_package_spec(spec).0).map_err(|e| anyhow::anyhow!(e))?;
+    }
+
     let git_config = GitConfig::load();
     let is_interactive = !args.no_interaction && std::io::stdin().is_terminal();
 
@@ -558,10 +570,12 @@ pub async fn execute(args: InitArgs) -> Result<i32> {
     }
 
     // --- Show confirmation ---
-    let json_content =
-        serde_json::to_string_pretty(&serde_json::Value::Object(json_obj.clone()))?;
+    // Written like Composer does: 4-space indentation and a trailing newline
+    let mut editor = JsonEditor::new();
+    editor.set(&[], serde_json::Value::Object(json_obj.clone()))?;
+    let json_content = editor.to_json_string()?;
 
-    println!("\n{}", json_content);
+    print!("\n{}", json_content);
 
     if is_interactive {
         let confirm = Confirm::new()
@@ -687,3 +701,25 @@ fn parse_author(author: &str) -> Vec<serde_json::Value> {
 
     vec![author_obj]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_package_name() {
+        assert!(validate_package_name("acme/my-lib").is_ok());
+        assert!(validate_package_name("acme/my_lib.v2").is_ok());
+        assert!(validate_package_name("Acme/Lib").is_err());
+        assert!(validate_package_name("acme").is_err());
+        assert!(validate_package_name("acme/lib/extra").is_err());
+    }
+
+    #[test]
+    fn test_validate_requirement_name() {
+        assert!(validate_requirement_name("php").is_ok());
+        assert!(validate_requirement_name("ext-json").is_ok());
+        assert!(validate_requirement_name("Monolog/Monolog").is_ok());
+        assert!(validate_requirement_name("monolog").is_err());
+    }
+}
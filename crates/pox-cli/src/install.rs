@@ -3,19 +3,18 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
 
 use pox_pm::{
     ComposerBuilder,
     config::Config,
-    installer::Installer,
+    installer::{Installer, ProcessLock},
     json::{ComposerJson, ComposerLock},
 };
 
 #[derive(Args, Debug)]
 pub struct InstallArgs {
-    /// Prefer source installation (git clone)
-    #[arg(long)]
+    /// Prefer source installation (git clone), falling back to dist for packages without a source
+    #[arg(long, conflicts_with = "prefer_dist")]
     pub prefer_source: bool,
 
     /// Prefer dist installation (zip download)
@@ -42,6 +41,10 @@ pub struct InstallArgs {
     #[arg(long)]
     pub no_progress: bool,
 
+    /// Do not list packages suggested by newly installed packages
+    #[arg(long)]
+    pub no_suggest: bool,
+
     /// Optimize autoloader (convert PSR-4/PSR-0 to classmap)
     #[arg(short = 'o', long)]
     pub optimize_autoloader: bool,
@@ -58,9 +61,10 @@ pub struct InstallArgs {
     #[arg(long)]
     pub ignore_platform_reqs: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// Fail right away when another process is installing into the vendor directory,
+    /// instead of waiting for it to finish
+    #[arg(long)]
+    pub no_wait: bool,
 
     // Common Composer flags (for compatibility)
     /// Force ANSI output
@@ -90,6 +94,15 @@ pub struct InstallArgs {
     /// Audit output format (table, plain, json, or summary)
     #[arg(long, default_value = "summary")]
     pub audit_format: String,
+
+    /// Fail if the audit finds advisories at or above --audit-level, or abandoned packages
+    /// when the audit.abandoned config is "fail"
+    #[arg(long, conflicts_with = "no_audit")]
+    pub audit: bool,
+
+    /// Minimum advisory severity that fails the command with --audit (low, medium, high, or critical)
+    #[arg(long, default_value = "low", value_parser = ["low", "medium", "high", "critical"])]
+    pub audit_level: String,
 }
 
 use crate::pm::platform::PlatformInfo;
@@ -97,9 +110,20 @@ use crate::pm::platform::PlatformInfo;
 pub async fn execute(args: InstallArgs) -> Result<i32> {
     let skip_audit = args.no_audit || std::env::var("COMPOSER_NO_AUDIT").unwrap_or_default() == "1";
 
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
+    // Load config
+    let config = Config::build(Some(&working_dir), true)?;
+
+    // Lock the vendor directory before reading composer.json and composer.lock, so waiting
+    // for another install or update doesn't leave us with what it replaced
+    let _vendor_lock = if args.dry_run {
+        None
+    } else {
+        Some(ProcessLock::acquire_async(working_dir.join(&config.vendor_dir), !args.no_wait).await?)
+    };
+
     // Load composer.json
     let json_path = working_dir.join("composer.json");
     let composer_json: ComposerJson = if json_path.exists() {
@@ -122,9 +146,6 @@ pub async fn execute(args: InstallArgs) -> Result<i32> {
         (None, true)
     };
 
-    // Load config
-    let config = Config::build(Some(&working_dir), true)?;
-
     // Detect platform
     let platform = PlatformInfo::detect();
 
@@ -147,7 +168,8 @@ pub async fn execute(args: InstallArgs) -> Result<i32> {
     let composer = builder.build()?;
 
     // Run Installer
-    let installer = Installer::new(composer);
+    let installer = Installer::new(composer)
+        .no_suggest(args.no_suggest);
 
     let result = if run_update {
         installer.update(
@@ -166,18 +188,18 @@ pub async fn execute(args: InstallArgs) -> Result<i32> {
     };
 
     if result.is_ok() && !skip_audit {
-        let audit_args = crate::pm::audit::AuditArgs {
-            no_dev: args.no_dev,
-            format: args.audit_format.clone(),
-            locked: false,
-            abandoned: Some("report".to_string()),
-            working_dir: working_dir.clone(),
-        };
-
-        if let Err(e) = crate::pm::audit::execute(audit_args).await {
-            eprintln!("Warning: Audit failed: {}", e);
+        let exit_code = crate::pm::audit::audit_after_install(
+            working_dir.clone(),
+            args.no_dev,
+            args.audit_format.clone(),
+            args.audit,
+            args.audit_level.clone(),
+        ).await;
+
+        if exit_code != 0 {
+            return Ok(exit_code);
         }
     }
 
-    result
+    Ok(result?)
 }
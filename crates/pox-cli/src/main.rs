@@ -10,8 +10,7 @@ mod update;
 use config::PoxConfig;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand, CommandFactory};
-use clap_complete::{generate, Shell};
+use clap::{CommandFactory, Parser, Subcommand};
 use pox_embed::{HttpRequest, Php, PhpWeb, PhpWorker};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
@@ -23,6 +22,9 @@ use notify::RecursiveMode;
 use notify_debouncer_full::{new_debouncer, DebouncedEvent};
 use globset::{Glob, GlobSetBuilder};
 
+/// Version of this build, with the commit it was built from
+pub(crate) const POX_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("POX_GIT_SHA"), ")");
+
 #[derive(Parser, Debug)]
 #[command(name = "pox")]
 #[command(about = "PHP CLI embedded in Rust")]
@@ -57,6 +59,22 @@ struct Args {
     #[arg(short = 'd', value_name = "KEY=VALUE", action = clap::ArgAction::Append)]
     define: Vec<String>,
 
+    /// Print the wall-clock time of each phase (config load, solve, download, ...) to stderr
+    #[arg(long, global = true)]
+    profile: bool,
+
+    /// Write install and update steps to stdout as NDJSON events, moving other output to stderr
+    #[arg(long = "events-json", alias = "porcelain", global = true)]
+    events_json: bool,
+
+    /// Neither read nor write any cache (repository metadata, archives, advisories)
+    #[arg(long = "no-cache", global = true)]
+    no_cache: bool,
+
+    /// Use the given directory as working directory, for every command
+    #[arg(long = "working-dir", value_name = "DIR", global = true)]
+    working_dir: Option<PathBuf>,
+
     /// PHP script to execute and its arguments
     #[arg(value_name = "FILE", trailing_var_arg = true, allow_hyphen_values = true)]
     script_and_args: Vec<String>,
@@ -114,20 +132,21 @@ enum Commands {
     Remove(remove::RemoveArgs),
 
     /// Package manager commands (show, validate, dump-autoload)
+    #[command(arg_required_else_help = true)]
     Pm {
+        /// Print the pox version and the commit it was built from
+        #[arg(short = 'V', long = "version")]
+        version: bool,
+
         #[command(subcommand)]
-        command: pm::PmCommands,
+        command: Option<pm::PmCommands>,
     },
 
     /// Run a script defined in composer.json
     Run(pm::RunArgs),
 
     /// Generate shell completion scripts
-    Completion {
-        /// The shell to generate completions for
-        #[arg(value_enum)]
-        shell: Shell,
-    },
+    Completion(pm::CompletionArgs),
 }
 
 fn print_version() {
@@ -602,6 +621,24 @@ fn guess_content_type(path: &Path) -> String {
 fn run() -> Result<i32> {
     let args = Args::parse();
 
+    if args.profile {
+        pox_pm::profile::enable();
+    }
+
+    if args.no_cache {
+        pox_pm::cache::disable_all();
+    }
+
+    if args.events_json {
+        enable_event_stream()?;
+    }
+
+    // Switch directories before anything reads files, so relative paths resolve against it
+    if let Some(dir) = &args.working_dir {
+        std::env::set_current_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to change to working directory {}: {}", dir.display(), e))?;
+    }
+
     // Load pox.toml config if present
     let config = PoxConfig::load_from_cwd()?;
 
@@ -691,7 +728,17 @@ fn run() -> Result<i32> {
                     .map_err(|e| anyhow::anyhow!("Failed to create async runtime: {}", e))?;
                 return rt.block_on(remove::execute(remove_args));
             }
-            Commands::Pm { command } => {
+            Commands::Pm { version: true, .. } => {
+                println!("pox {}", POX_VERSION);
+                return Ok(0);
+            }
+            Commands::Pm { command: None, .. } => {
+                Args::command().find_subcommand_mut("pm")
+                    .expect("pm subcommand is defined")
+                    .print_help()?;
+                return Ok(2);
+            }
+            Commands::Pm { command: Some(command), .. } => {
                 let rt = tokio::runtime::Runtime::new()
                     .map_err(|e| anyhow::anyhow!("Failed to create async runtime: {}", e))?;
                 return rt.block_on(pm::execute(command));
@@ -701,10 +748,10 @@ fn run() -> Result<i32> {
                     .map_err(|e| anyhow::anyhow!("Failed to create async runtime: {}", e))?;
                 return rt.block_on(pm::run::execute(run_args));
             }
-            Commands::Completion { shell } => {
-                let mut cmd = Args::command();
-                generate(shell, &mut cmd, "pox", &mut std::io::stdout());
-                return Ok(0);
+            Commands::Completion(completion_args) => {
+                let rt = tokio::runtime::Runtime::new()
+                    .map_err(|e| anyhow::anyhow!("Failed to create async runtime: {}", e))?;
+                return rt.block_on(pm::completion::execute(completion_args));
             }
         }
     }
@@ -764,7 +811,7 @@ fn run() -> Result<i32> {
 
     // No action specified - show usage
     let v = Php::version();
-    eprintln!("pox {} - PHP {} embedded in Rust", env!("CARGO_PKG_VERSION"), v.version);
+    eprintln!("pox {} - PHP {} embedded in Rust", POX_VERSION, v.version);
     eprintln!();
     eprintln!("Usage: pox [options] [-f] <file> [--] [args...]");
     eprintln!("       pox [options] -r <code> [--] [args...]");
@@ -795,8 +842,46 @@ fn run() -> Result<i32> {
     Ok(0)
 }
 
+/// Send events to the original stdout and point stdout at stderr, so nothing else
+/// printed by commands, scripts or PHP ends up between the events
+#[cfg(unix)]
+fn enable_event_stream() -> Result<()> {
+    use std::os::fd::AsFd;
+
+    let events = std::io::stdout().as_fd().try_clone_to_owned()?;
+    // SAFETY: dup2 only replaces the stdout descriptor, which stays open
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    pox_pm::event_stream::enable(Box::new(std::fs::File::from(events)));
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn enable_event_stream() -> Result<()> {
+    pox_pm::event_stream::enable(Box::new(std::io::stdout()));
+    Ok(())
+}
+
 fn main() -> ExitCode {
-    match run() {
+    let start = std::time::Instant::now();
+    let result = run();
+
+    if pox_pm::profile::is_enabled() {
+        eprint!("{}", pox_pm::profile::report(start.elapsed()));
+    }
+
+    if pox_pm::event_stream::is_enabled() {
+        use pox_pm::event_stream::{emit, Event, Status};
+
+        if let Err(e) = &result {
+            emit(&Event::Error { message: format!("{:#}", e) });
+        }
+        let status = if matches!(result, Ok(0)) { Status::Ok } else { Status::Error };
+        emit(&Event::Done { status });
+    }
+
+    match result {
         Ok(code) => ExitCode::from(code as u8),
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -0,0 +1,180 @@
+//! Archive command - create a zip or tar archive of the project or of a package.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use pox_pm::{
+    archiver::{self, ArchiveFormat},
+    config::{AuthConfig, Config},
+    downloader::{DownloadConfig, DownloadManager},
+    http::{HttpClient, HttpClientConfig},
+    json::ComposerJson,
+    repository::{ComposerRepository, RepositoryManager},
+    Package,
+};
+
+#[derive(Args, Debug)]
+pub struct ArchiveArgs {
+    /// The package to archive instead of the current project
+    #[arg(value_name = "PACKAGE")]
+    pub package: Option<String>,
+
+    /// A version constraint for the package to archive
+    #[arg(value_name = "VERSION", requires = "package")]
+    pub version: Option<String>,
+
+    /// Format of the resulting archive: tar or zip (default: config archive-format)
+    #[arg(short = 'f', long, value_parser = ["tar", "zip"])]
+    pub format: Option<String>,
+
+    /// Write the archive to this directory (default: config archive-dir)
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// Write the archive with the given file name, without extension
+    #[arg(long)]
+    pub file: Option<String>,
+}
+
+pub async fn execute(args: ArchiveArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+
+    let json_path = working_dir.join("composer.json");
+    let composer_json: ComposerJson = if json_path.exists() {
+        let content = std::fs::read_to_string(&json_path)?;
+        serde_json::from_str(&content)?
+    } else {
+        ComposerJson::default()
+    };
+
+    let config = Config::build(Some(&working_dir), true)?;
+    let format: ArchiveFormat = args.format.as_deref()
+        .unwrap_or(&config.archive_format)
+        .parse()?;
+    let target_dir = args.dir.clone()
+        .unwrap_or_else(|| working_dir.join(&config.archive_dir));
+
+    // Packages are downloaded into a temporary directory that lives until the archive is written
+    let download_dir = tempfile::tempdir().context("Failed to create temporary directory")?;
+
+    let (source_dir, name, excludes) = match &args.package {
+        Some(package_name) => {
+            let http_client = http_client(&config)?;
+            let Some(package) = find_package(&composer_json, &config, &http_client, package_name, args.version.as_deref()).await else {
+                eprintln!("{} Could not find a version of package {} matching your constraint",
+                    style("Error:").red().bold(),
+                    package_name
+                );
+                return Ok(1);
+            };
+
+            let version = package.pretty_version.as_deref().unwrap_or(&package.version);
+            println!("{} Downloading {} ({})",
+                style("Info:").cyan(),
+                style(&package.name).white().bold(),
+                style(version).yellow()
+            );
+
+            let source_dir = download_package(&config, http_client, &package, download_dir.path()).await?;
+            let archive = package.archive.clone().unwrap_or_default();
+            let name = archiver::archive_file_name(&package.name, archive.name.as_deref(), Some(version));
+            (source_dir, name, archive.exclude)
+        }
+        None => {
+            let Some(package_name) = composer_json.name.clone().or_else(|| {
+                working_dir.file_name().map(|n| n.to_string_lossy().to_string())
+            }) else {
+                eprintln!("{} Could not determine the name of the project", style("Error:").red().bold());
+                return Ok(1);
+            };
+
+            let name = archiver::archive_file_name(
+                &package_name,
+                composer_json.archive.name.as_deref(),
+                composer_json.version.as_deref(),
+            );
+            (working_dir.clone(), name, composer_json.archive.exclude.clone())
+        }
+    };
+
+    let file_name = format!("{}.{}", args.file.as_deref().unwrap_or(&name), format.extension());
+    let target = target_dir.join(file_name);
+
+    let files = archiver::archivable_files(&source_dir, &excludes)?
+        .into_iter()
+        .filter(|file| source_dir.join(file) != target)
+        .collect::<Vec<_>>();
+    archiver::create_archive(&source_dir, &files, format, &target)
+        .context("Failed to write archive")?;
+
+    println!("{} Created {} ({} files)",
+        style("Success:").green().bold(),
+        display_path(&target, &working_dir),
+        files.len()
+    );
+
+    Ok(0)
+}
+
+/// Find the best version of a package in the project's repositories and packagist
+async fn find_package(
+    composer_json: &ComposerJson,
+    config: &Config,
+    http_client: &Arc<HttpClient>,
+    name: &str,
+    version: Option<&str>,
+) -> Option<Arc<Package>> {
+    let mut repo_manager = RepositoryManager::new();
+    repo_manager.set_http_client(http_client.clone());
+    for repo in composer_json.repositories.as_vec() {
+        repo_manager.add_from_json_repository(&repo);
+    }
+    let mut packagist = if let Some(cache_dir) = &config.cache_dir {
+        ComposerRepository::packagist_with_cache(cache_dir.join("repo"))
+    } else {
+        ComposerRepository::packagist()
+    };
+    packagist.set_http_client(http_client.clone());
+    repo_manager.add_repository(Arc::new(packagist));
+
+    let packages = repo_manager.find_packages(name).await;
+    let stability = composer_json.minimum_stability.as_deref().unwrap_or("stable");
+    crate::create_project::find_best_version(&packages, version, stability)
+}
+
+/// HTTP client with the configured certificates and timeouts and the credentials from auth.json
+fn http_client(config: &Config) -> Result<Arc<HttpClient>> {
+    let auth = AuthConfig::build(None::<&Path>)?;
+    let client = HttpClient::with_config(HttpClientConfig::from_config(config))
+        .context("Failed to create HTTP client")?
+        .with_auth(auth);
+    Ok(Arc::new(client))
+}
+
+/// Download a package's dist into `download_dir`, returning the directory holding its files
+async fn download_package(config: &Config, http_client: Arc<HttpClient>, package: &Package, download_dir: &Path) -> Result<PathBuf> {
+    let download_config = DownloadConfig {
+        cache_dir: config.cache_dir.clone().unwrap_or_else(|| PathBuf::from(".composer/cache")),
+        vendor_dir: download_dir.to_path_buf(),
+        ..Default::default()
+    };
+    let download_manager = DownloadManager::new(http_client, download_config);
+
+    let result = download_manager
+        .download(package)
+        .await
+        .context("Failed to download package")?;
+    Ok(result.path)
+}
+
+/// Show a path relative to the working directory when it is inside it
+fn display_path(path: &Path, working_dir: &Path) -> String {
+    path.strip_prefix(working_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
@@ -1,16 +1,13 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
+use pox_pm::audit::{Advisories, AdvisoryFetcher, FailedBatch, SecurityAdvisory, Severity};
 use pox_pm::json::{ComposerLock, LockedPackage};
-use pox_pm::cache::Cache;
 use pox_pm::config::Config;
-use pox_semver::VersionParser;
-use serde::{Deserialize, Serialize};
+use pox_pm::http::{HttpClient, HttpClientConfig};
+use serde::Serialize;
 use std::collections::HashMap;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
 
 #[derive(Args, Debug)]
 pub struct AuditArgs {
@@ -18,7 +15,7 @@ pub struct AuditArgs {
     #[arg(long)]
     pub no_dev: bool,
 
-    /// Output format (table, plain, json, or summary)
+    /// Output format (table, plain, json, summary, or sarif)
     #[arg(short, long, default_value = "table")]
     pub format: String,
 
@@ -30,52 +27,27 @@ pub struct AuditArgs {
     #[arg(long, value_parser = ["ignore", "report", "fail"])]
     pub abandoned: Option<String>,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct SecurityAdvisoriesResponse {
-    advisories: HashMap<String, Vec<SecurityAdvisory>>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct SecurityAdvisory {
-    #[serde(rename = "advisoryId")]
-    advisory_id: String,
-    #[serde(rename = "packageName")]
-    package_name: String,
-    title: String,
-    #[serde(default)]
-    cve: Option<String>,
-    #[serde(default)]
-    link: Option<String>,
-    #[serde(default)]
-    severity: Option<String>,
-    #[serde(rename = "affectedVersions")]
-    affected_versions: String,
-    #[serde(rename = "reportedAt")]
-    reported_at: String,
-    #[serde(default)]
-    sources: Vec<AdvisorySource>,
-}
+    /// Minimum severity of advisories that fail the command (low, medium, high, or critical)
+    #[arg(long, default_value = "low", value_parser = ["low", "medium", "high", "critical"])]
+    pub audit_level: String,
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct AdvisorySource {
-    #[serde(rename = "name")]
-    _name: String,
-    #[serde(rename = "remoteId")]
-    _remote_id: String,
+    /// Refresh the cached advisories of all audited packages, however recent they are
+    #[arg(long)]
+    pub update_advisories: bool,
 }
 
 pub async fn execute(args: AuditArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
+    audit_project(args, &working_dir).await
+}
 
+/// Audit the locked packages of the project in `working_dir`
+///
+/// The exit code is a bitmask: 1 for advisories at or above the audit level, 2 for
+/// abandoned packages with `--abandoned=fail` and 4 for packages whose advisories could
+/// not be fetched.
+async fn audit_project(args: AuditArgs, working_dir: &Path) -> Result<i32> {
     let lock_path = working_dir.join("composer.lock");
     let lock: ComposerLock = if lock_path.exists() {
         let content = std::fs::read_to_string(&lock_path)?;
@@ -85,130 +57,63 @@ pub async fn execute(args: AuditArgs) -> Result<i32> {
         return Err(anyhow::anyhow!("No composer.lock found. Run 'install' or 'update' first."));
     };
 
-    let packages_with_versions: HashMap<String, String> = if args.no_dev {
-        lock.packages
-            .iter()
-            .map(|p| (p.name.clone(), p.version.clone()))
-            .collect()
-    } else {
-        lock.packages
-            .iter()
-            .chain(lock.packages_dev.iter())
-            .map(|p| (p.name.clone(), p.version.clone()))
-            .collect()
-    };
+    let dev_packages: &[LockedPackage] = if args.no_dev { &[] } else { &lock.packages_dev };
+    let locked_packages: Vec<&LockedPackage> = lock.packages.iter().chain(dev_packages).collect();
 
-    let packages: Vec<String> = packages_with_versions.keys().cloned().collect();
+    let packages_with_versions: HashMap<String, String> = locked_packages
+        .iter()
+        .map(|p| (p.name.clone(), p.version.clone()))
+        .collect();
 
-    if packages.is_empty() {
+    if packages_with_versions.is_empty() {
         println!("{}", "No packages - skipping audit.".yellow());
         return Ok(0);
     }
 
-    let version_parser = VersionParser::new();
-
+    let threshold: Severity = args.audit_level.parse()?;
     let config = Config::build(Some(&working_dir), true)?;
-    let cache_dir = config.cache_dir
-        .context("Cache directory not configured")?
-        .join("audit");
-    let cache = Cache::new(cache_dir);
-
-    let cache_ttl = Duration::from_secs(10 * 60);
-
-    // Create a hash of all package names to use as a single cache key
-    let mut sorted_packages = packages.clone();
-    sorted_packages.sort();
-    let mut hasher = DefaultHasher::new();
-    sorted_packages.hash(&mut hasher);
-    let cache_key = format!("bulk-{:x}", hasher.finish());
-
-    // Try to read from cache first
-    let all_advisories: HashMap<String, Vec<SecurityAdvisory>> =
-        if let Ok(Some(age)) = cache.age(&cache_key) {
-            if age < cache_ttl {
-                if let Ok(Some(data)) = cache.read(&cache_key) {
-                    if let Ok(cached) = serde_json::from_slice::<SecurityAdvisoriesResponse>(&data) {
-                        cached.advisories
-                    } else {
-                        fetch_and_cache_advisories(&cache, &cache_key, &packages).await?
-                    }
-                } else {
-                    fetch_and_cache_advisories(&cache, &cache_key, &packages).await?
-                }
-            } else {
-                fetch_and_cache_advisories(&cache, &cache_key, &packages).await?
-            }
-        } else {
-            fetch_and_cache_advisories(&cache, &cache_key, &packages).await?
-        };
-
-    let mut filtered_advisories: HashMap<String, Vec<SecurityAdvisory>> = HashMap::new();
-
-    for (package_name, advisories) in all_advisories {
-        if !packages.contains(&package_name) {
-            continue;
-        }
-
-        let installed_version = match packages_with_versions.get(&package_name) {
-            Some(v) => v,
-            None => continue,
-        };
-
-        let normalized_version = match version_parser.normalize(installed_version) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
-        let matching_advisories: Vec<SecurityAdvisory> = advisories
-            .into_iter()
-            .filter(|advisory| {
-                match version_parser.parse_constraints_cached(&advisory.affected_versions) {
-                    Ok(constraint) => constraint.matches_normalized(&normalized_version),
-                    Err(_) => false,
-                }
-            })
-            .collect();
-
-        if !matching_advisories.is_empty() {
-            filtered_advisories.insert(package_name, matching_advisories);
-        }
+    let http_client = HttpClient::with_config(HttpClientConfig::from_config(&config))
+        .context("Failed to create HTTP client")?;
+
+    // With the global --no-cache the advisory cache is created disabled
+    let report = AdvisoryFetcher::new(config.cache_dir.clone())
+        .with_http_client(std::sync::Arc::new(http_client))
+        .with_refresh(args.update_advisories)
+        .fetch_affected(&packages_with_versions)
+        .await
+        .context("Failed to query security advisories API")?;
+    for batch in &report.failed_batches {
+        eprintln!("{} Could not fetch advisories for {}", "Warning:".yellow(), batch);
     }
+    let advisories = report.advisories;
+    let unaudited = report.failed_batches;
 
-    let advisories_response = SecurityAdvisoriesResponse {
-        advisories: filtered_advisories,
-    };
-
-    let abandoned_behavior = args.abandoned.as_deref().unwrap_or("fail");
-    let abandoned_packages: Vec<_> = if abandoned_behavior != "ignore" {
-        lock.packages
-            .iter()
-            .chain(if args.no_dev {
-                [].iter()
-            } else {
-                lock.packages_dev.iter()
-            })
-            .filter(|p| p.is_abandoned())
-            .collect()
+    let abandoned_behavior = args.abandoned.as_deref().unwrap_or(&config.audit.abandoned);
+    let abandoned_packages: Vec<&LockedPackage> = if abandoned_behavior != "ignore" {
+        locked_packages.iter().copied().filter(|p| p.is_abandoned()).collect()
     } else {
         Vec::new()
     };
 
-    let has_vulnerabilities = !advisories_response.advisories.is_empty();
+    let has_vulnerabilities = advisories.values().flatten().any(|a| a.meets(threshold));
     let has_abandoned = !abandoned_packages.is_empty();
 
     match args.format.as_str() {
         "json" => {
-            output_json(&advisories_response, &abandoned_packages)?;
+            output_json(&advisories, &abandoned_packages, &unaudited)?;
         }
         "plain" => {
-            output_plain(&advisories_response, &abandoned_packages)?;
+            output_plain(&advisories, &abandoned_packages)?;
         }
         "summary" => {
-            output_summary(&advisories_response)?;
+            output_summary(&advisories, &unaudited)?;
+        }
+        "sarif" => {
+            output_sarif(&advisories, &abandoned_packages, abandoned_behavior, &unaudited)?;
         }
         _ => {
             // table format (default)
-            output_table(&advisories_response, &abandoned_packages)?;
+            output_table(&advisories, &abandoned_packages)?;
         }
     }
 
@@ -219,18 +124,73 @@ pub async fn execute(args: AuditArgs) -> Result<i32> {
     if has_abandoned && abandoned_behavior == "fail" {
         exit_code |= 2;
     }
+    // Packages whose advisories could not be fetched were not audited at all
+    if !unaudited.is_empty() {
+        exit_code |= 4;
+    }
 
     Ok(exit_code)
 }
 
+/// Run the audit at the end of install or update, returning its exit code
+///
+/// Findings are only reported unless `strict` is set (`--audit`), in which case advisories
+/// at or above `audit_level` and abandoned packages (per `audit.abandoned`) fail the command.
+/// When offline the audit is skipped with a warning instead.
+pub async fn audit_after_install(
+    working_dir: PathBuf,
+    no_dev: bool,
+    format: String,
+    strict: bool,
+    audit_level: String,
+) -> i32 {
+    if pox_pm::is_network_disabled() {
+        eprintln!("{} Network is disabled, skipping audit", "Warning:".yellow());
+        return 0;
+    }
+
+    let audit_args = AuditArgs {
+        no_dev,
+        format,
+        locked: false,
+        abandoned: if strict { None } else { Some("report".to_string()) },
+        audit_level,
+        update_advisories: false,
+    };
+
+    match audit_project(audit_args, &working_dir).await {
+        Ok(exit_code) if strict => exit_code,
+        Ok(_) => 0,
+        Err(e) if is_offline_error(&e) => {
+            eprintln!("{} Could not reach the advisory database, skipping audit: {}", "Warning:".yellow(), e);
+            0
+        }
+        Err(e) => {
+            eprintln!("Warning: Audit failed: {}", e);
+            if strict { 1 } else { 0 }
+        }
+    }
+}
+
+/// Check if an error was caused by being unable to connect at all
+fn is_offline_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|e| e.is_connect() || e.is_timeout())
+}
+
 fn output_json(
-    response: &SecurityAdvisoriesResponse,
+    advisories: &Advisories,
     abandoned_packages: &[&LockedPackage],
+    unaudited: &[FailedBatch],
 ) -> Result<()> {
     #[derive(Serialize)]
-    struct JsonOutput {
+    struct JsonOutput<'a> {
         advisories: HashMap<String, Vec<SecurityAdvisory>>,
         abandoned: HashMap<String, Option<String>>,
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        unaudited: &'a [FailedBatch],
     }
 
     let abandoned_map: HashMap<String, Option<String>> = abandoned_packages
@@ -239,61 +199,152 @@ fn output_json(
         .collect();
 
     let output = JsonOutput {
-        advisories: response.advisories.clone(),
+        advisories: advisories.clone(),
         abandoned: abandoned_map,
+        unaudited,
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
 
-async fn fetch_and_cache_advisories(
-    cache: &Cache,
-    cache_key: &str,
-    packages: &[String],
-) -> Result<HashMap<String, Vec<SecurityAdvisory>>> {
-    let api_url = "https://packagist.org/api/security-advisories/";
-
-    let form_data = packages
-        .iter()
-        .map(|p| format!("packages[]={}", p))
-        .collect::<Vec<_>>()
-        .join("&");
-
-    let client = reqwest::Client::new();
-    let response = client
-        .post(api_url)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .body(form_data)
-        .send()
-        .await
-        .context("Failed to query security advisories API")?;
+fn output_sarif(
+    advisories: &Advisories,
+    abandoned_packages: &[&LockedPackage],
+    abandoned_behavior: &str,
+    unaudited: &[FailedBatch],
+) -> Result<()> {
+    let document = sarif_document(advisories, abandoned_packages, abandoned_behavior, unaudited);
+    println!("{}", serde_json::to_string_pretty(&document)?);
+    Ok(())
+}
 
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Security advisories API returned status: {}",
-            response.status()
-        ));
+/// Build a SARIF 2.1.0 document with one result per advisory, abandoned package and
+/// package that could not be audited
+///
+/// Each advisory becomes a rule; results point at `composer.lock` with the package as
+/// their logical location, since that is where the dependency is pinned.
+fn sarif_document(
+    advisories: &Advisories,
+    abandoned_packages: &[&LockedPackage],
+    abandoned_behavior: &str,
+    unaudited: &[FailedBatch],
+) -> serde_json::Value {
+    let mut packages: Vec<&String> = advisories.keys().collect();
+    packages.sort();
+
+    let mut rules = Vec::new();
+    let mut results = Vec::new();
+
+    for package in packages {
+        for advisory in &advisories[package] {
+            let (level, security_severity) = match advisory.severity.as_deref() {
+                Some("critical") => ("error", "9.5"),
+                Some("high") => ("error", "8.0"),
+                Some("medium") => ("warning", "5.5"),
+                Some("low") => ("note", "2.0"),
+                _ => ("warning", "5.0"),
+            };
+
+            let mut rule = serde_json::json!({
+                "id": advisory.advisory_id,
+                "name": advisory.cve.as_deref().unwrap_or(&advisory.advisory_id),
+                "shortDescription": {"text": advisory.title},
+                "properties": {
+                    "tags": ["security"],
+                    "security-severity": security_severity,
+                },
+            });
+            if let Some(link) = &advisory.link {
+                rule["helpUri"] = serde_json::json!(link);
+            }
+            rules.push(rule);
+
+            let mut message = format!(
+                "{} ({}) affects {} {}",
+                advisory.title,
+                advisory.cve.as_deref().unwrap_or("NO CVE"),
+                package,
+                advisory.affected_versions
+            );
+            if let Some(note) = &advisory.note {
+                message.push_str(&format!(". {}", note));
+            }
+            results.push(sarif_result(&advisory.advisory_id, level, &message, package));
+        }
     }
 
-    let api_response: SecurityAdvisoriesResponse = response
-        .json()
-        .await
-        .context("Failed to parse security advisories response")?;
+    if !abandoned_packages.is_empty() {
+        rules.push(serde_json::json!({
+            "id": "abandoned-package",
+            "name": "AbandonedPackage",
+            "shortDescription": {"text": "Package is abandoned"},
+            "properties": {"tags": ["maintenance"]},
+        }));
+
+        let level = if abandoned_behavior == "fail" { "error" } else { "note" };
+        for package in abandoned_packages {
+            let replacement = package
+                .abandoned_replacement()
+                .map(|r| format!("Use {} instead", r))
+                .unwrap_or_else(|| "No replacement was suggested".to_string());
+            let message = format!("{} is abandoned. {}", package.name, replacement);
+            results.push(sarif_result("abandoned-package", level, &message, &package.name));
+        }
+    }
 
-    if let Ok(data) = serde_json::to_vec(&api_response) {
-        let _ = cache.write(cache_key, &data);
+    if !unaudited.is_empty() {
+        rules.push(serde_json::json!({
+            "id": "audit-incomplete",
+            "name": "AuditIncomplete",
+            "shortDescription": {"text": "Advisories of the package could not be fetched"},
+            "properties": {"tags": ["security"]},
+        }));
+
+        for batch in unaudited {
+            for package in &batch.packages {
+                let message = format!("Advisories for {} could not be fetched, it was not audited: {}", package, batch.error);
+                results.push(sarif_result("audit-incomplete", "error", &message, package));
+            }
+        }
     }
 
-    Ok(api_response.advisories)
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pox",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(rule_id: &str, level: &str, message: &str, package: &str) -> serde_json::Value {
+    serde_json::json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": {"text": message},
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {"uri": "composer.lock"},
+            },
+            "logicalLocations": [{"name": package, "kind": "package"}],
+        }],
+    })
 }
 
 fn output_table(
-    response: &SecurityAdvisoriesResponse,
+    advisories: &Advisories,
     abandoned_packages: &[&LockedPackage],
 ) -> Result<()> {
-    let total_advisories: usize = response.advisories.values().map(|v| v.len()).sum();
-    let affected_packages = response.advisories.len();
+    let total_advisories: usize = advisories.values().map(|v| v.len()).sum();
+    let affected_packages = advisories.len();
 
     if total_advisories > 0 {
         let plurality = if total_advisories == 1 { "y" } else { "ies" };
@@ -310,8 +361,8 @@ fn output_table(
         );
         println!();
 
-        for advisories in response.advisories.values() {
-            for advisory in advisories {
+        for package_advisories in advisories.values() {
+            for advisory in package_advisories {
                 println!("{}", "─".repeat(80).bright_black());
                 println!("{}: {}", "Package".bold(), advisory.package_name);
                 println!(
@@ -335,6 +386,9 @@ fn output_table(
                     advisory.affected_versions
                 );
                 println!("{}: {}", "Reported at".bold(), advisory.reported_at);
+                if let Some(note) = &advisory.note {
+                    println!("{}: {}", "Note".bold(), note.yellow());
+                }
                 println!();
             }
         }
@@ -367,11 +421,11 @@ fn output_table(
 }
 
 fn output_plain(
-    response: &SecurityAdvisoriesResponse,
+    advisories: &Advisories,
     abandoned_packages: &[&LockedPackage],
 ) -> Result<()> {
-    let total_advisories: usize = response.advisories.values().map(|v| v.len()).sum();
-    let affected_packages = response.advisories.len();
+    let total_advisories: usize = advisories.values().map(|v| v.len()).sum();
+    let affected_packages = advisories.len();
 
     if total_advisories > 0 {
         let plurality = if total_advisories == 1 { "y" } else { "ies" };
@@ -383,8 +437,8 @@ fn output_plain(
         );
 
         let mut first = true;
-        for advisories in response.advisories.values() {
-            for advisory in advisories {
+        for package_advisories in advisories.values() {
+            for advisory in package_advisories {
                 if !first {
                     eprintln!("--------");
                 }
@@ -399,6 +453,9 @@ fn output_plain(
                 eprintln!("URL: {}", advisory.link.as_deref().unwrap_or(""));
                 eprintln!("Affected versions: {}", advisory.affected_versions);
                 eprintln!("Reported at: {}", advisory.reported_at);
+                if let Some(note) = &advisory.note {
+                    eprintln!("Note: {}", note);
+                }
                 first = false;
             }
         }
@@ -425,9 +482,9 @@ fn output_plain(
     Ok(())
 }
 
-fn output_summary(response: &SecurityAdvisoriesResponse) -> Result<()> {
-    let total_advisories: usize = response.advisories.values().map(|v| v.len()).sum();
-    let affected_packages = response.advisories.len();
+fn output_summary(advisories: &Advisories, unaudited: &[FailedBatch]) -> Result<()> {
+    let total_advisories: usize = advisories.values().map(|v| v.len()).sum();
+    let affected_packages = advisories.len();
 
     if total_advisories > 0 {
         let plurality = if total_advisories == 1 { "y" } else { "ies" };
@@ -442,6 +499,16 @@ fn output_summary(response: &SecurityAdvisoriesResponse) -> Result<()> {
         eprintln!("No security vulnerability advisories found.");
     }
 
+    let unaudited_packages: Vec<&str> = unaudited.iter().flat_map(|batch| &batch.packages).map(String::as_str).collect();
+    if !unaudited_packages.is_empty() {
+        eprintln!(
+            "Could not fetch advisories for {} package{}, they were not audited: {}",
+            unaudited_packages.len(),
+            if unaudited_packages.len() == 1 { "" } else { "s" },
+            unaudited_packages.join(", ")
+        );
+    }
+
     Ok(())
 }
 
@@ -454,3 +521,50 @@ fn colorize_severity(severity: Option<&str>) -> colored::ColoredString {
         _ => "unknown".normal(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sarif_document() {
+        let advisory: SecurityAdvisory = serde_json::from_value(serde_json::json!({
+            "advisoryId": "PKSA-1234",
+            "packageName": "acme/lib",
+            "title": "Remote code execution",
+            "cve": "CVE-2024-0001",
+            "link": "https://example.com/advisory",
+            "severity": "high",
+            "affectedVersions": "<1.2.0",
+            "reportedAt": "2024-01-01 00:00:00",
+        }))
+        .unwrap();
+        let advisories = Advisories::from([("acme/lib".to_string(), vec![advisory])]);
+        let abandoned: LockedPackage = serde_json::from_value(serde_json::json!({
+            "name": "acme/old",
+            "version": "1.0.0",
+            "abandoned": "acme/new",
+        }))
+        .unwrap();
+
+        let unaudited = [FailedBatch { packages: vec!["acme/flaky".to_string()], error: "HTTP 502".to_string() }];
+
+        let document = sarif_document(&advisories, &[&abandoned], "report", &unaudited);
+        assert_eq!(document["version"], "2.1.0");
+
+        let run = &document["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "PKSA-1234");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["helpUri"], "https://example.com/advisory");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["locations"][0]["logicalLocations"][0]["name"], "acme/lib");
+        assert_eq!(results[1]["ruleId"], "abandoned-package");
+        assert_eq!(results[1]["level"], "note");
+        assert_eq!(results[1]["message"]["text"], "acme/old is abandoned. Use acme/new instead");
+        assert_eq!(results[2]["ruleId"], "audit-incomplete");
+        assert_eq!(results[2]["level"], "error");
+        assert_eq!(results[2]["locations"][0]["logicalLocations"][0]["name"], "acme/flaky");
+    }
+}
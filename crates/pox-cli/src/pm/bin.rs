@@ -10,57 +10,11 @@ use console::style;
 use std::path::PathBuf;
 use std::process::Command;
 
-/// Configuration for the bin plugin from composer.json extra.bamarni-bin
-#[derive(Debug, Clone)]
-pub struct BinConfig {
-    /// Whether to create bin links in the main vendor/bin directory
-    pub bin_links: bool,
-    /// Target directory for bin namespaces (default: vendor-bin)
-    pub target_directory: String,
-    /// Whether to forward install/update commands to all namespaces
-    pub forward_command: bool,
-}
-
-impl Default for BinConfig {
-    fn default() -> Self {
-        Self {
-            bin_links: false,  // Default to false in 2.x behavior
-            target_directory: "vendor-bin".to_string(),
-            forward_command: false,
-        }
-    }
-}
-
-impl BinConfig {
-    /// Parse config from composer.json extra field
-    pub fn from_extra(extra: &serde_json::Value) -> Self {
-        let bamarni_bin = extra.get("bamarni-bin");
-
-        let mut config = Self::default();
-
-        if let Some(obj) = bamarni_bin.and_then(|v| v.as_object()) {
-            if let Some(bin_links) = obj.get("bin-links").and_then(|v| v.as_bool()) {
-                config.bin_links = bin_links;
-            }
-            if let Some(target_dir) = obj.get("target-directory").and_then(|v| v.as_str()) {
-                config.target_directory = target_dir.to_string();
-            }
-            if let Some(forward) = obj.get("forward-command").and_then(|v| v.as_bool()) {
-                config.forward_command = forward;
-            }
-        }
-
-        config
-    }
-}
+use pox_pm::plugin::BinConfig;
 
 #[derive(Args, Debug)]
 #[command(trailing_var_arg = true)]
 pub struct BinArgs {
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".", global = true)]
-    pub working_dir: PathBuf,
-
     /// Bin namespace (e.g., 'php-cs-fixer', 'phpstan', or 'all' for all namespaces)
     #[arg(value_name = "NAMESPACE")]
     pub namespace: String,
@@ -71,7 +25,7 @@ pub struct BinArgs {
 }
 
 pub async fn execute(args: BinArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     // Load composer.json to get config
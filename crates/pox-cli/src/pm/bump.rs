@@ -2,8 +2,8 @@ use anyhow::{Context, Result};
 use clap::Args;
 use indexmap::IndexMap;
 use regex::Regex;
-use std::path::PathBuf;
 
+use pox_pm::config::Config;
 use pox_pm::json::{ComposerJson, ComposerLock};
 use pox_pm::package::version_bumper::bump_requirement;
 use pox_pm::{compute_content_hash, is_platform_package};
@@ -15,20 +15,21 @@ pub struct BumpArgs {
     pub packages: Vec<String>,
 
     /// Only bump requirements in "require-dev"
-    #[arg(short = 'D', long)]
+    #[arg(short = 'D', long, conflicts_with = "no_dev_only")]
     pub dev_only: bool,
 
     /// Only bump requirements in "require"
     #[arg(short = 'R', long)]
     pub no_dev_only: bool,
 
+    /// Bump to the versions in composer.lock only, instead of falling back to
+    /// vendor/composer/installed.json when there is no lock file
+    #[arg(long)]
+    pub locked: bool,
+
     /// Outputs the packages to bump, but will not execute anything
     #[arg(long)]
     pub dry_run: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub struct BumpUpdates {
@@ -66,44 +67,31 @@ pub fn calculate_updates(
     };
 
     if !dev_only {
-        for (name, constraint) in &composer_json.require {
-            if is_platform_package(name) {
-                continue;
-            }
-            if !matches_filter(name) {
-                continue;
-            }
-
-            if let Some(pkg) = lock.find_package(name) {
-                let bumped = bump_requirement(constraint, &pkg.version);
-                if bumped != *constraint {
-                    updates.require.insert(name.clone(), bumped);
-                }
-            }
-        }
+        updates.require = bump_section(&composer_json.require, lock, &matches_filter);
     }
-
     if !no_dev_only {
-        for (name, constraint) in &composer_json.require_dev {
-            if is_platform_package(name) {
-                continue;
-            }
-            if !matches_filter(name) {
-                continue;
-            }
-
-            if let Some(pkg) = lock.find_package(name) {
-                let bumped = bump_requirement(constraint, &pkg.version);
-                if bumped != *constraint {
-                    updates.require_dev.insert(name.clone(), bumped);
-                }
-            }
-        }
+        updates.require_dev = bump_section(&composer_json.require_dev, lock, &matches_filter);
     }
 
     updates
 }
 
+/// Bumped constraints of one requirement section, for the packages that changed
+fn bump_section(
+    requirements: &IndexMap<String, String>,
+    lock: &ComposerLock,
+    matches_filter: &dyn Fn(&str) -> bool,
+) -> IndexMap<String, String> {
+    requirements
+        .iter()
+        .filter(|(name, _)| !is_platform_package(name) && matches_filter(name))
+        .filter_map(|(name, constraint)| {
+            let bumped = bump_requirement(constraint, lock.locked_version(name)?);
+            (bumped != *constraint).then(|| (name.clone(), bumped))
+        })
+        .collect()
+}
+
 pub fn apply_updates_to_json(content: &str, updates: &BumpUpdates) -> Result<String> {
     let mut result = content.to_string();
 
@@ -125,7 +113,7 @@ fn update_dependency_in_json(
     new_version: &str,
 ) -> Result<String> {
     let escaped_name = regex::escape(name);
-    let pattern = format!(r#"("{}")\s*:\s*"([^"]*)""#, escaped_name);
+    let pattern = format!(r#"("{}"\s*:\s*)"([^"]*)""#, escaped_name);
 
     let re = Regex::new(&pattern).context("Failed to build regex pattern")?;
 
@@ -156,7 +144,7 @@ fn update_dependency_in_json(
 
         if let Some(caps) = re.captures(section_content) {
             let full_match = caps.get(0).unwrap();
-            let replacement = format!(r#"{}": "{}""#, &caps[1], new_version);
+            let replacement = format!(r#"{}"{}""#, &caps[1], new_version);
 
             let new_section = format!(
                 "{}{}{}",
@@ -174,25 +162,13 @@ fn update_dependency_in_json(
         }
     }
 
-    if let Some(caps) = re.captures(content) {
-        let full_match = caps.get(0).unwrap();
-        let replacement = format!(r#"{}": "{}""#, &caps[1], new_version);
-
-        return Ok(format!(
-            "{}{}{}",
-            &content[..full_match.start()],
-            replacement,
-            &content[full_match.end()..]
-        ));
-    }
-
+    // Never fall back to a match outside the section: the same package may be listed in
+    // the other one, which the --dev-only/--no-dev-only flags must leave alone
     Ok(content.to_string())
 }
 
 pub async fn execute(args: BumpArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
@@ -219,12 +195,27 @@ pub async fn execute(args: BumpArgs) -> Result<i32> {
         }
     }
 
-    let lock: ComposerLock = if lock_path.exists() {
+    let config = Config::build(Some(&working_dir), true)?;
+    let has_lock = lock_path.exists();
+
+    let lock: ComposerLock = if has_lock {
         let lock_content =
             std::fs::read_to_string(&lock_path).context("Failed to read composer.lock")?;
-        serde_json::from_str(&lock_content).context("Failed to parse composer.lock")?
+        let lock: ComposerLock =
+            serde_json::from_str(&lock_content).context("Failed to parse composer.lock")?;
+        if lock.content_hash != compute_content_hash(&json_content) {
+            eprintln!("The lock file is not up to date with the latest changes in composer.json. Run the appropriate `update` to fix that before you use the `bump` command.");
+            return Ok(1);
+        }
+        lock
+    } else if args.locked {
+        eprintln!("No composer.lock found, --locked requires one.");
+        eprintln!("Run 'pox update' first to create a lock file.");
+        return Ok(1);
     } else {
-        let installed_path = working_dir.join("vendor/composer/installed.json");
+        let installed_path = config
+            .get_vendor_dir()
+            .join("composer/installed.json");
         if installed_path.exists() {
             let installed_content =
                 std::fs::read_to_string(&installed_path).context("Failed to read installed.json")?;
@@ -273,7 +264,9 @@ pub async fn execute(args: BumpArgs) -> Result<i32> {
             change_count
         );
 
-        if lock_path.exists() {
+        // The lock stays as it is, only its hash follows composer.json so it isn't
+        // reported as outdated
+        if has_lock && config.lock && new_content != json_content {
             update_lock_hash(&lock_path, &new_content)?;
         }
     } else {
@@ -329,9 +322,50 @@ fn update_lock_hash(lock_path: &std::path::Path, json_content: &str) -> Result<(
 
     lock.content_hash = compute_content_hash(json_content);
 
-    let new_lock_content = lock.to_json()
-        .context("Failed to serialize composer.lock")?;
-    std::fs::write(lock_path, new_lock_content)?;
+    lock.write(lock_path)
+        .context("Failed to write composer.lock")?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_sections_independently() {
+        let content = r#"{
+    "require": {
+        "acme/lib": "^1.0",
+        "acme/other": "^2.0"
+    },
+    "require-dev": {
+        "acme/lib": "^1.0"
+    }
+}"#;
+        let composer_json: ComposerJson = serde_json::from_str(content).unwrap();
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "packages": [
+                {"name": "acme/lib", "version": "1.4.2"},
+                {"name": "acme/other", "version": "2.0.0"}
+            ]
+        })).unwrap();
+
+        let updates = calculate_updates(&composer_json, &lock, &[], false, true);
+        assert_eq!(updates.require.get("acme/lib").map(String::as_str), Some("^1.4.2"));
+        assert!(!updates.require.contains_key("acme/other"));
+        assert!(updates.require_dev.is_empty());
+
+        let bumped = apply_updates_to_json(content, &updates).unwrap();
+        let bumped: ComposerJson = serde_json::from_str(&bumped).unwrap();
+        assert_eq!(bumped.require["acme/lib"], "^1.4.2");
+        assert_eq!(bumped.require_dev["acme/lib"], "^1.0");
+
+        let updates = calculate_updates(&composer_json, &lock, &[], true, false);
+        assert!(updates.require.is_empty());
+        let bumped = apply_updates_to_json(content, &updates).unwrap();
+        let bumped: ComposerJson = serde_json::from_str(&bumped).unwrap();
+        assert_eq!(bumped.require["acme/lib"], "^1.0");
+        assert_eq!(bumped.require_dev["acme/lib"], "^1.4.2");
+    }
+}
@@ -0,0 +1,343 @@
+//! Check-platform-reqs command - verify the PHP platform satisfies the locked requirements.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::collections::{BTreeMap, HashMap};
+
+use pox_pm::{
+    is_platform_package,
+    json::{ComposerJson, ComposerLock, LockedPackage},
+};
+use pox_semver::{ConstraintInterface, MatchAllConstraint, VersionParser};
+
+use crate::pm::platform::PlatformInfo;
+
+#[derive(Args, Debug)]
+pub struct CheckPlatformReqsArgs {
+    /// Disables checking of require-dev packages requirements
+    #[arg(long)]
+    pub no_dev: bool,
+}
+
+/// Outcome of checking one platform requirement
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+    Success,
+    Failed,
+    Missing,
+}
+
+/// A platform requirement checked against the current platform
+#[derive(Debug)]
+struct RequirementCheck {
+    name: String,
+    /// Version provided by the platform, if any
+    version: Option<String>,
+    /// The strongest constraint placed on the requirement
+    constraint: String,
+    /// Packages placing a constraint on the requirement
+    required_by: Vec<String>,
+    /// Locked package providing the requirement when the platform doesn't satisfy it
+    provided_by: Option<String>,
+    status: Status,
+}
+
+/// A locked package that provides or replaces a platform requirement
+#[derive(Debug)]
+struct Provider {
+    name: String,
+    version: String,
+    /// Constraint the requirement is provided at
+    constraint: String,
+}
+
+pub async fn execute(args: CheckPlatformReqsArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+
+    let json_path = working_dir.join("composer.json");
+    let composer_json: ComposerJson = if json_path.exists() {
+        let content = std::fs::read_to_string(&json_path)?;
+        serde_json::from_str(&content)?
+    } else {
+        eprintln!("Error: composer.json not found in working directory");
+        return Ok(1);
+    };
+
+    let lock_path = working_dir.join("composer.lock");
+    if !lock_path.exists() {
+        eprintln!("Error: No composer.lock found. Run 'install' or 'update' first.");
+        return Ok(1);
+    }
+    let lock_content = std::fs::read_to_string(&lock_path).context("Failed to read composer.lock")?;
+    let lock: ComposerLock = serde_json::from_str(&lock_content).context("Failed to parse composer.lock")?;
+
+    let root_name = composer_json.name.as_deref().unwrap_or("__root__");
+    let requirements = collect_requirements(&lock, root_name, args.no_dev);
+
+    let platform: HashMap<String, String> = PlatformInfo::detect()
+        .to_packages()
+        .into_iter()
+        .map(|p| {
+            let version = p.pretty_version.clone().unwrap_or(p.version.clone());
+            (p.name, version)
+        })
+        .collect();
+
+    let checks: Vec<RequirementCheck> = requirements
+        .iter()
+        .map(|(name, constraints)| {
+            let providers: Vec<Provider> = lock
+                .providers_of(name, args.no_dev)
+                .into_iter()
+                .map(|(package, constraint)| Provider {
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    constraint,
+                })
+                .collect();
+            check_requirement(name, constraints, platform.get(name).map(String::as_str), &providers)
+        })
+        .collect();
+
+    let name_width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let version_width = checks
+        .iter()
+        .map(|c| c.version.as_deref().unwrap_or("n/a").len())
+        .max()
+        .unwrap_or(0);
+
+    let mut exit_code = 0;
+    for check in &checks {
+        let mut required_by = format!("{} required by {}", check.constraint, check.required_by.join(", "));
+        if let Some(provider) = &check.provided_by {
+            required_by.push_str(&format!(", provided by {}", provider));
+        }
+        let (mark, reason) = match check.status {
+            Status::Success => (style("✓").green(), required_by),
+            Status::Failed => {
+                exit_code = exit_code.max(1);
+                (style("✗").red(), format!("{} is not satisfied", required_by))
+            }
+            Status::Missing => {
+                exit_code = exit_code.max(2);
+                (style("✗").red(), format!("missing from your system, {}", required_by))
+            }
+        };
+
+        println!(
+            "{} {:<name_width$} {:<version_width$} {}",
+            mark,
+            check.name,
+            check.version.as_deref().unwrap_or("n/a"),
+            reason,
+            name_width = name_width,
+            version_width = version_width
+        );
+    }
+
+    Ok(exit_code)
+}
+
+/// Collect the platform requirements of the root package and all locked packages
+///
+/// Requirements are keyed by lowercase name, with each requiring package and its constraint.
+fn collect_requirements(lock: &ComposerLock, root_name: &str, no_dev: bool) -> BTreeMap<String, Vec<(String, String)>> {
+    let mut requirements: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    let mut add = |source: &str, name: &str, constraint: &str| {
+        if is_platform_package(name) && !name.starts_with("composer") {
+            requirements
+                .entry(name.to_lowercase())
+                .or_default()
+                .push((source.to_string(), constraint.to_string()));
+        }
+    };
+
+    for (name, constraint) in &lock.platform {
+        add(root_name, name, constraint);
+    }
+    if !no_dev {
+        for (name, constraint) in &lock.platform_dev {
+            add(root_name, name, constraint);
+        }
+    }
+
+    let dev_packages: &[LockedPackage] = if no_dev { &[] } else { &lock.packages_dev };
+    for package in lock.packages.iter().chain(dev_packages) {
+        for (name, constraint) in &package.require {
+            add(&package.name, name, constraint);
+        }
+    }
+
+    requirements
+}
+
+/// Check one requirement, collapsing all constraints placed on it into the strongest one
+///
+/// When the platform doesn't satisfy the requirement, a locked package providing it at a
+/// matching constraint does, like a polyfill does for a missing extension.
+fn check_requirement(
+    name: &str,
+    constraints: &[(String, String)],
+    available: Option<&str>,
+    providers: &[Provider],
+) -> RequirementCheck {
+    let parser = VersionParser::new();
+    let mut required_by: Vec<String> = Vec::new();
+    for (source, _) in constraints {
+        if !required_by.contains(source) {
+            required_by.push(source.clone());
+        }
+    }
+
+    let parsed: Vec<(&str, Option<Box<dyn ConstraintInterface>>)> = constraints
+        .iter()
+        .map(|(_, c)| (c.as_str(), parser.parse_constraints(c).ok()))
+        .collect();
+
+    // All constraints must hold, so the requirement is their intersection
+    let combined = parsed.iter().fold(
+        Box::new(MatchAllConstraint::new()) as Box<dyn ConstraintInterface>,
+        |acc, (_, c)| match c {
+            Some(c) => acc.intersect(c.as_ref()),
+            None => acc,
+        },
+    );
+
+    // Prefer showing a constraint as written when it already implies all the others
+    let all = MatchAllConstraint::new();
+    let combined_string = combined.to_string();
+    let constraint = parsed
+        .iter()
+        .find(|(_, c)| c.as_ref().is_some_and(|c| c.intersect(&all).to_string() == combined_string))
+        .map(|(written, _)| written.to_string())
+        .unwrap_or_else(|| combined.canonical_string());
+
+    let invalid = parsed.iter().any(|(_, c)| c.is_none());
+    let status = match available {
+        None => Status::Missing,
+        Some(version) => {
+            if combined.allows(version) && !invalid { Status::Success } else { Status::Failed }
+        }
+    };
+
+    let provider = (status != Status::Success && !invalid)
+        .then(|| {
+            providers.iter().find(|provider| {
+                parser
+                    .parse_constraints(&provider.constraint)
+                    .is_ok_and(|provided| combined.matches(provided.as_ref()))
+            })
+        })
+        .flatten();
+
+    match provider {
+        Some(provider) => RequirementCheck {
+            name: name.to_string(),
+            version: Some(provider.version.clone()),
+            constraint,
+            required_by,
+            provided_by: Some(provider.name.clone()),
+            status: Status::Success,
+        },
+        None => RequirementCheck {
+            name: name.to_string(),
+            version: available.map(String::from),
+            constraint,
+            required_by,
+            provided_by: None,
+            status,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraints(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter().map(|(s, c)| (s.to_string(), c.to_string())).collect()
+    }
+
+    #[test]
+    fn test_duplicate_requirements_collapse_to_strongest() {
+        let check = check_requirement(
+            "php",
+            &constraints(&[("a/a", ">=7.4"), ("b/b", "^8.1"), ("c/c", ">=8.0"), ("c/c", ">=8.0")]),
+            Some("8.3.0"),
+            &[],
+        );
+
+        assert_eq!(check.constraint, "^8.1");
+        assert_eq!(check.required_by, vec!["a/a", "b/b", "c/c"]);
+        assert_eq!(check.status, Status::Success);
+    }
+
+    #[test]
+    fn test_requirements_without_single_strongest_are_intersected() {
+        let check = check_requirement("php", &constraints(&[("a/a", ">=8.1"), ("b/b", "<8.3")]), Some("8.3.0"), &[]);
+
+        assert_eq!(check.constraint, ">=8.1.0 <8.3.0");
+        assert_eq!(check.status, Status::Failed);
+    }
+
+    #[test]
+    fn test_missing_requirement() {
+        let check = check_requirement("ext-redis", &constraints(&[("a/a", "*")]), None, &[]);
+        assert_eq!(check.status, Status::Missing);
+        assert_eq!(check.version, None);
+    }
+
+    #[test]
+    fn test_requirement_provided_by_locked_package() {
+        let polyfill = Provider {
+            name: "symfony/polyfill-mbstring".to_string(),
+            version: "v1.29.0".to_string(),
+            constraint: "*".to_string(),
+        };
+        let check = check_requirement("ext-mbstring", &constraints(&[("a/a", "*")]), None, &[polyfill]);
+        assert_eq!(check.status, Status::Success);
+        assert_eq!(check.version.as_deref(), Some("v1.29.0"));
+        assert_eq!(check.provided_by.as_deref(), Some("symfony/polyfill-mbstring"));
+
+        // A provider only counts when it provides a matching version
+        let old = Provider {
+            name: "acme/intl".to_string(),
+            version: "1.0.0".to_string(),
+            constraint: "1.0.0".to_string(),
+        };
+        let check = check_requirement("ext-intl", &constraints(&[("a/a", "^2.0")]), None, &[old]);
+        assert_eq!(check.status, Status::Missing);
+        assert_eq!(check.provided_by, None);
+    }
+
+    #[test]
+    fn test_collect_requirements() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [
+                {"name": "a/a", "version": "1.0.0", "require": {"php": ">=8.1", "ext-json": "*", "b/b": "^1.0"}},
+            ],
+            "packages-dev": [
+                {"name": "c/c", "version": "1.0.0", "require": {"php": "^8.2", "ext-xdebug": "*"}},
+            ],
+            "platform": {"php": ">=8.0", "composer-runtime-api": "^2.2"},
+            "platform-dev": {"ext-pcov": "*"},
+        }))
+        .unwrap();
+
+        let requirements = collect_requirements(&lock, "acme/app", false);
+        assert_eq!(
+            requirements.keys().collect::<Vec<_>>(),
+            vec!["ext-json", "ext-pcov", "ext-xdebug", "php"]
+        );
+        assert_eq!(
+            requirements["php"],
+            constraints(&[("acme/app", ">=8.0"), ("a/a", ">=8.1"), ("c/c", "^8.2")])
+        );
+
+        let requirements = collect_requirements(&lock, "acme/app", true);
+        assert_eq!(requirements.keys().collect::<Vec<_>>(), vec!["ext-json", "php"]);
+    }
+}
@@ -4,9 +4,12 @@ use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use pox_pm::cache::Cache;
-use pox_pm::config::ConfigLoader;
+use pox_pm::cache::{Cache, CacheEntry};
+use pox_pm::config::{Config, ConfigLoader};
+use pox_pm::downloader::DownloadManager;
+use pox_pm::repository::ComposerRepository;
 
 #[derive(Args, Debug)]
 pub struct ClearCacheArgs {
@@ -22,18 +25,47 @@ pub struct ClearCacheArgs {
     #[arg(long)]
     pub vcs: bool,
 
-    /// Run garbage collection instead of full clear (removes stale entries)
+    /// Only clear the cached archives and metadata of this package
+    #[arg(long, value_name = "PACKAGE", conflicts_with = "gc")]
+    pub package: Vec<String>,
+
+    /// Run garbage collection with the configured cache-ttl, cache-files-ttl and
+    /// cache-files-maxsize instead of clearing everything
     #[arg(long)]
     pub gc: bool,
 
-    /// TTL in seconds for garbage collection (default: 6 months)
-    #[arg(long, default_value = "15552000")]
-    pub gc_ttl: u64,
+    /// TTL in seconds for garbage collection, instead of the configured ones
+    #[arg(long)]
+    pub gc_ttl: Option<u64>,
+
+    /// Report what would be freed without removing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// One of the cache directories and how garbage collection treats it
+struct CacheArea {
+    name: &'static str,
+    path: PathBuf,
+    ttl: Duration,
+    /// Size limit, only the files cache has one
+    max_size: Option<u64>,
 }
 
 pub async fn execute(args: ClearCacheArgs) -> Result<i32> {
-    let loader = ConfigLoader::new(true);
-    let cache_dir = loader.get_cache_dir();
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+    let config = Config::build(Some(&working_dir), true)?;
+    let cache_dir = config.cache_dir.clone()
+        .unwrap_or_else(|| ConfigLoader::new(true).get_cache_dir());
+
+    if pox_pm::cache::is_disabled() {
+        println!("{} Cache is not enabled (--no-cache): {}",
+            style("Info:").cyan(),
+            cache_dir.display()
+        );
+        return Ok(0);
+    }
 
     if !cache_dir.exists() {
         println!("{} Cache directory does not exist: {}",
@@ -43,64 +75,88 @@ pub async fn execute(args: ClearCacheArgs) -> Result<i32> {
         return Ok(0);
     }
 
+    let ttl = Duration::from_secs(args.gc_ttl.unwrap_or(config.cache_ttl));
+    let files_ttl = Duration::from_secs(args.gc_ttl.or(config.cache_files_ttl).unwrap_or(config.cache_ttl));
+    let areas = [
+        CacheArea {
+            name: "files",
+            path: config.cache_files_dir.clone().unwrap_or_else(|| cache_dir.join("files")),
+            ttl: files_ttl,
+            max_size: Some(config.cache_files_maxsize),
+        },
+        CacheArea {
+            name: "repo",
+            path: config.cache_repo_dir.clone().unwrap_or_else(|| cache_dir.join("repo")),
+            ttl,
+            max_size: None,
+        },
+        CacheArea {
+            name: "vcs",
+            path: config.cache_vcs_dir.clone().unwrap_or_else(|| cache_dir.join("vcs")),
+            ttl,
+            max_size: None,
+        },
+    ];
+
     // Determine which caches to clear
     let clear_all = !args.files && !args.repo && !args.vcs;
-    let clear_files = clear_all || args.files;
-    let clear_repo = clear_all || args.repo;
-    let clear_vcs = clear_all || args.vcs;
+    let selected = areas.iter().filter(|area| match area.name {
+        "files" => clear_all || args.files,
+        "repo" => clear_all || args.repo,
+        _ => clear_all || args.vcs,
+    });
 
     let mut total_freed: u64 = 0;
 
     if args.gc {
         // Garbage collection mode
-        let ttl = std::time::Duration::from_secs(args.gc_ttl);
-
-        println!("{} Running garbage collection (TTL: {} days)...",
+        println!("{} Running garbage collection (TTL: {} days, files TTL: {} days, files max size: {}){}...",
             style("Info:").cyan(),
-            args.gc_ttl / 86400
+            ttl.as_secs() / 86400,
+            files_ttl.as_secs() / 86400,
+            format_bytes(config.cache_files_maxsize),
+            if args.dry_run { ", dry run" } else { "" }
         );
 
-        if clear_files {
-            let freed = gc_cache_dir(&cache_dir.join("files"), ttl, "files")?;
-            total_freed += freed;
+        for area in selected {
+            total_freed += gc_cache_area(area, args.dry_run)?;
         }
+    } else if !args.package.is_empty() {
+        // Package mode
+        let packages: Vec<String> = args.package.iter().map(|name| name.to_lowercase()).collect();
+        println!("{} Clearing cache of {}{}...",
+            style("Info:").cyan(),
+            packages.join(", "),
+            if args.dry_run { ", dry run" } else { "" }
+        );
 
-        if clear_repo {
-            let freed = gc_cache_dir(&cache_dir.join("repo"), ttl, "repo")?;
-            total_freed += freed;
+        for area in selected {
+            total_freed += clear_package_cache(area, &packages, args.dry_run)?;
         }
+    } else {
+        // Full clear mode
+        println!("{} Clearing cache at {}{}...",
+            style("Info:").cyan(),
+            cache_dir.display(),
+            if args.dry_run { ", dry run" } else { "" }
+        );
 
-        if clear_vcs {
-            let freed = gc_vcs_cache(&cache_dir.join("vcs"), ttl)?;
-            total_freed += freed;
+        for area in selected {
+            total_freed += clear_cache_area(area, args.dry_run)?;
         }
+    }
 
+    if args.dry_run {
+        println!("\n{} Would free {}",
+            style("Info:").cyan(),
+            format_bytes(total_freed)
+        );
+    } else if args.gc {
         println!("\n{} Freed {}",
             style("Success:").green().bold(),
             format_bytes(total_freed)
         );
     } else {
-        // Full clear mode
-        println!("{} Clearing cache at {}...",
-            style("Info:").cyan(),
-            cache_dir.display()
-        );
-
-        if clear_files {
-            let freed = clear_cache_dir(&cache_dir.join("files"), "files")?;
-            total_freed += freed;
-        }
-
-        if clear_repo {
-            let freed = clear_cache_dir(&cache_dir.join("repo"), "repo")?;
-            total_freed += freed;
-        }
-
-        if clear_vcs {
-            let freed = clear_cache_dir(&cache_dir.join("vcs"), "vcs")?;
-            total_freed += freed;
-        }
-
         println!("\n{} Cache cleared. Freed {}",
             style("Success:").green().bold(),
             format_bytes(total_freed)
@@ -111,57 +167,125 @@ pub async fn execute(args: ClearCacheArgs) -> Result<i32> {
 }
 
 /// Clear a cache directory completely
-fn clear_cache_dir(path: &PathBuf, name: &str) -> Result<u64> {
-    if !path.exists() {
-        println!("  {} cache: not present", name);
+fn clear_cache_area(area: &CacheArea, dry_run: bool) -> Result<u64> {
+    if !area.path.exists() {
+        println!("  {} cache: not present", area.name);
         return Ok(0);
     }
 
-    let cache = Cache::new(path.clone());
+    let cache = Cache::new(area.path.clone());
     let size = cache.size().context("Failed to calculate cache size")?;
 
-    cache.clear().context(format!("Failed to clear {} cache", name))?;
+    if dry_run {
+        println!("  {} cache: would clear {}", area.name, format_bytes(size));
+        return Ok(size);
+    }
 
-    println!("  {} cache: cleared ({})", name, format_bytes(size));
+    cache.clear().context(format!("Failed to clear {} cache", area.name))?;
+
+    println!("  {} cache: cleared ({})", area.name, format_bytes(size));
     Ok(size)
 }
 
-/// Run garbage collection on a cache directory
-fn gc_cache_dir(path: &PathBuf, ttl: std::time::Duration, name: &str) -> Result<u64> {
-    if !path.exists() {
-        println!("  {} cache: not present", name);
+/// Clear the entries of some packages from a cache directory
+///
+/// Archives live under the package name in the files cache, and metadata under the
+/// package's keys in each repository's directory of the repo cache.
+fn clear_package_cache(area: &CacheArea, packages: &[String], dry_run: bool) -> Result<u64> {
+    if !area.path.exists() {
+        println!("  {} cache: not present", area.name);
         return Ok(0);
     }
 
-    let cache = Cache::new(path.clone());
-    let freed = cache.gc(ttl).context(format!("Failed to GC {} cache", name))?;
+    let prefixes: Vec<(PathBuf, String)> = match area.name {
+        "files" => packages.iter()
+            .map(|name| (area.path.clone(), DownloadManager::archive_cache_prefix(name)))
+            .collect(),
+        "repo" => {
+            let repositories: Vec<PathBuf> = std::fs::read_dir(&area.path)
+                .context("Failed to read repo cache")?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect();
+            repositories.iter()
+                .flat_map(|repository| packages.iter().flat_map(move |name| {
+                    ComposerRepository::package_cache_keys(name).map(|key| (repository.clone(), key))
+                }))
+                .collect()
+        }
+        _ => {
+            println!("  {} cache: skipped, clones are cached by repository URL", area.name);
+            return Ok(0);
+        }
+    };
+
+    let mut entries = 0;
+    let mut bytes = 0;
+    for (root, prefix) in &prefixes {
+        let cache = Cache::new(root.clone());
+        let found = cache.entries_with_prefix(prefix)
+            .context(format!("Failed to read {} cache", area.name))?;
+        entries += found.len();
+        bytes += if dry_run {
+            found.iter().map(|entry| entry.size).sum()
+        } else {
+            cache.remove_prefix(prefix).context(format!("Failed to clear {} cache", area.name))?
+        };
+    }
 
-    if freed > 0 {
-        println!("  {} cache: freed {}", name, format_bytes(freed));
+    if entries == 0 {
+        println!("  {} cache: nothing cached", area.name);
     } else {
-        println!("  {} cache: nothing to clean", name);
+        println!("  {} cache: {} {} ({} {})",
+            area.name,
+            if dry_run { "would free" } else { "freed" },
+            format_bytes(bytes),
+            entries,
+            if entries == 1 { "entry" } else { "entries" }
+        );
     }
 
-    Ok(freed)
+    Ok(bytes)
 }
 
-/// Run garbage collection on VCS cache (directory-based)
-fn gc_vcs_cache(path: &PathBuf, ttl: std::time::Duration) -> Result<u64> {
-    if !path.exists() {
-        println!("  vcs cache: not present");
+/// Run garbage collection on a cache directory
+///
+/// VCS clones are collected as whole directories, the other areas file by file.
+fn gc_cache_area(area: &CacheArea, dry_run: bool) -> Result<u64> {
+    if !area.path.exists() {
+        println!("  {} cache: not present", area.name);
         return Ok(0);
     }
 
-    let cache = Cache::new(path.clone());
-    let freed = cache.gc_vcs(ttl).context("Failed to GC vcs cache")?;
-
-    if freed > 0 {
-        println!("  vcs cache: freed {}", format_bytes(freed));
+    let cache = Cache::new(area.path.clone());
+    let entries: Vec<CacheEntry> = if area.name == "vcs" {
+        cache.gc_vcs_candidates(area.ttl)
     } else {
-        println!("  vcs cache: nothing to clean");
+        cache.gc_candidates(area.ttl, area.max_size)
+    }.context(format!("Failed to GC {} cache", area.name))?;
+
+    if entries.is_empty() {
+        println!("  {} cache: nothing to clean", area.name);
+        return Ok(0);
     }
 
-    Ok(freed)
+    let (verb, bytes) = if dry_run {
+        ("would free", entries.iter().map(|entry| entry.size).sum())
+    } else {
+        let freed = cache.remove_entries(&entries)
+            .context(format!("Failed to GC {} cache", area.name))?;
+        ("freed", freed)
+    };
+
+    println!("  {} cache: {} {} ({} {})",
+        area.name,
+        verb,
+        format_bytes(bytes),
+        entries.len(),
+        if entries.len() == 1 { "entry" } else { "entries" }
+    );
+
+    Ok(bytes)
 }
 
 /// Format bytes into human-readable string
@@ -0,0 +1,123 @@
+//! Completion command - generate shell completion scripts.
+
+use anyhow::Result;
+use clap::{Args, CommandFactory};
+use clap_complete::{generate, Shell};
+use std::io::Write;
+use std::path::Path;
+
+use pox_pm::json::ComposerLock;
+
+/// Subcommands whose positional arguments are installed package names
+const PACKAGE_COMMANDS: &[&str] = &[
+    "why", "depends", "why-not", "prohibits", "show", "info", "remove", "rm", "reinstall", "browse", "home",
+];
+
+#[derive(Args, Debug)]
+pub struct CompletionArgs {
+    /// The shell to generate completions for
+    #[arg(value_enum, required_unless_present = "packages")]
+    pub shell: Option<Shell>,
+
+    /// Print the names of the locked packages, used by the bash and fish scripts
+    #[arg(long, hide = true)]
+    pub packages: bool,
+}
+
+pub async fn execute(args: CompletionArgs) -> Result<i32> {
+    if args.packages {
+        for name in locked_package_names(&std::env::current_dir()?) {
+            println!("{}", name);
+        }
+        return Ok(0);
+    }
+
+    let Some(shell) = args.shell else {
+        return Ok(1);
+    };
+
+    let mut stdout = std::io::stdout();
+    let mut cmd = crate::Args::command();
+    generate(shell, &mut cmd, "pox", &mut stdout);
+    stdout.write_all(package_completion(shell).as_bytes())?;
+
+    Ok(0)
+}
+
+/// Names of the packages in the project's composer.lock, empty without one
+fn locked_package_names(working_dir: &Path) -> Vec<String> {
+    let Ok(lock) = ComposerLock::from_file(working_dir.join("composer.lock")) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = lock.packages
+        .iter()
+        .chain(lock.packages_dev.iter())
+        .map(|p| p.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Shell code completing installed package names for the package commands
+///
+/// The names are looked up when completing, so outside a project only the static
+/// completions remain. Shells other than bash and fish only get the static completions.
+fn package_completion(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => format!(
+            r#"
+_pox_packages() {{
+    _pox "$@"
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    [[ "$cur" == -* ]] && return
+    local word
+    for word in "${{COMP_WORDS[@]:1:COMP_CWORD-1}}"; do
+        case "$word" in
+            {})
+                COMPREPLY+=($(compgen -W "$(pox pm completion --packages 2>/dev/null)" -- "$cur"))
+                return
+                ;;
+        esac
+    done
+}}
+
+complete -F _pox_packages -o bashdefault -o default pox
+"#,
+            PACKAGE_COMMANDS.join("|")
+        ),
+        Shell::Fish => format!(
+            "complete -c pox -n \"__fish_seen_subcommand_from {}\" -f -a \"(pox pm completion --packages 2>/dev/null)\"\n",
+            PACKAGE_COMMANDS.join(" ")
+        ),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_locked_package_names() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(locked_package_names(temp_dir.path()).is_empty());
+
+        std::fs::write(
+            temp_dir.path().join("composer.lock"),
+            r#"{
+                "packages": [{"name": "psr/log", "version": "3.0.0"}],
+                "packages-dev": [{"name": "phpunit/phpunit", "version": "11.0.0"}]
+            }"#,
+        ).unwrap();
+        assert_eq!(locked_package_names(temp_dir.path()), vec!["phpunit/phpunit", "psr/log"]);
+    }
+
+    #[test]
+    fn test_package_completion() {
+        assert!(package_completion(Shell::Bash).contains("why|depends|why-not"));
+        assert!(package_completion(Shell::Fish).starts_with("complete -c pox -n \"__fish_seen_subcommand_from why "));
+        assert!(package_completion(Shell::Zsh).is_empty());
+    }
+}
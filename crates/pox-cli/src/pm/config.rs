@@ -0,0 +1,663 @@
+//! Config command - read and write composer.json, config.json and auth.json settings.
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use console::style;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use pox_pm::config::Config;
+use pox_pm::json::JsonEditor;
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    /// Setting key (e.g. minimum-stability, vendor-dir, config.platform.php, github-oauth.github.com)
+    #[arg(value_name = "KEY")]
+    pub key: Option<String>,
+
+    /// Setting value(s)
+    #[arg(value_name = "VALUE", allow_hyphen_values = true)]
+    pub values: Vec<String>,
+
+    /// Apply command to the global config file (COMPOSER_HOME/config.json)
+    #[arg(short = 'g', long)]
+    pub global: bool,
+
+    /// Unset the given setting key
+    #[arg(long)]
+    pub unset: bool,
+
+    /// List the effective configuration
+    #[arg(short = 'l', long)]
+    pub list: bool,
+
+    /// Display where each listed setting comes from
+    #[arg(long)]
+    pub source: bool,
+
+    /// Interpret the value as JSON
+    #[arg(short = 'j', long)]
+    pub json: bool,
+}
+
+/// Credential types that live in auth.json rather than composer.json
+const AUTH_KEYS: &[&str] = &[
+    "http-basic",
+    "bearer",
+    "github-oauth",
+    "gitlab-oauth",
+    "gitlab-token",
+    "bitbucket-oauth",
+    "forgejo-token",
+];
+
+/// Top-level composer.json properties that can be edited with this command
+const ROOT_KEYS: &[&str] = &[
+    "name",
+    "type",
+    "description",
+    "homepage",
+    "version",
+    "license",
+    "minimum-stability",
+    "prefer-stable",
+];
+
+/// Top-level composer.json properties holding a map of arbitrary sub keys
+const ROOT_MAP_KEYS: &[&str] = &["extra", "suggest", "scripts-descriptions"];
+
+const BOOLEAN_SETTINGS: &[&str] = &[
+    "use-include-path",
+    "notify-on-install",
+    "optimize-autoloader",
+    "sort-packages",
+    "prefer-newer-by-time",
+    "classmap-authoritative",
+    "apcu-autoloader",
+    "prepend-autoloader",
+    "lock",
+    "secure-http",
+    "disable-tls",
+    "cache-read-only",
+    "htaccess-protect",
+    "use-github-api",
+    "github-expose-hostname",
+    "bitbucket-expose-hostname",
+];
+
+const INTEGER_SETTINGS: &[&str] = &["process-timeout", "http-connect-timeout", "http-read-timeout", "cache-ttl", "cache-files-ttl"];
+
+const MULTI_VALUE_SETTINGS: &[&str] = &[
+    "github-protocols",
+    "github-domains",
+    "gitlab-domains",
+    "bitbucket-domains",
+    "forgejo-domains",
+    "secure-svn-domains",
+];
+
+/// Settings accepting either a boolean or one of a fixed set of strings
+const BOOL_OR_ENUM_SETTINGS: &[(&str, &[&str])] = &[
+    ("discard-changes", &["stash"]),
+    ("platform-check", &["php-only"]),
+    ("store-auths", &["prompt"]),
+    ("use-parent-dir", &["prompt"]),
+    ("allow-plugins", &[]),
+];
+
+/// Where a setting key is stored
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// A key inside the `config` section (path excludes the `config` prefix)
+    Setting(Vec<String>),
+    /// A top-level property of composer.json
+    Root(Vec<String>),
+    /// A named repository
+    Repository(String),
+    /// A credential in auth.json
+    Auth(String, String),
+}
+
+impl Target {
+    fn parse(key: &str) -> Result<Self> {
+        let explicit_config = key.starts_with("config.");
+        let key = key.strip_prefix("config.").unwrap_or(key);
+        let (head, rest) = match key.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (key, None),
+        };
+
+        if AUTH_KEYS.contains(&head) {
+            return match rest {
+                Some(host) if !host.is_empty() => {
+                    Ok(Target::Auth(head.to_string(), host.to_string()))
+                }
+                _ => bail!(
+                    "The \"{}\" setting requires a host, e.g. {}.example.org",
+                    head,
+                    head
+                ),
+            };
+        }
+
+        if !explicit_config {
+            if head == "repositories" || head == "repo" {
+                return match rest {
+                    Some(name) if !name.is_empty() => Ok(Target::Repository(name.to_string())),
+                    _ => bail!("Repository keys must be given as repositories.<name>"),
+                };
+            }
+            if ROOT_KEYS.contains(&head) && rest.is_none() {
+                return Ok(Target::Root(vec![head.to_string()]));
+            }
+            if ROOT_MAP_KEYS.contains(&head) {
+                let mut path = vec![head.to_string()];
+                if head == "extra" {
+                    path.extend(
+                        rest.into_iter()
+                            .flat_map(|r| r.split('.'))
+                            .map(String::from),
+                    );
+                } else {
+                    path.extend(rest.map(String::from));
+                }
+                return Ok(Target::Root(path));
+            }
+        }
+
+        let mut path = vec![head.to_string()];
+        path.extend(rest.map(String::from));
+        Ok(Target::Setting(path))
+    }
+}
+
+pub async fn execute(args: ConfigArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+    let composer_home = Config::global_home();
+
+    let Some(key) = args.key.as_deref() else {
+        return list_config(&working_dir, args.source);
+    };
+    if args.list {
+        return list_config(&working_dir, args.source);
+    }
+
+    let target = Target::parse(key)?;
+
+    if args.global && matches!(target, Target::Root(_)) {
+        bail!(
+            "The \"{}\" property can only be set in a project composer.json",
+            key
+        );
+    }
+
+    let file = config_file(&target, args.global, &working_dir, &composer_home);
+
+    if args.unset {
+        let mut editor = JsonEditor::load(&file)?;
+        let removed = match &target {
+            Target::Repository(name) => remove_repository(&mut editor, name),
+            _ => editor.remove(&storage_path(&target)).is_some(),
+        };
+        if removed {
+            editor.save(&file)?;
+        } else {
+            println!(
+                "{} {} is not set in {}",
+                style("Info:").cyan(),
+                key,
+                file.display()
+            );
+        }
+        return Ok(0);
+    }
+
+    if args.values.is_empty() {
+        return read_setting(&target, key, &file, &working_dir, args.global);
+    }
+
+    let value = if args.json {
+        let raw = args.values.join(" ");
+        serde_json::from_str(&raw).with_context(|| format!("\"{}\" is not valid JSON", raw))?
+    } else {
+        parse_value(&target, &args.values)?
+    };
+
+    let mut editor = JsonEditor::load(&file)?;
+    match &target {
+        Target::Repository(name) => add_repository(&mut editor, name, value)?,
+        _ => editor.set(&storage_path(&target), value)?,
+    }
+    editor.save(&file)?;
+
+    Ok(0)
+}
+
+/// The file a target is read from and written to
+fn config_file(target: &Target, global: bool, working_dir: &Path, composer_home: &Path) -> PathBuf {
+    match (target, global) {
+        (Target::Auth(..), true) => composer_home.join("auth.json"),
+        (Target::Auth(..), false) => working_dir.join("auth.json"),
+        (_, true) => composer_home.join("config.json"),
+        (_, false) => working_dir.join("composer.json"),
+    }
+}
+
+/// The key path of a target inside its file
+fn storage_path(target: &Target) -> Vec<&str> {
+    match target {
+        Target::Setting(path) => std::iter::once("config")
+            .chain(path.iter().map(String::as_str))
+            .collect(),
+        Target::Root(path) => path.iter().map(String::as_str).collect(),
+        Target::Repository(name) => vec!["repositories", name.as_str()],
+        Target::Auth(kind, host) => vec![kind.as_str(), host.as_str()],
+    }
+}
+
+/// Print a single setting
+fn read_setting(
+    target: &Target,
+    key: &str,
+    file: &Path,
+    working_dir: &Path,
+    global: bool,
+) -> Result<i32> {
+    let value = match target {
+        Target::Setting(path) if !global => {
+            let config = Config::build(Some(working_dir), true)?;
+            let effective = serde_json::to_value(&config)?;
+            lookup(&effective, path.iter().map(String::as_str))
+                .cloned()
+                .or_else(|| {
+                    JsonEditor::load(file)
+                        .ok()
+                        .and_then(|e| e.get(&storage_path(target)).cloned())
+                })
+        }
+        Target::Repository(name) => {
+            let editor = JsonEditor::load(file)?;
+            find_repository(editor.root(), name).cloned()
+        }
+        _ => JsonEditor::load(file)?.get(&storage_path(target)).cloned(),
+    };
+
+    match value {
+        Some(value) => {
+            println!("{}", display_value(&value));
+            Ok(0)
+        }
+        None => {
+            eprintln!(
+                "{} Setting \"{}\" does not exist or is not supported by this command",
+                style("Error:").red().bold(),
+                key
+            );
+            Ok(1)
+        }
+    }
+}
+
+/// Print the effective configuration, one `[key] value` line per setting
+fn list_config(working_dir: &Path, show_source: bool) -> Result<i32> {
+    let config = Config::build(Some(working_dir), true)?;
+    let effective = serde_json::to_value(&config)?;
+
+    let mut lines = Vec::new();
+    if let Some(settings) = effective.as_object() {
+        for (key, value) in settings {
+            flatten(key, value, &mut lines);
+        }
+    }
+
+    let editor = JsonEditor::load(&working_dir.join("composer.json"))?;
+    if let Some(repositories) = editor.get(&["repositories"]) {
+        flatten("repositories", repositories, &mut lines);
+    }
+
+    for (key, value) in lines {
+        if show_source {
+            let setting = key.split('.').next().unwrap_or(&key);
+            let source = config
+                .get_source(setting)
+                .map(|s| s.as_str())
+                .unwrap_or("default");
+            println!("[{}] {} ({})", key, value, source);
+        } else {
+            println!("[{}] {}", key, value);
+        }
+    }
+
+    Ok(0)
+}
+
+fn flatten(prefix: &str, value: &Value, lines: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                flatten(&format!("{}.{}", prefix, key), value, lines);
+            }
+        }
+        _ => lines.push((prefix.to_string(), display_value(value))),
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: impl IntoIterator<Item = &'a str>) -> Option<&'a Value> {
+    path.into_iter()
+        .try_fold(value, |current, key| current.get(key))
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "null".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Convert command line values into the JSON value stored for a target
+fn parse_value(target: &Target, values: &[String]) -> Result<Value> {
+    match target {
+        Target::Auth(kind, host) => parse_auth_value(kind, host, values),
+        Target::Repository(_) => parse_repository_value(values),
+        Target::Root(path) => {
+            let first = single_value(&path.join("."), values)?;
+            match path[0].as_str() {
+                "prefer-stable" => Ok(Value::Bool(parse_bool(first)?)),
+                "minimum-stability" => {
+                    let valid = ["dev", "alpha", "beta", "rc", "stable"];
+                    if !valid.contains(&first.to_lowercase().as_str()) {
+                        bail!("\"{}\" is an invalid value, expected one of: dev, alpha, beta, RC, stable", first);
+                    }
+                    Ok(Value::String(first.to_string()))
+                }
+                "license" if values.len() > 1 => Ok(json!(values)),
+                _ => Ok(Value::String(first.to_string())),
+            }
+        }
+        Target::Setting(path) => {
+            let setting = path[0].as_str();
+            let key = path.join(".");
+
+            if MULTI_VALUE_SETTINGS.contains(&setting) {
+                return Ok(json!(values));
+            }
+
+            let first = single_value(&key, values)?;
+
+            if path.len() > 1 {
+                return match setting {
+                    "platform" if first == "false" => Ok(Value::Bool(false)),
+                    "allow-plugins" => Ok(Value::Bool(parse_bool(first)?)),
+                    "preferred-install" => parse_enum(first, &["auto", "source", "dist"]),
+                    _ => Ok(Value::String(first.to_string())),
+                };
+            }
+
+            if BOOLEAN_SETTINGS.contains(&setting) {
+                return Ok(Value::Bool(parse_bool(first)?));
+            }
+            if INTEGER_SETTINGS.contains(&setting) {
+                let n: u64 = first.parse().map_err(|_| {
+                    anyhow::anyhow!("\"{}\" is an invalid value, expected an integer", first)
+                })?;
+                return Ok(json!(n));
+            }
+            if let Some((_, choices)) = BOOL_OR_ENUM_SETTINGS
+                .iter()
+                .find(|(name, _)| *name == setting)
+            {
+                if let Ok(b) = parse_bool(first) {
+                    return Ok(Value::Bool(b));
+                }
+                return parse_enum(first, choices);
+            }
+            if setting == "preferred-install" {
+                return parse_enum(first, &["auto", "source", "dist"]);
+            }
+
+            Ok(Value::String(first.to_string()))
+        }
+    }
+}
+
+fn single_value<'a>(key: &str, values: &'a [String]) -> Result<&'a str> {
+    match values {
+        [value] => Ok(value),
+        _ => bail!("You can only pass one value for \"{}\"", key),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => bail!("\"{}\" is an invalid value, expected true or false", value),
+    }
+}
+
+fn parse_enum(value: &str, choices: &[&str]) -> Result<Value> {
+    if choices.contains(&value) {
+        Ok(Value::String(value.to_string()))
+    } else if choices.is_empty() {
+        bail!("\"{}\" is an invalid value, expected true or false", value)
+    } else {
+        bail!(
+            "\"{}\" is an invalid value, expected one of: {}",
+            value,
+            choices.join(", ")
+        )
+    }
+}
+
+fn parse_auth_value(kind: &str, host: &str, values: &[String]) -> Result<Value> {
+    match (kind, values) {
+        ("http-basic", [username, password]) => {
+            Ok(json!({ "username": username, "password": password }))
+        }
+        ("http-basic", _) => bail!(
+            "Expected two arguments (username, password) for http-basic.{}",
+            host
+        ),
+        ("bitbucket-oauth", [key, secret]) => {
+            Ok(json!({ "consumer-key": key, "consumer-secret": secret }))
+        }
+        ("bitbucket-oauth", _) => bail!(
+            "Expected two arguments (consumer-key, consumer-secret) for bitbucket-oauth.{}",
+            host
+        ),
+        (_, [token]) => Ok(Value::String(token.clone())),
+        _ => bail!("Expected one argument (token) for {}.{}", kind, host),
+    }
+}
+
+fn parse_repository_value(values: &[String]) -> Result<Value> {
+    match values {
+        [flag] if flag == "false" => Ok(Value::Bool(false)),
+        [json] if json.trim_start().starts_with('{') => {
+            serde_json::from_str(json).with_context(|| format!("\"{}\" is not valid JSON", json))
+        }
+        [repo_type, url] => Ok(json!({ "type": repo_type, "url": url })),
+        _ => bail!("Repositories expect a type and url (e.g. vcs https://example.org/repo.git), a JSON object, or false"),
+    }
+}
+
+fn find_repository<'a>(root: &'a Value, name: &str) -> Option<&'a Value> {
+    match root.get("repositories")? {
+        Value::Object(map) => map.get(name),
+        Value::Array(list) => list.iter().find(|repo| {
+            repo.get("name").and_then(Value::as_str) == Some(name) || repo.get(name).is_some()
+        }),
+        _ => None,
+    }
+}
+
+/// Add or replace a named repository, supporting both the object and the list form
+fn add_repository(editor: &mut JsonEditor, name: &str, value: Value) -> Result<()> {
+    if let Some(Value::Array(list)) = editor.get(&["repositories"]) {
+        let mut list = list.clone();
+        list.retain(|repo| {
+            repo.get("name").and_then(Value::as_str) != Some(name) && repo.get(name).is_none()
+        });
+        let entry = match value {
+            Value::Object(mut map) => {
+                map.insert("name".to_string(), Value::String(name.to_string()));
+                Value::Object(map)
+            }
+            other => json!({ name: other }),
+        };
+        list.insert(0, entry);
+        return Ok(editor.set(&["repositories"], Value::Array(list))?);
+    }
+
+    Ok(editor.set(&["repositories", name], value)?)
+}
+
+fn remove_repository(editor: &mut JsonEditor, name: &str) -> bool {
+    match editor.get(&["repositories"]) {
+        Some(Value::Array(list)) => {
+            let mut list = list.clone();
+            let before = list.len();
+            list.retain(|repo| {
+                repo.get("name").and_then(Value::as_str) != Some(name) && repo.get(name).is_none()
+            });
+            let removed = list.len() != before;
+            if removed {
+                if list.is_empty() {
+                    editor.remove(&["repositories"]);
+                } else {
+                    let _ = editor.set(&["repositories"], Value::Array(list));
+                }
+            }
+            removed
+        }
+        _ => editor.remove(&["repositories", name]).is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(
+            Target::parse("vendor-dir").unwrap(),
+            Target::Setting(strings(&["vendor-dir"]))
+        );
+        assert_eq!(
+            Target::parse("config.platform.php").unwrap(),
+            Target::Setting(strings(&["platform", "php"]))
+        );
+        assert_eq!(
+            Target::parse("minimum-stability").unwrap(),
+            Target::Root(strings(&["minimum-stability"]))
+        );
+        assert_eq!(
+            Target::parse("extra.symfony.require").unwrap(),
+            Target::Root(strings(&["extra", "symfony", "require"]))
+        );
+        assert_eq!(
+            Target::parse("repositories.packagist.org").unwrap(),
+            Target::Repository("packagist.org".to_string())
+        );
+        assert_eq!(
+            Target::parse("github-oauth.github.com").unwrap(),
+            Target::Auth("github-oauth".to_string(), "github.com".to_string())
+        );
+        assert!(Target::parse("http-basic").is_err());
+    }
+
+    #[test]
+    fn test_parse_boolean_and_string_values() {
+        let target = Target::parse("sort-packages").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["true"])).unwrap(),
+            json!(true)
+        );
+        assert!(parse_value(&target, &strings(&["yes"])).is_err());
+
+        let target = Target::parse("vendor-dir").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["lib"])).unwrap(),
+            json!("lib")
+        );
+
+        let target = Target::parse("process-timeout").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["600"])).unwrap(),
+            json!(600)
+        );
+
+        let target = Target::parse("discard-changes").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["stash"])).unwrap(),
+            json!("stash")
+        );
+        assert_eq!(
+            parse_value(&target, &strings(&["false"])).unwrap(),
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_values() {
+        let target = Target::parse("config.platform.ext-intl").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["false"])).unwrap(),
+            json!(false)
+        );
+
+        let target = Target::parse("platform.php").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["8.2.0"])).unwrap(),
+            json!("8.2.0")
+        );
+
+        let target = Target::parse("github-protocols").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["https", "ssh"])).unwrap(),
+            json!(["https", "ssh"])
+        );
+    }
+
+    #[test]
+    fn test_parse_auth_values() {
+        let target = Target::parse("http-basic.repo.example.org").unwrap();
+        assert_eq!(
+            parse_value(&target, &strings(&["user", "pass"])).unwrap(),
+            json!({"username": "user", "password": "pass"})
+        );
+        assert!(parse_value(&target, &strings(&["user"])).is_err());
+    }
+
+    #[test]
+    fn test_repository_object_and_list_forms() {
+        let mut editor = JsonEditor::parse(r#"{"name": "a/b"}"#).unwrap();
+        add_repository(
+            &mut editor,
+            "foo",
+            json!({"type": "vcs", "url": "https://x"}),
+        )
+        .unwrap();
+        assert_eq!(
+            editor.get(&["repositories", "foo", "type"]),
+            Some(&json!("vcs"))
+        );
+        assert!(remove_repository(&mut editor, "foo"));
+        assert!(editor.get(&["repositories"]).is_none());
+
+        let mut editor =
+            JsonEditor::parse(r#"{"repositories": [{"type": "path", "url": "../x"}]}"#).unwrap();
+        add_repository(&mut editor, "packagist.org", json!(false)).unwrap();
+        let list = editor.get(&["repositories"]).unwrap().as_array().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0], json!({"packagist.org": false}));
+        assert!(remove_repository(&mut editor, "packagist.org"));
+    }
+}
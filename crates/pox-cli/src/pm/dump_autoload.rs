@@ -2,7 +2,6 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
-use std::path::PathBuf;
 
 use pox_pm::{
     ComposerBuilder,
@@ -17,7 +16,7 @@ pub struct DumpAutoloadArgs {
     #[arg(short = 'o', long)]
     pub optimize: bool,
 
-    /// Use authoritative classmap (only load from classmap)
+    /// Use authoritative classmap (only load from classmap, implies --optimize)
     #[arg(short = 'a', long)]
     pub classmap_authoritative: bool,
 
@@ -28,14 +27,10 @@ pub struct DumpAutoloadArgs {
     /// Skip dev dependencies
     #[arg(long)]
     pub no_dev: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: DumpAutoloadArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     // Load composer.json
@@ -61,6 +56,11 @@ pub async fn execute(args: DumpAutoloadArgs) -> Result<i32> {
     // Load config
     let config = Config::build(Some(&working_dir), true)?;
 
+    // The flags only enable, the config can turn each level on by default
+    let authoritative = args.classmap_authoritative || config.classmap_authoritative;
+    let optimize = args.optimize || config.optimize_autoloader || authoritative;
+    let apcu = args.apcu || config.apcu_autoloader;
+
     // Create Composer using builder
     let composer = ComposerBuilder::new(working_dir.clone())
         .with_config(config)
@@ -72,12 +72,7 @@ pub async fn execute(args: DumpAutoloadArgs) -> Result<i32> {
     // Run Installer
     let installer = Installer::new(composer);
     
-    installer.dump_autoload(
-        args.optimize,
-        args.classmap_authoritative,
-        args.apcu,
-        args.no_dev,
-    )?;
+    installer.dump_autoload(optimize, authoritative, apcu, args.no_dev)?;
 
     Ok(0)
 }
@@ -6,32 +6,44 @@ use console::style;
 use dialoguer::{theme::ColorfulTheme, Select};
 use std::io::IsTerminal;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, ExitStatus};
+
+use pox_pm::config::Config;
+use pox_pm::scripts;
 
 #[derive(Args, Debug)]
 pub struct ExecArgs {
-    /// Binary name to execute
-    #[arg(value_name = "BINARY")]
-    pub binary: Option<String>,
-
     /// List available binaries
     #[arg(short = 'l', long)]
     pub list: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// Binary name to execute, followed by the arguments passed to it. Everything after
+    /// the binary name (or after `--`) is forwarded as is.
+    #[arg(value_name = "BINARY", trailing_var_arg = true, allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
 
-    /// Arguments passed to the binary
-    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
-    pub args: Vec<String>,
+impl ExecArgs {
+    /// The binary to execute, if one was given
+    pub fn binary(&self) -> Option<&str> {
+        self.command.first().map(String::as_str)
+    }
+
+    /// Arguments for the binary, without a `--` separating them from the binary name
+    pub fn forwarded_args(&self) -> &[String] {
+        match self.command.get(1).map(String::as_str) {
+            Some("--") => &self.command[2..],
+            Some(_) => &self.command[1..],
+            None => &[],
+        }
+    }
 }
 
 pub async fn execute(args: ExecArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
-    let vendor_bin = working_dir.join("vendor/bin");
+    let vendor_bin = Config::build(Some(&working_dir), true)?.get_bin_dir();
 
     let binaries = get_available_binaries(&vendor_bin)?;
 
@@ -39,8 +51,8 @@ pub async fn execute(args: ExecArgs) -> Result<i32> {
         return list_binaries(&binaries, &vendor_bin);
     }
 
-    let binary_name = if let Some(name) = args.binary.as_ref() {
-        name.clone()
+    let binary_name = if let Some(name) = args.binary() {
+        name.to_string()
     } else {
         if binaries.is_empty() {
             if !vendor_bin.exists() {
@@ -75,7 +87,7 @@ pub async fn execute(args: ExecArgs) -> Result<i32> {
     let binary_path = find_binary(&vendor_bin, &binary_name)?;
 
     match binary_path {
-        Some(path) => execute_binary(&path, &args.args, &working_dir),
+        Some(path) => execute_binary(&path, args.forwarded_args(), &working_dir),
         None => {
             eprintln!("{} Binary '{}' not found in vendor/bin",
                 style("Error:").red().bold(),
@@ -218,6 +230,7 @@ fn list_binaries(binaries: &[String], vendor_bin: &PathBuf) -> Result<i32> {
 /// Execute a binary with arguments
 fn execute_binary(path: &PathBuf, args: &[String], working_dir: &PathBuf) -> Result<i32> {
     let is_php_script = is_php_file(path)?;
+    let env = scripts::script_env(working_dir);
 
     let status = if is_php_script {
         let pox_binary = std::env::current_exe()
@@ -227,6 +240,7 @@ fn execute_binary(path: &PathBuf, args: &[String], working_dir: &PathBuf) -> Res
             .arg(path)
             .args(args)
             .current_dir(working_dir)
+            .envs(env.iter().cloned())
             .status()
             .with_context(|| format!("Failed to execute {}", path.display()))?
     } else {
@@ -235,6 +249,7 @@ fn execute_binary(path: &PathBuf, args: &[String], working_dir: &PathBuf) -> Res
             Command::new(path)
                 .args(args)
                 .current_dir(working_dir)
+                .envs(env.iter().cloned())
                 .status()
                 .with_context(|| format!("Failed to execute {}", path.display()))?
         }
@@ -246,12 +261,26 @@ fn execute_binary(path: &PathBuf, args: &[String], working_dir: &PathBuf) -> Res
                 .arg(path)
                 .args(args)
                 .current_dir(working_dir)
+                .envs(env.iter().cloned())
                 .status()
                 .with_context(|| format!("Failed to execute {}", path.display()))?
         }
     };
 
-    Ok(status.code().unwrap_or(1))
+    Ok(exit_code(status))
+}
+
+/// Exit code of a finished binary, using the shell's 128 + signal for killed processes
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    status.code().unwrap_or(1)
 }
 
 fn is_php_file(path: &PathBuf) -> Result<bool> {
@@ -285,3 +314,63 @@ fn is_php_file(path: &PathBuf) -> Result<bool> {
 
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        exec: ExecArgs,
+    }
+
+    fn parse(argv: &[&str]) -> ExecArgs {
+        Cli::try_parse_from(std::iter::once("exec").chain(argv.iter().copied())).unwrap().exec
+    }
+
+    #[test]
+    fn test_args_are_forwarded() {
+        for (argv, binary, forwarded) in [
+            (vec!["phpstan", "analyse", "--level=9"], "phpstan", vec!["analyse", "--level=9"]),
+            (vec!["phpunit", "--filter", "Foo"], "phpunit", vec!["--filter", "Foo"]),
+            (vec!["phpunit", "-l"], "phpunit", vec!["-l"]),
+            (vec!["--", "phpunit", "-l"], "phpunit", vec!["-l"]),
+            (vec!["phpunit", "--", "--filter", "Foo"], "phpunit", vec!["--filter", "Foo"]),
+            (vec!["phpunit", "--", "--"], "phpunit", vec!["--"]),
+        ] {
+            let args = parse(&argv);
+            assert_eq!(args.binary(), Some(binary), "{:?}", argv);
+            assert_eq!(args.forwarded_args(), forwarded, "{:?}", argv);
+            assert!(!args.list, "{:?}", argv);
+        }
+
+        assert!(parse(&["-l"]).list);
+        assert!(parse(&["--list"]).binary().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_binary_forwards_argv_and_exit_code() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let stub = dir.path().join("stub");
+        let argv_file = dir.path().join("argv");
+        std::fs::write(&stub, format!(
+            "#!/bin/sh\nfor arg in \"$@\"; do echo \"$arg\" >> {}; done\nexit 7\n",
+            argv_file.display()
+        )).unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let args = parse(&["stub", "analyse", "--level=9", "--", "-l", "two words"]);
+        let code = execute_binary(&stub, args.forwarded_args(), &dir.path().to_path_buf()).unwrap();
+
+        assert_eq!(code, 7);
+        assert_eq!(
+            std::fs::read_to_string(&argv_file).unwrap(),
+            "analyse\n--level=9\n--\n-l\ntwo words\n"
+        );
+    }
+}
@@ -3,7 +3,6 @@ use clap::Args;
 use indexmap::IndexMap;
 use regex::Regex;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use pox_pm::{
@@ -17,16 +16,10 @@ pub struct FundArgs {
     /// Output format: text or json
     #[arg(short = 'f', long, default_value = "text")]
     pub format: String,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: FundArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     if args.format != "text" && args.format != "json" {
@@ -74,49 +67,7 @@ pub async fn execute(args: FundArgs) -> Result<i32> {
         packages
     };
 
-    let mut fundings: BTreeMap<String, IndexMap<String, Vec<String>>> = BTreeMap::new();
-    let github_user_regex = Regex::new(r"^https://github\.com/([^/]+)$").unwrap();
-
-    for package in &packages {
-        if package.funding.is_empty() {
-            continue;
-        }
-
-        let parts: Vec<&str> = package.pretty_name().split('/').collect();
-        if parts.len() != 2 {
-            continue;
-        }
-        let vendor = parts[0].to_string();
-        let package_name = parts[1].to_string();
-
-        for funding in &package.funding {
-            let url = match &funding.url {
-                Some(u) if !u.is_empty() => u.clone(),
-                _ => continue,
-            };
-
-            let url = if let Some(funding_type) = &funding.funding_type {
-                if funding_type == "github" {
-                    if let Some(caps) = github_user_regex.captures(&url) {
-                        format!("https://github.com/sponsors/{}", &caps[1])
-                    } else {
-                        url
-                    }
-                } else {
-                    url
-                }
-            } else {
-                url
-            };
-
-            fundings
-                .entry(vendor.clone())
-                .or_default()
-                .entry(url)
-                .or_default()
-                .push(package_name.clone());
-        }
-    }
+    let fundings = collect_fundings(&packages);
 
     if fundings.is_empty() {
         if args.format == "json" {
@@ -161,6 +112,57 @@ pub async fn execute(args: FundArgs) -> Result<i32> {
     Ok(0)
 }
 
+/// Collect funding URLs by vendor, listing the packages behind each URL
+///
+/// GitHub profile links are rewritten to their sponsors page.
+fn collect_fundings(packages: &[Arc<pox_pm::Package>]) -> BTreeMap<String, IndexMap<String, Vec<String>>> {
+    let mut fundings: BTreeMap<String, IndexMap<String, Vec<String>>> = BTreeMap::new();
+    let github_user_regex = Regex::new(r"^https://github\.com/([^/]+)$").unwrap();
+
+    for package in packages {
+        if package.funding.is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = package.pretty_name().split('/').collect();
+        if parts.len() != 2 {
+            continue;
+        }
+        let vendor = parts[0].to_string();
+        let package_name = parts[1].to_string();
+
+        for funding in &package.funding {
+            let url = match &funding.url {
+                Some(u) if !u.is_empty() => u.clone(),
+                _ => continue,
+            };
+
+            let url = if let Some(funding_type) = &funding.funding_type {
+                if funding_type == "github" {
+                    if let Some(caps) = github_user_regex.captures(&url) {
+                        format!("https://github.com/sponsors/{}", &caps[1])
+                    } else {
+                        url
+                    }
+                } else {
+                    url
+                }
+            } else {
+                url
+            };
+
+            // The same package can be listed more than once (e.g. aliased versions), and
+            // may declare the same URL several times
+            let names = fundings.entry(vendor.clone()).or_default().entry(url).or_default();
+            if !names.contains(&package_name) {
+                names.push(package_name.clone());
+            }
+        }
+    }
+
+    fundings
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,6 +178,55 @@ mod tests {
         }
     }
 
+    fn package(name: &str, version: &str, funding: &[(&str, &str)]) -> Arc<pox_pm::Package> {
+        let mut package = pox_pm::Package::new(name, version);
+        package.funding = funding
+            .iter()
+            .map(|(funding_type, url)| pox_pm::package::Funding {
+                funding_type: Some(funding_type.to_string()),
+                url: Some(url.to_string()),
+            })
+            .collect();
+        Arc::new(package)
+    }
+
+    #[test]
+    fn test_collect_fundings_multiple_entries_and_duplicates() {
+        let packages = vec![
+            package(
+                "symfony/console",
+                "6.0.0",
+                &[("github", "https://github.com/fabpot"), ("tidelift", "https://tidelift.com/funding/symfony")],
+            ),
+            package("symfony/console", "6.0.1", &[("github", "https://github.com/fabpot")]),
+            package(
+                "symfony/process",
+                "6.0.0",
+                &[("github", "https://github.com/fabpot"), ("github", "https://github.com/fabpot")],
+            ),
+            package("acme/lib", "1.0.0", &[("patreon", "https://www.patreon.com/acme"), ("custom", "")]),
+            package("acme/unfunded", "1.0.0", &[]),
+        ];
+
+        let fundings = collect_fundings(&packages);
+
+        assert_eq!(fundings.keys().collect::<Vec<_>>(), vec!["acme", "symfony"]);
+        assert_eq!(
+            fundings["acme"].iter().collect::<Vec<_>>(),
+            vec![(&"https://www.patreon.com/acme".to_string(), &vec!["lib".to_string()])]
+        );
+        assert_eq!(
+            fundings["symfony"].iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    &"https://github.com/sponsors/fabpot".to_string(),
+                    &vec!["console".to_string(), "process".to_string()]
+                ),
+                (&"https://tidelift.com/funding/symfony".to_string(), &vec!["console".to_string()]),
+            ]
+        );
+    }
+
     #[test]
     fn test_github_sponsor_url_no_match() {
         let regex = Regex::new(r"^https://github\.com/([^/]+)$").unwrap();
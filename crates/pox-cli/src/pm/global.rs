@@ -0,0 +1,75 @@
+//! Global command - run install commands against the global Composer home.
+//!
+//! Tools installed this way live in `<composer-home>/vendor`, and their binaries are
+//! linked into the home's bin directory, which users are expected to add to PATH.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+use pox_pm::config::Config;
+use pox_pm::scripts::is_on_path;
+
+use crate::add::AddArgs;
+use crate::install::InstallArgs;
+use crate::remove::RemoveArgs;
+use crate::update::UpdateArgs;
+
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    #[command(subcommand)]
+    pub command: GlobalCommands,
+}
+
+/// Commands that can run against the global Composer home
+#[derive(Subcommand, Debug)]
+pub enum GlobalCommands {
+    /// Add a package to the global composer.json
+    #[command(alias = "require")]
+    Add(AddArgs),
+
+    /// Install the global dependencies from composer.lock
+    #[command(alias = "i")]
+    Install(InstallArgs),
+
+    /// Update the global dependencies to their latest versions
+    Update(UpdateArgs),
+
+    /// Remove a package from the global composer.json
+    #[command(alias = "rm")]
+    Remove(RemoveArgs),
+}
+
+pub async fn execute(args: GlobalArgs) -> Result<i32> {
+    let home = Config::global_home();
+    std::fs::create_dir_all(&home)
+        .with_context(|| format!("Failed to create Composer home {}", home.display()))?;
+
+    println!("{} Changed current directory to {}",
+        style("Info:").cyan(),
+        home.display()
+    );
+    std::env::set_current_dir(&home)
+        .with_context(|| format!("Failed to change to Composer home {}", home.display()))?;
+
+    let code = match args.command {
+        GlobalCommands::Add(args) => crate::add::execute(args).await?,
+        GlobalCommands::Install(args) => crate::install::execute(args).await?,
+        GlobalCommands::Update(args) => crate::update::execute(args).await?,
+        GlobalCommands::Remove(args) => crate::remove::execute(args).await?,
+    };
+
+    if code == 0 {
+        let bin_dir = Config::build(Some(&home), true)?.get_bin_dir();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        if !is_on_path(&bin_dir, &path) {
+            println!();
+            println!("{} {} is not in your PATH. Add it to run globally installed binaries:",
+                style("Hint:").yellow(),
+                bin_dir.display()
+            );
+            println!("  export PATH=\"{}:$PATH\"", bin_dir.display());
+        }
+    }
+
+    Ok(code)
+}
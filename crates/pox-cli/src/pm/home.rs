@@ -2,7 +2,6 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use pox_pm::{
@@ -24,16 +23,10 @@ pub struct HomeArgs {
     /// Only show the homepage or repository URL (don't open browser)
     #[arg(short = 's', long)]
     pub show: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: HomeArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
@@ -1,11 +1,12 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 
+use pox_spdx::LicenseExpression;
+
 use pox_pm::{
-    Repository,
+    Package, Repository,
     config::Config,
     json::{ComposerJson, ComposerLock},
     package::detect_root_version,
@@ -26,15 +27,17 @@ pub struct LicensesArgs {
     #[arg(long)]
     pub locked: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// Fail unless every package can be used under one of these licenses (multiple allowed)
+    #[arg(long = "allow", value_name = "LICENSE")]
+    pub allow: Vec<String>,
+
+    /// Fail if a package can only be used under one of these licenses (multiple allowed)
+    #[arg(long = "disallow", value_name = "LICENSE")]
+    pub disallow: Vec<String>,
 }
 
 pub async fn execute(args: LicensesArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     if args.format != "text" && args.format != "json" && args.format != "summary" {
@@ -190,22 +193,7 @@ pub async fn execute(args: LicensesArgs) -> Result<i32> {
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
         "summary" => {
-            let mut used_licenses: HashMap<String, usize> = HashMap::new();
-
-            for package in &packages {
-                let licenses = if package.license.is_empty() {
-                    vec!["none".to_string()]
-                } else {
-                    package.license.clone()
-                };
-
-                for license in licenses {
-                    *used_licenses.entry(license).or_insert(0) += 1;
-                }
-            }
-
-            let mut license_counts: Vec<_> = used_licenses.into_iter().collect();
-            license_counts.sort_by(|a, b| b.1.cmp(&a.1));
+            let license_counts = summarize_licenses(&packages);
 
             println!(" ----------------------- ----------------------- ");
             println!("  License                 Number of dependencies ");
@@ -220,9 +208,100 @@ pub async fn execute(args: LicensesArgs) -> Result<i32> {
         _ => unreachable!(),
     }
 
+    let policy = LicensePolicy {
+        allow: if args.allow.is_empty() { config.licenses.allow.clone() } else { args.allow },
+        disallow: if args.disallow.is_empty() { config.licenses.disallow.clone() } else { args.disallow },
+    };
+
+    if !policy.is_empty() {
+        let violations: Vec<_> = packages
+            .iter()
+            .filter_map(|package| policy.violation(package).map(|expression| (package, expression)))
+            .collect();
+
+        if !violations.is_empty() {
+            eprintln!();
+            eprintln!("Error: {} package(s) are not permitted by the license policy:", violations.len());
+            for (package, expression) in violations {
+                let version = package.pretty_version.as_deref().unwrap_or(&package.version);
+                eprintln!("  {} {}: {}", package.name, version, expression);
+            }
+            return Ok(1);
+        }
+    }
+
     Ok(0)
 }
 
+/// Allow and deny lists of SPDX license identifiers
+///
+/// Identifiers match case-insensitively and ignore the `-only`, `-or-later` and `+`
+/// suffixes, so disallowing `GPL-3.0` also rejects `GPL-3.0-or-later`.
+struct LicensePolicy {
+    allow: Vec<String>,
+    disallow: Vec<String>,
+}
+
+impl LicensePolicy {
+    fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.disallow.is_empty()
+    }
+
+    fn permits(&self, license: &str) -> bool {
+        let license = normalize_license(license);
+        let listed = |list: &[String]| list.iter().any(|l| normalize_license(l) == license);
+        !listed(&self.disallow) && (self.allow.is_empty() || listed(&self.allow))
+    }
+
+    /// Get the declared license of a package the policy does not permit
+    ///
+    /// Multiple declared licenses and `OR` expressions pass as long as one permitted
+    /// option remains. Packages without a license are checked as `none`.
+    fn violation(&self, package: &Package) -> Option<String> {
+        let expression = match package.license.as_slice() {
+            [] => "none".to_string(),
+            [license] => license.clone(),
+            licenses => format!("({})", licenses.join(" OR ")),
+        };
+
+        let licenses: Vec<&str> = package.license.iter().map(String::as_str).collect();
+        let permitted = match LicenseExpression::parse_any(&licenses) {
+            Some(parsed) => parsed.is_satisfied_by(&|license| self.permits(license)),
+            None => self.permits(&expression),
+        };
+
+        if permitted { None } else { Some(expression) }
+    }
+}
+
+fn normalize_license(license: &str) -> String {
+    let license = license.trim().to_lowercase();
+    let license = license.strip_suffix('+').unwrap_or(&license);
+    let license = license.strip_suffix("-or-later").unwrap_or(license);
+    license.strip_suffix("-only").unwrap_or(license).to_string()
+}
+
+/// Count the packages using each license, most used first
+///
+/// Dual-licensed packages count towards each of their licenses; packages without a
+/// declared license are counted as `none`.
+fn summarize_licenses(packages: &[Arc<pox_pm::Package>]) -> Vec<(String, usize)> {
+    let mut used_licenses: HashMap<String, usize> = HashMap::new();
+
+    for package in packages {
+        if package.license.is_empty() {
+            *used_licenses.entry("none".to_string()).or_insert(0) += 1;
+        }
+        for license in &package.license {
+            *used_licenses.entry(license.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let mut license_counts: Vec<_> = used_licenses.into_iter().collect();
+    license_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    license_counts
+}
+
 fn get_short_git_ref(path: &std::path::Path) -> Option<String> {
     let git_dir = path.join(".git");
     if !git_dir.exists() {
@@ -320,4 +399,74 @@ mod tests {
         let result = get_short_git_ref(temp_dir.path());
         assert!(result.is_none());
     }
+
+    fn package(name: &str, licenses: &[&str]) -> Arc<pox_pm::Package> {
+        let mut package = pox_pm::Package::new(name, "1.0.0");
+        package.license = licenses.iter().map(|l| l.to_string()).collect();
+        Arc::new(package)
+    }
+
+    #[test]
+    fn test_summarize_licenses() {
+        let packages = vec![
+            package("a/a", &["MIT"]),
+            package("b/b", &["MIT", "GPL-2.0-only"]),
+            package("c/c", &[]),
+            package("d/d", &["BSD-3-Clause"]),
+            package("e/e", &[]),
+            package("f/f", &["MIT"]),
+        ];
+
+        assert_eq!(
+            summarize_licenses(&packages),
+            vec![
+                ("MIT".to_string(), 3),
+                ("none".to_string(), 2),
+                ("BSD-3-Clause".to_string(), 1),
+                ("GPL-2.0-only".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_license_policy_disallow() {
+        let policy = LicensePolicy {
+            allow: Vec::new(),
+            disallow: vec!["GPL-3.0".to_string(), "AGPL-3.0".to_string()],
+        };
+
+        assert_eq!(policy.violation(&package("a/a", &["MIT"])), None);
+        assert_eq!(policy.violation(&package("b/b", &["(MIT OR GPL-3.0-only)"])), None);
+        assert_eq!(policy.violation(&package("c/c", &["GPL-3.0-only", "MIT"])), None);
+        assert_eq!(policy.violation(&package("d/d", &[])), None);
+        assert_eq!(
+            policy.violation(&package("e/e", &["GPL-3.0-or-later"])),
+            Some("GPL-3.0-or-later".to_string())
+        );
+        assert_eq!(
+            policy.violation(&package("f/f", &["(MIT AND agpl-3.0-only)"])),
+            Some("(MIT AND agpl-3.0-only)".to_string())
+        );
+        assert_eq!(
+            policy.violation(&package("g/g", &["GPL-3.0+", "AGPL-3.0"])),
+            Some("(GPL-3.0+ OR AGPL-3.0)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_license_policy_allow() {
+        let policy = LicensePolicy {
+            allow: vec!["MIT".to_string(), "BSD-3-Clause".to_string()],
+            disallow: Vec::new(),
+        };
+
+        assert_eq!(policy.violation(&package("a/a", &["mit"])), None);
+        assert_eq!(policy.violation(&package("b/b", &["(GPL-2.0-only OR BSD-3-Clause)"])), None);
+        assert_eq!(policy.violation(&package("c/c", &[])), Some("none".to_string()));
+        assert_eq!(
+            policy.violation(&package("d/d", &["(MIT AND Apache-2.0)"])),
+            Some("(MIT AND Apache-2.0)".to_string())
+        );
+        assert_eq!(policy.violation(&package("e/e", &["proprietary"])), Some("proprietary".to_string()));
+    }
 }
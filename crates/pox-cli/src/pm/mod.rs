@@ -1,42 +1,62 @@
 //! Package manager subcommands.
 
+mod archive;
 pub mod bin;
 mod bump;
+mod config;
 mod exec;
 mod dump_autoload;
 mod clear_cache;
+pub mod completion;
 pub mod run;
 pub mod platform;
 mod why;
+mod why_not;
 mod show;
 mod search;
 mod outdated;
 pub mod audit;
+mod check_platform_reqs;
 mod licenses;
 mod home;
 mod suggests;
 mod fund;
+mod global;
 mod reinstall;
+mod self_update;
+mod status;
+mod validate;
+mod verify;
 
 use clap::Subcommand;
 use anyhow::Result;
 
+pub use archive::ArchiveArgs;
 pub use bin::BinArgs;
 pub use bump::BumpArgs;
+pub use config::ConfigArgs;
 pub use exec::ExecArgs;
 pub use dump_autoload::DumpAutoloadArgs;
 pub use clear_cache::ClearCacheArgs;
+pub use completion::CompletionArgs;
 pub use run::RunArgs;
 pub use why::WhyArgs;
+pub use why_not::WhyNotArgs;
 pub use show::ShowArgs;
 pub use search::SearchArgs;
 pub use outdated::OutdatedArgs;
 pub use audit::AuditArgs;
+pub use check_platform_reqs::CheckPlatformReqsArgs;
 pub use licenses::LicensesArgs;
 pub use home::HomeArgs;
 pub use suggests::SuggestsArgs;
 pub use fund::FundArgs;
+pub use global::GlobalArgs;
 pub use reinstall::ReinstallArgs;
+pub use self_update::SelfUpdateArgs;
+pub use status::StatusArgs;
+pub use validate::ValidateArgs;
+pub use verify::VerifyArgs;
 
 // Re-export args for pm subcommand aliases
 pub use crate::install::InstallArgs;
@@ -44,16 +64,23 @@ pub use crate::update::UpdateArgs;
 pub use crate::add::AddArgs;
 pub use crate::remove::RemoveArgs;
 pub use crate::create_project::CreateProjectArgs;
+pub use crate::init::InitArgs;
 
 /// Package manager subcommands
 #[derive(Subcommand, Debug)]
 pub enum PmCommands {
+    /// Create an archive of this project or of a package
+    Archive(ArchiveArgs),
+
     /// Run a command in a bin namespace (vendor-bin plugin)
     Bin(BinArgs),
 
     /// Increases the lower limit of your composer.json requirements to the currently installed versions
     Bump(BumpArgs),
 
+    /// Read and write composer.json, config.json and auth.json settings
+    Config(ConfigArgs),
+
     /// Execute a vendored binary/script
     Exec(ExecArgs),
 
@@ -65,13 +92,16 @@ pub enum PmCommands {
     #[command(name = "clear-cache", alias = "clearcache")]
     ClearCache(ClearCacheArgs),
 
+    /// Generate shell completion scripts
+    Completion(CompletionArgs),
+
     /// Shows which packages cause the given package to be installed
     #[command(alias = "depends")]
     Why(WhyArgs),
 
-    /// Shows which packages prevent the given package from being installed
+    /// Explains what prevents the given package version from being installed
     #[command(name = "why-not", alias = "prohibits")]
-    WhyNot(WhyArgs),
+    WhyNot(WhyNotArgs),
 
     /// Shows information about packages
     #[command(alias = "info")]
@@ -86,12 +116,19 @@ pub enum PmCommands {
     /// Check for security vulnerabilities in dependencies
     Audit(AuditArgs),
 
+    /// Check that platform requirements are satisfied
+    #[command(name = "check-platform-reqs")]
+    CheckPlatformReqs(CheckPlatformReqsArgs),
+
     /// Shows information about licenses of dependencies
     Licenses(LicensesArgs),
 
     /// Discover how to help fund the maintenance of your dependencies
     Fund(FundArgs),
 
+    /// Run install, update, require or remove against the global Composer home
+    Global(GlobalArgs),
+
     /// Opens the package's repository URL or homepage in your browser
     #[command(alias = "home")]
     Browse(HomeArgs),
@@ -103,6 +140,19 @@ pub enum PmCommands {
     /// Uninstall and reinstall packages
     Reinstall(ReinstallArgs),
 
+    /// Update pox to the latest release
+    #[command(name = "self-update", alias = "selfupdate")]
+    SelfUpdate(SelfUpdateArgs),
+
+    /// Shows a list of locally modified packages
+    Status(StatusArgs),
+
+    /// Validates a composer.json and composer.lock
+    Validate(ValidateArgs),
+
+    /// Verifies that installed packages match composer.lock
+    Verify(VerifyArgs),
+
     /// Install project dependencies from composer.lock (alias for top-level install)
     #[command(alias = "i")]
     Install(InstallArgs),
@@ -120,31 +170,44 @@ pub enum PmCommands {
 
     /// Create a new project from a package into a directory
     CreateProject(CreateProjectArgs),
+
+    /// Create a basic composer.json file in the current directory (alias for top-level init)
+    Init(InitArgs),
 }
 
 /// Execute a package manager command
 pub async fn execute(command: PmCommands) -> Result<i32> {
     match command {
+        PmCommands::Archive(args) => archive::execute(args).await,
         PmCommands::Bin(args) => bin::execute(args).await,
         PmCommands::Bump(args) => bump::execute(args).await,
+        PmCommands::Config(args) => config::execute(args).await,
         PmCommands::Exec(args) => exec::execute(args).await,
         PmCommands::DumpAutoload(args) => dump_autoload::execute(args).await,
         PmCommands::ClearCache(args) => clear_cache::execute(args).await,
-        PmCommands::Why(args) => why::execute(args, false).await,
-        PmCommands::WhyNot(args) => why::execute(args, true).await,
+        PmCommands::Completion(args) => completion::execute(args).await,
+        PmCommands::Why(args) => why::execute(args).await,
+        PmCommands::WhyNot(args) => why_not::execute(args).await,
         PmCommands::Show(args) => show::execute(args).await,
         PmCommands::Search(args) => search::execute(args).await,
         PmCommands::Outdated(args) => outdated::execute(args).await,
         PmCommands::Audit(args) => audit::execute(args).await,
+        PmCommands::CheckPlatformReqs(args) => check_platform_reqs::execute(args).await,
         PmCommands::Licenses(args) => licenses::execute(args).await,
         PmCommands::Fund(args) => fund::execute(args).await,
+        PmCommands::Global(args) => global::execute(args).await,
         PmCommands::Browse(args) => home::execute(args).await,
         PmCommands::Suggests(args) => suggests::execute(args).await,
         PmCommands::Reinstall(args) => reinstall::execute(args).await,
+        PmCommands::SelfUpdate(args) => self_update::execute(args).await,
+        PmCommands::Status(args) => status::execute(args).await,
+        PmCommands::Validate(args) => validate::execute(args).await,
+        PmCommands::Verify(args) => verify::execute(args).await,
         PmCommands::Install(args) => crate::install::execute(args).await,
         PmCommands::Update(args) => crate::update::execute(args).await,
         PmCommands::Add(args) => crate::add::execute(args).await,
         PmCommands::Remove(args) => crate::remove::execute(args).await,
         PmCommands::CreateProject(args) => crate::create_project::execute(args).await,
+        PmCommands::Init(args) => crate::init::execute(args).await,
     }
 }
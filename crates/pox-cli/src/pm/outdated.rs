@@ -2,7 +2,6 @@
 
 use anyhow::Result;
 use clap::Args;
-use std::path::PathBuf;
 
 use super::show::{self, ShowArgs};
 
@@ -23,16 +22,16 @@ pub struct OutdatedArgs {
     #[arg(short = 'D', long)]
     pub direct: bool,
 
-    /// Return a non-zero exit code when there are outdated packages
+    /// Return a non-zero exit code when directly required packages are outdated
     #[arg(long)]
     pub strict: bool,
 
     /// Show only packages that have major SemVer-compatible updates
-    #[arg(short = 'M', long)]
+    #[arg(short = 'M', long, conflicts_with_all = ["minor_only", "patch_only"])]
     pub major_only: bool,
 
     /// Show only packages that have minor SemVer-compatible updates
-    #[arg(short = 'm', long)]
+    #[arg(short = 'm', long, conflicts_with = "patch_only")]
     pub minor_only: bool,
 
     /// Show only packages that have patch SemVer-compatible updates
@@ -50,10 +49,6 @@ pub struct OutdatedArgs {
     /// Disables search in require-dev packages
     #[arg(long)]
     pub no_dev: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: OutdatedArgs) -> Result<i32> {
@@ -71,19 +66,16 @@ pub async fn execute(args: OutdatedArgs) -> Result<i32> {
         latest: true,
         outdated: !args.all,
         direct: args.direct,
+        major_only: args.major_only,
+        minor_only: args.minor_only,
+        patch_only: args.patch_only,
+        strict: args.strict,
+        ignore: args.ignore,
         format: args.format,
         no_dev: args.no_dev,
-        working_dir: args.working_dir,
     };
 
-    let result = show::execute(show_args).await?;
-
-    if args.strict && result == 0 {
-        // TODO: Return non-zero if there were outdated packages
-        // For now, just return the result from show
-    }
-
-    Ok(result)
+    show::execute(show_args).await
 }
 
 #[cfg(test)]
@@ -104,7 +96,6 @@ mod tests {
             format: "text".to_string(),
             ignore: vec![],
             no_dev: false,
-            working_dir: PathBuf::from("."),
         };
         assert!(!args.all);
     }
@@ -123,7 +114,6 @@ mod tests {
             format: "text".to_string(),
             ignore: vec![],
             no_dev: false,
-            working_dir: PathBuf::from("."),
         };
         assert!(args.all);
     }
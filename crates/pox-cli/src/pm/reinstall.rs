@@ -4,7 +4,6 @@ use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
 use regex::Regex;
-use std::path::PathBuf;
 
 use pox_pm::{
     ComposerBuilder,
@@ -25,8 +24,8 @@ pub struct ReinstallArgs {
     #[arg(long = "type", value_name = "TYPE")]
     pub package_types: Vec<String>,
 
-    /// Prefer source installation (git clone)
-    #[arg(long)]
+    /// Prefer source installation (git clone), falling back to dist for packages without a source
+    #[arg(long, conflicts_with = "prefer_dist")]
     pub prefer_source: bool,
 
     /// Prefer dist installation (zip download)
@@ -65,10 +64,6 @@ pub struct ReinstallArgs {
     #[arg(long = "ignore-platform-req", value_name = "REQ")]
     pub ignore_platform_req: Vec<String>,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
-
     /// Do not ask any interactive question
     #[arg(short = 'n', long)]
     pub no_interaction: bool,
@@ -94,7 +89,7 @@ fn package_name_to_regexp(pattern: &str) -> Result<Regex> {
 }
 
 pub async fn execute(args: ReinstallArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
@@ -186,31 +181,14 @@ pub async fn execute(args: ReinstallArgs) -> Result<i32> {
 
     let composer = builder.build()?;
     let manager = &composer.installation_manager;
-    let vendor_dir = manager.config().vendor_dir.clone();
-
-    println!("{} Removing packages...", style("Info:").cyan());
-    for pkg in &packages_to_reinstall {
-        let install_path = vendor_dir.join(&pkg.name);
-        if install_path.exists() {
-            tokio::fs::remove_dir_all(&install_path).await
-                .with_context(|| format!("Failed to remove {}", pkg.name))?;
-            println!(
-                "  {} {} ({})",
-                style("-").red(),
-                style(&pkg.name).white().bold(),
-                style(&pkg.version).yellow()
-            );
-        }
-    }
 
-    println!("{} Installing packages...", style("Info:").cyan());
-    let result = manager.install_packages(&packages_to_reinstall).await
+    let result = manager.reinstall_packages(&packages_to_reinstall).await
         .context("Failed to reinstall packages")?;
 
     for pkg in &result.installed {
         println!(
             "  {} {} ({})",
-            style("+").green(),
+            style("~").cyan(),
             style(&pkg.name).white().bold(),
             style(&pkg.version).yellow()
         );
@@ -283,4 +261,22 @@ mod tests {
         assert!(re.is_match("vendor/pkg.name"));
         assert!(!re.is_match("vendor/pkgXname")); // . should be literal, not any char
     }
+
+    #[test]
+    fn test_prefer_source_and_prefer_dist_conflict() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: ReinstallArgs,
+        }
+
+        let err = Cli::try_parse_from(["reinstall", "--prefer-source", "--prefer-dist", "acme/foo"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let cli = Cli::try_parse_from(["reinstall", "--prefer-source", "acme/foo"]).unwrap();
+        assert!(cli.args.prefer_source);
+        assert!(!cli.args.prefer_dist);
+    }
 }
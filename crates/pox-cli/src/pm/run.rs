@@ -3,7 +3,6 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
 
 use pox_pm::json::ComposerJson;
 
@@ -19,17 +18,13 @@ pub struct RunArgs {
     #[arg(short = 'l', long)]
     pub list: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
-
     /// Arguments passed to the script
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub args: Vec<String>,
 }
 
 pub async fn execute(args: RunArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     // Load composer.json
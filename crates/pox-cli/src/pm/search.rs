@@ -2,10 +2,10 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
-use std::path::PathBuf;
 
 use pox_pm::{
-    config::Config,
+    config::{AuthConfig, Config},
+    http::{HttpClient, HttpClientConfig},
     json::ComposerJson,
     repository::{ComposerRepository, RepositoryManager, SearchMode},
 };
@@ -31,10 +31,6 @@ pub struct SearchArgs {
     /// Output format: text or json
     #[arg(short = 'f', long, default_value = "text")]
     pub format: String,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 fn is_valid_format(format: &str) -> bool {
@@ -72,9 +68,7 @@ fn truncate_description(description: &str, max_len: usize) -> String {
 }
 
 pub async fn execute(args: SearchArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     if !is_valid_format(&args.format) {
@@ -93,8 +87,15 @@ pub async fn execute(args: SearchArgs) -> Result<i32> {
     let query = args.tokens.join(" ");
 
     let config = Config::build(Some(&working_dir), true)?;
+    let auth = AuthConfig::build(Some(&working_dir))?;
+    let http_client = std::sync::Arc::new(
+        HttpClient::with_config(HttpClientConfig::from_config(&config))
+            .context("Failed to create HTTP client")?
+            .with_auth(auth),
+    );
 
     let mut repo_manager = RepositoryManager::new();
+    repo_manager.set_http_client(http_client.clone());
 
     let json_path = working_dir.join("composer.json");
     if json_path.exists() {
@@ -106,11 +107,12 @@ pub async fn execute(args: SearchArgs) -> Result<i32> {
         }
     }
 
-    let packagist = if let Some(cache_dir) = config.cache_dir {
+    let mut packagist = if let Some(cache_dir) = config.cache_dir {
         ComposerRepository::packagist_with_cache(cache_dir.join("repo"))
     } else {
         ComposerRepository::packagist()
     };
+    packagist.set_http_client(http_client);
     repo_manager.add_repository(std::sync::Arc::new(packagist));
 
     let results = repo_manager.search(&query, mode).await;
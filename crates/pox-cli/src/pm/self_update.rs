@@ -0,0 +1,167 @@
+//! Self-update command - replace the running binary with the latest release.
+//!
+//! Releases are looked up at `<url>/pox-<os>-<arch>` with a `.sha256` checksum file
+//! next to it. The binary is only downloaded when the checksum differs from the running
+//! one, verified against it, and moved over the old binary with a single rename.
+//!
+//! There is no default `<url>` since no release binaries are published for this
+//! repository yet, it comes from `--url` or `POX_RELEASE_URL`.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+use pox_pm::config::Config;
+use pox_pm::http::{HttpClient, HttpClientConfig};
+
+#[derive(Args, Debug)]
+pub struct SelfUpdateArgs {
+    /// Base URL of the release to update to (default: POX_RELEASE_URL)
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Only check whether an update is available
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub async fn execute(args: SelfUpdateArgs) -> Result<i32> {
+    let Some(base_url) = args.url
+        .or_else(|| std::env::var("POX_RELEASE_URL").ok().filter(|url| !url.is_empty()))
+    else {
+        eprintln!("{} No release URL given, pass --url or set POX_RELEASE_URL to where pox releases are published",
+            style("Error:").red().bold()
+        );
+        return Ok(1);
+    };
+    let asset_url = format!("{}/{}", base_url.trim_end_matches('/'), asset_name());
+
+    let current_exe = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .context("Failed to locate the running binary")?;
+
+    println!("{} Current version is {}", style("Info:").cyan(), crate::POX_VERSION);
+
+    let config = Config::build(None::<&Path>, true)?;
+    let http_client = HttpClient::with_config(HttpClientConfig::from_config(&config))
+        .context("Failed to create HTTP client")?;
+    let checksum_body = http_client
+        .download_bytes(&format!("{}.sha256", asset_url))
+        .await
+        .with_context(|| format!("Failed to fetch checksum from {}.sha256", asset_url))?;
+    let Some(expected) = parse_checksum(&String::from_utf8_lossy(&checksum_body)) else {
+        eprintln!("{} The release checksum at {}.sha256 is not a SHA-256 hash",
+            style("Error:").red().bold(),
+            asset_url
+        );
+        return Ok(1);
+    };
+
+    let current = std::fs::read(&current_exe)
+        .with_context(|| format!("Failed to read {}", current_exe.display()))?;
+    if sha256_hex(&current) == expected {
+        println!("{} You are already using the latest version", style("Success:").green().bold());
+        return Ok(0);
+    }
+
+    if args.check {
+        println!("{} A new version is available at {}", style("Info:").cyan(), asset_url);
+        return Ok(0);
+    }
+
+    println!("{} Downloading {}", style("Info:").cyan(), asset_url);
+    let binary = http_client
+        .download_bytes(&asset_url)
+        .await
+        .with_context(|| format!("Failed to download {}", asset_url))?;
+
+    let actual = sha256_hex(&binary);
+    if actual != expected {
+        eprintln!("{} Checksum mismatch for the downloaded binary (expected {}, got {})",
+            style("Error:").red().bold(),
+            expected,
+            actual
+        );
+        return Ok(1);
+    }
+
+    if let Err(e) = replace_binary(&current_exe, &binary) {
+        if e.kind() == ErrorKind::PermissionDenied {
+            eprintln!("{} No permission to replace {}",
+                style("Error:").red().bold(),
+                current_exe.display()
+            );
+            eprintln!("Run the command again with sudo or use a user install of pox.");
+            return Ok(1);
+        }
+        return Err(e).with_context(|| format!("Failed to replace {}", current_exe.display()));
+    }
+
+    println!("{} Updated {}", style("Success:").green().bold(), current_exe.display());
+
+    Ok(0)
+}
+
+/// Name of the release asset for the platform this binary was built for
+fn asset_name() -> String {
+    format!("pox-{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Read the hash from a checksum file in `sha256sum` format, or holding only the hash
+fn parse_checksum(content: &str) -> Option<String> {
+    let hash = content.split_whitespace().next()?.to_lowercase();
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Write the new binary next to the old one and rename it into place, so the old
+/// binary stays intact if anything fails halfway
+fn replace_binary(target: &Path, binary: &[u8]) -> std::io::Result<()> {
+    let dir = target.parent().unwrap_or(Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(binary)?;
+    file.flush()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(target).map(|m| m.permissions().mode()).unwrap_or(0o755);
+        std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(mode | 0o111))?;
+    }
+
+    file.persist(target).map_err(|e| e.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checksum() {
+        let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(parse_checksum(hash).as_deref(), Some(hash));
+        assert_eq!(parse_checksum(&format!("{}  pox-linux-x86_64\n", hash.to_uppercase())).as_deref(), Some(hash));
+        assert_eq!(parse_checksum("not a hash"), None);
+        assert_eq!(parse_checksum(""), None);
+    }
+
+    #[test]
+    fn test_replace_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("pox");
+        std::fs::write(&target, b"old").unwrap();
+
+        replace_binary(&target, b"new").unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+}
@@ -3,18 +3,22 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::collections::{HashMap, HashSet};
 
 use pox_pm::{
     Repository,
-    config::Config,
-    json::{ComposerJson, ComposerLock},
+    composer::is_packagist_disabled,
+    config::{AuthConfig, Config},
+    http::{HttpClient, HttpClientConfig},
+    installer::InstallerPaths,
+    json::{Autoload, ComposerJson, ComposerLock},
     is_platform_package,
-    repository::ComposerRepository,
+    package::{RootVersionSource, detect_root_version, get_nearest_git_tag},
+    repository::{ComposerRepository, RepositoryManager},
 };
-use pox_semver::VersionParser;
+use pox_semver::{Comparator, VersionParser};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum UpdateType {
@@ -58,6 +62,50 @@ fn determine_update_type(current: &str, latest: &str) -> UpdateType {
     }
 }
 
+/// Which releases count as the latest version of an installed package
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LatestScope {
+    Any,
+    /// Only releases with a higher major version
+    Major,
+    /// Only releases within the installed major version
+    Minor,
+    /// Only releases within the installed minor version
+    Patch,
+}
+
+impl LatestScope {
+    fn from_args(args: &ShowArgs) -> Self {
+        if args.major_only {
+            LatestScope::Major
+        } else if args.minor_only {
+            LatestScope::Minor
+        } else if args.patch_only {
+            LatestScope::Patch
+        } else {
+            LatestScope::Any
+        }
+    }
+
+    fn contains(self, current: &str, candidate: &str) -> bool {
+        let parser = VersionParser::new();
+        let parts = |version: &str| -> (u64, u64) {
+            let normalized = parser.normalize(version).unwrap_or_else(|_| version.to_string());
+            let mut parts = normalized.split('.').filter_map(|s| s.split('-').next()?.parse().ok());
+            (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+        };
+        let (current_major, current_minor) = parts(current);
+        let (major, minor) = parts(candidate);
+
+        match self {
+            LatestScope::Any => true,
+            LatestScope::Major => major > current_major,
+            LatestScope::Minor => major == current_major,
+            LatestScope::Patch => major == current_major && minor == current_minor,
+        }
+    }
+}
+
 struct PackageWithLatest {
     package: Arc<pox_pm::Package>,
     latest_version: Option<String>,
@@ -72,8 +120,8 @@ pub struct ShowArgs {
     /// Version or version constraint to inspect
     pub version: Option<String>,
 
-    /// List all packages
-    #[arg(long)]
+    /// List all packages available in the repositories, or every version of the given package
+    #[arg(long, alias = "all-versions")]
     pub all: bool,
 
     /// List all locked packages
@@ -116,6 +164,26 @@ pub struct ShowArgs {
     #[arg(short = 'D', long)]
     pub direct: bool,
 
+    /// Show only packages that have major SemVer-compatible updates
+    #[arg(short = 'M', long, conflicts_with_all = ["minor_only", "patch_only"])]
+    pub major_only: bool,
+
+    /// Show only packages that have minor SemVer-compatible updates
+    #[arg(short = 'm', long, conflicts_with = "patch_only")]
+    pub minor_only: bool,
+
+    /// Show only packages that have patch SemVer-compatible updates
+    #[arg(long)]
+    pub patch_only: bool,
+
+    /// Return a non-zero exit code when directly required packages are outdated
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Ignore specified package(s) when looking for updates, can contain wildcards (*)
+    #[arg(long)]
+    pub ignore: Vec<String>,
+
     /// Output format: text or json
     #[arg(short = 'f', long, default_value = "text")]
     pub format: String,
@@ -123,16 +191,10 @@ pub struct ShowArgs {
     /// Disables search in require-dev packages
     #[arg(long)]
     pub no_dev: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: ShowArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     if args.format != "text" && args.format != "json" {
@@ -183,6 +245,7 @@ pub async fn execute(args: ShowArgs) -> Result<i32> {
     };
 
     let config = Config::build(Some(&working_dir), true)?;
+    let http_client = http_client(&config, &working_dir)?;
 
     let vendor_dir = working_dir.join(&config.vendor_dir);
     let installed_repo = Arc::new(pox_pm::repository::InstalledRepository::new(vendor_dir.clone()));
@@ -202,7 +265,7 @@ pub async fn execute(args: ShowArgs) -> Result<i32> {
             return Ok(1);
         }
 
-        print_root_package_info(&composer_json, &args.format)?;
+        print_root_package_info(&composer_json, &working_dir, args.no_dev, &args.format)?;
         return Ok(0);
     }
 
@@ -213,46 +276,89 @@ pub async fn execute(args: ShowArgs) -> Result<i32> {
         }
     }
 
+    if args.all || args.available {
+        let repo_manager = build_repository_manager(&composer_json, &config, &http_client);
+        return match args.package.as_deref() {
+            Some(name) if !name.contains('*') => show_all_versions(&repo_manager, name, &args.format).await,
+            filter => list_available_packages(&repo_manager, filter, &args).await,
+        };
+    }
+
     if installed_packages.is_empty() && (!composer_json.require.is_empty() || !composer_json.require_dev.is_empty()) {
         eprintln!("Warning: No dependencies installed. Try running install or update.");
     }
 
     let show_latest = args.latest || args.outdated;
+    let installer_paths = InstallerPaths::new(&working_dir, &composer_json.extra);
 
     if let Some(package_name) = &args.package {
         if !package_name.contains('*') {
+            if args.format == "json" && !args.path && !args.tree {
+                let repo_manager = build_repository_manager(&composer_json, &config, &http_client);
+                return show_package_json(&installed_packages, package_name, &repo_manager, &installer_paths, &vendor_dir).await;
+            }
             show_single_package(
                 &installed_packages,
                 package_name,
                 args.version.as_deref(),
                 &args,
+                &installer_paths,
                 &vendor_dir,
             )?;
+        } else if args.path {
+            list_package_paths(&installed_packages, Some(package_name), &installer_paths, &vendor_dir)?;
         } else {
-            list_packages_with_latest(&installed_packages, Some(package_name), &composer_json, &args, &config, show_latest).await?;
+            return list_packages_with_latest(&installed_packages, Some(package_name), &composer_json, &args, &config, &http_client, show_latest).await;
         }
     } else {
         if args.tree {
             show_tree_all(&installed_packages, &composer_json)?;
+        } else if args.path {
+            list_package_paths(&installed_packages, None, &installer_paths, &vendor_dir)?;
         } else {
-            list_packages_with_latest(&installed_packages, None, &composer_json, &args, &config, show_latest).await?;
+            return list_packages_with_latest(&installed_packages, None, &composer_json, &args, &config, &http_client, show_latest).await;
         }
     }
 
     Ok(0)
 }
 
-fn print_root_package_info(composer_json: &ComposerJson, format: &str) -> Result<()> {
+/// The root version as reported by `show --self`, with where it came from
+///
+/// COMPOSER_ROOT_VERSION, the version field and a tag on HEAD win, then the nearest
+/// version tag. A branch is not a version of the root, so without any of these there is none.
+fn root_package_version(composer_json: &ComposerJson, working_dir: &Path) -> Option<(String, String)> {
+    let detected = detect_root_version(
+        working_dir,
+        composer_json.version.as_deref(),
+        &composer_json.get_branch_aliases(),
+    );
+    match detected.source {
+        RootVersionSource::Environment | RootVersionSource::ComposerJson | RootVersionSource::GitTag => {
+            Some((detected.pretty_version, detected.source.to_string()))
+        }
+        _ => get_nearest_git_tag(working_dir).map(|tag| (tag, "nearest git tag".to_string())),
+    }
+}
+
+fn print_root_package_info(composer_json: &ComposerJson, working_dir: &Path, no_dev: bool, format: &str) -> Result<()> {
+    let version = root_package_version(composer_json, working_dir);
+
     if format == "json" {
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "name": composer_json.name,
-            "version": composer_json.version,
+            "version": version.as_ref().map(|(version, _)| version),
+            "version-source": version.as_ref().map(|(_, source)| source),
             "description": composer_json.description,
             "type": composer_json.package_type,
             "license": composer_json.license,
             "require": composer_json.require,
-            "require-dev": composer_json.require_dev,
+            "autoload": composer_json.autoload,
         });
+        if !no_dev {
+            json["require-dev"] = serde_json::to_value(&composer_json.require_dev)?;
+            json["autoload-dev"] = serde_json::to_value(&composer_json.autoload_dev)?;
+        }
         println!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         if let Some(name) = &composer_json.name {
@@ -261,11 +367,17 @@ fn print_root_package_info(composer_json: &ComposerJson, format: &str) -> Result
         if let Some(desc) = &composer_json.description {
             println!("descrip. : {}", desc);
         }
-        if let Some(version) = &composer_json.version {
-            println!("version  : {}", version);
+        match &version {
+            Some((version, source)) => println!("version  : {} {}", version, style(format!("({})", source)).dim()),
+            None => println!("version  : {}", style("no version set").dim()),
         }
         println!("type     : {}", &composer_json.package_type);
 
+        print_autoload("autoload", &composer_json.autoload);
+        if !no_dev {
+            print_autoload("autoload (dev)", &composer_json.autoload_dev);
+        }
+
         if !composer_json.require.is_empty() {
             println!("\nrequires");
             for (name, constraint) in &composer_json.require {
@@ -273,7 +385,7 @@ fn print_root_package_info(composer_json: &ComposerJson, format: &str) -> Result
             }
         }
 
-        if !composer_json.require_dev.is_empty() {
+        if !no_dev && !composer_json.require_dev.is_empty() {
             println!("\nrequires (dev)");
             for (name, constraint) in &composer_json.require_dev {
                 println!("{} {}", name, constraint);
@@ -283,12 +395,155 @@ fn print_root_package_info(composer_json: &ComposerJson, format: &str) -> Result
     Ok(())
 }
 
+fn print_autoload(title: &str, autoload: &Autoload) {
+    if autoload.is_empty() {
+        return;
+    }
+
+    println!("\n{}", title);
+    for (standard, mappings) in [("psr-4", &autoload.psr4), ("psr-0", &autoload.psr0)] {
+        if mappings.is_empty() {
+            continue;
+        }
+        println!("{}", standard);
+        for (namespace, paths) in mappings {
+            println!("{} => {}", namespace, paths.as_vec().join(", "));
+        }
+    }
+    for (kind, paths) in [
+        ("classmap", &autoload.classmap),
+        ("files", &autoload.files),
+        ("exclude-from-classmap", &autoload.exclude_from_classmap),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        println!("{}", kind);
+        for path in paths {
+            println!("{}", path);
+        }
+    }
+}
+
+/// HTTP client with the configured certificates and timeouts and the credentials from auth.json
+fn http_client(config: &Config, working_dir: &Path) -> Result<Arc<HttpClient>> {
+    let auth = AuthConfig::build(Some(working_dir))?;
+    let client = HttpClient::with_config(HttpClientConfig::from_config(config))
+        .context("Failed to create HTTP client")?
+        .with_auth(auth);
+    Ok(Arc::new(client))
+}
+
+fn build_repository_manager(composer_json: &ComposerJson, config: &Config, http_client: &Arc<HttpClient>) -> RepositoryManager {
+    let mut repo_manager = RepositoryManager::new();
+    repo_manager.set_http_client(http_client.clone());
+    for repo in composer_json.repositories.as_vec() {
+        repo_manager.add_from_json_repository(&repo);
+    }
+
+    if is_packagist_disabled(&composer_json.repositories) {
+        return repo_manager;
+    }
+
+    let mut packagist = if let Some(cache_dir) = &config.cache_dir {
+        ComposerRepository::packagist_with_cache(cache_dir.join("repo"))
+    } else {
+        ComposerRepository::packagist()
+    };
+    packagist.set_http_client(http_client.clone());
+    repo_manager.add_repository(Arc::new(packagist));
+    repo_manager
+}
+
+/// List every version of a package the repositories offer, newest first
+async fn show_all_versions(repo_manager: &RepositoryManager, name: &str, format: &str) -> Result<i32> {
+    let mut versions = repo_manager.find_packages_by_repository(name).await;
+    if versions.is_empty() {
+        eprintln!("Error: Package '{}' not found in any repository", name);
+        return Ok(1);
+    }
+    // Branches share a version, the default branch and then the most recently updated come first
+    versions.sort_by(|(_, a), (_, b)| {
+        compare_normalized_versions(&b.version, &a.version)
+            .then_with(|| b.is_default_branch().cmp(&a.is_default_branch()))
+            .then_with(|| a.compare_release_time(b))
+    });
+
+    if format == "json" {
+        let json: Vec<_> = versions
+            .iter()
+            .map(|(repo, pkg)| {
+                serde_json::json!({
+                    "version": pkg.pretty_version.as_deref().unwrap_or(&pkg.version),
+                    "php": pkg.require.get("php"),
+                    "time": pkg.time.map(|t| t.to_rfc3339()),
+                    "default-branch": pkg.is_default_branch(),
+                    "repository": repo,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "name": versions[0].1.name,
+            "versions": json,
+        }))?);
+        return Ok(0);
+    }
+
+    let rows: Vec<(String, String, String, &str)> = versions
+        .iter()
+        .map(|(repo, pkg)| {
+            (
+                strip_version_prefix(pkg.pretty_version.as_deref().unwrap_or(&pkg.version)).to_string(),
+                pkg.require.get("php").cloned().unwrap_or_else(|| "-".to_string()),
+                pkg.time.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string()),
+                repo.as_str(),
+            )
+        })
+        .collect();
+    let version_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+    let php_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+
+    println!("name     : {}", versions[0].1.name);
+    println!("versions :");
+    for (version, php, date, repo) in &rows {
+        println!(
+            "  {:<vw$}  php {:<pw$}  {:<10}  {}",
+            version, php, date, style(repo).dim(),
+            vw = version_width,
+            pw = php_width
+        );
+    }
+
+    Ok(0)
+}
+
+/// List the names of all packages available in the repositories
+async fn list_available_packages(repo_manager: &RepositoryManager, filter: Option<&str>, args: &ShowArgs) -> Result<i32> {
+    let mut names = repo_manager.package_names().await;
+    if let Some(pattern) = filter {
+        let re = regex::Regex::new(&format!("^{}$", regex::escape(&pattern.to_lowercase()).replace("\\*", ".*")))?;
+        names.retain(|name| re.is_match(name));
+    }
+
+    if args.format == "json" {
+        let json: Vec<_> = names.iter().map(|name| serde_json::json!({ "name": name })).collect();
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "available": json }))?);
+    } else {
+        for name in &names {
+            println!("{}", name);
+        }
+    }
+
+    Ok(0)
+}
+
 fn show_single_package(
     packages: &[Arc<pox_pm::Package>],
     name: &str,
     _version: Option<&str>,
     args: &ShowArgs,
-    vendor_dir: &PathBuf,
+    installer_paths: &InstallerPaths,
+    vendor_dir: &Path,
 ) -> Result<()> {
     let name_lower = name.to_lowercase();
     let package = packages
@@ -304,12 +559,7 @@ fn show_single_package(
     };
 
     if args.path {
-        let install_path = vendor_dir.join(&package.name);
-        if install_path.exists() {
-            println!("{} {}", package.name, install_path.display());
-        } else {
-            println!("{} null", package.name);
-        }
+        print_package_path(package, installer_paths, vendor_dir);
         return Ok(());
     }
 
@@ -318,10 +568,45 @@ fn show_single_package(
         return Ok(());
     }
 
-    if args.format == "json" {
-        print_package_json(package)?;
-    } else {
-        print_package_info(package)?;
+    print_package_info(package)?;
+
+    Ok(())
+}
+
+/// Resolve where a package is installed, honouring `installer-paths` and following
+/// symlinks so path repository packages report their real location
+fn resolve_install_path(package: &pox_pm::Package, installer_paths: &InstallerPaths, vendor_dir: &Path) -> Option<PathBuf> {
+    installer_paths
+        .install_path(vendor_dir, &package.name, &package.package_type, package.extra.as_ref())
+        .canonicalize()
+        .ok()
+}
+
+fn print_package_path(package: &pox_pm::Package, installer_paths: &InstallerPaths, vendor_dir: &Path) {
+    match resolve_install_path(package, installer_paths, vendor_dir) {
+        Some(path) => println!("{} {}", package.name, path.display()),
+        None => println!("{} null", package.name),
+    }
+}
+
+fn list_package_paths(
+    packages: &[Arc<pox_pm::Package>],
+    filter: Option<&str>,
+    installer_paths: &InstallerPaths,
+    vendor_dir: &Path,
+) -> Result<()> {
+    let re = filter
+        .map(|pattern| regex::Regex::new(&format!("^{}$", regex::escape(&pattern.to_lowercase()).replace("\\*", ".*"))))
+        .transpose()?;
+
+    let mut packages: Vec<_> = packages
+        .iter()
+        .filter(|p| re.as_ref().is_none_or(|re| re.is_match(&p.name.to_lowercase())))
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for package in packages {
+        print_package_path(package, installer_paths, vendor_dir);
     }
 
     Ok(())
@@ -381,7 +666,44 @@ fn print_package_info(package: &pox_pm::Package) -> Result<()> {
     Ok(())
 }
 
-fn print_package_json(package: &pox_pm::Package) -> Result<()> {
+/// Print the full record of a package, from its installed copy when there is one and
+/// otherwise from the newest version the repositories offer
+async fn show_package_json(
+    installed_packages: &[Arc<pox_pm::Package>],
+    name: &str,
+    repo_manager: &RepositoryManager,
+    installer_paths: &InstallerPaths,
+    vendor_dir: &Path,
+) -> Result<i32> {
+    let name_lower = name.to_lowercase();
+    let installed = installed_packages
+        .iter()
+        .find(|p| p.name.to_lowercase() == name_lower);
+
+    let mut available = repo_manager.find_packages(&name_lower).await;
+    available.sort_by(|a, b| {
+        compare_normalized_versions(&b.version, &a.version)
+            .then_with(|| b.is_default_branch().cmp(&a.is_default_branch()))
+            .then_with(|| a.compare_release_time(b))
+    });
+
+    let Some(package) = installed.or(available.first()) else {
+        eprintln!("Error: Package '{}' not found", name);
+        return Ok(1);
+    };
+
+    let mut json = package_json(package, installed.map(|p| p.as_ref()), &available);
+    if installed.is_some() {
+        json["path"] = serde_json::json!(resolve_install_path(package, installer_paths, vendor_dir));
+    }
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(0)
+}
+
+/// Build the JSON record of a package, keeping the installed version apart from the
+/// versions available in the repositories
+fn package_json(package: &pox_pm::Package, installed: Option<&pox_pm::Package>, available: &[Arc<pox_pm::Package>]) -> serde_json::Value {
+    let pretty_version = |p: &pox_pm::Package| p.pretty_version.clone().unwrap_or_else(|| p.version.clone());
     let abandoned_value = package.abandoned.as_ref().map(|a| {
         match a.replacement() {
             Some(pkg) => serde_json::json!(pkg),
@@ -389,40 +711,59 @@ fn print_package_json(package: &pox_pm::Package) -> Result<()> {
         }
     });
 
-    let json = serde_json::json!({
-        "name": package.name,
-        "version": package.pretty_version.as_deref().unwrap_or(&package.version),
+    serde_json::json!({
+        "name": package.pretty_name.as_deref().unwrap_or(&package.name),
         "description": package.description,
+        "keywords": package.keywords,
         "type": package.package_type,
+        "homepage": package.homepage,
+        "version": pretty_version(package),
+        "versions": {
+            "installed": installed.map(pretty_version),
+            "available": available.iter().map(|p| pretty_version(p)).collect::<Vec<_>>(),
+        },
+        "released": package.time.map(|t| t.to_rfc3339()),
+        "licenses": package.license,
+        "authors": package.authors,
+        "source": package.source,
+        "dist": package.dist,
+        "autoload": package.autoload,
+        "support": package.support,
         "abandoned": abandoned_value,
-        "require": package.require,
-        "require-dev": package.require_dev,
-        "provide": package.provide,
-        "conflict": package.conflict,
-        "replace": package.replace,
-    });
-    println!("{}", serde_json::to_string_pretty(&json)?);
-    Ok(())
+        "requires": package.require,
+        "devRequires": package.require_dev,
+        "suggests": package.suggest,
+        "provides": package.provide,
+        "conflicts": package.conflict,
+        "replaces": package.replace,
+    })
 }
 
 async fn fetch_latest_versions(
     packages: &[Arc<pox_pm::Package>],
     config: &Config,
+    http_client: &Arc<HttpClient>,
+    scope: LatestScope,
 ) -> HashMap<String, String> {
     let mut latest_versions = HashMap::new();
 
-    let packagist = if let Some(cache_dir) = &config.cache_dir {
+    let mut packagist = if let Some(cache_dir) = &config.cache_dir {
         ComposerRepository::packagist_with_cache(cache_dir.join("repo"))
     } else {
         ComposerRepository::packagist()
     };
+    packagist.set_http_client(http_client.clone());
 
     for pkg in packages {
         if is_platform_package(&pkg.name) {
             continue;
         }
 
-        let versions = packagist.find_packages(&pkg.name).await;
+        let current = pkg.pretty_version.as_deref().unwrap_or(&pkg.version);
+        let versions: Vec<_> = packagist.find_packages(&pkg.name).await
+            .into_iter()
+            .filter(|p| scope.contains(current, p.pretty_version.as_deref().unwrap_or(&p.version)))
+            .collect();
         if let Some(latest) = find_latest_stable_version(&versions) {
             latest_versions.insert(pkg.name.to_lowercase(), latest);
         }
@@ -475,6 +816,16 @@ fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     std::cmp::Ordering::Equal
 }
 
+fn compare_normalized_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    if Comparator::greater_than(a, b) {
+        std::cmp::Ordering::Greater
+    } else if Comparator::less_than(a, b) {
+        std::cmp::Ordering::Less
+    } else {
+        std::cmp::Ordering::Equal
+    }
+}
+
 fn strip_version_prefix(version: &str) -> &str {
     version.strip_prefix('v').or_else(|| version.strip_prefix('V')).unwrap_or(version)
 }
@@ -485,8 +836,14 @@ async fn list_packages_with_latest(
     composer_json: &ComposerJson,
     args: &ShowArgs,
     config: &Config,
+    http_client: &Arc<HttpClient>,
     show_latest: bool,
-) -> Result<()> {
+) -> Result<i32> {
+    let ignored: Vec<regex::Regex> = args.ignore
+        .iter()
+        .filter_map(|pattern| regex::Regex::new(&format!("^{}$", regex::escape(&pattern.to_lowercase()).replace("\\*", ".*"))).ok())
+        .collect();
+
     let mut filtered: Vec<_> = packages
         .iter()
         .filter(|p| {
@@ -520,10 +877,14 @@ async fn list_packages_with_latest(
         });
     }
 
+    if show_latest && !ignored.is_empty() {
+        filtered.retain(|p| !ignored.iter().any(|re| re.is_match(&p.name.to_lowercase())));
+    }
+
     filtered.sort_by(|a, b| a.name.cmp(&b.name));
 
     let latest_versions = if show_latest {
-        fetch_latest_versions(&filtered, config).await
+        fetch_latest_versions(&filtered, config, http_client, LatestScope::from_args(args)).await
     } else {
         HashMap::new()
     };
@@ -550,8 +911,20 @@ async fn list_packages_with_latest(
         packages_with_latest.retain(|p| p.update_type != UpdateType::UpToDate);
     }
 
+    let is_direct = |name: &str| {
+        let name = name.to_lowercase();
+        root_requires.contains(&name) || root_requires_dev.contains(&name)
+    };
+
+    // Transitive packages are only updated through their dependents, so they don't fail --strict
+    let exit_code = if args.strict && packages_with_latest.iter().any(|p| p.update_type != UpdateType::UpToDate && is_direct(&p.package.name)) {
+        1
+    } else {
+        0
+    };
+
     if packages_with_latest.is_empty() {
-        return Ok(());
+        return Ok(exit_code);
     }
 
     if args.format == "json" {
@@ -592,15 +965,9 @@ async fn list_packages_with_latest(
             eprintln!("- {} release available - update possible", style("major").yellow());
             eprintln!();
 
-            let direct: Vec<_> = packages_with_latest
+            let (direct, transitive): (Vec<_>, Vec<_>) = packages_with_latest
                 .iter()
-                .filter(|p| root_requires.contains(&p.package.name.to_lowercase()) || root_requires_dev.contains(&p.package.name.to_lowercase()))
-                .collect();
-
-            let transitive: Vec<_> = packages_with_latest
-                .iter()
-                .filter(|p| !root_requires.contains(&p.package.name.to_lowercase()) && !root_requires_dev.contains(&p.package.name.to_lowercase()))
-                .collect();
+                .partition(|p| is_direct(&p.package.name));
 
             if !direct.is_empty() {
                 eprintln!("{}", style("Direct dependencies required in composer.json:").green());
@@ -619,7 +986,7 @@ async fn list_packages_with_latest(
         }
     }
 
-    Ok(())
+    Ok(exit_code)
 }
 
 fn make_packagist_link(name: &str) -> String {
@@ -815,6 +1182,17 @@ mod tests {
         assert_eq!(determine_update_type("1.5.3", "3.0.0"), UpdateType::Major);
     }
 
+    #[test]
+    fn test_latest_scope() {
+        assert!(LatestScope::Any.contains("1.2.0", "3.0.0"));
+        assert!(LatestScope::Major.contains("v1.2.0", "2.0.0"));
+        assert!(!LatestScope::Major.contains("1.2.0", "1.9.0"));
+        assert!(LatestScope::Minor.contains("1.2.0", "1.9.0"));
+        assert!(!LatestScope::Minor.contains("1.2.0", "2.0.0"));
+        assert!(LatestScope::Patch.contains("1.2.0", "1.2.7"));
+        assert!(!LatestScope::Patch.contains("1.2.0", "1.3.0"));
+    }
+
     #[test]
     fn test_compare_versions() {
         assert_eq!(compare_versions("1.0.0", "1.0.0"), std::cmp::Ordering::Equal);
@@ -829,6 +1207,68 @@ mod tests {
         assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), std::cmp::Ordering::Equal);
     }
 
+    #[test]
+    fn test_compare_normalized_versions() {
+        let mut versions = vec!["1.10.0.0", "2.0.0.0-beta1", "1.9.0.0", "2.0.0.0"];
+        versions.sort_by(|a, b| compare_normalized_versions(b, a));
+        assert_eq!(versions, vec!["2.0.0.0", "2.0.0.0-beta1", "1.10.0.0", "1.9.0.0"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_install_path_follows_symlinks_and_installer_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let vendor_dir = root.join("vendor");
+
+        let local = root.join("packages/local");
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::create_dir_all(vendor_dir.join("acme")).unwrap();
+        std::os::unix::fs::symlink(&local, vendor_dir.join("acme/local")).unwrap();
+        std::fs::create_dir_all(root.join("plugins/seo")).unwrap();
+
+        let extra = serde_json::json!({"installer-paths": {"plugins/{$name}/": ["type:wordpress-plugin"]}});
+        let installer_paths = InstallerPaths::new(&root, &extra);
+
+        let linked = pox_pm::Package::new("acme/local", "1.0.0");
+        assert_eq!(resolve_install_path(&linked, &installer_paths, &vendor_dir), Some(local));
+
+        let mut plugin = pox_pm::Package::new("acme/seo", "1.0.0");
+        plugin.package_type = "wordpress-plugin".to_string();
+        assert_eq!(resolve_install_path(&plugin, &installer_paths, &vendor_dir), Some(root.join("plugins/seo")));
+
+        let missing = pox_pm::Package::new("acme/missing", "1.0.0");
+        assert_eq!(resolve_install_path(&missing, &installer_paths, &vendor_dir), None);
+    }
+
+    #[test]
+    fn test_package_json_separates_installed_from_available() {
+        let mut installed = pox_pm::Package::new("acme/lib", "1.0.0.0");
+        installed.pretty_version = Some("v1.0.0".to_string());
+        installed.description = Some("A library".to_string());
+        installed.require.insert("php".to_string(), ">=8.1".to_string());
+
+        let available: Vec<_> = ["2.0.0", "1.0.0"]
+            .iter()
+            .map(|v| {
+                let mut pkg = pox_pm::Package::new("acme/lib", format!("{}.0", v));
+                pkg.pretty_version = Some(format!("v{}", v));
+                Arc::new(pkg)
+            })
+            .collect();
+
+        let json = package_json(&installed, Some(&installed), &available);
+        assert_eq!(json["name"], "acme/lib");
+        assert_eq!(json["description"], "A library");
+        assert_eq!(json["requires"]["php"], ">=8.1");
+        assert_eq!(json["versions"]["installed"], "v1.0.0");
+        assert_eq!(json["versions"]["available"], serde_json::json!(["v2.0.0", "v1.0.0"]));
+
+        let json = package_json(&available[0], None, &available);
+        assert_eq!(json["version"], "v2.0.0");
+        assert!(json["versions"]["installed"].is_null());
+    }
+
     #[test]
     fn test_strip_version_prefix() {
         assert_eq!(strip_version_prefix("v1.0.0"), "1.0.0");
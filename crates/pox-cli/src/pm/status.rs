@@ -0,0 +1,167 @@
+//! Status command - show locally modified files in vendor packages.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::Path;
+use std::sync::Arc;
+
+use pox_pm::{
+    Repository,
+    config::Config,
+    downloader::{DistManifest, FileChange, GitDownloader},
+    installer::InstallerPaths,
+    json::ComposerJson,
+    repository::InstalledRepository,
+};
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Show the modified files of each package
+    #[arg(short = 'v', long)]
+    pub verbose: bool,
+}
+
+/// Local changes found in an installed package
+#[derive(Debug, PartialEq, Eq)]
+enum PackageChanges {
+    /// Uncommitted changes in a source (git) install, as `git status --porcelain` lines
+    Source(Vec<String>),
+    /// Files of a dist install that differ from the extracted archive
+    Dist(Vec<FileChange>),
+}
+
+pub async fn execute(args: StatusArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+
+    let json_path = working_dir.join("composer.json");
+    let composer_json: ComposerJson = if json_path.exists() {
+        let content = std::fs::read_to_string(&json_path)?;
+        serde_json::from_str(&content).context("Failed to parse composer.json")?
+    } else {
+        ComposerJson::default()
+    };
+
+    let config = Config::build(Some(&working_dir), true)?;
+    let vendor_dir = working_dir.join(&config.vendor_dir);
+    let installer_paths = InstallerPaths::new(&working_dir, &composer_json.extra);
+
+    let installed_repo = Arc::new(InstalledRepository::new(vendor_dir.clone()));
+    installed_repo.load().await.ok();
+    let mut packages = installed_repo.get_packages().await;
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut changed = Vec::new();
+    let mut unchecked = Vec::new();
+
+    for package in &packages {
+        let install_path = installer_paths.install_path(
+            &vendor_dir,
+            &package.name,
+            &package.package_type,
+            package.extra.as_ref(),
+        );
+        if package.is_metapackage() || !install_path.is_dir() {
+            continue;
+        }
+
+        match package_changes(&vendor_dir, &package.name, &install_path)? {
+            Some(changes) if !changes.is_empty() => changed.push((install_path, changes)),
+            Some(_) => {}
+            None => unchecked.push(package.name.clone()),
+        }
+    }
+
+    if args.verbose {
+        for name in &unchecked {
+            eprintln!("Skipping {}: no dist manifest recorded, reinstall it to enable checks", name);
+        }
+    }
+
+    if changed.is_empty() {
+        println!("No local changes");
+        return Ok(0);
+    }
+
+    println!("You have changes in the following dependencies:");
+    for (path, changes) in &changed {
+        println!("{}", path.display());
+        if args.verbose {
+            for line in changes.lines() {
+                println!("    {}", line);
+            }
+        }
+    }
+
+    if !args.verbose {
+        println!("Use --verbose (-v) to see a list of files");
+    }
+
+    Ok(1)
+}
+
+impl PackageChanges {
+    fn is_empty(&self) -> bool {
+        match self {
+            PackageChanges::Source(lines) => lines.is_empty(),
+            PackageChanges::Dist(changes) => changes.is_empty(),
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        match self {
+            PackageChanges::Source(lines) => lines.clone(),
+            PackageChanges::Dist(changes) => changes
+                .iter()
+                .map(|change| match change {
+                    FileChange::Modified(path) => format!("M {}", path),
+                    FileChange::Deleted(path) => format!("D {}", path),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Find local changes in an installed package
+///
+/// Returns `None` for dist installs without a manifest, which cannot be checked.
+fn package_changes(vendor_dir: &Path, name: &str, install_path: &Path) -> Result<Option<PackageChanges>> {
+    if install_path.join(".git").exists() {
+        let changes = GitDownloader::get_local_changes(install_path)
+            .with_context(|| format!("Failed to read git status of {}", name))?;
+        return Ok(Some(PackageChanges::Source(changes)));
+    }
+
+    match DistManifest::load(vendor_dir, name)? {
+        Some(manifest) => Ok(Some(PackageChanges::Dist(manifest.changes(install_path)?))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_package_changes_dist() {
+        let temp_dir = TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let install_path = vendor_dir.join("acme/lib");
+        std::fs::create_dir_all(&install_path).unwrap();
+        std::fs::write(install_path.join("Lib.php"), "<?php").unwrap();
+
+        assert_eq!(package_changes(&vendor_dir, "acme/lib", &install_path).unwrap(), None);
+
+        DistManifest::create(&install_path, "1.0.0.0")
+            .unwrap()
+            .save(&vendor_dir, "acme/lib")
+            .unwrap();
+        let changes = package_changes(&vendor_dir, "acme/lib", &install_path).unwrap().unwrap();
+        assert!(changes.is_empty());
+
+        std::fs::write(install_path.join("Lib.php"), "<?php // patched").unwrap();
+        let changes = package_changes(&vendor_dir, "acme/lib", &install_path).unwrap().unwrap();
+        assert_eq!(changes.lines(), vec!["M Lib.php"]);
+    }
+}
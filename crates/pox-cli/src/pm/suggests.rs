@@ -3,7 +3,6 @@ use clap::Args;
 use colored::Colorize;
 use pox_pm::json::{ComposerJson, ComposerLock};
 use std::collections::{BTreeMap, HashSet};
-use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct SuggestsArgs {
@@ -30,10 +29,6 @@ pub struct SuggestsArgs {
     /// Packages to show suggestions from
     #[arg(name = "packages")]
     pub packages: Vec<String>,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 #[derive(Debug, Clone)]
@@ -52,9 +47,7 @@ enum OutputMode {
 }
 
 pub async fn execute(args: SuggestsArgs) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
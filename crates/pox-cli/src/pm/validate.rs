@@ -0,0 +1,113 @@
+//! Validate command - check composer.json for errors and likely mistakes.
+//!
+//! Exits with 2 when errors are found, with 1 when only warnings are found and `--strict`
+//! is given, and with 0 otherwise.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::path::PathBuf;
+
+use pox_pm::json::{ComposerLock, Diagnostic, validate_manifest};
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Path to composer.json file
+    #[arg(default_value = "./composer.json")]
+    pub file: PathBuf,
+
+    /// Output format: text or json
+    #[arg(short = 'f', long, default_value = "text")]
+    pub format: String,
+
+    /// Return a non-zero exit code for warnings as well as errors
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Do not check if the lock file is up to date
+    #[arg(long)]
+    pub no_check_lock: bool,
+}
+
+pub async fn execute(args: ValidateArgs) -> Result<i32> {
+    if args.format != "text" && args.format != "json" {
+        eprintln!(
+            "Error: Unsupported format '{}'. See help for supported formats.",
+            args.format
+        );
+        return Ok(1);
+    }
+
+    if !args.file.is_file() {
+        eprintln!("{} {} not found.",
+            style("Error:").red().bold(),
+            args.file.display()
+        );
+        return Ok(3);
+    }
+
+    let content = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("Failed to read {}", args.file.display()))?;
+    let base_dir = args.file.parent().unwrap_or_else(|| std::path::Path::new("."));
+    let mut report = validate_manifest(&content, Some(base_dir));
+
+    let lock_path = args.file.with_extension("lock");
+    if !args.no_check_lock && report.is_valid() && lock_path.is_file() {
+        let lock: ComposerLock = serde_json::from_str(
+            &std::fs::read_to_string(&lock_path).context("Failed to read composer.lock")?
+        ).context("Failed to parse composer.lock")?;
+        report.check_lock(&content, &lock);
+    }
+
+    let code = if !report.errors.is_empty() {
+        2
+    } else if args.strict && !report.warnings.is_empty() {
+        1
+    } else {
+        0
+    };
+
+    if args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(code);
+    }
+
+    let file = args.file.display();
+    if report.errors.is_empty() && report.warnings.is_empty() {
+        println!("{} {} is valid", style("Success:").green().bold(), file);
+        return Ok(code);
+    }
+
+    if report.errors.is_empty() {
+        println!("{} {} is valid, but with a few warnings", style("Info:").cyan(), file);
+    } else {
+        println!("{} {} is invalid, the following errors/warnings were found:", style("Error:").red().bold(), file);
+        print_diagnostics("Errors", &report.errors);
+    }
+    print_diagnostics("Warnings", &report.warnings);
+
+    if !report.publishable {
+        println!();
+        println!("{} The package cannot be published until the above is fixed",
+            style("Hint:").yellow()
+        );
+    }
+
+    Ok(code)
+}
+
+fn print_diagnostics(title: &str, diagnostics: &[Diagnostic]) {
+    if diagnostics.is_empty() {
+        return;
+    }
+    println!();
+    println!("# {}", title);
+    for diagnostic in diagnostics {
+        let location = if diagnostic.path.is_empty() { String::new() } else { format!(" ({})", diagnostic.path) };
+        println!("- {}{} {}",
+            diagnostic.message,
+            style(location).dim(),
+            style(format!("[{}]", diagnostic.code)).dim()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Verify command - check that installed packages match composer.lock.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::path::Path;
+
+use pox_pm::{
+    config::Config,
+    downloader::{DistManifest, FileChange, GitDownloader},
+    installer::InstallerPaths,
+    json::{ComposerJson, ComposerLock, LockedPackage},
+    package::Package,
+};
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Skip packages from require-dev
+    #[arg(long)]
+    pub no_dev: bool,
+
+    /// Also list packages that passed or could not be fully checked
+    #[arg(short = 'v', long)]
+    pub verbose: bool,
+}
+
+/// Result of verifying one installed package
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    /// The installed files match what was locked
+    Intact,
+    /// Installed, but there is nothing recorded to compare the files against
+    PresenceOnly,
+    /// Not checked, with the reason
+    Skipped(String),
+    /// The installation differs from the lock file
+    Drift(Vec<String>),
+}
+
+pub async fn execute(args: VerifyArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+
+    let lock_path = working_dir.join("composer.lock");
+    if !lock_path.exists() {
+        eprintln!("Error: No composer.lock found. Run 'install' or 'update' first.");
+        return Ok(1);
+    }
+    let lock_content = std::fs::read_to_string(&lock_path).context("Failed to read composer.lock")?;
+    let lock: ComposerLock = serde_json::from_str(&lock_content).context("Failed to parse composer.lock")?;
+
+    let json_path = working_dir.join("composer.json");
+    let composer_json: ComposerJson = if json_path.exists() {
+        let content = std::fs::read_to_string(&json_path)?;
+        serde_json::from_str(&content).context("Failed to parse composer.json")?
+    } else {
+        ComposerJson::default()
+    };
+
+    let config = Config::build(Some(&working_dir), true)?;
+    let vendor_dir = working_dir.join(&config.vendor_dir);
+    let installer_paths = InstallerPaths::new(&working_dir, &composer_json.extra);
+
+    let dev_packages: &[LockedPackage] = if args.no_dev { &[] } else { &lock.packages_dev };
+    let mut drifted = 0;
+    let mut checked = 0;
+
+    for locked in lock.packages.iter().chain(dev_packages) {
+        let package = Package::from(locked);
+        if package.is_metapackage() {
+            continue;
+        }
+
+        let install_path = installer_paths.install_path(
+            &vendor_dir,
+            &package.name,
+            &package.package_type,
+            package.extra.as_ref(),
+        );
+        checked += 1;
+
+        match verify_package(&vendor_dir, &install_path, &package)? {
+            Verdict::Intact => {
+                if args.verbose {
+                    println!("{} {}", style("✓").green(), package.name);
+                }
+            }
+            Verdict::PresenceOnly => {
+                if args.verbose {
+                    println!("{} {} (installed, no manifest to compare files against)", style("✓").green(), package.name);
+                }
+            }
+            Verdict::Skipped(reason) => {
+                if args.verbose {
+                    println!("{} {} ({})", style("-").yellow(), package.name, reason);
+                }
+            }
+            Verdict::Drift(problems) => {
+                drifted += 1;
+                println!("{} {}", style("✗").red(), package.name);
+                for problem in problems {
+                    println!("    {}", problem);
+                }
+            }
+        }
+    }
+
+    if drifted > 0 {
+        println!();
+        println!(
+            "{} {} of {} packages do not match composer.lock, run 'pm reinstall' on them to restore",
+            style("Error:").red().bold(),
+            drifted,
+            checked
+        );
+        return Ok(1);
+    }
+
+    println!("{} All {} packages match composer.lock", style("Success:").green().bold(), checked);
+    Ok(0)
+}
+
+/// Compare an installed package against its locked version
+///
+/// Source installs are checked against the locked commit, dist installs against the
+/// manifest recorded at extraction. Symlinked path repositories point at code that is
+/// edited in place, so only their target is checked.
+fn verify_package(vendor_dir: &Path, install_path: &Path, package: &Package) -> Result<Verdict> {
+    let is_symlink = std::fs::symlink_metadata(install_path).is_ok_and(|m| m.file_type().is_symlink());
+    if is_symlink {
+        if install_path.is_dir() {
+            return Ok(Verdict::Skipped("symlinked path repository".to_string()));
+        }
+        return Ok(Verdict::Drift(vec!["symlink target is missing".to_string()]));
+    }
+
+    if !install_path.is_dir() {
+        return Ok(Verdict::Drift(vec![format!("not installed at {}", install_path.display())]));
+    }
+
+    if install_path.join(".git").exists() {
+        let mut problems = Vec::new();
+        let locked_reference = package.source.as_ref().map(|s| s.reference.as_str());
+        match GitDownloader::get_head_commit(install_path) {
+            Ok(head) => {
+                if let Some(reference) = locked_reference {
+                    if !reference.is_empty() && !head.starts_with(reference) {
+                        problems.push(format!("checked out at {} instead of {}", head, reference));
+                    }
+                }
+            }
+            Err(e) => problems.push(format!("could not read git HEAD: {}", e)),
+        }
+        let changes = GitDownloader::get_local_changes(install_path)
+            .with_context(|| format!("Failed to read git status of {}", package.name))?;
+        problems.extend(changes);
+
+        return Ok(if problems.is_empty() { Verdict::Intact } else { Verdict::Drift(problems) });
+    }
+
+    let Some(manifest) = DistManifest::load(vendor_dir, &package.name)? else {
+        return Ok(Verdict::PresenceOnly);
+    };
+
+    let mut problems = Vec::new();
+    if manifest.version != package.version {
+        problems.push(format!(
+            "installed version {} does not match locked version {}",
+            manifest.version, package.version
+        ));
+    }
+    for change in manifest.changes(install_path)? {
+        problems.push(match change {
+            FileChange::Modified(path) => format!("M {}", path),
+            FileChange::Deleted(path) => format!("D {}", path),
+        });
+    }
+
+    Ok(if problems.is_empty() { Verdict::Intact } else { Verdict::Drift(problems) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_verify_dist_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let install_path = vendor_dir.join("acme/lib");
+        let package = Package::new("acme/lib", "1.0.0.0");
+
+        assert_eq!(
+            verify_package(&vendor_dir, &install_path, &package).unwrap(),
+            Verdict::Drift(vec![format!("not installed at {}", install_path.display())])
+        );
+
+        std::fs::create_dir_all(&install_path).unwrap();
+        std::fs::write(install_path.join("Lib.php"), "<?php").unwrap();
+        assert_eq!(verify_package(&vendor_dir, &install_path, &package).unwrap(), Verdict::PresenceOnly);
+
+        DistManifest::create(&install_path, "1.0.0.0")
+            .unwrap()
+            .save(&vendor_dir, "acme/lib")
+            .unwrap();
+        assert_eq!(verify_package(&vendor_dir, &install_path, &package).unwrap(), Verdict::Intact);
+
+        std::fs::remove_file(install_path.join("Lib.php")).unwrap();
+        let newer = Package::new("acme/lib", "1.1.0.0");
+        assert_eq!(
+            verify_package(&vendor_dir, &install_path, &newer).unwrap(),
+            Verdict::Drift(vec![
+                "installed version 1.0.0.0 does not match locked version 1.1.0.0".to_string(),
+                "D Lib.php".to_string(),
+            ])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_skips_symlinked_path_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let target = temp_dir.path().join("packages/lib");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::create_dir_all(vendor_dir.join("acme")).unwrap();
+        let install_path = vendor_dir.join("acme/lib");
+        std::os::unix::fs::symlink(&target, &install_path).unwrap();
+
+        let package = Package::new("acme/lib", "dev-main");
+        assert_eq!(
+            verify_package(&vendor_dir, &install_path, &package).unwrap(),
+            Verdict::Skipped("symlinked path repository".to_string())
+        );
+
+        std::fs::remove_dir_all(&target).unwrap();
+        assert_eq!(
+            verify_package(&vendor_dir, &install_path, &package).unwrap(),
+            Verdict::Drift(vec!["symlink target is missing".to_string()])
+        );
+    }
+}
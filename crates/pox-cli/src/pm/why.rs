@@ -2,20 +2,19 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use pox_pm::{
     ComposerBuilder, DependencyResult, Repository,
     config::Config,
-    find_packages_with_replacers_and_providers, get_dependents,
-    is_platform_package,
+    find_packages_with_replacers_and_providers, get_blocking_dependents, get_dependents,
     json::{ComposerJson, ComposerLock},
+    package::detect_root_version,
 };
 
 #[derive(Args, Debug)]
 pub struct WhyArgs {
-    /// Package name to analyze
+    /// Package name to analyze, optionally with a constraint (vendor/package:^2.0)
     pub package: String,
 
     /// Version constraint (optional)
@@ -29,15 +28,14 @@ pub struct WhyArgs {
     #[arg(short = 'r', long)]
     pub recursive: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// Only show the packages whose requirements exclude the given constraint, or
+    /// that conflict with it, to find what blocks an upgrade. Exits with 1 if any do
+    #[arg(short = 'm', long)]
+    pub match_constraint: bool,
 }
 
-pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
-    let working_dir = args
-        .working_dir
-        .canonicalize()
+pub async fn execute(args: WhyArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
@@ -65,11 +63,16 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
     installed_repo.load().await.ok();
     let mut installed_packages = installed_repo.get_packages().await;
 
+    let root_version = detect_root_version(
+        &working_dir,
+        composer_json.version.as_deref(),
+        &composer_json.get_branch_aliases(),
+    );
     let root_package = pox_pm::Package {
         name: composer_json.name.clone().unwrap_or_else(|| "__root__".to_string()),
         pretty_name: composer_json.name.clone(),
-        version: composer_json.version.clone().unwrap_or_else(|| "dev-main".to_string()),
-        pretty_version: composer_json.version.clone(),
+        version: root_version.version,
+        pretty_version: Some(root_version.pretty_version),
         package_type: "root-package".to_string(),
         require: composer_json.require.clone(),
         require_dev: composer_json.require_dev.clone(),
@@ -91,8 +94,16 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
         return Ok(1);
     }
 
-    let needle = &args.package;
-    let constraint_str = args.constraint.as_deref().unwrap_or("*");
+    // "vendor/package:^2.0" is short for "vendor/package ^2.0"
+    let (needle, constraint_str) = match (args.package.split_once(':'), args.constraint.as_deref()) {
+        (Some((name, constraint)), None) => (name, constraint),
+        (_, constraint) => (args.package.as_str(), constraint.unwrap_or("*")),
+    };
+
+    if args.match_constraint && constraint_str == "*" {
+        eprintln!("Error: --match-constraint needs a constraint, e.g. {}:^2.0", needle);
+        return Ok(1);
+    }
 
     let constraint = if constraint_str != "*" {
         let parser = pox_semver::VersionParser;
@@ -107,10 +118,11 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
         None
     };
 
+    // Blockers are looked for because no installed version matches the constraint
     let matching_packages = find_packages_with_replacers_and_providers(
         &installed_packages,
         needle,
-        constraint.as_ref().map(|v| &**v),
+        constraint.as_ref().filter(|_| !args.match_constraint).map(|v| &**v),
     );
 
     if matching_packages.is_empty() {
@@ -118,47 +130,42 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
         return Ok(1);
     }
 
-    let matched_package = installed_packages.iter()
-        .find(|p| p.name.to_lowercase() == needle.to_lowercase());
+    let recursive = args.tree || args.recursive;
 
-    if matched_package.is_some() && inverted {
-        if let Some(pkg) = matched_package {
+    if let (true, Some(constraint)) = (args.match_constraint, &constraint) {
+        let blockers = get_blocking_dependents(&installed_packages, needle, constraint.as_ref(), recursive);
+        if blockers.is_empty() {
             println!(
-                "Package \"{}\" {} is already installed! To find out why, run `pox pm why {}`",
-                needle,
-                pkg.pretty_version.as_deref().unwrap_or(&pkg.version),
-                needle
+                "There is no installed package preventing \"{}\" from being installed in versions matching {}",
+                needle, constraint_str
             );
             return Ok(0);
         }
-    }
 
-    let mut needles = vec![needle.to_string()];
-    if inverted {
-        for package in &matching_packages {
-            for (target, _constraint) in &package.replace {
-                needles.push(target.clone());
-            }
+        println!("Installed packages preventing \"{}\" from being installed in versions matching {}:", needle, constraint_str);
+        println!();
+        if args.tree {
+            print_tree(&blockers, &matching_packages[0]);
+        } else {
+            print_table(&blockers);
         }
+        // Like Composer's why-not, a blocked version fails so scripts can check for upgrades
+        return Ok(1);
     }
 
-    let recursive = args.tree || args.recursive;
+    let needles = vec![needle.to_string()];
     let results = get_dependents(
         &installed_packages,
         &needles,
         constraint.as_ref().map(|v| &**v),
-        inverted,
+        false,
         recursive,
         None,
     );
 
     if results.is_empty() {
         let extra = if constraint.is_some() {
-            format!(
-                " in versions {}matching {}",
-                if inverted { "not " } else { "" },
-                constraint_str
-            )
+            format!(" in versions matching {}", constraint_str)
         } else {
             String::new()
         };
@@ -166,7 +173,7 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
             "There is no installed package depending on \"{}\"{}",
             needle, extra
         );
-        return Ok(if inverted { 0 } else { 1 });
+        return Ok(1);
     }
 
     if args.tree {
@@ -175,30 +182,7 @@ pub async fn execute(args: WhyArgs, inverted: bool) -> Result<i32> {
         print_table(&results);
     }
 
-    if inverted && args.constraint.is_some() && !is_platform_package(needle) {
-        let mut command = "update";
-
-        for req in &composer_json.require {
-            if req.0.to_lowercase() == needle.to_lowercase() {
-                command = "require";
-                break;
-            }
-        }
-
-        for req in &composer_json.require_dev {
-            if req.0.to_lowercase() == needle.to_lowercase() {
-                command = "require --dev";
-                break;
-            }
-        }
-
-        eprintln!(
-            "\nNot finding what you were looking for? Try calling `pox {} \"{}:{}\" --dry-run` to get another view on the problem.",
-            command, needle, constraint_str
-        );
-    }
-
-    Ok(if inverted { 1 } else { 0 })
+    Ok(0)
 }
 
 fn print_table(results: &[DependencyResult]) {
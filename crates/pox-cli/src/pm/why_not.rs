@@ -0,0 +1,96 @@
+//! Why-not command - explain what prevents a package version from being installed.
+//!
+//! The package is added to the root requirements of a copy of the project and the solver
+//! is run against it, without touching composer.json, the lock file or vendor.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+
+use pox_pm::{
+    ComposerBuilder,
+    config::Config,
+    installer::Installer,
+    json::{ComposerJson, ComposerLock},
+};
+
+use crate::pm::platform::PlatformInfo;
+
+#[derive(Args, Debug)]
+pub struct WhyNotArgs {
+    /// Package name to check
+    pub package: String,
+
+    /// Version or constraint to check (e.g. 2.0 or ^2.0)
+    pub version: String,
+}
+
+pub async fn execute(args: WhyNotArgs) -> Result<i32> {
+    let working_dir = std::env::current_dir()
+        .context("Failed to resolve working directory")?;
+
+    let json_path = working_dir.join("composer.json");
+    if !json_path.exists() {
+        eprintln!("{} No composer.json found in {}",
+            style("Error:").red().bold(),
+            working_dir.display()
+        );
+        return Ok(1);
+    }
+
+    let composer_json: ComposerJson = serde_json::from_str(
+        &std::fs::read_to_string(&json_path).context("Failed to read composer.json")?
+    ).context("Failed to parse composer.json")?;
+
+    let lock_path = working_dir.join("composer.lock");
+    let lock: Option<ComposerLock> = if lock_path.exists() {
+        let content = std::fs::read_to_string(&lock_path)
+            .context("Failed to read composer.lock")?;
+        Some(serde_json::from_str(&content).context("Failed to parse composer.lock")?)
+    } else {
+        None
+    };
+
+    let config = Config::build(Some(&working_dir), true)?;
+    let platform = PlatformInfo::detect();
+
+    let composer = ComposerBuilder::new(working_dir)
+        .with_config(config)
+        .with_composer_json(composer_json)
+        .with_composer_lock(lock)
+        .with_platform_packages(platform.to_packages())
+        .build()?;
+
+    let Some(failure) = Installer::new(composer).why_not(&args.package, &args.version).await else {
+        println!("{} There is no conflict, {} {} would install",
+            style("Success:").green().bold(),
+            args.package,
+            args.version
+        );
+        return Ok(0);
+    };
+
+    let platform_problems = failure.platform_descriptions();
+    if !platform_problems.is_empty() {
+        println!("{} {} {} is blocked by your platform:",
+            style("Info:").cyan(),
+            args.package,
+            args.version
+        );
+        for description in platform_problems {
+            println!();
+            println!("{}", description);
+        }
+        return Ok(1);
+    }
+
+    println!("{} {} {} cannot be installed together with your requirements:",
+        style("Info:").cyan(),
+        args.package,
+        args.version
+    );
+    println!();
+    println!("{}", failure);
+
+    Ok(1)
+}
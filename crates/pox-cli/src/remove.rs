@@ -3,13 +3,12 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
 
 use pox_pm::{
     ComposerBuilder,
     config::Config,
-    installer::Installer,
-    json::{ComposerJson, ComposerLock},
+    installer::{Installer, ProcessLock},
+    json::{ComposerJson, ComposerLock, JsonEditor},
 };
 use crate::pm::platform::PlatformInfo;
 
@@ -42,14 +41,10 @@ pub struct RemoveArgs {
     /// Optimize autoloader
     #[arg(short = 'o', long)]
     pub optimize_autoloader: bool,
-
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
 }
 
 pub async fn execute(args: RemoveArgs) -> Result<i32> {
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     let json_path = working_dir.join("composer.json");
@@ -61,6 +56,16 @@ pub async fn execute(args: RemoveArgs) -> Result<i32> {
         return Ok(1);
     }
 
+    // Load config
+    let config = Config::build(Some(&working_dir), true)?;
+
+    // Like install and update, hold the vendor lock while composer.json and composer.lock are read
+    let _vendor_lock = if args.dry_run {
+        None
+    } else {
+        Some(ProcessLock::acquire_async(working_dir.join(&config.vendor_dir), true).await?)
+    };
+
     // Load composer.json
     let content = std::fs::read_to_string(&json_path)?;
     let composer_json: ComposerJson = serde_json::from_str(&content)?;
@@ -75,12 +80,13 @@ pub async fn execute(args: RemoveArgs) -> Result<i32> {
         None
     };
 
-    // Load config
-    let config = Config::build(Some(&working_dir), true)?;
 
     // Detect platform
     let platform = PlatformInfo::detect();
 
+    // The builder merges files included through extra.merge-plugin; only the root file is written back
+    let mut editor = JsonEditor::parse(&content)?;
+
     // Create Composer using builder
     let mut composer = ComposerBuilder::new(working_dir.clone())
         .with_config(config)
@@ -101,6 +107,8 @@ pub async fn execute(args: RemoveArgs) -> Result<i32> {
         // Try to remove from require or require-dev
         let was_in_require = composer.composer_json.require.shift_remove(name).is_some();
         let was_in_dev = composer.composer_json.require_dev.shift_remove(name).is_some();
+        editor.remove_link("require", name);
+        editor.remove_link("require-dev", name);
 
         if was_in_require || was_in_dev {
             println!("  {} {}",
@@ -123,9 +131,7 @@ pub async fn execute(args: RemoveArgs) -> Result<i32> {
 
     // Write updated composer.json
     if !args.dry_run {
-        let content = serde_json::to_string_pretty(&composer.composer_json)
-            .context("Failed to serialize composer.json")?;
-        std::fs::write(&json_path, content)
+        editor.save(&json_path)
             .context("Failed to write composer.json")?;
     }
 
@@ -133,11 +139,11 @@ pub async fn execute(args: RemoveArgs) -> Result<i32> {
     if !args.no_update {
         let installer = Installer::new(composer);
 
-        installer.update(
+        Ok(installer.update(
             args.optimize_autoloader,
             false,
             None,
-        ).await
+        ).await?)
     } else {
         println!("{} {} packages removed from composer.json",
             style("Success:").green().bold(),
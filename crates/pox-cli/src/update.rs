@@ -8,8 +8,9 @@ use std::path::PathBuf;
 use pox_pm::{
     ComposerBuilder,
     config::Config,
-    installer::Installer,
+    installer::{Installer, ProcessLock},
     json::{ComposerJson, ComposerLock},
+    solver::UpdateAllowTransitive,
 };
 
 use crate::pm::platform::PlatformInfo;
@@ -20,8 +21,8 @@ pub struct UpdateArgs {
     #[arg(value_name = "PACKAGES")]
     pub packages: Vec<String>,
 
-    /// Prefer source installation
-    #[arg(long)]
+    /// Prefer source installation, falling back to dist for packages without a source
+    #[arg(long, conflicts_with = "prefer_dist")]
     pub prefer_source: bool,
 
     /// Prefer dist installation
@@ -48,11 +49,16 @@ pub struct UpdateArgs {
     #[arg(long)]
     pub no_progress: bool,
 
+    /// Do not list packages suggested by newly installed packages
+    #[arg(long)]
+    pub no_suggest: bool,
+
     /// Update also dependencies of the listed packages
     #[arg(short = 'w', long)]
     pub with_dependencies: bool,
 
-    /// Update all dependencies including root requirements
+    /// Update also dependencies of the listed packages including root requirements,
+    /// and packages depending on the listed packages
     #[arg(short = 'W', long)]
     pub with_all_dependencies: bool,
 
@@ -64,17 +70,42 @@ pub struct UpdateArgs {
     #[arg(long)]
     pub prefer_lowest: bool,
 
+    /// Resolve the lowest stable versions the constraints allow, to test against the minimum
+    /// supported versions (same as --prefer-lowest --prefer-stable)
+    #[arg(long)]
+    pub lowest: bool,
+
     /// Only update the lock file
     #[arg(long)]
     pub lock: bool,
 
+    /// Skip the install step after updating the composer.lock file
+    #[arg(long)]
+    pub no_install: bool,
+
     /// Optimize autoloader
     #[arg(short = 'o', long)]
     pub optimize_autoloader: bool,
 
-    /// Working directory
-    #[arg(short = 'd', long, default_value = ".")]
-    pub working_dir: PathBuf,
+    /// Write the generated SAT problem to this file as DIMACS CNF, with a <FILE>.json
+    /// sidecar mapping variables to package versions
+    #[arg(long, value_name = "FILE")]
+    pub dump_solve: Option<PathBuf>,
+
+    /// Report solver propagations, conflicts and backtracks, and the packages involved
+    /// in the most conflicts
+    #[arg(long)]
+    pub solver_stats: bool,
+
+    /// When the requirements can't be resolved, look for the root constraints to relax to
+    /// make them installable (solves again for every candidate, so it can be slow)
+    #[arg(long)]
+    pub explain_fix: bool,
+
+    /// Fail right away when another process is installing into the vendor directory,
+    /// instead of waiting for it to finish
+    #[arg(long)]
+    pub no_wait: bool,
 
     // Common Composer flags (for compatibility)
     /// Force ANSI output
@@ -104,6 +135,15 @@ pub struct UpdateArgs {
     /// Audit output format (table, plain, json, or summary)
     #[arg(long, default_value = "summary")]
     pub audit_format: String,
+
+    /// Fail if the audit finds advisories at or above --audit-level, or abandoned packages
+    /// when the audit.abandoned config is "fail"
+    #[arg(long, conflicts_with = "no_audit")]
+    pub audit: bool,
+
+    /// Minimum advisory severity that fails the command with --audit (low, medium, high, or critical)
+    #[arg(long, default_value = "low", value_parser = ["low", "medium", "high", "critical"])]
+    pub audit_level: String,
 }
 
 pub async fn execute(args: UpdateArgs) -> Result<i32> {
@@ -125,7 +165,7 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
         .format_target(false)
         .init();
 
-    let working_dir = args.working_dir.canonicalize()
+    let working_dir = std::env::current_dir()
         .context("Failed to resolve working directory")?;
 
     // Check for composer.json
@@ -138,6 +178,18 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
         return Ok(1);
     }
 
+    // Load config
+    let config = Config::build(Some(&working_dir), true)?;
+
+    // Lock the vendor directory before reading composer.json and composer.lock, so waiting
+    // for another install or update doesn't leave us with what it replaced. --lock still
+    // takes it, since installs read the lock file it writes
+    let _vendor_lock = if args.dry_run {
+        None
+    } else {
+        Some(ProcessLock::acquire_async(working_dir.join(&config.vendor_dir), !args.no_wait).await?)
+    };
+
     // Parse composer.json
     let json_content = std::fs::read_to_string(&json_path)
         .context("Failed to read composer.json")?;
@@ -156,9 +208,6 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
         None
     };
 
-    // Load config
-    let config = Config::build(Some(&working_dir), true)?;
-
     // Detect platform
     let platform = PlatformInfo::detect();
 
@@ -170,7 +219,8 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
         .with_platform_packages(platform.to_packages())
         .dry_run(args.dry_run)
         .no_dev(args.no_dev)
-        .prefer_lowest(args.prefer_lowest);
+        .prefer_lowest(args.prefer_lowest || args.lowest)
+        .prefer_stable(args.prefer_stable || args.lowest);
 
     // Apply prefer_source/prefer_dist flags
     if args.prefer_source {
@@ -182,7 +232,19 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
     let composer = builder.build()?;
 
     // Run Installer
-    let installer = Installer::new(composer);
+    let update_allow_transitive = if args.with_all_dependencies {
+        UpdateAllowTransitive::All
+    } else if args.with_dependencies {
+        UpdateAllowTransitive::DependenciesNoRootRequire
+    } else {
+        UpdateAllowTransitive::ListedOnly
+    };
+    let installer = Installer::new(composer)
+        .no_suggest(args.no_suggest)
+        .update_allow_transitive(update_allow_transitive)
+        .dump_solve(args.dump_solve.clone())
+        .solver_stats(args.solver_stats)
+        .explain_fix(args.explain_fix);
 
     let update_packages = if args.packages.is_empty() {
         None
@@ -192,23 +254,23 @@ pub async fn execute(args: UpdateArgs) -> Result<i32> {
 
     let result = installer.update(
         args.optimize_autoloader,
-        args.lock,
+        args.lock || args.no_install,
         update_packages,
     ).await;
 
     if result.is_ok() && !skip_audit {
-        let audit_args = crate::pm::audit::AuditArgs {
-            no_dev: args.no_dev,
-            format: args.audit_format.clone(),
-            locked: false,
-            abandoned: Some("report".to_string()),
-            working_dir: working_dir.clone(),
-        };
-
-        if let Err(e) = crate::pm::audit::execute(audit_args).await {
-            eprintln!("Warning: Audit failed: {}", e);
+        let exit_code = crate::pm::audit::audit_after_install(
+            working_dir.clone(),
+            args.no_dev,
+            args.audit_format.clone(),
+            args.audit,
+            args.audit_level.clone(),
+        ).await;
+
+        if exit_code != 0 {
+            return Ok(exit_code);
         }
     }
 
-    result
+    Ok(result?)
 }
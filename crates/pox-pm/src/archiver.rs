@@ -0,0 +1,466 @@
+//! Package archiving (zip, tar), as done by `composer archive`.
+//!
+//! Files are collected from a package directory and filtered like Composer does: first by
+//! the `export-ignore` attributes of its `.gitattributes` files, then by the package's
+//! `archive.exclude` patterns. Both use gitignore-style rules where later rules win, so a
+//! `!keep` rule brings back files excluded by an earlier one.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use regex::Regex;
+use walkdir::WalkDir;
+
+use crate::{ComposerError, Result};
+
+/// Directories of version control systems, never archived
+const VCS_DIRS: &[&str] = &[".git", ".svn", ".hg", ".bzr", "_darcs", "CVS", ".arch-params", ".monotone"];
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+}
+
+impl ArchiveFormat {
+    /// File extension of the format, without a leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::Tar => "tar",
+        }
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = ComposerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "zip" => Ok(ArchiveFormat::Zip),
+            "tar" => Ok(ArchiveFormat::Tar),
+            _ => Err(ComposerError::Config(format!(
+                "Unsupported archive format \"{}\", expected zip or tar",
+                s
+            ))),
+        }
+    }
+}
+
+/// Ordered gitignore-style rules; the last matching rule decides whether a path is excluded
+#[derive(Debug, Default)]
+struct ExcludeFilter {
+    rules: Vec<(Regex, bool)>,
+}
+
+impl ExcludeFilter {
+    /// Rules from `archive.exclude` patterns
+    fn from_patterns(patterns: &[String]) -> Self {
+        Self {
+            rules: patterns.iter().filter_map(|p| exclude_rule(p)).collect(),
+        }
+    }
+
+    /// Rules from the `export-ignore` and `-export-ignore` attributes of a `.gitattributes` file
+    fn from_gitattributes(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let attributes: Vec<&str> = parts.collect();
+                if attributes.contains(&"export-ignore") {
+                    exclude_rule(pattern)
+                } else if attributes.contains(&"-export-ignore") {
+                    exclude_rule(&format!("!{}", pattern))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Apply the rules to a path relative to the archive root, starting with a `/`
+    fn filter(&self, relative_path: &str, mut exclude: bool) -> bool {
+        for (pattern, negate) in &self.rules {
+            if pattern.is_match(relative_path) {
+                exclude = !negate;
+            }
+        }
+        exclude
+    }
+}
+
+/// Compile a gitignore-style rule into a regex and whether it is negated
+///
+/// A leading `/` anchors the rule at the archive root, a rule without a `/` matches a
+/// file or directory name at any depth, and a matching directory excludes its contents.
+fn exclude_rule(rule: &str) -> Option<(Regex, bool)> {
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return None;
+    }
+    let (negate, rule) = match rule.strip_prefix('!') {
+        Some(rule) => (true, rule),
+        None => (false, rule),
+    };
+
+    let mut pattern = String::new();
+    let rule = if let Some(rule) = rule.strip_prefix('/') {
+        pattern.push_str("^/");
+        rule
+    } else if let Some(rule) = rule.strip_suffix('/') {
+        pattern.push('/');
+        rule
+    } else if !rule.contains('/') {
+        pattern.push('/');
+        rule
+    } else {
+        rule
+    };
+    pattern.push_str(&glob_to_regex(rule));
+    pattern.push_str("(?:$|/)");
+
+    Regex::new(&pattern).ok().map(|regex| (regex, negate))
+}
+
+/// Translate a glob to a regex fragment: `**` crosses directories, `*` and `?` do not
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    let mut in_braces = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.next_if_eq(&'/').is_some() {
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                regex.push('[');
+                if chars.next_if_eq(&'!').is_some() {
+                    regex.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        regex.push('\\');
+                    }
+                    regex.push(c);
+                }
+                regex.push(']');
+            }
+            '{' => {
+                in_braces = true;
+                regex.push_str("(?:");
+            }
+            '}' if in_braces => {
+                in_braces = false;
+                regex.push(')');
+            }
+            ',' if in_braces => regex.push('|'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+/// `export-ignore` rules of all `.gitattributes` files in a package, by directory
+///
+/// Patterns of a nested `.gitattributes` are relative to its directory and take precedence
+/// over those of its parent directories, as with `git archive`.
+#[derive(Debug, Default)]
+struct GitAttributes {
+    /// Filters keyed by directory relative to the package root, parents first
+    filters: Vec<(PathBuf, ExcludeFilter)>,
+}
+
+impl GitAttributes {
+    /// Read the `.gitattributes` files among `files`, relative to `source_dir`
+    fn load(source_dir: &Path, files: &[PathBuf]) -> Self {
+        let mut filters: Vec<(PathBuf, ExcludeFilter)> = files
+            .iter()
+            .filter(|file| file.file_name().is_some_and(|name| name == ".gitattributes"))
+            .filter_map(|file| {
+                let content = std::fs::read_to_string(source_dir.join(file)).ok()?;
+                let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+                Some((dir, ExcludeFilter::from_gitattributes(&content)))
+            })
+            .collect();
+        filters.sort_by_key(|(dir, _)| dir.components().count());
+        Self { filters }
+    }
+
+    fn filter(&self, relative: &Path, mut exclude: bool) -> bool {
+        for (dir, filter) in &self.filters {
+            if let Ok(rest) = relative.strip_prefix(dir) {
+                exclude = filter.filter(&rooted_path(rest), exclude);
+            }
+        }
+        exclude
+    }
+}
+
+/// A relative path in the form the filters match against: `/`-separated with a leading `/`
+fn rooted_path(path: &Path) -> String {
+    format!("/{}", path.to_string_lossy().replace('\\', "/"))
+}
+
+/// All files below `source_dir`, relative and sorted
+///
+/// Version control directories are skipped, as are symlinks pointing outside of `source_dir`
+/// and symlinks to directories.
+fn walk_files(source_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(source_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !VCS_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()));
+
+    for entry in walker {
+        let entry = entry.map_err(io::Error::from)?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        if entry.path_is_symlink() {
+            let Ok(target) = entry.path().canonicalize() else {
+                continue;
+            };
+            if !target.starts_with(source_dir) || target.is_dir() {
+                continue;
+            }
+        }
+
+        if let Ok(relative) = entry.path().strip_prefix(source_dir) {
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Collect the files of `source_dir` not excluded by its `.gitattributes` or `composer_filter`
+fn collect_files(source_dir: &Path, composer_filter: &ExcludeFilter) -> Result<Vec<PathBuf>> {
+    let source_dir = source_dir.canonicalize()?;
+    let files = walk_files(&source_dir)?;
+    let git_attributes = GitAttributes::load(&source_dir, &files);
+
+    Ok(files
+        .into_iter()
+        .filter(|file| !composer_filter.filter(&rooted_path(file), git_attributes.filter(file, false)))
+        .collect())
+}
+
+/// Collect the files of a package directory as they end up in its dist archive, relative and sorted
+///
+/// Files marked `export-ignore` in the package's `.gitattributes` files are left out, matching
+/// the zips GitHub and Packagist build with `git archive`.
+pub fn export_files(package_dir: &Path) -> Result<Vec<PathBuf>> {
+    collect_files(package_dir, &ExcludeFilter::default())
+}
+
+/// Collect the files of `source_dir` that belong in its archive, relative and sorted
+///
+/// On top of [`export_files`], the package's `archive.exclude` patterns in `excludes` apply.
+/// They are applied last, so `!keep` brings back a file marked `export-ignore`.
+pub fn archivable_files(source_dir: &Path, excludes: &[String]) -> Result<Vec<PathBuf>> {
+    collect_files(source_dir, &ExcludeFilter::from_patterns(excludes))
+}
+
+/// Write `files`, relative to `source_dir`, into an archive at `target`
+pub fn create_archive(source_dir: &Path, files: &[PathBuf], format: ArchiveFormat, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let output = File::create(target)?;
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipWriter::new(output);
+            for file in files {
+                let path = source_dir.join(file);
+                let mut options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    options = options.unix_permissions(std::fs::metadata(&path)?.permissions().mode());
+                }
+
+                let name = file.to_string_lossy().replace('\\', "/");
+                zip.start_file(name, options).map_err(io::Error::other)?;
+                io::copy(&mut BufReader::new(File::open(&path)?), &mut zip)?;
+            }
+            zip.finish().map_err(io::Error::other)?;
+        }
+        ArchiveFormat::Tar => {
+            let mut tar = tar::Builder::new(output);
+            tar.follow_symlinks(true);
+            for file in files {
+                tar.append_path_with_name(source_dir.join(file), file)?;
+            }
+            tar.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// File name of a package archive without extension, like `acme-lib-1.2.0`
+///
+/// `archive_name` is the package's `archive.name`, used instead of its name when set.
+pub fn archive_file_name(name: &str, archive_name: Option<&str>, version: Option<&str>) -> String {
+    let base = archive_name.unwrap_or(name);
+    let name = match version {
+        Some(version) if !version.is_empty() => format!("{}-{}", base, version),
+        _ => base.to_string(),
+    };
+
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, path: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "x").unwrap();
+    }
+
+    #[test]
+    fn test_exclude_rules() {
+        let filter = ExcludeFilter::from_patterns(&[
+            "/tests".to_string(),
+            "*.md".to_string(),
+            "docs/".to_string(),
+            "!/docs/keep.md".to_string(),
+        ]);
+
+        assert!(filter.filter("/tests/FooTest.php", false));
+        assert!(!filter.filter("/src/tests.php", false));
+        assert!(!filter.filter("/src/tests/Foo.php", false));
+        assert!(filter.filter("/README.md", false));
+        assert!(filter.filter("/src/docs/index.html", false));
+        assert!(!filter.filter("/docs/keep.md", false));
+        assert!(!filter.filter("/src/Foo.php", false));
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let (regex, _) = exclude_rule("/src/**/*Test.php").unwrap();
+        assert!(regex.is_match("/src/FooTest.php"));
+        assert!(regex.is_match("/src/a/b/FooTest.php"));
+        assert!(!regex.is_match("/src/Foo.php"));
+
+        let (regex, _) = exclude_rule("*.{yml,xml}").unwrap();
+        assert!(regex.is_match("/phpunit.xml"));
+        assert!(regex.is_match("/config/services.yml"));
+        assert!(!regex.is_match("/composer.json"));
+    }
+
+    #[test]
+    fn test_gitattributes() {
+        let filter = ExcludeFilter::from_gitattributes(
+            "# comment\n* text=auto\n/tests export-ignore\n/tests/fixtures -export-ignore\n",
+        );
+
+        assert!(filter.filter("/tests/FooTest.php", false));
+        assert!(!filter.filter("/tests/fixtures/data.json", false));
+        assert!(!filter.filter("/src/Foo.php", false));
+    }
+
+    #[test]
+    fn test_archivable_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for file in ["src/Foo.php", "tests/FooTest.php", "tests/keep.php", "build/out.txt", ".git/HEAD", "README.md"] {
+            write(dir, file);
+        }
+        std::fs::write(dir.join(".gitattributes"), "/build export-ignore\n").unwrap();
+
+        let files = archivable_files(dir, &["/tests".to_string(), "!/tests/keep.php".to_string()]).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from(".gitattributes"),
+                PathBuf::from("README.md"),
+                PathBuf::from("src/Foo.php"),
+                PathBuf::from("tests/keep.php"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("pkg");
+        write(&dir, "src/Foo.php");
+        let files = vec![PathBuf::from("src/Foo.php")];
+
+        let zip_path = temp_dir.path().join("out/pkg.zip");
+        create_archive(&dir, &files, ArchiveFormat::Zip, &zip_path).unwrap();
+        let zip = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(zip.file_names().collect::<Vec<_>>(), vec!["src/Foo.php"]);
+
+        let tar_path = temp_dir.path().join("out/pkg.tar");
+        create_archive(&dir, &files, ArchiveFormat::Tar, &tar_path).unwrap();
+        let mut tar = tar::Archive::new(File::open(&tar_path).unwrap());
+        let names: Vec<PathBuf> = tar.entries().unwrap().map(|e| e.unwrap().path().unwrap().into_owned()).collect();
+        assert_eq!(names, vec![PathBuf::from("src/Foo.php")]);
+    }
+
+    #[test]
+    fn test_archive_file_name() {
+        assert_eq!(archive_file_name("acme/lib", None, Some("1.2.0")), "acme-lib-1.2.0");
+        assert_eq!(archive_file_name("acme/lib", Some("lib"), Some("dev-main")), "lib-dev-main");
+        assert_eq!(archive_file_name("acme/app", None, None), "acme-app");
+        assert_eq!("tar".parse::<ArchiveFormat>().unwrap(), ArchiveFormat::Tar);
+        assert!("rar".parse::<ArchiveFormat>().is_err());
+    }
+
+    #[test]
+    fn test_export_files_nested_gitattributes() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for file in ["src/Foo.php", "src/Foo.md", "docs/index.md", "docs/api/ref.md", "README.md"] {
+            write(dir, file);
+        }
+        std::fs::write(dir.join(".gitattributes"), "*.md export-ignore\n").unwrap();
+        std::fs::write(dir.join("docs/.gitattributes"), "*.md -export-ignore\n/api export-ignore\n").unwrap();
+
+        assert_eq!(
+            export_files(dir).unwrap(),
+            vec![
+                PathBuf::from(".gitattributes"),
+                PathBuf::from("docs/.gitattributes"),
+                PathBuf::from("docs/index.md"),
+                PathBuf::from("src/Foo.php"),
+            ]
+        );
+
+        // archive.exclude is applied after export-ignore
+        let files = archivable_files(dir, &["!/README.md".to_string(), "/docs/index.md".to_string()]).unwrap();
+        assert!(files.contains(&PathBuf::from("README.md")));
+        assert!(!files.contains(&PathBuf::from("docs/index.md")));
+    }
+}
@@ -0,0 +1,541 @@
+//! Security audit against the Packagist advisory database.
+//!
+//! Fetches advisories for a set of packages and matches them against the installed
+//! versions. Shared by `pm audit` and the audit step run after install and update, which
+//! only format the results.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use pox_semver::VersionParser;
+
+use crate::cache::Cache;
+use crate::error::{ComposerError, Result};
+use crate::http::{HttpClient, default_client};
+
+const ADVISORIES_URL: &str = "https://packagist.org/api/security-advisories/";
+
+/// How long a fetched set of advisories is reused
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Packages per request, to keep request bodies short for large projects
+const BATCH_SIZE: usize = 100;
+
+/// Advisories keyed by package name
+pub type Advisories = HashMap<String, Vec<SecurityAdvisory>>;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SecurityAdvisoriesResponse {
+    advisories: Advisories,
+}
+
+/// A security advisory affecting a range of versions of a package
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityAdvisory {
+    pub advisory_id: String,
+    pub package_name: String,
+    pub title: String,
+    #[serde(default)]
+    pub cve: Option<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    pub affected_versions: String,
+    pub reported_at: String,
+    #[serde(default)]
+    pub sources: Vec<AdvisorySource>,
+    /// Why the advisory is reported without confirming it affects the installed version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Where an advisory was reported
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AdvisorySource {
+    pub name: String,
+    pub remote_id: String,
+}
+
+/// Severity of an advisory, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl FromStr for Severity {
+    type Err = ComposerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(ComposerError::Config(format!(
+                "Invalid audit level \"{}\", expected low, medium, high or critical",
+                s
+            ))),
+        }
+    }
+}
+
+impl SecurityAdvisory {
+    /// Check if the advisory is at least as severe as `threshold`
+    ///
+    /// Advisories without a known severity only count at the lowest threshold.
+    pub fn meets(&self, threshold: Severity) -> bool {
+        match self.severity.as_deref().and_then(|s| s.parse::<Severity>().ok()) {
+            Some(severity) => severity >= threshold,
+            None => threshold == Severity::Low,
+        }
+    }
+}
+
+/// Advisories of a fetch, along with the batches of packages that could not be fetched
+#[derive(Debug, Default)]
+pub struct AdvisoryReport {
+    pub advisories: Advisories,
+    pub failed_batches: Vec<FailedBatch>,
+}
+
+/// A batch of packages whose advisories could not be fetched
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedBatch {
+    pub packages: Vec<String>,
+    pub error: String,
+}
+
+impl fmt::Display for FailedBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.packages.as_slice() {
+            [] => write!(f, "no packages: {}", self.error),
+            [package] => write!(f, "{}: {}", package, self.error),
+            [first, .., last] => write!(f, "{} packages from {} to {}: {}", self.packages.len(), first, last, self.error),
+        }
+    }
+}
+
+/// Fetches advisories from Packagist, caching the result per set of packages
+pub struct AdvisoryFetcher {
+    cache: Option<Cache>,
+    url: String,
+    refresh: bool,
+    http: Arc<HttpClient>,
+}
+
+impl AdvisoryFetcher {
+    /// Create a fetcher caching below `<cache_dir>/audit`, or not caching at all without one
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            cache: cache_dir.map(|dir| Cache::new(dir.join("audit"))),
+            url: ADVISORIES_URL.to_string(),
+            refresh: false,
+            http: default_client(),
+        }
+    }
+
+    /// Query a mirror of the Packagist advisories API instead
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Query the API through a client with the configured certificates, proxy and timeouts
+    pub fn with_http_client(mut self, http: Arc<HttpClient>) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Always query the API and overwrite the cache, however fresh it is
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Get all advisories for the given packages, regardless of version
+    ///
+    /// When the API can't be reached, advisories cached earlier are used whatever their age,
+    /// so an audit keeps working offline after a refresh. When only some batches fail, the
+    /// others are still returned and the failed ones listed in the report, which is then
+    /// not cached.
+    pub async fn fetch(&self, packages: &[String]) -> Result<AdvisoryReport> {
+        let cache_key = cache_key(packages);
+
+        if !self.refresh {
+            let fresh = self.cache.as_ref()
+                .and_then(|cache| cache.age(&cache_key).ok().flatten())
+                .is_some_and(|age| age < CACHE_TTL);
+            if let Some(cached) = self.read_cache(&cache_key).filter(|_| fresh) {
+                return Ok(AdvisoryReport { advisories: cached, ..Default::default() });
+            }
+        }
+
+        let report = match fetch_batched(&self.http, &self.url, packages).await {
+            Ok(report) => report,
+            Err(e) => match self.read_cache(&cache_key) {
+                Some(cached) => {
+                    eprintln!("Warning: Could not fetch security advisories, using cached ones: {}", e);
+                    return Ok(AdvisoryReport { advisories: cached, ..Default::default() });
+                }
+                None => return Err(e),
+            },
+        };
+
+        if let Some(cache) = self.cache.as_ref().filter(|_| report.failed_batches.is_empty()) {
+            if let Ok(data) = serde_json::to_vec(&serde_json::json!({ "advisories": &report.advisories })) {
+                let _ = cache.write(&cache_key, &data);
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn read_cache(&self, cache_key: &str) -> Option<Advisories> {
+        let data = self.cache.as_ref()?.read(cache_key).ok().flatten()?;
+        serde_json::from_slice::<SecurityAdvisoriesResponse>(&data)
+            .ok()
+            .map(|cached| cached.advisories)
+    }
+
+    /// Get the advisories affecting the given package versions
+    ///
+    /// `versions` maps package names to their installed version. Packages without
+    /// affecting advisories are left out of the result.
+    pub async fn fetch_affected(&self, versions: &HashMap<String, String>) -> Result<AdvisoryReport> {
+        let mut packages: Vec<String> = versions.keys().cloned().collect();
+        packages.sort();
+
+        let report = self.fetch(&packages).await?;
+        Ok(AdvisoryReport {
+            advisories: filter_affected(report.advisories, versions),
+            failed_batches: report.failed_batches,
+        })
+    }
+}
+
+/// Query the advisories API in batches of [`BATCH_SIZE`] packages
+///
+/// A failed batch doesn't stop the others; only when all of them fail is the error returned.
+async fn fetch_batched(client: &HttpClient, url: &str, packages: &[String]) -> Result<AdvisoryReport> {
+    let mut report = AdvisoryReport::default();
+    let mut last_error = None;
+
+    for batch in packages.chunks(BATCH_SIZE) {
+        match fetch_batch(client, url, batch).await {
+            Ok(advisories) => report.advisories.extend(advisories),
+            Err(e) => {
+                report.failed_batches.push(FailedBatch { packages: batch.to_vec(), error: e.to_string() });
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if report.failed_batches.len() == packages.chunks(BATCH_SIZE).len() => Err(e),
+        _ => Ok(report),
+    }
+}
+
+async fn fetch_batch(client: &HttpClient, url: &str, packages: &[String]) -> Result<Advisories> {
+    let form_data = packages
+        .iter()
+        .map(|p| format!("packages[]={}", p))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let response = client
+        .post_request(url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(form_data)
+        .send()
+        .await?
+        .error_for_status()?;
+    let response: SecurityAdvisoriesResponse = response.json().await?;
+    Ok(response.advisories)
+}
+
+/// Keep only the advisories affecting the given package versions
+///
+/// `versions` maps package names to their installed version. Advisories are matched by
+/// parsing their affected versions as a constraint, so `||`-joined ranges and branch names
+/// like `dev-master` are supported. When either side cannot be parsed the advisory is kept
+/// with a note rather than hidden.
+pub fn filter_affected(advisories: Advisories, versions: &HashMap<String, String>) -> Advisories {
+    let parser = VersionParser::new();
+
+    advisories
+        .into_iter()
+        .filter_map(|(name, advisories)| {
+            let version = versions.get(&name)?;
+            let normalized = parser.normalize(version).ok();
+            let affected: Vec<SecurityAdvisory> = advisories
+                .into_iter()
+                .filter_map(|mut advisory| {
+                    let Some(normalized) = &normalized else {
+                        advisory.note = Some(format!(
+                            "Could not parse the installed version {}, it may be affected",
+                            version
+                        ));
+                        return Some(advisory);
+                    };
+                    match parser.parse_constraints_cached(&advisory.affected_versions) {
+                        Ok(constraint) => constraint.matches_normalized(normalized).then_some(advisory),
+                        Err(_) => {
+                            advisory.note = Some(format!(
+                                "Could not parse the affected versions, {} may be affected",
+                                version
+                            ));
+                            Some(advisory)
+                        }
+                    }
+                })
+                .collect();
+            (!affected.is_empty()).then_some((name, affected))
+        })
+        .collect()
+}
+
+/// Cache key for a set of packages, independent of their order
+fn cache_key(packages: &[String]) -> String {
+    let mut sorted = packages.to_vec();
+    sorted.sort();
+    let mut hasher = DefaultHasher::new();
+    sorted.hash(&mut hasher);
+    format!("bulk-{:x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(package: &str, affected: &str, severity: Option<&str>) -> SecurityAdvisory {
+        SecurityAdvisory {
+            advisory_id: format!("PKSA-{}", affected),
+            package_name: package.to_string(),
+            title: "Remote code execution".to_string(),
+            cve: None,
+            link: None,
+            severity: severity.map(String::from),
+            affected_versions: affected.to_string(),
+            reported_at: "2024-01-01 00:00:00".to_string(),
+            sources: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_affected() {
+        let mut advisories = Advisories::new();
+        advisories.insert(
+            "acme/lib".to_string(),
+            vec![advisory("acme/lib", "<1.2.0", Some("high")), advisory("acme/lib", ">=2.0,<2.1", None)],
+        );
+        advisories.insert("acme/other".to_string(), vec![advisory("acme/other", "<1.0", None)]);
+        advisories.insert("acme/gone".to_string(), vec![advisory("acme/gone", "*", None)]);
+
+        let versions = HashMap::from([
+            ("acme/lib".to_string(), "1.1.0".to_string()),
+            ("acme/other".to_string(), "1.5.0".to_string()),
+        ]);
+
+        let affected = filter_affected(advisories, &versions);
+        assert_eq!(affected.len(), 1);
+        assert_eq!(affected["acme/lib"].len(), 1);
+        assert_eq!(affected["acme/lib"][0].affected_versions, "<1.2.0");
+        assert_eq!(affected["acme/lib"][0].note, None);
+    }
+
+    #[test]
+    fn test_filter_affected_ranges_and_branches() {
+        let mut advisories = Advisories::new();
+        advisories.insert(
+            "acme/lib".to_string(),
+            vec![
+                advisory("acme/lib", ">=1.0,<1.2|>=2.0,<2.1", None),
+                advisory("acme/lib", ">=1.0.0,<1.0.5 || >=2.0.0,<2.0.2", None),
+            ],
+        );
+        advisories.insert(
+            "acme/branch".to_string(),
+            vec![advisory("acme/branch", "dev-master|<1.0", None), advisory("acme/branch", "<1.0", None)],
+        );
+
+        let versions = HashMap::from([
+            ("acme/lib".to_string(), "2.0.3".to_string()),
+            ("acme/branch".to_string(), "dev-master".to_string()),
+        ]);
+
+        let affected = filter_affected(advisories, &versions);
+        let ranges = |name: &str| affected[name].iter().map(|a| a.affected_versions.as_str()).collect::<Vec<_>>();
+        assert_eq!(ranges("acme/lib"), vec![">=1.0,<1.2|>=2.0,<2.1"]);
+        assert_eq!(ranges("acme/branch"), vec!["dev-master|<1.0"]);
+    }
+
+    #[test]
+    fn test_filter_affected_keeps_unparseable_ranges() {
+        let mut advisories = Advisories::new();
+        advisories.insert(
+            "acme/lib".to_string(),
+            vec![advisory("acme/lib", "before the fix", None), advisory("acme/lib", "<1.0", None)],
+        );
+
+        let versions = HashMap::from([("acme/lib".to_string(), "1.5.0".to_string())]);
+
+        let affected = filter_affected(advisories, &versions);
+        assert_eq!(affected["acme/lib"].len(), 1);
+        assert_eq!(
+            affected["acme/lib"][0].note.as_deref(),
+            Some("Could not parse the affected versions, 1.5.0 may be affected")
+        );
+    }
+
+    #[test]
+    fn test_severity_threshold() {
+        assert!("HIGH".parse::<Severity>().unwrap() > Severity::Medium);
+        assert!("urgent".parse::<Severity>().is_err());
+
+        let high = advisory("acme/lib", "*", Some("high"));
+        assert!(high.meets(Severity::Low));
+        assert!(high.meets(Severity::High));
+        assert!(!high.meets(Severity::Critical));
+
+        let unknown = advisory("acme/lib", "*", None);
+        assert!(unknown.meets(Severity::Low));
+        assert!(!unknown.meets(Severity::Medium));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_affected_uses_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let fetcher = AdvisoryFetcher::new(Some(temp_dir.path().to_path_buf()));
+
+        let mut advisories = Advisories::new();
+        advisories.insert("acme/lib".to_string(), vec![advisory("acme/lib", "<1.2.0", Some("critical"))]);
+        let response = SecurityAdvisoriesResponse { advisories };
+        let packages = vec!["acme/lib".to_string(), "acme/other".to_string()];
+        Cache::new(temp_dir.path().join("audit"))
+            .write(&cache_key(&packages), &serde_json::to_vec(&response).unwrap())
+            .unwrap();
+
+        let versions = HashMap::from([
+            ("acme/other".to_string(), "2.0.0".to_string()),
+            ("acme/lib".to_string(), "1.0.0".to_string()),
+        ]);
+        let affected = fetcher.fetch_affected(&versions).await.unwrap().advisories;
+        assert_eq!(affected.keys().collect::<Vec<_>>(), vec!["acme/lib"]);
+        assert!(affected["acme/lib"][0].meets(Severity::Critical));
+    }
+
+    /// Answer requests with no advisories, or an error if they mention `failing`, counting them
+    fn serve_empty(failing: Option<&'static str>) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+
+                counter.fetch_add(1, Ordering::SeqCst);
+                if failing.is_some_and(|failing| String::from_utf8_lossy(&body).contains(failing)) {
+                    let _ = stream.write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                    continue;
+                }
+                let body = b"{\"advisories\":{}}";
+                let _ = stream.write_all(format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                ).as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batches_and_refreshes() {
+        use std::sync::atomic::Ordering;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (url, requests) = serve_empty(None);
+        let packages: Vec<String> = (0..150).map(|i| format!("acme/lib{}", i)).collect();
+
+        let fetcher = AdvisoryFetcher::new(Some(temp_dir.path().to_path_buf())).with_url(&url);
+        assert!(fetcher.fetch(&packages).await.unwrap().advisories.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Fresh cache is reused, unless refreshing
+        fetcher.fetch(&packages).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        let fetcher = fetcher.with_refresh(true);
+        fetcher.fetch(&packages).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+        // Without a cache every fetch goes to the API
+        let uncached = AdvisoryFetcher::new(None).with_url(&url);
+        uncached.fetch(&packages).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 6);
+
+        // An unreachable API falls back to the cache, whatever its age
+        let offline = AdvisoryFetcher::new(Some(temp_dir.path().to_path_buf()))
+            .with_url("http://127.0.0.1:1/")
+            .with_refresh(true);
+        assert!(offline.fetch(&packages).await.is_ok());
+        assert!(offline.fetch(&packages[..1]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reports_failed_batches() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (url, _) = serve_empty(Some("acme/lib149"));
+        let packages: Vec<String> = (0..150).map(|i| format!("acme/lib{}", i)).collect();
+
+        let fetcher = AdvisoryFetcher::new(Some(temp_dir.path().to_path_buf())).with_url(&url);
+        let report = fetcher.fetch(&packages).await.unwrap();
+        assert_eq!(report.failed_batches.len(), 1);
+        assert_eq!(report.failed_batches[0].packages, packages[100..]);
+        assert!(report.failed_batches[0].to_string().starts_with("50 packages from acme/lib100 to acme/lib149: "));
+
+        // An incomplete result is not cached
+        assert!(fetcher.read_cache(&cache_key(&packages)).is_none());
+
+        // Failing every batch is an error
+        assert!(fetcher.fetch(&packages[100..]).await.is_err());
+    }
+
+    #[test]
+    fn test_cache_key_ignores_order() {
+        let a = vec!["b/b".to_string(), "a/a".to_string()];
+        let b = vec!["a/a".to_string(), "b/b".to_string()];
+        assert_eq!(cache_key(&a), cache_key(&b));
+    }
+}
@@ -1,13 +1,14 @@
 //! Autoload generator - creates PHP autoloader files.
 
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use indexmap::IndexMap;
 
 use md5::{Md5, Digest};
 use regex::Regex;
 
 use crate::package::Autoload;
+use crate::profile::{self, Phase};
 use crate::Result;
 
 use super::classmap::ClassMapGenerator;
@@ -54,6 +55,22 @@ fn sort_packages_by_dependency(packages: &[PackageAutoload]) -> Vec<PackageAutol
     sorted
 }
 
+/// Lexically resolve `.` and `..` components, so a vendor-dir like `../shared/vendor`
+/// can be compared with the project root without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 /// Configuration for autoload generation
 #[derive(Debug, Clone)]
 pub struct AutoloadConfig {
@@ -61,14 +78,17 @@ pub struct AutoloadConfig {
     pub vendor_dir: PathBuf,
     /// Base directory (project root)
     pub base_dir: PathBuf,
-    /// Whether to optimize autoloader (authoritative classmap)
+    /// Whether to optimize autoloader (scan PSR-4/PSR-0 directories into the classmap)
     pub optimize: bool,
-    /// Whether to use APCu for caching
+    /// Whether the runtime loader caches lookups, including misses, in APCu
     pub apcu: bool,
-    /// Whether to generate authoritative classmap
+    /// Whether the runtime loader only uses the classmap, without PSR-4/PSR-0 fallback.
+    /// Implies `optimize`
     pub authoritative: bool,
     /// Suffix for class names (content-hash from lock file)
     pub suffix: Option<String>,
+    /// Whether dev packages are autoloaded; packages marked as dev requirements are skipped otherwise
+    pub dev_mode: bool,
 }
 
 impl Default for AutoloadConfig {
@@ -80,6 +100,7 @@ impl Default for AutoloadConfig {
             apcu: false,
             authoritative: false,
             suffix: None,
+            dev_mode: true,
         }
     }
 }
@@ -140,7 +161,10 @@ impl Default for PackageAutoload {
 }
 
 /// Root package information for installed.php
-#[derive(Debug, Clone, Default)]
+///
+/// The default describes an anonymous `__root__` package, used when `generate` is not
+/// given one; override single fields with struct update syntax.
+#[derive(Debug, Clone)]
 pub struct RootPackageInfo {
     /// Package name (vendor/package format)
     pub name: String,
@@ -158,6 +182,20 @@ pub struct RootPackageInfo {
     pub dev_mode: bool,
 }
 
+impl Default for RootPackageInfo {
+    fn default() -> Self {
+        Self {
+            name: "__root__".to_string(),
+            pretty_version: "dev-main".to_string(),
+            version: "dev-main".to_string(),
+            reference: None,
+            package_type: "library".to_string(),
+            aliases: Vec::new(),
+            dev_mode: true,
+        }
+    }
+}
+
 /// Autoload generator
 pub struct AutoloadGenerator {
     config: AutoloadConfig,
@@ -241,13 +279,19 @@ impl AutoloadGenerator {
 
     /// Generate autoloader for installed packages
     pub fn generate(&self, packages: &[PackageAutoload], root_autoload: Option<&Autoload>, root_package: Option<&RootPackageInfo>) -> Result<()> {
+        let _timer = profile::start(Phase::AutoloadDump);
         let composer_dir = self.config.vendor_dir.join("composer");
         std::fs::create_dir_all(&composer_dir)?;
 
         let suffix = self.get_suffix();
 
+        let packages: Vec<PackageAutoload> = packages.iter()
+            .filter(|pkg| self.config.dev_mode || !pkg.dev_requirement)
+            .cloned()
+            .collect();
+
         // Sort packages by dependency weight for reproducible output
-        let sorted_packages = sort_packages_by_dependency(packages);
+        let sorted_packages = sort_packages_by_dependency(&packages);
 
         // Collect exclude-from-classmap patterns from all packages
         let exclude_patterns = self.collect_exclude_patterns(&sorted_packages, root_autoload);
@@ -352,9 +396,13 @@ impl AutoloadGenerator {
             }
         }
 
-        // Files - compute identifier as md5(package_name:path)
+        // Files - compute identifier as md5(package_name:path), keeping the first
+        // occurrence so a file is never registered twice
         for path in &autoload.files {
             let file_identifier = Self::compute_file_identifier(package_name, path);
+            if files.iter().any(|(identifier, _)| *identifier == file_identifier) {
+                continue;
+            }
             let full_path = self.get_path_code(install_path, path, is_root);
             files.push((file_identifier, full_path));
         }
@@ -455,13 +503,14 @@ impl AutoloadGenerator {
             // Extract path after "$vendorDir . '"
             let parts: Vec<&str> = code.splitn(2, "'").collect();
             if parts.len() >= 2 {
-                let rel_path = parts[1].trim_end_matches('\'');
+                // The path starts with a slash, which `join` would take as absolute
+                let rel_path = parts[1].trim_end_matches('\'').trim_start_matches('/');
                 return Some(self.config.vendor_dir.join(rel_path).to_string_lossy().to_string());
             }
         } else if code.starts_with("$baseDir") {
             let parts: Vec<&str> = code.splitn(2, "'").collect();
             if parts.len() >= 2 {
-                let rel_path = parts[1].trim_end_matches('\'');
+                let rel_path = parts[1].trim_end_matches('\'').trim_start_matches('/');
                 return Some(self.config.base_dir.join(rel_path).to_string_lossy().to_string());
             }
         }
@@ -564,8 +613,8 @@ class ComposerAutoloaderInit{suffix}
         require __DIR__ . '/autoload_static.php';
         call_user_func(\Composer\Autoload\ComposerStaticInit{suffix}::getInitializer($loader));
 
-        $loader->register(true);
-{apcu_prefix}{authoritative}{files_loader}
+{authoritative}{apcu_prefix}        $loader->register(true);
+{files_loader}
         return $loader;
     }}
 }}
@@ -576,18 +625,47 @@ class ComposerAutoloaderInit{suffix}
     }
 
     /// Convert $vendorDir/$baseDir paths to __DIR__ format for static file
-    fn to_static_path(path: &str) -> String {
+    fn to_static_path(&self, path: &str) -> String {
         if path.starts_with("$vendorDir") {
             // $vendorDir . '/x' => __DIR__ . '/..' . '/x'
             path.replace("$vendorDir", "__DIR__ . '/..'")
         } else if path.starts_with("$baseDir") {
-            // $baseDir . '/x' => __DIR__ . '/../..' . '/x'
-            path.replace("$baseDir", "__DIR__ . '/../..'")
+            // $baseDir . '/x' => __DIR__ . '/../..' . '/x' for the default vendor dir
+            path.replacen("$baseDir", &self.base_dir_from_composer_dir(""), 1)
         } else {
             path.to_string()
         }
     }
 
+    /// PHP expression for the project root, relative to `$vendorDir`
+    ///
+    /// `dirname($vendorDir)` for the default `vendor` dir, one `dirname` per level for a
+    /// nested vendor dir, and a relative or absolute path for one outside the project.
+    fn base_dir_code(&self) -> String {
+        let vendor_dir = normalize_path(&self.config.vendor_dir);
+        let base_dir = normalize_path(&self.config.base_dir);
+
+        match pathdiff::diff_paths(&base_dir, &vendor_dir) {
+            Some(rel) if rel.as_os_str().is_empty() => "$vendorDir".to_string(),
+            Some(rel) if rel.components().all(|c| c == Component::ParentDir) => {
+                rel.components().fold("$vendorDir".to_string(), |code, _| format!("dirname({})", code))
+            }
+            Some(rel) => format!("$vendorDir . '/{}'", rel.to_string_lossy().replace('\\', "/")),
+            None => Self::php_string(&base_dir.to_string_lossy()),
+        }
+    }
+
+    /// PHP expression for the project root followed by `suffix`, relative to `vendor/composer`
+    fn base_dir_from_composer_dir(&self, suffix: &str) -> String {
+        let composer_dir = normalize_path(&self.config.vendor_dir.join("composer"));
+        let base_dir = normalize_path(&self.config.base_dir);
+
+        match pathdiff::diff_paths(&base_dir, &composer_dir) {
+            Some(rel) => format!("__DIR__ . '/{}{}'", rel.to_string_lossy().replace('\\', "/"), suffix),
+            None => Self::php_string(&format!("{}{}", base_dir.to_string_lossy(), suffix)),
+        }
+    }
+
     /// Generate vendor/composer/autoload_static.php
     fn generate_autoload_static(
         &self,
@@ -612,7 +690,7 @@ class ComposerStaticInit{suffix}
         if !files.is_empty() {
             content.push_str("    public static $files = array (\n");
             for (identifier, path) in files {
-                content.push_str(&format!("        '{}' => {},\n", identifier, Self::to_static_path(path)));
+                content.push_str(&format!("        '{}' => {},\n", identifier, self.to_static_path(path)));
             }
             content.push_str("    );\n\n");
         }
@@ -653,7 +731,7 @@ class ComposerStaticInit{suffix}
                 let ns_escaped = namespace.replace('\\', "\\\\");
                 content.push_str(&format!("        '{}' =>\n        array (\n", ns_escaped));
                 for (i, path) in paths.iter().enumerate() {
-                    content.push_str(&format!("            {} => {},\n", i, Self::to_static_path(path)));
+                    content.push_str(&format!("            {} => {},\n", i, self.to_static_path(path)));
                 }
                 content.push_str("        ),\n");
             }
@@ -684,7 +762,7 @@ class ComposerStaticInit{suffix}
                     let ns_escaped = ns.replace('\\', "\\\\");
                     content.push_str(&format!("            '{}' =>\n            array (\n", ns_escaped));
                     for (i, path) in paths.iter().enumerate() {
-                        content.push_str(&format!("                {} => {},\n", i, Self::to_static_path(path)));
+                        content.push_str(&format!("                {} => {},\n", i, self.to_static_path(path)));
                     }
                     content.push_str("            ),\n");
                 }
@@ -697,7 +775,7 @@ class ComposerStaticInit{suffix}
         content.push_str("    public static $classMap = array (\n");
         for (class, path) in classmap {
             let class_escaped = class.replace('\\', "\\\\");
-            content.push_str(&format!("        '{}' => {},\n", class_escaped, Self::to_static_path(path)));
+            content.push_str(&format!("        '{}' => {},\n", class_escaped, self.to_static_path(path)));
         }
         content.push_str("    );\n\n");
 
@@ -762,12 +840,12 @@ class ComposerStaticInit{suffix}
 // autoload_psr4.php @generated by Composer
 
 $vendorDir = dirname(__DIR__);
-$baseDir = dirname($vendorDir);
+$baseDir = {base_dir};
 
 return array(
 {},
 );
-"#, entries.join(",\n"));
+"#, entries.join(",\n"), base_dir = self.base_dir_code());
 
         std::fs::write(composer_dir.join("autoload_psr4.php"), content)?;
         Ok(())
@@ -803,11 +881,11 @@ return array(
 // autoload_namespaces.php @generated by Composer
 
 $vendorDir = dirname(__DIR__);
-$baseDir = dirname($vendorDir);
+$baseDir = {base_dir};
 
 return array(
 {});
-"#, entries_str);
+"#, entries_str, base_dir = self.base_dir_code());
 
         std::fs::write(composer_dir.join("autoload_namespaces.php"), content)?;
         Ok(())
@@ -830,11 +908,11 @@ return array(
 // autoload_classmap.php @generated by Composer
 
 $vendorDir = dirname(__DIR__);
-$baseDir = dirname($vendorDir);
+$baseDir = {base_dir};
 
 return array(
 {});
-"#, entries_str);
+"#, entries_str, base_dir = self.base_dir_code());
 
         std::fs::write(composer_dir.join("autoload_classmap.php"), content)?;
         Ok(())
@@ -857,11 +935,11 @@ return array(
 // autoload_files.php @generated by Composer
 
 $vendorDir = dirname(__DIR__);
-$baseDir = dirname($vendorDir);
+$baseDir = {base_dir};
 
 return array(
 {});
-"#, entries_str);
+"#, entries_str, base_dir = self.base_dir_code());
 
         std::fs::write(composer_dir.join("autoload_files.php"), content)?;
         Ok(())
@@ -1039,37 +1117,17 @@ if ($issues) {
         }
 
         // Build root package entry
-        let (root_name, root_pretty_version, root_version, root_reference, root_type, root_aliases, root_dev) =
-            if let Some(root) = root_package {
-                (
-                    root.name.clone(),
-                    root.pretty_version.clone(),
-                    root.version.clone(),
-                    root.reference.clone(),
-                    root.package_type.clone(),
-                    root.aliases.clone(),
-                    root.dev_mode,
-                )
-            } else {
-                (
-                    "__root__".to_string(),
-                    "dev-main".to_string(),
-                    "dev-main".to_string(),
-                    None,
-                    "library".to_string(),
-                    Vec::new(),
-                    true,
-                )
-            };
+        let default_root = RootPackageInfo::default();
+        let root = root_package.unwrap_or(&default_root);
 
         // Also add root package to versions (Composer does this)
-        versions.insert(root_name.clone(), PackageVersionEntry {
-            pretty_version: Some(root_pretty_version.clone()),
-            version: Some(root_version.clone()),
-            reference: root_reference.clone(),
-            package_type: Some(root_type.clone()),
-            install_path: Some("__DIR__ . '/../../'".to_string()),
-            aliases: root_aliases.clone(),
+        versions.insert(root.name.clone(), PackageVersionEntry {
+            pretty_version: Some(root.pretty_version.clone()),
+            version: Some(root.version.clone()),
+            reference: root.reference.clone(),
+            package_type: Some(root.package_type.clone()),
+            install_path: Some(self.base_dir_from_composer_dir("/")),
+            aliases: root.aliases.clone(),
             dev_requirement: false,
             replaced: Vec::new(),
             provided: Vec::new(),
@@ -1080,14 +1138,14 @@ if ($issues) {
 
         // Root section
         content.push_str("    'root' => array(\n");
-        content.push_str(&format!("        'name' => {},\n", Self::php_string(&root_name)));
-        content.push_str(&format!("        'pretty_version' => {},\n", Self::php_string(&root_pretty_version)));
-        content.push_str(&format!("        'version' => {},\n", Self::php_string(&root_version)));
-        content.push_str(&format!("        'reference' => {},\n", Self::php_value_or_null(&root_reference)));
-        content.push_str(&format!("        'type' => {},\n", Self::php_string(&root_type)));
-        content.push_str("        'install_path' => __DIR__ . '/../../',\n");
-        content.push_str(&format!("        'aliases' => {},\n", Self::php_string_array(&root_aliases)));
-        content.push_str(&format!("        'dev' => {},\n", if root_dev { "true" } else { "false" }));
+        content.push_str(&format!("        'name' => {},\n", Self::php_string(&root.name)));
+        content.push_str(&format!("        'pretty_version' => {},\n", Self::php_string(&root.pretty_version)));
+        content.push_str(&format!("        'version' => {},\n", Self::php_string(&root.version)));
+        content.push_str(&format!("        'reference' => {},\n", Self::php_value_or_null(&root.reference)));
+        content.push_str(&format!("        'type' => {},\n", Self::php_string(&root.package_type)));
+        content.push_str(&format!("        'install_path' => {},\n", self.base_dir_from_composer_dir("/")));
+        content.push_str(&format!("        'aliases' => {},\n", Self::php_string_array(&root.aliases)));
+        content.push_str(&format!("        'dev' => {},\n", if root.dev_mode { "true" } else { "false" }));
         content.push_str("    ),\n");
 
         // Versions section
@@ -1203,6 +1261,88 @@ mod tests {
         assert!(temp_dir.path().join("vendor/composer/autoload_real.php").exists());
     }
 
+    #[test]
+    fn test_generate_loader_levels() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/Foo.php"), "<?php\nnamespace App;\nclass Foo {}\n").unwrap();
+
+        let mut root_autoload = Autoload::default();
+        root_autoload.psr4.insert("App\\".to_string(), crate::package::AutoloadPath::Single("src/".to_string()));
+
+        // Returns the loader setup of autoload_real.php and whether App\Foo is in the classmap
+        let dump = |optimize: bool, authoritative: bool, apcu: bool| {
+            let generator = AutoloadGenerator::new(AutoloadConfig {
+                vendor_dir: temp_dir.path().join("vendor"),
+                base_dir: temp_dir.path().to_path_buf(),
+                optimize,
+                authoritative,
+                apcu,
+                suffix: Some("abc".to_string()),
+                ..Default::default()
+            });
+            generator.generate(&[], Some(&root_autoload), None).unwrap();
+
+            let real = std::fs::read_to_string(temp_dir.path().join("vendor/composer/autoload_real.php")).unwrap();
+            let start = real.find("getInitializer($loader));").unwrap() + "getInitializer($loader));".len();
+            let end = real.find("return $loader;").unwrap();
+            let classmap = std::fs::read_to_string(temp_dir.path().join("vendor/composer/autoload_classmap.php")).unwrap();
+            (real[start..end].trim().to_string(), classmap.contains("'App\\\\Foo'"))
+        };
+
+        assert_eq!(dump(false, false, false), ("$loader->register(true);".to_string(), false));
+        assert_eq!(dump(true, false, false), ("$loader->register(true);".to_string(), true));
+        assert_eq!(dump(false, true, false), (
+            "$loader->setClassMapAuthoritative(true);\n        $loader->register(true);".to_string(),
+            true,
+        ));
+        assert_eq!(dump(false, false, true), (
+            "$loader->setApcuPrefix('ComposerAutoloaderabc');\n        $loader->register(true);".to_string(),
+            false,
+        ));
+        assert_eq!(dump(true, true, true), (
+            "$loader->setClassMapAuthoritative(true);\n        $loader->setApcuPrefix('ComposerAutoloaderabc');\n        $loader->register(true);".to_string(),
+            true,
+        ));
+    }
+
+    #[test]
+    fn test_generate_dedups_files_by_identifier() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AutoloadConfig {
+            vendor_dir: temp_dir.path().join("vendor"),
+            base_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let package = PackageAutoload {
+            name: "vendor/helpers".to_string(),
+            install_path: "vendor/helpers".to_string(),
+            autoload: Autoload {
+                files: vec!["src/functions.php".to_string(), "src/functions.php".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let other = PackageAutoload {
+            name: "vendor/other".to_string(),
+            install_path: "vendor/other".to_string(),
+            autoload: Autoload {
+                files: vec!["src/functions.php".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let generator = AutoloadGenerator::new(config);
+        generator.generate(&[package.clone(), other, package], None, None).unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.path().join("vendor/composer/autoload_files.php")).unwrap();
+        let identifier = AutoloadGenerator::compute_file_identifier("vendor/helpers", "src/functions.php");
+        assert_eq!(content.matches(&identifier).count(), 1);
+        assert_eq!(content.matches("functions.php").count(), 2);
+    }
+
     #[test]
     fn test_generate_installed_php_with_packages() {
         let temp_dir = TempDir::new().unwrap();
@@ -1267,6 +1407,77 @@ mod tests {
         assert!(content.contains("'abc123'"));
         assert!(content.contains("'dev_requirement' => false"));
         assert!(content.contains("'dev_requirement' => true"));
+
+        // Without dev mode the dev package is left out entirely
+        let generator = AutoloadGenerator::new(AutoloadConfig {
+            vendor_dir: temp_dir.path().join("vendor"),
+            base_dir: temp_dir.path().to_path_buf(),
+            dev_mode: false,
+            ..Default::default()
+        });
+        generator.generate(&packages, None, Some(&RootPackageInfo { dev_mode: false, ..root })).unwrap();
+        let content = std::fs::read_to_string(&installed_path).unwrap();
+        assert!(content.contains("'vendor/package1'"));
+        assert!(!content.contains("'vendor/package2'"));
+        assert!(content.contains("'dev' => false"));
+    }
+
+    #[test]
+    fn test_generate_installed_php_package_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = AutoloadGenerator::new(AutoloadConfig {
+            vendor_dir: temp_dir.path().join("vendor"),
+            base_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+
+        let packages = vec![PackageAutoload {
+            name: "acme/bundle".to_string(),
+            install_path: "vendor/acme/bundle".to_string(),
+            pretty_version: Some("1.0.0".to_string()),
+            version: Some("1.0.0.0".to_string()),
+            package_type: "symfony-bundle".to_string(),
+            ..Default::default()
+        }];
+        let root = RootPackageInfo {
+            name: "my/project".to_string(),
+            package_type: "project".to_string(),
+            ..Default::default()
+        };
+        generator.generate(&packages, None, Some(&root)).unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.path().join("vendor/composer/installed.php")).unwrap();
+        assert!(content.contains("'root' => array(\n        'name' => 'my/project',"));
+        assert!(content.contains("'type' => 'project'"));
+        assert!(content.contains("'type' => 'symfony-bundle'"));
+    }
+
+    #[test]
+    fn test_generate_installed_php_root_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = AutoloadGenerator::new(AutoloadConfig {
+            vendor_dir: temp_dir.path().join("vendor"),
+            base_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let installed_path = temp_dir.path().join("vendor/composer/installed.php");
+
+        generator.generate(&[], None, None).unwrap();
+        let content = std::fs::read_to_string(&installed_path).unwrap();
+        assert!(content.contains("'root' => array(\n        'name' => '__root__',\n        'pretty_version' => 'dev-main',"));
+
+        let root = RootPackageInfo {
+            name: "acme/app".to_string(),
+            pretty_version: "v2.1.0".to_string(),
+            version: "2.1.0.0".to_string(),
+            reference: Some("abc123".to_string()),
+            ..Default::default()
+        };
+        generator.generate(&[], None, Some(&root)).unwrap();
+        let content = std::fs::read_to_string(&installed_path).unwrap();
+        assert!(content.contains("'root' => array(\n        'name' => 'acme/app',\n        'pretty_version' => 'v2.1.0',\n        'version' => '2.1.0.0',\n        'reference' => 'abc123',\n        'type' => 'library',"));
+        assert!(content.contains("'acme/app' => array("));
+        assert!(!content.contains("__root__"));
     }
 
     #[test]
@@ -1313,4 +1524,41 @@ mod tests {
         assert!(content.contains("'old/package'"));
         assert!(content.contains("'replaced'"));
     }
+
+    #[test]
+    fn test_generate_with_custom_vendor_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AutoloadConfig {
+            vendor_dir: temp_dir.path().join("lib/vendor"),
+            base_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let generator = AutoloadGenerator::new(config);
+        generator.generate(&[], None, None).unwrap();
+
+        let composer_dir = temp_dir.path().join("lib/vendor/composer");
+        let content = std::fs::read_to_string(composer_dir.join("autoload_psr4.php")).unwrap();
+        assert!(content.contains("$baseDir = dirname(dirname($vendorDir));"));
+
+        let content = std::fs::read_to_string(composer_dir.join("installed.php")).unwrap();
+        assert!(content.contains("'install_path' => __DIR__ . '/../../../',"));
+
+        assert_eq!(generator.to_static_path("$baseDir . '/src'"), "__DIR__ . '/../../..' . '/src'");
+    }
+
+    #[test]
+    fn test_base_dir_code() {
+        let code = |vendor_dir: &str| {
+            AutoloadGenerator::new(AutoloadConfig {
+                vendor_dir: PathBuf::from(vendor_dir),
+                base_dir: PathBuf::from("/app"),
+                ..Default::default()
+            }).base_dir_code()
+        };
+
+        assert_eq!(code("/app/vendor"), "dirname($vendorDir)");
+        assert_eq!(code("/app/./lib/vendor"), "dirname(dirname($vendorDir))");
+        assert_eq!(code("/app/../shared/vendor"), "$vendorDir . '/../../app'");
+    }
 }
@@ -3,6 +3,7 @@ use sha2::{Digest, Sha256};
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
@@ -12,6 +13,21 @@ fn sanitize_regex() -> &'static Regex {
     REGEX.get_or_init(|| Regex::new("[^a-z0-9._]").unwrap())
 }
 
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disable caching for the rest of the process, for `--no-cache`
+///
+/// Caches created afterwards start out disabled, and downloads are kept out of the
+/// cache directory.
+pub fn disable_all() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether caching was disabled with [`disable_all`]
+pub fn is_disabled() -> bool {
+    DISABLED.load(Ordering::Relaxed)
+}
+
 /// Filesystem cache for composer data
 ///
 /// Supports caching of:
@@ -27,6 +43,30 @@ pub struct Cache {
     read_only: bool,
 }
 
+/// A file or VCS directory in the cache, as found by garbage collection
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    /// Size in bytes, including everything below a directory
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+impl CacheEntry {
+    fn from_path(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+
+    fn is_older_than(&self, ttl: Duration, now: SystemTime) -> bool {
+        now.duration_since(self.modified).is_ok_and(|age| age > ttl)
+    }
+}
+
 impl Cache {
     /// Create a new cache instance
     ///
@@ -43,7 +83,7 @@ impl Cache {
     pub fn new(root: PathBuf) -> Self {
         Self {
             root,
-            enabled: true,
+            enabled: !is_disabled(),
             read_only: false,
         }
     }
@@ -244,6 +284,64 @@ impl Cache {
         }
     }
 
+    /// Remove every file whose key starts with `prefix`
+    ///
+    /// See [`entries_with_prefix`](Self::entries_with_prefix) for how keys are matched.
+    /// Directories left empty are removed too.
+    ///
+    /// # Returns
+    /// Number of bytes freed
+    pub fn remove_prefix(&self, prefix: &str) -> io::Result<u64> {
+        if !self.enabled || self.read_only {
+            return Ok(0);
+        }
+
+        let entries = self.entries_with_prefix(prefix)?;
+        let freed = self.remove_entries(&entries)?;
+
+        for entry in &entries {
+            let mut dir = entry.path.parent();
+            while let Some(parent) = dir.filter(|parent| *parent != self.root) {
+                if fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+
+        Ok(freed)
+    }
+
+    /// Files whose key starts with `prefix`, without removing them
+    ///
+    /// The prefix is sanitized like keys are on write, one `/`-separated segment at a
+    /// time, so `vendor/package/` matches the files in that subdirectory and a full key
+    /// matches just that file.
+    pub fn entries_with_prefix(&self, prefix: &str) -> io::Result<Vec<CacheEntry>> {
+        if !self.enabled || prefix.is_empty() || !self.root.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let prefix: Vec<String> = prefix.split('/').map(|segment| self.sanitize_key(segment)).collect();
+        let prefix = prefix.join("/");
+
+        Ok(WalkDir::new(&self.root)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| {
+                entry.path().strip_prefix(&self.root).is_ok_and(|relative| {
+                    let key: Vec<_> = relative.components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect();
+                    key.join("/").starts_with(&prefix)
+                })
+            })
+            .filter_map(|entry| CacheEntry::from_path(entry.path()))
+            .collect())
+    }
+
     /// Clear the entire cache
     ///
     /// Removes all files and directories under the cache root
@@ -276,42 +374,17 @@ impl Cache {
     /// # Returns
     /// Number of bytes freed
     pub fn gc(&self, ttl: Duration) -> io::Result<u64> {
-        if !self.enabled || self.read_only {
-            return Ok(0);
-        }
-
-        let now = SystemTime::now();
-        let mut freed = 0u64;
-
-        for entry in WalkDir::new(&self.root)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // Skip directories
-            if !path.is_file() {
-                continue;
-            }
-
-            // Get file metadata
-            if let Ok(metadata) = fs::metadata(path) {
-                // Check if file is older than TTL
-                if let Ok(modified) = metadata.modified() {
-                    if let Ok(age) = now.duration_since(modified) {
-                        if age > ttl {
-                            let size = metadata.len();
-                            if fs::remove_file(path).is_ok() {
-                                freed += size;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let entries = self.gc_candidates(ttl, None)?;
+        self.remove_entries(&entries)
+    }
 
-        Ok(freed)
+    /// Garbage collect the least recently written files until the cache fits in `max_size` bytes
+    ///
+    /// # Returns
+    /// Number of bytes freed
+    pub fn gc_by_size(&self, max_size: u64) -> io::Result<u64> {
+        let entries = self.gc_candidates(Duration::MAX, Some(max_size))?;
+        self.remove_entries(&entries)
     }
 
     /// Garbage collect VCS cache directories
@@ -324,40 +397,96 @@ impl Cache {
     /// # Returns
     /// Number of bytes freed
     pub fn gc_vcs(&self, ttl: Duration) -> io::Result<u64> {
+        let entries = self.gc_vcs_candidates(ttl)?;
+        self.remove_entries(&entries)
+    }
+
+    /// Files that [`gc`](Self::gc) and [`gc_by_size`](Self::gc_by_size) would remove,
+    /// without removing them
+    ///
+    /// Files older than `ttl` come first. With `max_size`, the least recently written of
+    /// the remaining files follow until the rest fits in `max_size` bytes.
+    pub fn gc_candidates(&self, ttl: Duration, max_size: Option<u64>) -> io::Result<Vec<CacheEntry>> {
         if !self.enabled || self.read_only {
-            return Ok(0);
+            return Ok(Vec::new());
         }
 
+        let files: Vec<CacheEntry> = WalkDir::new(&self.root)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| CacheEntry::from_path(entry.path()))
+            .collect();
+
         let now = SystemTime::now();
-        let mut freed = 0u64;
+        let (mut candidates, mut kept): (Vec<CacheEntry>, Vec<CacheEntry>) = files
+            .into_iter()
+            .partition(|entry| entry.is_older_than(ttl, now));
+
+        if let Some(max_size) = max_size {
+            kept.sort_by_key(|entry| entry.modified);
+            let mut remaining: u64 = kept.iter().map(|entry| entry.size).sum();
+            for entry in kept {
+                if remaining <= max_size {
+                    break;
+                }
+                remaining -= entry.size;
+                candidates.push(entry);
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// VCS directories that [`gc_vcs`](Self::gc_vcs) would remove, without removing them
+    pub fn gc_vcs_candidates(&self, ttl: Duration) -> io::Result<Vec<CacheEntry>> {
+        if !self.enabled || self.read_only {
+            return Ok(Vec::new());
+        }
+
+        let now = SystemTime::now();
+        let mut candidates = Vec::new();
 
         // Only check immediate subdirectories (depth 0)
         for entry in fs::read_dir(&self.root)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            // Only process directories
+            let path = entry?.path();
             if !path.is_dir() {
                 continue;
             }
-
-            // Get directory metadata
-            if let Ok(metadata) = fs::metadata(&path) {
-                // Check if directory is older than TTL
-                if let Ok(modified) = metadata.modified() {
-                    if let Ok(age) = now.duration_since(modified) {
-                        if age > ttl {
-                            // Calculate directory size before removal
-                            let size = self.dir_size(&path)?;
-                            if fs::remove_dir_all(&path).is_ok() {
-                                freed += size;
-                            }
-                        }
-                    }
+            if let Some(mut entry) = CacheEntry::from_path(&path) {
+                if entry.is_older_than(ttl, now) {
+                    entry.size = self.dir_size(&path)?;
+                    candidates.push(entry);
                 }
             }
         }
 
+        Ok(candidates)
+    }
+
+    /// Remove entries found by [`gc_candidates`](Self::gc_candidates) or
+    /// [`gc_vcs_candidates`](Self::gc_vcs_candidates)
+    ///
+    /// # Returns
+    /// Number of bytes freed by the entries that could be removed
+    pub fn remove_entries(&self, entries: &[CacheEntry]) -> io::Result<u64> {
+        if !self.enabled || self.read_only {
+            return Ok(0);
+        }
+
+        let mut freed = 0u64;
+        for entry in entries {
+            let removed = if entry.path.is_dir() {
+                fs::remove_dir_all(&entry.path)
+            } else {
+                fs::remove_file(&entry.path)
+            };
+            if removed.is_ok() {
+                freed += entry.size;
+            }
+        }
+
         Ok(freed)
     }
 
@@ -465,6 +594,33 @@ mod tests {
         assert!(!cache.is_read_only());
     }
 
+    #[test]
+    fn test_disable_all_disables_new_caches() {
+        // The switch is process-wide, so it is only flipped in a child process running
+        // just this test
+        if std::env::var_os("POX_TEST_CACHE_DISABLE_ALL").is_none() {
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "cache::cache::tests::test_disable_all_disables_new_caches"])
+                .env("POX_TEST_CACHE_DISABLE_ALL", "1")
+                .output()
+                .unwrap();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success() && stdout.contains("1 passed"), "{}", stdout);
+            return;
+        }
+
+        let temp = TempDir::new().unwrap();
+        let existing = Cache::new(temp.path().to_path_buf());
+        disable_all();
+
+        assert!(is_disabled());
+        assert!(existing.is_enabled());
+        let cache = Cache::new(temp.path().to_path_buf());
+        assert!(!cache.is_enabled());
+        cache.write("test.txt", b"data").unwrap();
+        assert!(!temp.path().join("test.txt").exists());
+    }
+
     #[test]
     fn test_cache_read_write() {
         let temp = TempDir::new().unwrap();
@@ -581,6 +737,54 @@ mod tests {
         assert!(cache.has("new.txt"));
     }
 
+    #[test]
+    fn test_cache_gc_by_size() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::new(temp.path().to_path_buf());
+
+        cache.write("oldest.txt", b"0123456789").unwrap();
+        thread::sleep(StdDuration::from_millis(20));
+        cache.write("older.txt", b"0123456789").unwrap();
+        thread::sleep(StdDuration::from_millis(20));
+        cache.write("newest.txt", b"0123456789").unwrap();
+
+        // Candidates are only reported, nothing is removed yet
+        let candidates = cache.gc_candidates(Duration::MAX, Some(15)).unwrap();
+        let names: Vec<_> = candidates.iter().map(|e| e.path.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, vec!["oldest.txt", "older.txt"]);
+        assert!(cache.has("oldest.txt"));
+
+        assert_eq!(cache.gc_by_size(15).unwrap(), 20);
+        assert!(!cache.has("oldest.txt"));
+        assert!(!cache.has("older.txt"));
+        assert!(cache.has("newest.txt"));
+    }
+
+    #[test]
+    fn test_cache_remove_prefix() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::new(temp.path().to_path_buf());
+
+        // Written keys are sanitized, so the prefix must be too
+        cache.write("provider-acme~lib.json", b"lib").unwrap();
+        cache.write("provider-acme~lib~dev.json", b"dev").unwrap();
+        cache.write("provider-acme~lib-extra.json", b"extra").unwrap();
+        fs::create_dir_all(temp.path().join("acme/lib")).unwrap();
+        fs::write(temp.path().join("acme/lib/acme-lib-1.0.0.0.zip"), b"zip").unwrap();
+        fs::create_dir_all(temp.path().join("acme/lib-extra")).unwrap();
+        fs::write(temp.path().join("acme/lib-extra/acme-lib-extra-1.0.0.0.zip"), b"zip").unwrap();
+
+        assert_eq!(cache.entries_with_prefix("acme/").unwrap().len(), 2);
+        assert_eq!(cache.remove_prefix("provider-acme~lib.json").unwrap(), 3);
+        assert!(!cache.has("provider-acme~lib.json"));
+        assert!(cache.has("provider-acme~lib~dev.json"));
+        assert!(cache.has("provider-acme~lib-extra.json"));
+
+        assert_eq!(cache.remove_prefix("acme/lib/").unwrap(), 3);
+        assert!(!temp.path().join("acme/lib").exists());
+        assert!(temp.path().join("acme/lib-extra/acme-lib-extra-1.0.0.0.zip").exists());
+    }
+
     #[test]
     fn test_cache_size() {
         let temp = TempDir::new().unwrap();
@@ -1,5 +1,5 @@
 mod cache;
 mod repo_cache;
 
-pub use cache::Cache;
+pub use cache::{Cache, CacheEntry, disable_all, is_disabled};
 pub use repo_cache::{RepoCache, CacheMetadata};
@@ -1,15 +1,16 @@
 use std::path::PathBuf;
 use std::sync::Arc;
-use anyhow::{Context, Result};
 
-use crate::config::{Config, PreferredInstall};
+use crate::config::{AuthConfig, Config, PreferredInstall};
 use crate::event::EventDispatcher;
-use crate::http::HttpClient;
+use crate::http::{HttpClient, HttpClientConfig, SignedOrigin};
 use crate::json::{ComposerJson, ComposerLock, Repository as JsonRepository, Repositories};
-use crate::plugin::register_plugins;
+use crate::plugin::{merge_includes, register_plugins};
 use crate::repository::{ComposerRepository, RepositoryManager, Repository};
 use crate::installer::InstallationManager;
 use crate::installer::InstallConfig;
+use crate::installer::InstallerPaths;
+use crate::{ComposerError, Result};
 
 /// The central Composer application object.
 pub struct Composer {
@@ -71,6 +72,7 @@ pub struct ComposerBuilder {
     dry_run: bool,
     no_dev: bool,
     prefer_lowest: bool,
+    prefer_stable: bool,
 
     // Platform packages (php, ext-*, lib-*)
     platform_packages: Vec<crate::package::Package>,
@@ -95,6 +97,7 @@ impl ComposerBuilder {
             dry_run: false,
             no_dev: false,
             prefer_lowest: false,
+            prefer_stable: false,
             platform_packages: Vec::new(),
             disable_packagist: None,
         }
@@ -161,6 +164,11 @@ impl ComposerBuilder {
         self
     }
 
+    pub fn prefer_stable(mut self, prefer: bool) -> Self {
+        self.prefer_stable = prefer;
+        self
+    }
+
     pub fn with_platform_packages(mut self, packages: Vec<crate::package::Package>) -> Self {
         self.platform_packages = packages;
         self
@@ -172,8 +180,11 @@ impl ComposerBuilder {
     }
 
     pub fn build(mut self) -> Result<Composer> {
-        let composer_json = self.composer_json.take()
-            .ok_or_else(|| anyhow::anyhow!("composer.json is required"))?;
+        let mut composer_json = self.composer_json.take()
+            .ok_or_else(|| ComposerError::InvalidManifest { message: "composer.json is required".to_string() })?;
+
+        // Files included through extra.merge-plugin take part in resolution like the root itself
+        merge_includes(&self.working_dir, &mut composer_json)?;
 
         let config = self.config.take().unwrap_or_else(|| {
             Config::with_base_dir(&self.working_dir)
@@ -181,11 +192,25 @@ impl ComposerBuilder {
 
         let http_client = match self.http_client.take() {
             Some(client) => client,
-            None => Arc::new(HttpClient::new().context("Failed to create HTTP client")?),
+            None => {
+                // Credentials from auth.json files and COMPOSER_AUTH
+                let auth = AuthConfig::build(Some(&self.working_dir))?;
+                let mut client = HttpClient::with_config(HttpClientConfig::from_config(&config))?.with_auth(auth);
+                // Dist downloads served by a signed repository must be signed as well
+                for repo in composer_json.repositories.as_vec() {
+                    if let JsonRepository::Composer { url, signature: Some(signature), .. } = &repo {
+                        // Invalid keys are reported when the repository itself is skipped
+                        if let Ok(origin) = SignedOrigin::from_repository(url, signature) {
+                            client = client.with_signed_origin(origin);
+                        }
+                    }
+                }
+                Arc::new(client)
+            }
         };
 
-        let repository_manager = self.build_repository_manager(&config, &composer_json)?;
-        let install_config = self.build_install_config(&config);
+        let repository_manager = self.build_repository_manager(&config, &composer_json, &http_client)?;
+        let install_config = self.build_install_config(&config, &composer_json);
 
         let installation_manager = Arc::new(InstallationManager::new(
             http_client.clone(),
@@ -213,12 +238,14 @@ impl ComposerBuilder {
         &mut self,
         config: &Config,
         composer_json: &ComposerJson,
+        http_client: &Arc<HttpClient>,
     ) -> Result<RepositoryManager> {
         if let Some(manager) = self.repository_manager.take() {
             return Ok(manager);
         }
 
         let mut repository_manager = RepositoryManager::new();
+        repository_manager.set_http_client(http_client.clone());
 
         for repo in composer_json.repositories.as_vec() {
             repository_manager.add_from_json_repository(&repo);
@@ -233,18 +260,19 @@ impl ComposerBuilder {
         });
 
         if !packagist_disabled {
-            let packagist = if let Some(cache_dir) = config.cache_dir.clone() {
+            let mut packagist = if let Some(cache_dir) = config.cache_dir.clone() {
                 ComposerRepository::packagist_with_cache(cache_dir)
             } else {
                 ComposerRepository::packagist()
             };
+            packagist.set_http_client(http_client.clone());
             repository_manager.add_repository(Arc::new(packagist));
         }
 
         Ok(repository_manager)
     }
 
-    fn build_install_config(&self, config: &Config) -> InstallConfig {
+    fn build_install_config(&self, config: &Config, composer_json: &ComposerJson) -> InstallConfig {
         let (prefer_source, prefer_dist) = match (self.prefer_source, self.prefer_dist) {
             (Some(src), Some(dst)) => (src, dst),
             (Some(src), None) => (src, !src),
@@ -258,6 +286,13 @@ impl ComposerBuilder {
             }
         };
 
+        // An explicit --prefer-source/--prefer-dist overrides the per-package map
+        let package_preferences = if self.prefer_source.is_some() || self.prefer_dist.is_some() {
+            Vec::new()
+        } else {
+            config.preferred_install_packages.clone()
+        };
+
         InstallConfig {
             vendor_dir: self.working_dir.join(&config.vendor_dir),
             bin_dir: self.working_dir.join(&config.bin_dir),
@@ -265,9 +300,12 @@ impl ComposerBuilder {
                 .unwrap_or_else(|| self.working_dir.join(".pox/cache")),
             prefer_source,
             prefer_dist,
+            package_preferences,
             dry_run: self.dry_run,
             no_dev: self.no_dev,
             prefer_lowest: self.prefer_lowest,
+            prefer_stable: self.prefer_stable,
+            installer_paths: InstallerPaths::new(&self.working_dir, &composer_json.extra),
         }
     }
 }
@@ -287,6 +325,7 @@ impl Clone for ComposerBuilder {
             dry_run: self.dry_run,
             no_dev: self.no_dev,
             prefer_lowest: self.prefer_lowest,
+            prefer_stable: self.prefer_stable,
             platform_packages: self.platform_packages.clone(),
             disable_packagist: self.disable_packagist,
         }
@@ -294,7 +333,7 @@ impl Clone for ComposerBuilder {
 }
 
 /// Check if packagist.org is disabled in the repositories configuration
-fn is_packagist_disabled(repositories: &Repositories) -> bool {
+pub fn is_packagist_disabled(repositories: &Repositories) -> bool {
     match repositories {
         Repositories::None => false,
         Repositories::Array(repos) => {
@@ -138,16 +138,25 @@ impl AuthConfig {
     }
 
     /// Load auth config from the COMPOSER_AUTH environment variable
+    ///
+    /// Malformed JSON is reported as an error instead of silently dropping the credentials.
     pub fn from_env() -> Result<Option<Self>> {
         match std::env::var("COMPOSER_AUTH") {
-            Ok(json) if !json.is_empty() => {
-                let config = Self::from_json(&json)?;
-                Ok(Some(config))
-            }
+            Ok(json) if !json.trim().is_empty() => Self::from_env_json(&json).map(Some),
             _ => Ok(None),
         }
     }
 
+    /// Parse the contents of the COMPOSER_AUTH environment variable
+    fn from_env_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| {
+            ComposerError::Config(format!(
+                "COMPOSER_AUTH environment variable is malformed, it must contain a valid JSON object: {}",
+                e
+            ))
+        })
+    }
+
     /// Build complete auth config from all sources
     ///
     /// Priority (highest to lowest):
@@ -182,7 +191,9 @@ impl AuthConfig {
         Ok(config)
     }
 
-    /// Merge another auth config into this one (other takes precedence)
+    /// Merge another auth config into this one
+    ///
+    /// Host entries are unioned; for a host present in both, `other` takes precedence.
     pub fn merge(&mut self, other: AuthConfig) {
         for (domain, creds) in other.http_basic {
             self.http_basic.insert(domain, creds);
@@ -653,4 +664,32 @@ mod tests {
             None => std::env::remove_var("COMPOSER_AUTH"),
         }
     }
+
+    #[test]
+    fn test_env_auth_malformed_json_is_an_error() {
+        let err = AuthConfig::from_env_json(r#"{"github-oauth": {"github.com": "#).unwrap_err();
+        assert!(err.to_string().contains("COMPOSER_AUTH"));
+
+        assert!(AuthConfig::from_env_json(r#"["not", "an", "object"]"#).is_err());
+    }
+
+    #[test]
+    fn test_env_auth_merges_per_host() {
+        let mut config = AuthConfig::from_json(r#"{
+            "github-oauth": {"github.com": "file_token"},
+            "http-basic": {
+                "repo.example.org": {"username": "file", "password": "file"},
+                "other.example.org": {"username": "other", "password": "other"}
+            }
+        }"#).unwrap();
+
+        let env = AuthConfig::from_env_json(r#"{
+            "http-basic": {"repo.example.org": {"username": "ci", "password": "secret"}}
+        }"#).unwrap();
+        config.merge(env);
+
+        assert_eq!(config.get_http_basic("repo.example.org").unwrap().username, "ci");
+        assert_eq!(config.get_http_basic("other.example.org").unwrap().username, "other");
+        assert_eq!(config.get_github_oauth("github.com"), Some("file_token"));
+    }
 }
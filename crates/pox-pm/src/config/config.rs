@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 
 use crate::error::Result;
 use super::source::{ConfigLoader, ConfigSource, RawConfig};
+use crate::profile::{self, Phase};
 
 /// Preferred installation method
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -150,6 +151,18 @@ impl Default for AuditConfig {
     }
 }
 
+/// License policy enforced by `pm licenses`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicensesConfig {
+    /// SPDX identifiers that are permitted; empty permits everything not disallowed
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    /// SPDX identifiers that are never permitted
+    #[serde(default)]
+    pub disallow: Vec<String>,
+}
+
 /// HTTP Basic authentication credentials
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpBasicAuth {
@@ -178,6 +191,18 @@ pub struct BitbucketOAuth {
     pub consumer_secret: String,
 }
 
+/// Client certificate presented to one host, from the `client-certificates` map
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientCertificate {
+    /// PEM file with the certificate, and the private key unless `local-pk` is set
+    #[serde(rename = "local-cert")]
+    pub local_cert: PathBuf,
+
+    /// PEM file with the PKCS#8 private key
+    #[serde(rename = "local-pk", skip_serializing_if = "Option::is_none")]
+    pub local_pk: Option<PathBuf>,
+}
+
 /// Main Composer configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -232,6 +257,11 @@ pub struct Config {
     #[serde(rename = "preferred-install", default)]
     pub preferred_install: PreferredInstall,
 
+    /// Per-package install method from a `preferred-install` map, in declaration order.
+    /// Keys are package name patterns where `*` matches any sequence of characters.
+    #[serde(skip)]
+    pub preferred_install_packages: Vec<(String, PreferredInstall)>,
+
     #[serde(rename = "store-auths", default)]
     pub store_auths: StoreAuths,
 
@@ -247,6 +277,9 @@ pub struct Config {
     #[serde(rename = "sort-packages", default)]
     pub sort_packages: bool,
 
+    #[serde(rename = "prefer-newer-by-time", default)]
+    pub prefer_newer_by_time: bool,
+
     #[serde(rename = "classmap-authoritative", default)]
     pub classmap_authoritative: bool,
 
@@ -271,6 +304,23 @@ pub struct Config {
     #[serde(default)]
     pub audit: AuditConfig,
 
+    #[serde(default)]
+    pub licenses: LicensesConfig,
+
+    /// Suggested packages to install as if required, from a `promote-suggestions` map of
+    /// package name to `true`. They are only promoted when a present package suggests them.
+    #[serde(skip)]
+    pub promote_suggestions: Vec<String>,
+
+    // Network - HTTP
+    /// Seconds to wait for a connection to be established
+    #[serde(rename = "http-connect-timeout", default = "default_http_connect_timeout")]
+    pub http_connect_timeout: u64,
+
+    /// Seconds to wait for data on an open connection, 0 to wait forever
+    #[serde(rename = "http-read-timeout", default = "default_http_read_timeout")]
+    pub http_read_timeout: u64,
+
     // Network - Security
     #[serde(rename = "secure-http", default = "default_true")]
     pub secure_http: bool,
@@ -287,6 +337,10 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capath: Option<PathBuf>,
 
+    /// Client certificates for mutual TLS, by host
+    #[serde(rename = "client-certificates", default)]
+    pub client_certificates: HashMap<String, ClientCertificate>,
+
     // Network - Protocols
     #[serde(rename = "github-protocols", default = "default_github_protocols")]
     pub github_protocols: Vec<String>,
@@ -384,6 +438,14 @@ fn default_process_timeout() -> u64 {
     300
 }
 
+fn default_http_connect_timeout() -> u64 {
+    10
+}
+
+fn default_http_read_timeout() -> u64 {
+    30
+}
+
 fn default_cache_ttl() -> u64 {
     15552000 // 6 months in seconds
 }
@@ -392,6 +454,20 @@ fn default_cache_files_maxsize() -> u64 {
     300 * 1024 * 1024 // 300 MiB
 }
 
+/// Parse a size like `300MiB`, `1G` or `512k` into bytes
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let value = value.strip_suffix("ib").or_else(|| value.strip_suffix('b')).unwrap_or(&value);
+    let (number, multiplier) = match value.chars().last()? {
+        'k' => (&value[..value.len() - 1], 1024.0),
+        'm' => (&value[..value.len() - 1], 1024.0 * 1024.0),
+        'g' => (&value[..value.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        _ => (value, 1.0),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    (number >= 0.0).then_some((number * multiplier) as u64)
+}
+
 fn default_github_protocols() -> Vec<String> {
     vec!["https".to_string(), "ssh".to_string(), "git".to_string()]
 }
@@ -446,11 +522,13 @@ impl Default for Config {
             use_include_path: false,
             use_parent_dir: Some("prompt".to_string()),
             preferred_install: PreferredInstall::default(),
+            preferred_install_packages: Vec::new(),
             store_auths: StoreAuths::default(),
             notify_on_install: true,
             discard_changes: DiscardChanges::default(),
             optimize_autoloader: false,
             sort_packages: false,
+            prefer_newer_by_time: false,
             classmap_authoritative: false,
             apcu_autoloader: false,
             prepend_autoloader: true,
@@ -459,6 +537,12 @@ impl Default for Config {
             platform_check: PlatformCheck::default(),
             allow_plugins: AllowPlugins::default(),
             audit: AuditConfig::default(),
+            licenses: LicensesConfig::default(),
+            promote_suggestions: Vec::new(),
+
+            // Network - HTTP
+            http_connect_timeout: default_http_connect_timeout(),
+            http_read_timeout: default_http_read_timeout(),
 
             // Network - Security
             secure_http: true,
@@ -466,6 +550,7 @@ impl Default for Config {
             secure_svn_domains: Vec::new(),
             cafile: None,
             capath: None,
+            client_certificates: HashMap::new(),
 
             // Network - Protocols
             github_protocols: default_github_protocols(),
@@ -526,6 +611,7 @@ impl Config {
 
     /// Build configuration from all sources (defaults, global, project, env)
     pub fn build<P: AsRef<Path>>(project_dir: Option<P>, use_environment: bool) -> Result<Self> {
+        let _timer = profile::start(Phase::ConfigLoad);
         let loader = ConfigLoader::new(use_environment);
         let mut config = Self::default();
 
@@ -558,6 +644,23 @@ impl Config {
         Ok(config)
     }
 
+    /// Resolve the global Composer home directory from the environment
+    ///
+    /// `COMPOSER_HOME` takes precedence, then `%APPDATA%\Composer` on Windows or
+    /// `$XDG_CONFIG_HOME/composer` elsewhere, falling back to `~/.composer`.
+    pub fn global_home() -> PathBuf {
+        ConfigLoader::new(true).get_composer_home()
+    }
+
+    /// Resolve the global Composer cache directory from the environment
+    ///
+    /// `COMPOSER_CACHE_DIR` takes precedence, then `$COMPOSER_HOME/cache`,
+    /// `%LOCALAPPDATA%\Composer` on Windows or the XDG cache directory elsewhere,
+    /// falling back to the `cache` folder inside the global home.
+    pub fn cache_dir() -> PathBuf {
+        ConfigLoader::new(true).get_cache_dir()
+    }
+
     /// Set base directory (must be absolute path)
     pub fn set_base_dir<P: AsRef<Path>>(&mut self, base_dir: P) {
         self.base_dir = Some(base_dir.as_ref().to_path_buf());
@@ -648,6 +751,25 @@ impl Config {
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "cache-ttl" => {
+                if let Some(n) = value.as_u64() {
+                    self.cache_ttl = n;
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "cache-files-ttl" => {
+                if let Some(n) = value.as_u64() {
+                    self.cache_files_ttl = Some(n);
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "cache-files-maxsize" => {
+                let size = value.as_u64().or_else(|| value.as_str().and_then(parse_size));
+                if let Some(size) = size {
+                    self.cache_files_maxsize = size;
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "data-dir" => {
                 if let Some(s) = value.as_str() {
                     self.data_dir = Some(PathBuf::from(s));
@@ -660,6 +782,18 @@ impl Config {
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "http-connect-timeout" => {
+                if let Some(n) = value.as_u64() {
+                    self.http_connect_timeout = n;
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "http-read-timeout" => {
+                if let Some(n) = value.as_u64() {
+                    self.http_read_timeout = n;
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "use-include-path" => {
                 if let Some(b) = value.as_bool() {
                     self.use_include_path = b;
@@ -670,8 +804,19 @@ impl Config {
                 if let Some(s) = value.as_str() {
                     if let Some(pi) = PreferredInstall::from_str(s) {
                         self.preferred_install = pi;
+                        self.preferred_install_packages.clear();
                         self.sources.insert(key.to_string(), source);
                     }
+                } else if let Some(map) = value.as_object() {
+                    self.preferred_install_packages = map
+                        .iter()
+                        .filter_map(|(pattern, v)| {
+                            v.as_str()
+                                .and_then(PreferredInstall::from_str)
+                                .map(|pi| (pattern.clone(), pi))
+                        })
+                        .collect();
+                    self.sources.insert(key.to_string(), source);
                 }
             }
             "store-auths" => {
@@ -711,6 +856,12 @@ impl Config {
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "prefer-newer-by-time" => {
+                if let Some(b) = value.as_bool() {
+                    self.prefer_newer_by_time = b;
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "classmap-authoritative" => {
                 if let Some(b) = value.as_bool() {
                     self.classmap_authoritative = b;
@@ -735,12 +886,50 @@ impl Config {
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "cafile" | "capath" => {
+                if let Some(s) = value.as_str() {
+                    let path = Some(self.resolve_path(Path::new(s)));
+                    if key == "cafile" {
+                        self.cafile = path;
+                    } else {
+                        self.capath = path;
+                    }
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "client-certificates" => {
+                if let Some(obj) = value.as_object() {
+                    for (host, certificate) in obj {
+                        match serde_json::from_value::<ClientCertificate>(certificate.clone()) {
+                            Ok(mut certificate) => {
+                                certificate.local_cert = self.resolve_path(&certificate.local_cert);
+                                certificate.local_pk = certificate.local_pk.map(|pk| self.resolve_path(&pk));
+                                self.client_certificates.insert(host.to_lowercase(), certificate);
+                            }
+                            Err(e) => log::debug!("Ignoring client certificate for {}: {}", host, e),
+                        }
+                    }
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "lock" => {
                 if let Some(b) = value.as_bool() {
                     self.lock = b;
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "archive-format" => {
+                if let Some(s) = value.as_str() {
+                    self.archive_format = s.to_string();
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "archive-dir" => {
+                if let Some(s) = value.as_str() {
+                    self.archive_dir = PathBuf::from(s);
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "platform-check" => {
                 if let Some(s) = value.as_str() {
                     if let Some(pc) = PlatformCheck::from_str(s) {
@@ -801,6 +990,34 @@ impl Config {
                     self.sources.insert(key.to_string(), source);
                 }
             }
+            "licenses" => {
+                if let Some(obj) = value.as_object() {
+                    let list = |name: &str| -> Option<Vec<String>> {
+                        obj.get(name)?
+                            .as_array()
+                            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                    };
+                    if let Some(allow) = list("allow") {
+                        self.licenses.allow = allow;
+                    }
+                    if let Some(disallow) = list("disallow") {
+                        self.licenses.disallow = disallow;
+                    }
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
+            "promote-suggestions" => {
+                if let Some(obj) = value.as_object() {
+                    for (name, enabled) in obj {
+                        let name = name.to_lowercase();
+                        self.promote_suggestions.retain(|n| *n != name);
+                        if enabled.as_bool() == Some(true) {
+                            self.promote_suggestions.push(name);
+                        }
+                    }
+                    self.sources.insert(key.to_string(), source);
+                }
+            }
             "github-oauth" => {
                 if let Some(obj) = value.as_object() {
                     for (k, v) in obj {
@@ -841,6 +1058,20 @@ impl Config {
             );
         }
 
+        // HTTP timeouts
+        for (key, value) in [
+            ("http-connect-timeout", &mut self.http_connect_timeout),
+            ("http-read-timeout", &mut self.http_read_timeout),
+        ] {
+            if let Some(timeout) = loader.get_env_u64(key) {
+                *value = timeout;
+                self.sources.insert(
+                    key.to_string(),
+                    ConfigSource::Environment(format!("COMPOSER_{}", key.replace('-', "_").to_uppercase())),
+                );
+            }
+        }
+
         // Cache directory
         if let Some(cache_dir) = loader.get_env_path("cache-dir") {
             self.cache_dir = Some(cache_dir);
@@ -922,6 +1153,11 @@ impl Config {
             self.cache_vcs_dir = Some(self.cache_dir.as_ref().unwrap().join("vcs"));
         }
 
+        // Without an explicit bin-dir, binaries follow a relocated vendor-dir
+        if matches!(self.sources.get("bin-dir"), None | Some(ConfigSource::Default)) {
+            self.bin_dir = self.vendor_dir.join("bin");
+        }
+
         let bin_dir_str = self.bin_dir.to_string_lossy();
         if bin_dir_str.contains("{$vendor-dir}") {
             let vendor_dir_str = self.vendor_dir.to_string_lossy();
@@ -936,8 +1172,13 @@ impl Config {
             "vendor-dir".to_string(),
             "bin-dir".to_string(),
             "cache-dir".to_string(),
+            "cache-ttl".to_string(),
+            "cache-files-ttl".to_string(),
+            "cache-files-maxsize".to_string(),
             "data-dir".to_string(),
             "process-timeout".to_string(),
+            "http-connect-timeout".to_string(),
+            "http-read-timeout".to_string(),
             "use-include-path".to_string(),
             "preferred-install".to_string(),
             "store-auths".to_string(),
@@ -945,11 +1186,17 @@ impl Config {
             "discard-changes".to_string(),
             "optimize-autoloader".to_string(),
             "sort-packages".to_string(),
+            "prefer-newer-by-time".to_string(),
             "classmap-authoritative".to_string(),
             "apcu-autoloader".to_string(),
             "secure-http".to_string(),
             "disable-tls".to_string(),
+            "cafile".to_string(),
+            "capath".to_string(),
+            "client-certificates".to_string(),
             "lock".to_string(),
+            "archive-format".to_string(),
+            "archive-dir".to_string(),
             "platform-check".to_string(),
             "github-protocols".to_string(),
             "github-domains".to_string(),
@@ -982,6 +1229,114 @@ mod tests {
         assert_eq!(PreferredInstall::from_str("invalid"), None);
     }
 
+    #[test]
+    fn test_preferred_install_per_package() {
+        let mut config = Config::default();
+        let value = serde_json::json!({"mycompany/*": "source", "*": "dist", "other/*": "bogus"});
+        config.merge_config_value("preferred-install", value, ConfigSource::Project).unwrap();
+
+        assert_eq!(config.preferred_install, PreferredInstall::Dist);
+        assert_eq!(
+            config.preferred_install_packages,
+            vec![
+                ("mycompany/*".to_string(), PreferredInstall::Source),
+                ("*".to_string(), PreferredInstall::Dist),
+            ]
+        );
+
+        config.merge_config_value("preferred-install", serde_json::json!("source"), ConfigSource::Project).unwrap();
+        assert_eq!(config.preferred_install, PreferredInstall::Source);
+        assert!(config.preferred_install_packages.is_empty());
+    }
+
+    #[test]
+    fn test_bin_dir_follows_vendor_dir() {
+        let loader = ConfigLoader::new(false);
+
+        let mut config = Config::default();
+        config.merge_config_value("vendor-dir", serde_json::json!("lib/vendor"), ConfigSource::Project).unwrap();
+        config.resolve_paths(&loader);
+        assert_eq!(config.bin_dir, PathBuf::from("lib/vendor/bin"));
+
+        let mut config = Config::default();
+        config.merge_config_value("vendor-dir", serde_json::json!("lib/vendor"), ConfigSource::Project).unwrap();
+        config.merge_config_value("bin-dir", serde_json::json!("{$vendor-dir}/tools"), ConfigSource::Project).unwrap();
+        config.resolve_paths(&loader);
+        assert_eq!(config.bin_dir, PathBuf::from("lib/vendor/tools"));
+    }
+
+    #[test]
+    fn test_promote_suggestions() {
+        let mut config = Config::default();
+        let value = serde_json::json!({"Acme/Redis-Cache": true, "acme/debug": false, "acme/queue": true});
+        config.merge_config_value("promote-suggestions", value, ConfigSource::Global).unwrap();
+        assert_eq!(config.promote_suggestions, vec!["acme/redis-cache", "acme/queue"]);
+
+        let value = serde_json::json!({"acme/queue": false, "acme/debug": true});
+        config.merge_config_value("promote-suggestions", value, ConfigSource::Project).unwrap();
+        assert_eq!(config.promote_suggestions, vec!["acme/redis-cache", "acme/debug"]);
+    }
+
+    #[test]
+    fn test_prefer_newer_by_time() {
+        let mut config = Config::default();
+        assert!(!config.prefer_newer_by_time);
+
+        config.merge_config_value("prefer-newer-by-time", serde_json::json!(true), ConfigSource::Project).unwrap();
+        assert!(config.prefer_newer_by_time);
+        assert!(config.config_keys().contains(&"prefer-newer-by-time".to_string()));
+    }
+
+    #[test]
+    fn test_tls_files_resolve_against_base_dir() {
+        let mut config = Config::with_base_dir("/project");
+        config.merge_config_value("cafile", serde_json::json!("certs/ca.pem"), ConfigSource::Project).unwrap();
+        let value = serde_json::json!({
+            "Repo.Example.org": {"local-cert": "certs/client.pem", "local-pk": "/keys/client.key"},
+            "broken.example.org": {"local-pk": "client.key"}
+        });
+        config.merge_config_value("client-certificates", value, ConfigSource::Project).unwrap();
+
+        assert_eq!(config.cafile, Some(PathBuf::from("/project/certs/ca.pem")));
+        assert_eq!(config.client_certificates.len(), 1);
+        assert_eq!(config.client_certificates["repo.example.org"], ClientCertificate {
+            local_cert: PathBuf::from("/project/certs/client.pem"),
+            local_pk: Some(PathBuf::from("/keys/client.key")),
+        });
+    }
+
+    #[test]
+    fn test_cache_gc_settings() {
+        let mut config = Config::default();
+        config.merge_config_value("cache-ttl", serde_json::json!(86400), ConfigSource::Global).unwrap();
+        config.merge_config_value("cache-files-ttl", serde_json::json!(3600), ConfigSource::Global).unwrap();
+        config.merge_config_value("cache-files-maxsize", serde_json::json!("1.5GiB"), ConfigSource::Global).unwrap();
+
+        assert_eq!(config.cache_ttl, 86400);
+        assert_eq!(config.cache_files_ttl, Some(3600));
+        assert_eq!(config.cache_files_maxsize, 1536 * 1024 * 1024);
+
+        assert_eq!(parse_size("300MiB"), Some(300 * 1024 * 1024));
+        assert_eq!(parse_size("512k"), Some(512 * 1024));
+        assert_eq!(parse_size("2048"), Some(2048));
+        assert_eq!(parse_size("lots"), None);
+    }
+
+    #[test]
+    fn test_licenses_policy() {
+        let mut config = Config::default();
+        let value = serde_json::json!({"disallow": ["GPL-3.0-only", "AGPL-3.0-only"]});
+        config.merge_config_value("licenses", value, ConfigSource::Project).unwrap();
+
+        assert!(config.licenses.allow.is_empty());
+        assert_eq!(config.licenses.disallow, vec!["GPL-3.0-only", "AGPL-3.0-only"]);
+
+        let value = serde_json::json!({"allow": ["MIT"]});
+        config.merge_config_value("licenses", value, ConfigSource::Project).unwrap();
+        assert_eq!(config.licenses.allow, vec!["MIT"]);
+        assert_eq!(config.licenses.disallow, vec!["GPL-3.0-only", "AGPL-3.0-only"]);
+    }
+
     #[test]
     fn test_store_auths_from_str() {
         assert_eq!(StoreAuths::from_str("true"), Some(StoreAuths::True));
@@ -46,7 +46,7 @@ mod source;
 
 pub use auth::{AuthConfig, AuthMatch, BitbucketOAuthCredentials, GitLabAuth, HttpBasicCredentials};
 pub use config::{
-    AllowPlugins, AuditConfig, BitbucketOAuth, Config, DiscardChanges, GitLabToken,
-    HttpBasicAuth, PlatformCheck, PreferredInstall, StoreAuths,
+    AllowPlugins, AuditConfig, BitbucketOAuth, ClientCertificate, Config, DiscardChanges, GitLabToken,
+    HttpBasicAuth, LicensesConfig, PlatformCheck, PreferredInstall, StoreAuths,
 };
 pub use source::{ConfigLoader, ConfigSource, RawConfig};
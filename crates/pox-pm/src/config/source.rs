@@ -66,39 +66,25 @@ impl ConfigLoader {
         env::var(var).ok().filter(|s| !s.is_empty())
     }
 
-    /// Get the composer home directory
+    /// Get the composer home directory (`COMPOSER_HOME`, then APPDATA/XDG, then `~/.composer`)
     pub fn get_composer_home(&self) -> PathBuf {
-        // Check COMPOSER_HOME env var first
-        if let Some(home) = self.get_composer_env("COMPOSER_HOME") {
-            return PathBuf::from(home);
-        }
-
-        // Fallback to platform-specific directories
-        if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "composer") {
-            proj_dirs.config_dir().to_path_buf()
-        } else {
-            // Ultimate fallback to ~/.composer
-            if let Some(home_dir) = directories::BaseDirs::new() {
-                home_dir.home_dir().join(".composer")
-            } else {
-                PathBuf::from(".composer")
-            }
-        }
+        resolve_composer_home(&|var| self.get_dir_env(var), cfg!(windows))
     }
 
-    /// Get the cache directory
+    /// Get the cache directory (`COMPOSER_CACHE_DIR`, then LOCALAPPDATA/XDG, then `<home>/cache`)
     pub fn get_cache_dir(&self) -> PathBuf {
-        // Check COMPOSER_CACHE_DIR env var first
-        if let Some(cache) = self.get_composer_env("COMPOSER_CACHE_DIR") {
-            return PathBuf::from(cache);
-        }
+        resolve_cache_dir(&|var| self.get_dir_env(var), cfg!(windows))
+    }
 
-        // Use XDG cache dir on Unix, AppData on Windows
-        if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "composer") {
-            proj_dirs.cache_dir().to_path_buf()
+    /// Look up an environment variable used to locate the home and cache directories.
+    ///
+    /// `COMPOSER_*` variables honor `use_environment`, platform variables such as
+    /// `HOME` or `XDG_CONFIG_HOME` are always read.
+    fn get_dir_env(&self, var: &str) -> Option<String> {
+        if var.starts_with("COMPOSER_") {
+            self.get_composer_env(var)
         } else {
-            // Fallback to home/.composer/cache
-            self.get_composer_home().join("cache")
+            env::var(var).ok().filter(|s| !s.is_empty())
         }
     }
 
@@ -171,6 +157,75 @@ impl ConfigLoader {
     }
 }
 
+/// Resolve the global Composer home directory.
+///
+/// `COMPOSER_HOME` always wins. On Windows the home is `%APPDATA%\Composer`, elsewhere
+/// `$XDG_CONFIG_HOME/composer` is used when `XDG_CONFIG_HOME` is set and `~/.composer`
+/// otherwise.
+pub(crate) fn resolve_composer_home(env: &dyn Fn(&str) -> Option<String>, windows: bool) -> PathBuf {
+    if let Some(home) = env("COMPOSER_HOME") {
+        return PathBuf::from(home);
+    }
+
+    if windows {
+        if let Some(app_data) = env("APPDATA") {
+            return PathBuf::from(app_data).join("Composer");
+        }
+        return user_dir(env, windows).join(".composer");
+    }
+
+    if let Some(xdg_config) = env("XDG_CONFIG_HOME") {
+        return PathBuf::from(xdg_config).join("composer");
+    }
+
+    user_dir(env, windows).join(".composer")
+}
+
+/// Resolve the Composer cache directory.
+///
+/// `COMPOSER_CACHE_DIR` wins, followed by `$COMPOSER_HOME/cache`. On Windows the cache
+/// lives in `%LOCALAPPDATA%\Composer`; elsewhere an XDG setup (`XDG_CONFIG_HOME` or
+/// `XDG_CACHE_HOME` set) uses `$XDG_CACHE_HOME/composer` (defaulting to `~/.cache`), and
+/// the `cache` folder inside the home directory is used otherwise.
+pub(crate) fn resolve_cache_dir(env: &dyn Fn(&str) -> Option<String>, windows: bool) -> PathBuf {
+    if let Some(cache) = env("COMPOSER_CACHE_DIR") {
+        return PathBuf::from(cache);
+    }
+
+    if let Some(home) = env("COMPOSER_HOME") {
+        return PathBuf::from(home).join("cache");
+    }
+
+    if windows {
+        if let Some(local_app_data) = env("LOCALAPPDATA") {
+            return PathBuf::from(local_app_data).join("Composer");
+        }
+        return resolve_composer_home(env, windows).join("cache");
+    }
+
+    if let Some(xdg_cache) = env("XDG_CACHE_HOME") {
+        return PathBuf::from(xdg_cache).join("composer");
+    }
+
+    if env("XDG_CONFIG_HOME").is_some() {
+        return user_dir(env, windows).join(".cache").join("composer");
+    }
+
+    resolve_composer_home(env, windows).join("cache")
+}
+
+/// The current user's home directory
+fn user_dir(env: &dyn Fn(&str) -> Option<String>, windows: bool) -> PathBuf {
+    let var = if windows { "USERPROFILE" } else { "HOME" };
+    if let Some(home) = env(var) {
+        return PathBuf::from(home);
+    }
+
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,4 +262,83 @@ mod tests {
         let cache = loader.get_cache_dir();
         assert!(cache.is_absolute() || cache.ends_with("cache"));
     }
+
+    fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |var| vars.get(var).cloned()
+    }
+
+    #[test]
+    fn test_resolve_composer_home_unix() {
+        let env = env_from(&[("HOME", "/home/user")]);
+        assert_eq!(resolve_composer_home(&env, false), PathBuf::from("/home/user/.composer"));
+
+        let env = env_from(&[("HOME", "/home/user"), ("XDG_CONFIG_HOME", "/home/user/.config")]);
+        assert_eq!(resolve_composer_home(&env, false), PathBuf::from("/home/user/.config/composer"));
+
+        let env = env_from(&[
+            ("HOME", "/home/user"),
+            ("XDG_CONFIG_HOME", "/home/user/.config"),
+            ("COMPOSER_HOME", "/opt/composer"),
+        ]);
+        assert_eq!(resolve_composer_home(&env, false), PathBuf::from("/opt/composer"));
+    }
+
+    #[test]
+    fn test_resolve_composer_home_windows() {
+        let env = env_from(&[
+            ("APPDATA", r"C:\Users\user\AppData\Roaming"),
+            ("XDG_CONFIG_HOME", "/ignored"),
+        ]);
+        assert_eq!(
+            resolve_composer_home(&env, true),
+            PathBuf::from(r"C:\Users\user\AppData\Roaming").join("Composer")
+        );
+
+        let env = env_from(&[("USERPROFILE", r"C:\Users\user")]);
+        assert_eq!(
+            resolve_composer_home(&env, true),
+            PathBuf::from(r"C:\Users\user").join(".composer")
+        );
+    }
+
+    #[test]
+    fn test_resolve_cache_dir_unix() {
+        let env = env_from(&[("HOME", "/home/user")]);
+        assert_eq!(resolve_cache_dir(&env, false), PathBuf::from("/home/user/.composer/cache"));
+
+        let env = env_from(&[("HOME", "/home/user"), ("XDG_CONFIG_HOME", "/home/user/.config")]);
+        assert_eq!(resolve_cache_dir(&env, false), PathBuf::from("/home/user/.cache/composer"));
+
+        let env = env_from(&[("HOME", "/home/user"), ("XDG_CACHE_HOME", "/var/cache")]);
+        assert_eq!(resolve_cache_dir(&env, false), PathBuf::from("/var/cache/composer"));
+
+        let env = env_from(&[("XDG_CACHE_HOME", "/var/cache"), ("COMPOSER_HOME", "/opt/composer")]);
+        assert_eq!(resolve_cache_dir(&env, false), PathBuf::from("/opt/composer/cache"));
+
+        let env = env_from(&[("COMPOSER_HOME", "/opt/composer"), ("COMPOSER_CACHE_DIR", "/tmp/cache")]);
+        assert_eq!(resolve_cache_dir(&env, false), PathBuf::from("/tmp/cache"));
+    }
+
+    #[test]
+    fn test_resolve_cache_dir_windows() {
+        let env = env_from(&[
+            ("APPDATA", r"C:\Users\user\AppData\Roaming"),
+            ("LOCALAPPDATA", r"C:\Users\user\AppData\Local"),
+            ("XDG_CACHE_HOME", "/ignored"),
+        ]);
+        assert_eq!(
+            resolve_cache_dir(&env, true),
+            PathBuf::from(r"C:\Users\user\AppData\Local").join("Composer")
+        );
+
+        let env = env_from(&[("APPDATA", r"C:\Users\user\AppData\Roaming")]);
+        assert_eq!(
+            resolve_cache_dir(&env, true),
+            PathBuf::from(r"C:\Users\user\AppData\Roaming").join("Composer").join("cache")
+        );
+    }
 }
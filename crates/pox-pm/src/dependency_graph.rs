@@ -38,6 +38,9 @@ pub fn get_dependents(
         let mut packages_in_tree = packages_found.clone();
 
         if !invert {
+            // Providing a package satisfies requirements on it just like replacing does
+            links.extend(hashmap_to_links(&package.provide, &package.name, LinkType::Provide));
+
             let replace_links = hashmap_to_links(&package.replace, &package.name, LinkType::Replace);
             links.extend(replace_links.clone());
 
@@ -161,6 +164,33 @@ pub fn get_dependents(
     results
 }
 
+/// Links on `needle` that keep it from being installed in a version matching `constraint`
+///
+/// These are the requirements whose constraint doesn't intersect `constraint` and the
+/// conflicts whose constraint does. With `recurse`, the children of each result are the
+/// packages depending on the blocking package, up to the root.
+pub fn get_blocking_dependents(
+    packages: &[Arc<Package>],
+    needle: &str,
+    constraint: &dyn ConstraintInterface,
+    recurse: bool,
+) -> Vec<DependencyResult> {
+    get_dependents(packages, &[needle.to_string()], None, false, recurse, None)
+        .into_iter()
+        .filter(|result| {
+            let Ok(link_constraint) = pox_semver::VersionParser.parse_constraints(&result.link.constraint) else {
+                return false;
+            };
+            let excludes = link_constraint.intersect(constraint).is_match_none();
+            match result.link.link_type {
+                LinkType::Require | LinkType::DevRequire => excludes,
+                LinkType::Conflict => !excludes,
+                _ => false,
+            }
+        })
+        .collect()
+}
+
 pub fn find_packages_with_replacers_and_providers(
     packages: &[Arc<Package>],
     name: &str,
@@ -371,6 +401,51 @@ mod tests {
         assert_eq!(results[0].name, "vendor/package1");
     }
 
+    #[test]
+    fn test_virtual_package_dependents_include_providers() {
+        let app = Arc::new(pkg_with_requires("acme/app", "1.0.0", vec![("psr/log-implementation", "^1.0 || ^3.0")]));
+        let monolog = Arc::new(pkg_with_provides("monolog/monolog", "3.5.0", vec![("psr/log-implementation", "3.0.0")]));
+
+        let packages = vec![app, monolog];
+        let results = get_dependents(&packages, &["psr/log-implementation".to_string()], None, false, false, None);
+
+        assert_eq!(results.len(), 2);
+        let provider = results.iter().find(|r| r.package.name == "monolog/monolog").unwrap();
+        assert_eq!(provider.link.link_type, LinkType::Provide);
+        assert_eq!(provider.link.constraint, "3.0.0");
+        assert!(results.iter().any(|r| r.package.name == "acme/app" && r.link.link_type == LinkType::Require));
+
+        // Inverted lookups (why-not) are about requirements only
+        let results = get_dependents(&packages, &["psr/log-implementation".to_string()], None, true, false, None);
+        assert!(results.iter().all(|r| r.link.link_type != LinkType::Provide));
+    }
+
+    #[test]
+    fn test_blocking_dependents() {
+        let framework = Arc::new(pkg_with_requires("acme/framework", "1.4.0", vec![("psr/log", "^1.0 || ^2.0")]));
+        let plugin = Arc::new(pkg_with_requires("acme/plugin", "1.0.0", vec![("psr/log", "^1.1")]));
+        let mut legacy = pkg("acme/legacy", "2.0.0");
+        legacy.conflict.insert("psr/log".to_string(), ">=2.0".to_string());
+        let log = Arc::new(pkg("psr/log", "1.1.4"));
+        let mut root = pkg_with_requires("__root__", "dev-main", vec![("acme/framework", "^1.4"), ("acme/plugin", "^1.0"), ("acme/legacy", "^2.0")]);
+        root.package_type = "root-package".to_string();
+
+        let packages = vec![Arc::new(root), framework, plugin, Arc::new(legacy), log];
+        let wanted = pox_semver::VersionParser.parse_constraints("^2.0").unwrap();
+        let results = get_blocking_dependents(&packages, "psr/log", wanted.as_ref(), false);
+
+        let blockers: Vec<(&str, LinkType)> = results.iter().map(|r| (r.package.name.as_str(), r.link.link_type)).collect();
+        assert_eq!(blockers, vec![("acme/plugin", LinkType::Require), ("acme/legacy", LinkType::Conflict)]);
+
+        let results = get_blocking_dependents(&packages, "psr/log", wanted.as_ref(), true);
+        let plugin = results.iter().find(|r| r.package.name == "acme/plugin").unwrap();
+        let requirers: Vec<&str> = plugin.children.as_ref().unwrap().iter().map(|r| r.package.name.as_str()).collect();
+        assert_eq!(requirers, vec!["__root__"]);
+
+        let wanted = pox_semver::VersionParser.parse_constraints("^1.1").unwrap();
+        assert!(get_blocking_dependents(&packages, "psr/log", wanted.as_ref(), false).is_empty());
+    }
+
     #[test]
     fn test_circular_dependency_detection() {
         let pkg1 = Arc::new(pkg_with_requires("vendor/package1", "1.0.0", vec![("vendor/package2", "^1.0")]));
@@ -200,6 +200,42 @@ impl GitDownloader {
         Ok(commit.id().to_string())
     }
 
+    /// Get uncommitted changes to tracked files, as `git status --porcelain` lines
+    ///
+    /// Untracked files are left out, like Composer does.
+    pub fn get_local_changes(repo_path: &Path) -> Result<Vec<String>> {
+        let repo = Repository::open(repo_path)?;
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+
+        let statuses = repo.statuses(Some(&mut options))?;
+        let changes = statuses
+            .iter()
+            .filter_map(|entry| {
+                let status = entry.status();
+                let code = if status.intersects(git2::Status::INDEX_NEW | git2::Status::WT_NEW) {
+                    'A'
+                } else if status.intersects(git2::Status::INDEX_DELETED | git2::Status::WT_DELETED) {
+                    'D'
+                } else if status.intersects(git2::Status::INDEX_RENAMED | git2::Status::WT_RENAMED) {
+                    'R'
+                } else if status.intersects(
+                    git2::Status::INDEX_MODIFIED
+                        | git2::Status::WT_MODIFIED
+                        | git2::Status::INDEX_TYPECHANGE
+                        | git2::Status::WT_TYPECHANGE,
+                ) {
+                    'M'
+                } else {
+                    return None;
+                };
+                Some(format!("{} {}", code, entry.path()?))
+            })
+            .collect();
+
+        Ok(changes)
+    }
+
     /// Check if a path is a git repository
     pub fn is_git_repo(path: &Path) -> bool {
         Repository::open(path).is_ok()
@@ -255,6 +291,32 @@ mod tests {
         assert!(GitDownloader::is_git_repo(temp_dir.path()));
     }
 
+    #[test]
+    fn test_get_local_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("a.php"), "<?php").unwrap();
+        std::fs::write(temp_dir.path().join("b.php"), "<?php").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.php")).unwrap();
+        index.add_path(Path::new("b.php")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[]).unwrap();
+
+        assert!(GitDownloader::get_local_changes(temp_dir.path()).unwrap().is_empty());
+
+        std::fs::write(temp_dir.path().join("a.php"), "<?php echo 1;").unwrap();
+        std::fs::remove_file(temp_dir.path().join("b.php")).unwrap();
+        std::fs::write(temp_dir.path().join("untracked.php"), "<?php").unwrap();
+
+        let mut changes = GitDownloader::get_local_changes(temp_dir.path()).unwrap();
+        changes.sort();
+        assert_eq!(changes, vec!["D b.php", "M a.php"]);
+    }
+
     #[test]
     #[ignore] // Requires network access
     fn test_clone_public_repo() {
@@ -3,14 +3,19 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use crate::cache;
+use crate::config::PreferredInstall;
 use crate::http::HttpClient;
+use crate::installer::InstallerPaths;
 use crate::package::{Dist, Source};
+use crate::profile::{self, Phase};
 use crate::{ComposerError, Package, Result};
 
 use super::archive::ArchiveExtractor;
 use super::checksum::{verify_checksum, ChecksumType};
 use super::file::FileDownloader;
 use super::git::GitDownloader;
+use super::manifest::DistManifest;
 use super::path::{PathDownloader, PathStrategy};
 
 /// Result of a download operation
@@ -31,10 +36,19 @@ pub struct DownloadConfig {
     pub prefer_source: bool,
     /// Prefer dist over source
     pub prefer_dist: bool,
+    /// Per-package install method by package name pattern (`vendor/*`), first match wins
+    pub package_preferences: Vec<(String, PreferredInstall)>,
     /// Cache directory for downloaded archives
     pub cache_dir: PathBuf,
+    /// Whether archives are read from and kept in `cache_dir`
+    ///
+    /// Without the cache, archives are downloaded to a temporary directory and removed
+    /// once extracted.
+    pub use_cache: bool,
     /// Vendor directory for extracted packages
     pub vendor_dir: PathBuf,
+    /// Custom install paths from the root package's `extra.installer-paths`
+    pub installer_paths: InstallerPaths,
 }
 
 impl Default for DownloadConfig {
@@ -42,8 +56,11 @@ impl Default for DownloadConfig {
         Self {
             prefer_source: false,
             prefer_dist: true,
+            package_preferences: Vec::new(),
             cache_dir: PathBuf::from(".composer/cache"),
+            use_cache: !cache::is_disabled(),
             vendor_dir: PathBuf::from("vendor"),
+            installer_paths: InstallerPaths::default(),
         }
     }
 }
@@ -69,8 +86,24 @@ impl DownloadManager {
 
     /// Download and install a package
     pub async fn download(&self, package: &Package) -> Result<DownloadResult> {
+        self.download_with(package, self.should_use_source(package)).await
+    }
+
+    /// Download and install a package, installing from source first when `use_source` is set
+    ///
+    /// Falls back to dist when the source install fails or there is no source.
+    pub async fn download_with(&self, package: &Package, use_source: bool) -> Result<DownloadResult> {
         let dest_dir = self.package_path(package);
 
+        // Metapackages only carry dependencies; there is nothing to download
+        if package.is_metapackage() {
+            return Ok(DownloadResult {
+                path: dest_dir,
+                from_cache: false,
+                skipped: true,
+            });
+        }
+
         if let Some(dist) = &package.dist {
             if dist.dist_type == "path" {
                 log::debug!("Installing {} ({}) from path", package.name, package.version);
@@ -78,24 +111,42 @@ impl DownloadManager {
             }
         }
 
-        let use_source = self.should_use_source(package);
+        // A manifest from an earlier dist install no longer describes the files
+        DistManifest::remove(&self.config.vendor_dir, &package.name)?;
 
         if use_source {
-            if let Some(source) = &package.source {
-                log::debug!("Installing {} ({}) from source ({})",
-                    package.name, package.version, source.source_type);
-                self.download_from_source(package, source, &dest_dir).await?;
-                return Ok(DownloadResult {
-                    path: dest_dir,
-                    from_cache: false,
-                    skipped: false,
-                });
+            match &package.source {
+                Some(source) => {
+                    log::debug!("Installing {} ({}) from source ({})",
+                        package.name, package.version, source.source_type);
+                    match self.download_from_source(package, source, &dest_dir).await {
+                        Ok(()) => {
+                            return Ok(DownloadResult {
+                                path: dest_dir,
+                                from_cache: false,
+                                skipped: false,
+                            });
+                        }
+                        Err(e) if package.dist.is_some() => {
+                            eprintln!("Warning: Failed to install {} from source: {}. Trying dist instead.", package.name, e);
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                // Dev packages without a source quietly use dist; only warn when asked for source
+                None if package.dist.is_some()
+                    && (self.config.prefer_source || self.package_preference(package).is_some()) =>
+                {
+                    eprintln!("Warning: {} has no source to install from, falling back to dist", package.name);
+                }
+                None => {}
             }
         }
 
         // Try dist download
         if let Some(dist) = &package.dist {
             let from_cache = self.download_from_dist(package, dist, &dest_dir).await?;
+            DistManifest::create(&dest_dir, &package.version)?.save(&self.config.vendor_dir, &package.name)?;
             if from_cache {
                 log::debug!("Loading {} ({}) from cache", package.name, package.version);
             } else {
@@ -147,56 +198,57 @@ impl DownloadManager {
         dist: &Dist,
         dest_dir: &Path,
     ) -> Result<bool> {
-        let cache_file = self.cache_path(package, &dist.dist_type);
+        // Held until the archive is extracted, the directory is removed when dropped
+        let temp_dir = if self.config.use_cache { None } else { Some(tempfile::tempdir()?) };
+        let cache_file = match &temp_dir {
+            Some(temp_dir) => temp_dir.path().join(format!("archive.{}", dist.dist_type)),
+            None => self.cache_path(package, &dist.dist_type),
+        };
         if let Some(parent) = cache_file.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        // Try URLs in order (primary + mirrors)
-        let urls = dist.urls();
-
         let checksum = dist.sha256.as_ref()
             .filter(|s| !s.is_empty())
             .or_else(|| dist.shasum.as_ref().filter(|s| !s.is_empty()));
-
-        for url in &urls {
-            if cache_file.exists() {
-                // Verify checksum if available
-                if let Some(checksum) = checksum {
-                    let checksum_type = ChecksumType::from_hex_length(checksum.len())
-                        .unwrap_or(ChecksumType::Sha256);
-
-                    if verify_checksum(&cache_file, checksum, checksum_type).await? {
-                        self.extract_archive(&cache_file, dest_dir)?;
-                        return Ok(true);
-                    }
-                    let _ = tokio::fs::remove_file(&cache_file).await;
-                } else {
-                    self.extract_archive(&cache_file, dest_dir)?;
-                    return Ok(true);
-                }
+        let checksum_type = checksum.map(|c| ChecksumType::from_hex_length(c.len()).unwrap_or(ChecksumType::Sha256));
+
+        if cache_file.exists() {
+            let valid = match (checksum, checksum_type) {
+                (Some(checksum), Some(checksum_type)) => verify_checksum(&cache_file, checksum, checksum_type).await?,
+                _ => true,
+            };
+            if valid {
+                self.extract_archive(&cache_file, dest_dir)?;
+                return Ok(true);
             }
+            let _ = tokio::fs::remove_file(&cache_file).await;
+        }
+
+        // Try URLs in order (primary + mirrors); a mirror serving a file with the wrong
+        // checksum counts as a failed download
+        let urls = dist.mirror_urls(&package.name, &package.version, package.pretty_version.as_deref());
+        let mut checksum_failed = false;
 
+        for url in &urls {
+            let download_timer = profile::start(Phase::Download);
             let result = self
                 .file_downloader
                 .download(url, &cache_file, None::<fn(u64, u64)>)
                 .await;
+            drop(download_timer);
 
             if let Err(e) = result {
                 eprintln!("Warning: Failed to download from {}: {}", url, e);
                 continue;
             }
 
-            // Verify checksum if available
-            if let Some(checksum) = checksum {
-                let checksum_type = ChecksumType::from_hex_length(checksum.len())
-                    .unwrap_or(ChecksumType::Sha256);
-
+            if let (Some(checksum), Some(checksum_type)) = (checksum, checksum_type) {
                 if !verify_checksum(&cache_file, checksum, checksum_type).await? {
                     let _ = tokio::fs::remove_file(&cache_file).await;
-                    return Err(ComposerError::ChecksumMismatch {
-                        package: package.name.clone(),
-                    });
+                    eprintln!("Warning: Checksum mismatch for {} downloaded from {}", package.name, url);
+                    checksum_failed = true;
+                    continue;
                 }
             }
 
@@ -205,6 +257,12 @@ impl DownloadManager {
             return Ok(false);
         }
 
+        if checksum_failed {
+            return Err(ComposerError::ChecksumMismatch {
+                package: package.name.clone(),
+            });
+        }
+
         Err(ComposerError::DownloadFailed {
             package: package.name.clone(),
             reason: "All download URLs failed".to_string(),
@@ -225,8 +283,10 @@ impl DownloadManager {
 
         match source.source_type.as_str() {
             "git" => {
-                // Try URLs in order
-                for url in source.urls() {
+                let _timer = profile::start(Phase::Download);
+
+                // Try URLs in order (primary + mirrors)
+                for url in source.mirror_urls(&package.name) {
                     let result = self.git_downloader.clone(
                         &url,
                         dest_dir,
@@ -292,6 +352,8 @@ impl DownloadManager {
 
     /// Extract an archive to destination
     fn extract_archive(&self, archive_path: &Path, dest_dir: &Path) -> Result<()> {
+        let _timer = profile::start(Phase::Extract);
+
         // Clean destination if it exists
         if dest_dir.exists() {
             std::fs::remove_dir_all(dest_dir)?;
@@ -303,29 +365,50 @@ impl DownloadManager {
 
     /// Get the path where a package should be installed
     fn package_path(&self, package: &Package) -> PathBuf {
-        self.config.vendor_dir.join(&package.name)
+        self.config.installer_paths.install_path(&self.config.vendor_dir, &package.name, &package.package_type, package.extra.as_ref())
     }
 
     /// Get the cache path for a package archive
     fn cache_path(&self, package: &Package, archive_type: &str) -> PathBuf {
         let safe_name = package.name.replace('/', "-");
         let filename = format!("{}-{}.{}", safe_name, package.version, archive_type);
-        self.config.cache_dir.join("files").join(&package.name).join(filename)
+        self.config.cache_dir.join("files").join(Self::archive_cache_prefix(&package.name)).join(filename)
     }
 
-    /// Determine if source should be used for a package
-    fn should_use_source(&self, package: &Package) -> bool {
-        // Always use source for dev packages
-        if package.is_dev() {
+    /// Path prefix of a package's archives within the files cache
+    pub fn archive_cache_prefix(package_name: &str) -> String {
+        format!("{}/", package_name)
+    }
+
+    /// Determine if source is preferred for a package
+    ///
+    /// `--prefer-source` wins, then the first matching `preferred-install` pattern, then
+    /// dev packages default to source. The caller falls back to dist when the package has
+    /// no source.
+    pub fn should_use_source(&self, package: &Package) -> bool {
+        if self.config.prefer_source {
             return true;
         }
 
-        // Use config preference
-        if self.config.prefer_source {
-            return package.source.is_some();
+        if let Some(preference) = self.package_preference(package) {
+            return match preference {
+                PreferredInstall::Source => true,
+                PreferredInstall::Dist => false,
+                PreferredInstall::Auto => package.is_dev(),
+            };
         }
 
-        false
+        // Always use source for dev packages
+        package.is_dev()
+    }
+
+    /// Find the `preferred-install` entry whose pattern matches the package name
+    fn package_preference(&self, package: &Package) -> Option<&PreferredInstall> {
+        self.config
+            .package_preferences
+            .iter()
+            .find(|(pattern, _)| package_pattern_matches(pattern, &package.name))
+            .map(|(_, preference)| preference)
     }
 
     /// Remove a package
@@ -349,9 +432,16 @@ impl DownloadManager {
     }
 }
 
+/// Match a package name against a pattern where `*` matches any characters, case-insensitively
+fn package_pattern_matches(pattern: &str, name: &str) -> bool {
+    let regex = format!("(?i)^{}$", regex::escape(pattern).replace(r"\*", ".*"));
+    regex::Regex::new(&regex).is_ok_and(|re| re.is_match(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_server::serve;
 
     #[test]
     fn test_download_config_default() {
@@ -375,6 +465,25 @@ mod tests {
         assert_eq!(path, PathBuf::from("/app/vendor/vendor/package"));
     }
 
+    #[test]
+    fn test_package_path_with_installer_paths() {
+        let client = Arc::new(HttpClient::new().unwrap());
+        let config = DownloadConfig {
+            vendor_dir: PathBuf::from("/app/vendor"),
+            installer_paths: InstallerPaths::new(
+                "/app",
+                &serde_json::json!({"installer-paths": {"web/plugins/{$name}": ["type:wordpress-plugin"]}}),
+            ),
+            ..Default::default()
+        };
+        let manager = DownloadManager::new(client, config);
+
+        let mut package = Package::new("wpackagist/akismet", "1.0.0");
+        package.package_type = "wordpress-plugin".to_string();
+
+        assert_eq!(manager.package_path(&package), PathBuf::from("/app/web/plugins/akismet"));
+    }
+
     #[test]
     fn test_cache_path() {
         let client = Arc::new(HttpClient::new().unwrap());
@@ -426,4 +535,134 @@ mod tests {
 
         assert!(manager.should_use_source(&package));
     }
+
+    #[test]
+    fn test_should_use_source_package_preferences() {
+        let client = Arc::new(HttpClient::new().unwrap());
+        let config = DownloadConfig {
+            package_preferences: vec![
+                ("mycompany/*".to_string(), PreferredInstall::Source),
+                ("other/dev-dist".to_string(), PreferredInstall::Dist),
+                ("*".to_string(), PreferredInstall::Auto),
+            ],
+            ..Default::default()
+        };
+        let manager = DownloadManager::new(client, config);
+
+        assert!(manager.should_use_source(&Package::new("MyCompany/lib", "1.0.0")));
+        assert!(!manager.should_use_source(&Package::new("other/dev-dist", "dev-main")));
+        assert!(!manager.should_use_source(&Package::new("vendor/package", "1.0.0")));
+        assert!(manager.should_use_source(&Package::new("vendor/package", "dev-main")));
+    }
+
+    #[test]
+    fn test_package_pattern_matches() {
+        assert!(package_pattern_matches("*", "vendor/package"));
+        assert!(package_pattern_matches("vendor/*", "vendor/package"));
+        assert!(package_pattern_matches("vendor/package", "Vendor/Package"));
+        assert!(!package_pattern_matches("vendor/*", "other/package"));
+        assert!(!package_pattern_matches("vendor.package", "vendor/package"));
+    }
+
+    /// A zip archive containing only a composer.json
+    fn test_archive() -> Vec<u8> {
+        use std::io::Write;
+
+        let mut archive = Vec::new();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut archive));
+        zip.start_file("composer.json", zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(br#"{"name": "vendor/package"}"#).unwrap();
+        zip.finish().unwrap();
+        archive
+    }
+
+    #[tokio::test]
+    async fn test_dist_falls_back_to_mirror_with_valid_checksum() {
+        use crate::package::Mirror;
+        use sha1::{Digest, Sha1};
+
+        let archive = test_archive();
+        let shasum = format!("{:x}", Sha1::digest(&archive));
+
+        let base = serve(vec![
+            ("/corrupt/vendor/package.zip", b"not the archive".to_vec()),
+            ("/good/vendor/package.zip", archive),
+        ]);
+
+        let temp = tempfile::tempdir().unwrap();
+        let config = DownloadConfig {
+            cache_dir: temp.path().join("cache"),
+            vendor_dir: temp.path().join("vendor"),
+            ..Default::default()
+        };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new().unwrap()), config);
+
+        let mut package = Package::new("vendor/package", "1.0.0.0");
+        package.dist = Some(
+            Dist::zip(format!("{}/missing.zip", base))
+                .with_shasum(&shasum)
+                .with_mirrors(vec![
+                    Mirror::fallback(format!("{}/corrupt/%package%.%type%", base)),
+                    Mirror::fallback(format!("{}/good/%package%.%type%", base)),
+                ]),
+        );
+
+        let result = manager.download(&package).await.unwrap();
+        assert!(!result.from_cache);
+        assert!(result.path.join("composer.json").exists());
+
+        // Every URL serving the wrong archive is a checksum failure
+        package.dist = Some(
+            Dist::zip(format!("{}/corrupt/vendor/package.zip", base))
+                .with_shasum(&shasum)
+                .with_mirrors(vec![Mirror::fallback(format!("{}/missing/%package%.zip", base))]),
+        );
+        package.version = "1.0.1.0".to_string();
+        let err = manager.download(&package).await.unwrap_err();
+        assert!(matches!(err, ComposerError::ChecksumMismatch { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_dist_download_without_cache() {
+        let base = serve(vec![("/package.zip", test_archive())]);
+
+        let temp = tempfile::tempdir().unwrap();
+        let config = DownloadConfig {
+            cache_dir: temp.path().join("cache"),
+            use_cache: false,
+            vendor_dir: temp.path().join("vendor"),
+            ..Default::default()
+        };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new().unwrap()), config);
+
+        let mut package = Package::new("vendor/package", "1.0.0.0");
+        package.dist = Some(Dist::zip(format!("{}/package.zip", base)));
+
+        for _ in 0..2 {
+            let result = manager.download(&package).await.unwrap();
+            assert!(!result.from_cache);
+            assert!(result.path.join("composer.json").exists());
+        }
+        assert!(!temp.path().join("cache").exists());
+    }
+
+    #[tokio::test]
+    async fn test_preferred_source_without_source_falls_back_to_dist() {
+        let base = serve(vec![("/package.zip", test_archive())]);
+
+        let temp = tempfile::tempdir().unwrap();
+        let config = DownloadConfig {
+            package_preferences: vec![("vendor/*".to_string(), PreferredInstall::Source)],
+            cache_dir: temp.path().join("cache"),
+            vendor_dir: temp.path().join("vendor"),
+            ..Default::default()
+        };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new().unwrap()), config);
+
+        let mut package = Package::new("vendor/package", "1.0.0.0");
+        package.dist = Some(Dist::zip(format!("{}/package.zip", base)));
+
+        let result = manager.download(&package).await.unwrap();
+        assert!(result.path.join("composer.json").exists());
+    }
 }
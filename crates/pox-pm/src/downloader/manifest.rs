@@ -0,0 +1,194 @@
+//! Per-file hash manifests of installed dist archives.
+//!
+//! A manifest is written right after a dist archive is extracted, so later edits to the
+//! package directory can be detected without downloading the archive again.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::archiver;
+use crate::Result;
+
+/// Directory below `vendor/composer/` holding the manifests
+const MANIFEST_DIR: &str = "dist-manifests";
+
+/// A local change to a file of an installed dist package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// The file's contents differ from the archive
+    Modified(String),
+    /// The file was part of the archive but no longer exists
+    Deleted(String),
+}
+
+impl FileChange {
+    /// Get the path of the file, relative to the package directory
+    pub fn path(&self) -> &str {
+        match self {
+            FileChange::Modified(path) | FileChange::Deleted(path) => path,
+        }
+    }
+}
+
+/// SHA-256 hashes of the files extracted from a dist archive
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistManifest {
+    /// Version the manifest was recorded for
+    pub version: String,
+    /// File hashes keyed by `/`-separated path relative to the package directory
+    pub files: BTreeMap<String, String>,
+}
+
+impl DistManifest {
+    /// Hash the files below `package_dir` that belong to the dist
+    ///
+    /// Files marked `export-ignore` in the package's `.gitattributes` are not part of a dist
+    /// built by `git archive`, so they are left out like in [`archiver::export_files`].
+    pub fn create(package_dir: &Path, version: &str) -> Result<Self> {
+        let mut files = BTreeMap::new();
+
+        for relative in archiver::export_files(package_dir)? {
+            let path = package_dir.join(&relative);
+            files.insert(slash_path(&relative), hash_file(&path)?);
+        }
+
+        Ok(Self {
+            version: version.to_string(),
+            files,
+        })
+    }
+
+    /// Get the manifest location for a package
+    pub fn path_for(vendor_dir: &Path, package_name: &str) -> PathBuf {
+        vendor_dir
+            .join("composer")
+            .join(MANIFEST_DIR)
+            .join(format!("{}.json", package_name.to_lowercase()))
+    }
+
+    /// Load the manifest of a package, if one was recorded
+    pub fn load(vendor_dir: &Path, package_name: &str) -> Result<Option<Self>> {
+        let path = Self::path_for(vendor_dir, package_name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Write the manifest of a package
+    pub fn save(&self, vendor_dir: &Path, package_name: &str) -> Result<()> {
+        let path = Self::path_for(vendor_dir, package_name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Remove the manifest of a package
+    pub fn remove(vendor_dir: &Path, package_name: &str) -> Result<()> {
+        let path = Self::path_for(vendor_dir, package_name);
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Compare the recorded hashes against the files in `package_dir`
+    ///
+    /// Files that are not part of the manifest are ignored, since installers and plugins
+    /// routinely generate files inside package directories after extraction.
+    pub fn changes(&self, package_dir: &Path) -> Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+
+        for (relative, hash) in &self.files {
+            let path = package_dir.join(relative);
+            if !path.is_file() {
+                changes.push(FileChange::Deleted(relative.clone()));
+            } else if hash_file(&path)? != *hash {
+                changes.push(FileChange::Modified(relative.clone()));
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let content = std::fs::read(path)?;
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_detects_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_dir = temp_dir.path().join("vendor/acme/lib");
+        std::fs::create_dir_all(package_dir.join("src")).unwrap();
+        std::fs::write(package_dir.join("composer.json"), "{}").unwrap();
+        std::fs::write(package_dir.join("src/Foo.php"), "<?php class Foo {}").unwrap();
+        std::fs::write(package_dir.join("src/Bar.php"), "<?php class Bar {}").unwrap();
+
+        let manifest = DistManifest::create(&package_dir, "1.0.0").unwrap();
+        assert_eq!(manifest.files.len(), 3);
+        assert!(manifest.files.contains_key("src/Foo.php"));
+        assert!(manifest.changes(&package_dir).unwrap().is_empty());
+
+        std::fs::write(package_dir.join("src/Foo.php"), "<?php class Foo { public $x; }").unwrap();
+        std::fs::remove_file(package_dir.join("src/Bar.php")).unwrap();
+        std::fs::write(package_dir.join("src/Generated.php"), "<?php").unwrap();
+
+        assert_eq!(
+            manifest.changes(&package_dir).unwrap(),
+            vec![
+                FileChange::Deleted("src/Bar.php".to_string()),
+                FileChange::Modified("src/Foo.php".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manifest_save_load_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let mut manifest = DistManifest {
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        manifest.files.insert("a.php".to_string(), "abc".to_string());
+
+        assert!(DistManifest::load(&vendor_dir, "Acme/Lib").unwrap().is_none());
+        manifest.save(&vendor_dir, "Acme/Lib").unwrap();
+        assert!(vendor_dir.join("composer/dist-manifests/acme/lib.json").exists());
+        assert_eq!(DistManifest::load(&vendor_dir, "acme/lib").unwrap(), Some(manifest));
+
+        DistManifest::remove(&vendor_dir, "acme/lib").unwrap();
+        assert!(DistManifest::load(&vendor_dir, "acme/lib").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_manifest_skips_export_ignored_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_dir = temp_dir.path().join("vendor/acme/lib");
+        std::fs::create_dir_all(package_dir.join("tests")).unwrap();
+        std::fs::write(package_dir.join(".gitattributes"), "/tests export-ignore\n").unwrap();
+        std::fs::write(package_dir.join("composer.json"), "{}").unwrap();
+        std::fs::write(package_dir.join("tests/FooTest.php"), "<?php").unwrap();
+
+        let manifest = DistManifest::create(&package_dir, "1.0.0").unwrap();
+        assert_eq!(manifest.files.keys().collect::<Vec<_>>(), vec![".gitattributes", "composer.json"]);
+    }
+}
@@ -6,6 +6,7 @@
 mod archive;
 mod file;
 mod git;
+mod manifest;
 mod manager;
 mod checksum;
 mod path;
@@ -13,6 +14,7 @@ mod path;
 pub use archive::{ArchiveExtractor, ArchiveType};
 pub use file::FileDownloader;
 pub use git::GitDownloader;
+pub use manifest::{DistManifest, FileChange};
 pub use manager::{DownloadManager, DownloadResult, DownloadConfig};
 pub use checksum::{verify_checksum, ChecksumType};
 pub use path::{PathDownloader, PathStrategy, PathInstallResult};
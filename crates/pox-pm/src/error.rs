@@ -1,5 +1,8 @@
 use thiserror::Error;
 
+use crate::http::HttpError;
+use crate::solver::Problem;
+
 #[derive(Error, Debug)]
 pub enum ComposerError {
     // JSON/parsing errors
@@ -24,6 +27,9 @@ pub enum ComposerError {
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
 
+    #[error("HTTP error: {0}")]
+    Http(#[from] HttpError),
+
     // IO errors
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -32,6 +38,18 @@ pub enum ComposerError {
     #[error("Could not resolve dependencies: {0}")]
     DependencyResolution(String),
 
+    /// The requirements have no solution; each problem carries its description as message
+    #[error("Your requirements could not be resolved to an installable set of packages.{}", describe_problems(.0))]
+    Solve(Vec<Problem>),
+
+    /// The lock file requires platform packages (php, extensions) that are missing or too old
+    #[error("Your lock file does not contain a compatible set of packages. Please run update.{}", describe_list(.0))]
+    PlatformUnmet(Vec<String>),
+
+    /// A partial update changed the version of locked packages it was not allowed to update
+    #[error("The update changed packages outside the list of packages to update, which should not happen. Please report this as a bug.{}", describe_list(.0))]
+    PartialUpdateChangedFixed(Vec<String>),
+
     // Download errors
     #[error("Download failed for {package}: {reason}")]
     DownloadFailed { package: String, reason: String },
@@ -43,6 +61,10 @@ pub enum ComposerError {
     #[error("Installation failed: {0}")]
     InstallationFailed(String),
 
+    /// Another process holds the install lock of the vendor directory
+    #[error("Another pox process{} is running in {}", pid.map(|pid| format!(" (pid {})", pid)).unwrap_or_default(), dir.display())]
+    Locked { dir: std::path::PathBuf, pid: Option<u32> },
+
     // Config errors
     #[error("Configuration error: {0}")]
     Config(String),
@@ -52,12 +74,64 @@ pub enum ComposerError {
     InvalidConstraint(String),
 
     // Lock file errors
-    #[error("Lock file is out of sync with composer.json")]
-    LockFileOutOfSync,
+    #[error("The lock file is not up to date with composer.json; required packages are missing from it: {}. Run update to update the lock file.", missing.join(", "))]
+    LockFileOutOfSync { missing: Vec<String> },
 
     // Git errors
     #[error("Git error: {0}")]
     Git(#[from] git2::Error),
+
+    // Errors from scripts, plugins and event listeners
+    #[error(transparent)]
+    Event(anyhow::Error),
+}
+
+fn describe_problems(problems: &[Problem]) -> String {
+    problems.iter()
+        .enumerate()
+        .map(|(i, problem)| {
+            let lines: Vec<String> = problem.message.as_deref().unwrap_or_default()
+                .lines()
+                .map(|line| format!("    {}", line))
+                .collect();
+            format!("\n\n  Problem {}\n{}", i + 1, lines.join("\n"))
+        })
+        .collect()
+}
+
+fn describe_list(items: &[String]) -> String {
+    items.iter().map(|item| format!("\n  - {}", item)).collect()
 }
 
 pub type Result<T> = std::result::Result<T, ComposerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_error_lists_problems() {
+        let error = ComposerError::Solve(vec![
+            Problem::new().with_message("Root composer.json requires acme/missing, it could not be found in any version."),
+            Problem::new().with_message("acme/a 1.0.0 requires acme/b ^2.0\n    -> found acme/b[1.0.0]"),
+        ]);
+
+        assert_eq!(
+            error.to_string(),
+            "Your requirements could not be resolved to an installable set of packages.\n\n  \
+             Problem 1\n    Root composer.json requires acme/missing, it could not be found in any version.\n\n  \
+             Problem 2\n    acme/a 1.0.0 requires acme/b ^2.0\n        -> found acme/b[1.0.0]"
+        );
+    }
+
+    #[test]
+    fn test_platform_unmet_error_lists_requirements() {
+        let error = ComposerError::PlatformUnmet(vec!["Root composer.json requires php ^8.2 -> your php version (8.1.0) does not satisfy that requirement".to_string()]);
+
+        assert_eq!(
+            error.to_string(),
+            "Your lock file does not contain a compatible set of packages. Please run update.\n  \
+             - Root composer.json requires php ^8.2 -> your php version (8.1.0) does not satisfy that requirement"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Machine-readable progress as newline-delimited JSON.
+//!
+//! The event stream is off by default; `--events-json` enables it with a writer, usually
+//! the original stdout. While enabled, installs and updates report each step as one JSON
+//! object per line, like `{"event":"install","package":"psr/log","version":"3.0.0"}`, and
+//! the command ends with a `done` event carrying its status.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// A step of a command, serialized with its kind in the `event` field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    /// The solver found a solution with this many packages
+    Resolved { packages: usize },
+    /// The lock file was written, or left alone when it was up to date
+    Lock { updated: bool },
+    /// A package was installed
+    Install { package: String, version: String },
+    /// A package was updated
+    Update { package: String, from: String, to: String },
+    /// A package was removed
+    Remove { package: String, version: String },
+    /// The autoloader was generated
+    Autoload,
+    /// Something went wrong; more than one error can precede `done`
+    Error { message: String },
+    /// The command finished; always the last event
+    Done { status: Status },
+}
+
+/// Outcome reported by the final `done` event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+/// Enable the event stream for the rest of the process, writing events to `sink`
+pub fn enable(sink: Box<dyn Write + Send>) {
+    if let Ok(mut guard) = SINK.lock() {
+        *guard = Some(sink);
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Check if the event stream is enabled
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Write an event as one line, if the event stream is enabled
+pub fn emit(event: &Event) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let (Ok(mut guard), Ok(line)) = (SINK.lock(), serde_json::to_string(event)) {
+        if let Some(sink) = guard.as_mut() {
+            let _ = writeln!(sink, "{}", line);
+            let _ = sink.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = Event::Install { package: "psr/log".to_string(), version: "3.0.0".to_string() };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"install","package":"psr/log","version":"3.0.0"}"#
+        );
+
+        let event = Event::Done { status: Status::Error };
+        assert_eq!(serde_json::to_string(&event).unwrap(), r#"{"event":"done","status":"error"}"#);
+
+        assert_eq!(serde_json::to_string(&Event::Autoload).unwrap(), r#"{"event":"autoload"}"#);
+    }
+}
@@ -1,3 +1,6 @@
 mod client;
+mod verify;
 
-pub use client::{HttpClient, HttpClientConfig, HttpError};
+pub use client::{HttpClient, HttpClientConfig, HttpError, no_proxy_matches};
+pub(crate) use client::default_client;
+pub use verify::{DEFAULT_SIGNATURE_SUFFIX, Ed25519Verifier, SignedOrigin, Verifier, find_signed_origin, url_origin};
@@ -0,0 +1,198 @@
+//! Signature verification of repository metadata and dist downloads.
+//!
+//! Repositories opt in to signing: every signed file has a detached signature next to it,
+//! at the file URL plus a suffix (`.sig` by default). Content from origins without a
+//! verifier is accepted as is, so unsigned repositories like Packagist keep working.
+
+use std::fmt;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ring::signature::{ED25519, UnparsedPublicKey};
+
+use super::HttpError;
+use crate::json::RepositorySignature;
+
+/// Signature suffix used when a repository doesn't configure one
+pub const DEFAULT_SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Checks detached signatures of downloaded content
+pub trait Verifier: Send + Sync + fmt::Debug {
+    /// Check that `signature` is a valid signature of `content`
+    fn verify(&self, content: &[u8], signature: &[u8]) -> Result<(), String>;
+}
+
+/// Ed25519 signatures, as produced by `signify`-style tools or `openssl pkeyutl -rawin`
+///
+/// Signature files hold the 64 signature bytes, either raw or base64 encoded.
+#[derive(Clone)]
+pub struct Ed25519Verifier {
+    public_key: Vec<u8>,
+}
+
+impl Ed25519Verifier {
+    /// Create a verifier from a base64 encoded 32 byte public key
+    pub fn from_base64(public_key: &str) -> Result<Self, HttpError> {
+        let public_key = STANDARD.decode(public_key.trim())
+            .map_err(|e| HttpError::Signature(format!("Invalid public key: {}", e)))?;
+        if public_key.len() != 32 {
+            return Err(HttpError::Signature(format!(
+                "Invalid public key: expected 32 bytes for Ed25519, got {}",
+                public_key.len()
+            )));
+        }
+        Ok(Self { public_key })
+    }
+}
+
+impl fmt::Debug for Ed25519Verifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ed25519Verifier")
+            .field("public_key", &STANDARD.encode(&self.public_key))
+            .finish()
+    }
+}
+
+impl Verifier for Ed25519Verifier {
+    fn verify(&self, content: &[u8], signature: &[u8]) -> Result<(), String> {
+        let signature = if signature.len() == 64 {
+            signature.to_vec()
+        } else {
+            let text = std::str::from_utf8(signature).map_err(|_| "signature is neither raw nor base64".to_string())?;
+            STANDARD.decode(text.trim()).map_err(|e| format!("signature is neither raw nor base64: {}", e))?
+        };
+
+        UnparsedPublicKey::new(&ED25519, &self.public_key)
+            .verify(content, &signature)
+            .map_err(|_| "signature does not match".to_string())
+    }
+}
+
+/// A verifier for every URL under a prefix
+#[derive(Debug, Clone)]
+pub struct SignedOrigin {
+    prefix: String,
+    suffix: String,
+    verifier: Arc<dyn Verifier>,
+}
+
+impl SignedOrigin {
+    pub fn new(prefix: impl Into<String>, verifier: Arc<dyn Verifier>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: DEFAULT_SIGNATURE_SUFFIX.to_string(),
+            verifier,
+        }
+    }
+
+    /// The signed origin of a composer repository, covering every URL on its host
+    pub fn from_repository(url: &str, signature: &RepositorySignature) -> Result<Self, HttpError> {
+        let prefix = url_origin(url)
+            .ok_or_else(|| HttpError::Signature(format!("Cannot verify signatures for {}, it is not an http(s) URL", url)))?;
+        let origin = Self::new(prefix, Arc::new(Ed25519Verifier::from_base64(&signature.public_key)?));
+        Ok(match &signature.suffix {
+            Some(suffix) => origin.with_suffix(suffix.clone()),
+            None => origin,
+        })
+    }
+
+    /// Look for signatures at the file URL plus `suffix` instead of `.sig`
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Whether content from `url` must be signed by this origin
+    pub fn matches(&self, url: &str) -> bool {
+        // "https://repo.example.org" must not match "https://repo.example.org.evil.com"
+        url.strip_prefix(&self.prefix)
+            .is_some_and(|rest| self.prefix.ends_with('/') || rest.is_empty() || rest.starts_with(['/', '?']))
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Where the signature of `url` is published
+    pub fn signature_url(&self, url: &str) -> String {
+        match url.split_once('?') {
+            Some((path, query)) => format!("{}{}?{}", path, self.suffix, query),
+            None => format!("{}{}", url, self.suffix),
+        }
+    }
+
+    /// Check the signature of content downloaded from `url`
+    pub fn verify(&self, url: &str, content: &[u8], signature: &[u8]) -> Result<(), HttpError> {
+        self.verifier.verify(content, signature)
+            .map_err(|reason| HttpError::Signature(format!("Verification of {} failed: {}", url, reason)))
+    }
+}
+
+/// The longest prefix among `origins` that `url` falls under
+pub fn find_signed_origin<'a>(origins: &'a [SignedOrigin], url: &str) -> Option<&'a SignedOrigin> {
+    origins.iter()
+        .filter(|origin| origin.matches(url))
+        .max_by_key(|origin| origin.prefix.len())
+}
+
+/// Scheme, host and port of `url`, e.g. `https://repo.example.org`
+pub fn url_origin(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let origin = parsed.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Public key of the RFC 8032 test 1 key pair
+    const PUBLIC_KEY: &str = "11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=";
+
+    fn key_pair() -> ring::signature::Ed25519KeyPair {
+        let seed = [
+            0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec, 0x2c, 0xc4,
+            0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03, 0x1c, 0xae, 0x7f, 0x60,
+        ];
+        ring::signature::Ed25519KeyPair::from_seed_unchecked(&seed).unwrap()
+    }
+
+    #[test]
+    fn test_ed25519_verifier() {
+        let verifier = Ed25519Verifier::from_base64(PUBLIC_KEY).unwrap();
+        let signature = key_pair().sign(b"{\"packages\":{}}");
+
+        assert!(verifier.verify(b"{\"packages\":{}}", signature.as_ref()).is_ok());
+        let encoded = STANDARD.encode(signature.as_ref());
+        assert!(verifier.verify(b"{\"packages\":{}}", format!("{}\n", encoded).as_bytes()).is_ok());
+        assert!(verifier.verify(b"{\"packages\":[]}", signature.as_ref()).is_err());
+
+        assert!(Ed25519Verifier::from_base64("not base64!").is_err());
+        assert!(Ed25519Verifier::from_base64("AAAA").is_err());
+    }
+
+    #[test]
+    fn test_signed_origin_matching() {
+        let verifier: Arc<dyn Verifier> = Arc::new(Ed25519Verifier::from_base64(PUBLIC_KEY).unwrap());
+        let origins = vec![
+            SignedOrigin::new("https://repo.example.org", verifier.clone()),
+            SignedOrigin::new("https://repo.example.org/private/", verifier).with_suffix(".asc"),
+        ];
+
+        assert_eq!(find_signed_origin(&origins, "https://repo.example.org/p2/acme/lib.json").unwrap().prefix(), "https://repo.example.org");
+        assert_eq!(find_signed_origin(&origins, "https://repo.example.org/private/a.zip").unwrap().prefix(), "https://repo.example.org/private/");
+        assert!(find_signed_origin(&origins, "https://repo.example.org.evil.com/a.zip").is_none());
+        assert!(find_signed_origin(&origins, "https://repo.packagist.org/p2/acme/lib.json").is_none());
+
+        assert_eq!(origins[0].signature_url("https://repo.example.org/a.zip?token=1"), "https://repo.example.org/a.zip.sig?token=1");
+        assert_eq!(origins[1].signature_url("https://repo.example.org/private/a.zip"), "https://repo.example.org/private/a.zip.asc");
+    }
+
+    #[test]
+    fn test_url_origin() {
+        assert_eq!(url_origin("https://repo.example.org/packages.json").as_deref(), Some("https://repo.example.org"));
+        assert_eq!(url_origin("http://localhost:8080/repo").as_deref(), Some("http://localhost:8080"));
+        assert_eq!(url_origin("not a url"), None);
+    }
+}
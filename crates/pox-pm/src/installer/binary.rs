@@ -3,6 +3,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::package::Package;
+use super::paths::InstallerPaths;
 use crate::Result;
 
 /// Binary installer for creating executable links
@@ -11,6 +12,8 @@ pub struct BinaryInstaller {
     bin_dir: PathBuf,
     /// Vendor directory where packages are installed
     vendor_dir: PathBuf,
+    /// Custom install paths of packages outside the vendor directory
+    installer_paths: InstallerPaths,
 }
 
 impl BinaryInstaller {
@@ -19,9 +22,16 @@ impl BinaryInstaller {
         Self {
             bin_dir: bin_dir.into(),
             vendor_dir: vendor_dir.into(),
+            installer_paths: InstallerPaths::default(),
         }
     }
 
+    /// Resolve package directories through `extra.installer-paths`
+    pub fn with_installer_paths(mut self, installer_paths: InstallerPaths) -> Self {
+        self.installer_paths = installer_paths;
+        self
+    }
+
     /// Install binaries for a package
     pub async fn install(&self, package: &Package) -> Result<Vec<PathBuf>> {
         if package.bin.is_empty() {
@@ -31,7 +41,7 @@ impl BinaryInstaller {
         tokio::fs::create_dir_all(&self.bin_dir).await?;
 
         let mut installed = Vec::new();
-        let package_dir = self.vendor_dir.join(&package.name);
+        let package_dir = self.installer_paths.install_path(&self.vendor_dir, &package.name, &package.package_type, package.extra.as_ref());
 
         for bin_path in &package.bin {
             let source = package_dir.join(bin_path);
@@ -1,29 +1,87 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use anyhow::{Context, Result};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use indexmap::IndexMap;
 
+use crate::{ComposerError, Result};
 use crate::composer::Composer;
+use crate::event_stream::{self, Event};
 use crate::event::{
     PostAutoloadDumpEvent, PostInstallEvent, PostUpdateEvent,
     PreAutoloadDumpEvent, PreInstallEvent, PreUpdateEvent,
 };
+use crate::installer::{InstallConfig, InstallResult};
 use crate::json::{ComposerLock, ComposerJson, LockedPackage};
 use crate::package::{Package, Stability, Autoload, detect_root_version, RootVersion};
-use crate::solver::{Pool, Policy, Request, Solver, Transaction};
+use crate::plugin::{plugin_support, PluginSupport};
+use crate::profile::{self, Phase};
+use crate::solver::{LockDiff, Pool, Policy, RelaxationAdvice, Request, SolveFailure, Solver, Transaction, UpdateAllowTransitive, diff_against_lock, non_dev_package_names, root_stability_flags, suggest_relaxations};
 use crate::autoload::{AutoloadConfig, AutoloadGenerator, PackageAutoload, RootPackageInfo, get_head_commit};
 use crate::util::is_platform_package;
 
 pub struct Installer {
     composer: Composer,
+    no_suggest: bool,
+    update_allow_transitive: UpdateAllowTransitive,
+    dump_solve: Option<PathBuf>,
+    solver_stats: bool,
+    explain_fix: bool,
+}
+
+/// Pool and request for resolving the root requirements, see [`Installer::load_update_pool`]
+struct UpdatePool {
+    pool: Pool,
+    request: Request,
+    /// Suggestions of installed packages promoted to requirements by the config
+    promoted: Vec<String>,
+    installed_packages: Vec<Arc<Package>>,
+    root_version: RootVersion,
 }
 
 impl Installer {
     pub fn new(composer: Composer) -> Self {
-        Self { composer }
+        Self {
+            composer,
+            no_suggest: false,
+            update_allow_transitive: UpdateAllowTransitive::ListedOnly,
+            dump_solve: None,
+            solver_stats: false,
+            explain_fix: false,
+        }
+    }
+
+    /// Skip listing the suggestions of newly installed packages
+    pub fn no_suggest(mut self, no_suggest: bool) -> Self {
+        self.no_suggest = no_suggest;
+        self
+    }
+
+    /// Set which packages besides the listed ones a partial update may change
+    pub fn update_allow_transitive(mut self, transitive: UpdateAllowTransitive) -> Self {
+        self.update_allow_transitive = transitive;
+        self
+    }
+
+    /// Export the SAT problem of the update to a file, see [`crate::solver::dump_problem`]
+    pub fn dump_solve(mut self, path: Option<PathBuf>) -> Self {
+        self.dump_solve = path;
+        self
+    }
+
+    /// Report the packages involved in the most solver conflicts after solving
+    pub fn solver_stats(mut self, enabled: bool) -> Self {
+        self.solver_stats = enabled;
+        self
+    }
+
+    /// When the update can't be solved, look for root constraints that could be relaxed
+    /// to make it solvable. This solves again for each candidate.
+    pub fn explain_fix(mut self, enabled: bool) -> Self {
+        self.explain_fix = enabled;
+        self
     }
 
     pub async fn update(&self, optimize_autoloader: bool, update_lock_only: bool, update_packages: Option<Vec<String>>) -> Result<i32> {
@@ -44,7 +102,7 @@ impl Installer {
         }
 
         // Dispatch pre-update event
-        let exit_code = self.composer.dispatch(&PreUpdateEvent::new(!no_dev))?;
+        let exit_code = self.composer.dispatch(&PreUpdateEvent::new(!no_dev)).map_err(ComposerError::Event)?;
         if exit_code != 0 {
             return Ok(exit_code);
         }
@@ -59,11 +117,351 @@ impl Installer {
         spinner.enable_steady_tick(Duration::from_millis(100));
         spinner.set_message("Loading repositories...");
 
-        // Setup repository manager
-        let repo_manager = self.composer.repository_manager.clone();
+        spinner.set_message("Resolving dependencies...");
+
+        let UpdatePool { pool, mut request, promoted, installed_packages, root_version } =
+            self.load_update_pool(composer_json, &spinner).await;
+        let stability_flags = root_stability_flags(composer_json);
+
+        let update_allowlist = match (&update_packages, &self.composer.composer_lock) {
+            (Some(packages_to_update), Some(lock)) if !packages_to_update.is_empty() => {
+                let root_requires: HashSet<String> = composer_json.require.keys()
+                    .chain(composer_json.require_dev.keys())
+                    .map(|name| name.to_lowercase())
+                    .collect();
+                let update_allowlist = expand_update_allowlist(
+                    lock,
+                    packages_to_update,
+                    &root_requires,
+                    self.update_allow_transitive,
+                );
+                log::debug!("Partial update: allowing updates of {} packages", update_allowlist.len());
+                Some((lock, update_allowlist))
+            }
+            _ => None,
+        };
+
+        let preferred_versions = match &update_allowlist {
+            Some((lock, update_allowlist)) => {
+                // Packages outside the allow-list are locked, so the solver explains why a
+                // partial update needs to change them instead of quietly moving them
+                let mut allowed: Vec<String> = update_allowlist.iter().cloned().collect();
+                allowed.sort();
+                request.update(allowed);
+
+                let mut preferred = HashMap::new();
+                for pkg in lock.packages.iter().chain(lock.packages_dev.iter()) {
+                    let pkg_name_lower = pkg.name.to_lowercase();
+                    if !update_allowlist.contains(&pkg_name_lower) {
+                        request.lock(Package::from(pkg));
+                        preferred.insert(pkg_name_lower, pkg.version.clone());
+                    }
+                }
+                log::debug!("Partial update: locking {} packages from lock file", preferred.len());
+                preferred
+            }
+            None => {
+                log::debug!("Full update: no preferred versions, updating all packages");
+                HashMap::new()
+            }
+        };
+
+        let policy = Policy::new()
+            .prefer_lowest(prefer_lowest)
+            .prefer_newer_by_time(self.composer.config.prefer_newer_by_time)
+            .preferred_versions(preferred_versions);
+        let solver = Solver::new(&pool, &policy)
+            .with_optimization(true)
+            .with_dump(self.dump_solve.clone())
+            .with_stats(self.solver_stats);
+
+        let solve_timer = profile::start(Phase::Solve);
+        let mut solve_result = solver.solve(&request);
+        let promoted_requires: Vec<String> = promoted.iter()
+            .filter(|name| request.requires.contains_key(*name))
+            .cloned()
+            .collect();
+        if solve_result.is_err() && !promoted_requires.is_empty() {
+            spinner.suspend(|| eprintln!(
+                "{} Skipping promoted suggestions that cannot be installed: {}",
+                style("Warning:").yellow(),
+                promoted_requires.join(", ")
+            ));
+            for name in &promoted_requires {
+                request.requires.shift_remove(name);
+            }
+            solve_result = solver.solve(&request);
+        }
+        drop(solve_timer);
+
+        if let Some(stats) = solver.stats() {
+            spinner.suspend(|| eprint!("{}", stats.report(10)));
+        }
+
+        let solver_result = match solve_result {
+            Ok(result) => result,
+            Err(failure) => {
+                if self.explain_fix {
+                    spinner.set_message("Looking for constraints to relax...");
+                    let advice = suggest_relaxations(&pool, &policy, &request, &failure);
+                    spinner.finish_and_clear();
+                    print_relaxation_advice(&advice);
+                }
+                spinner.finish_and_clear();
+                return Err(ComposerError::Solve(failure.into_described_problems()));
+            }
+        };
+
+        // Packages outside the allow-list are locked, so make sure the solver really kept them
+        // before anything is written
+        if let Some((lock, update_allowlist)) = &update_allowlist {
+            let changed = changed_outside_allowlist(lock, &solver_result.packages, update_allowlist);
+            if !changed.is_empty() {
+                spinner.finish_and_clear();
+                return Err(ComposerError::PartialUpdateChangedFixed(changed));
+            }
+        }
+
+        spinner.set_message("Installing packages...");
+
+        let transaction = Transaction::from_packages(
+            installed_packages,
+            solver_result.packages.clone(),
+            solver_result.aliases,
+        );
+
+        let packages: Vec<Package> = solver_result.packages.iter()
+            .map(|p| p.as_ref().clone())
+            .filter(|p| !is_platform_package(&p.name))
+            .collect();
+
+        event_stream::emit(&Event::Resolved { packages: packages.len() });
+
+        // Testing against the lowest versions usually moves packages below the lock, which is
+        // worth pointing out since it is the point of the run
+        if prefer_lowest {
+            if let Some(lock) = &self.composer.composer_lock {
+                let downgraded = diff_against_lock(lock, &packages).downgraded;
+                if !downgraded.is_empty() {
+                    spinner.suspend(|| {
+                        println!("{} Resolved lower versions than composer.lock for {} package(s):",
+                            style("Info:").cyan(),
+                            downgraded.len()
+                        );
+                        println!("{}", LockDiff { downgraded, ..Default::default() });
+                    });
+                }
+            }
+        }
+
+        let summary = transaction.summary();
+        let lock_file_changed = summary.installs > 0 || summary.updates > 0 || summary.uninstalls > 0;
+
+        let non_dev_packages = non_dev_package_names(&packages, composer_json.require.keys().map(String::as_str));
+        let (prod_packages, dev_packages): (Vec<_>, Vec<_>) = packages.iter()
+            .partition(|p| non_dev_packages.contains(&p.name.to_lowercase()));
+
+        let install_count = packages.len();
+        let update_count = 0; // TODO: track updates vs installs properly
+        let removal_count = 0; // TODO: track removals
+        log::info!("Lock file operations: {} installs, {} updates, {} removals",
+            install_count, update_count, removal_count);
+
+        // Extract platform requirements while preserving order from composer.json
+        let platform_reqs: IndexMap<String, String> = composer_json.require.iter()
+            .filter(|(name, _)| is_platform_package(name))
+            .map(|(name, constraint)| (name.clone(), constraint.clone()))
+            .collect();
 
+        let platform_dev_reqs: IndexMap<String, String> = composer_json.require_dev.iter()
+            .filter(|(name, _)| is_platform_package(name))
+            .map(|(name, constraint)| (name.clone(), constraint.clone()))
+            .collect();
+
+        // Composer hashes the file as written, which the parsed (and possibly merged) manifest
+        // may no longer match
+        let composer_json_content = std::fs::read_to_string(working_dir.join("composer.json"))
+            .unwrap_or_else(|_| serde_json::to_string(composer_json).unwrap_or_default());
+
+        let lock = ComposerLock {
+            content_hash: crate::util::compute_content_hash(&composer_json_content),
+            packages: prod_packages.iter().map(|p| LockedPackage::from(*p)).collect(),
+            packages_dev: dev_packages.iter().map(|p| LockedPackage::from(*p)).collect(),
+            minimum_stability: composer_json.minimum_stability.clone().unwrap_or_else(|| "stable".to_string()),
+            prefer_stable: composer_json.prefer_stable.unwrap_or(false) || install_config.prefer_stable,
+            prefer_lowest,
+            platform: platform_reqs,
+            platform_dev: platform_dev_reqs,
+            stability_flags: stability_flags.iter().map(|(name, s)| (name.clone(), s.priority())).collect(),
+            plugin_api_version: "2.9.0".to_string(),
+            ..Default::default()
+        };
+
+        // The transaction is computed against vendor, which a lock-only update may never have
+        // populated, so the lock on disk is compared as well
+        let lock_outdated = lock_file_changed
+            || self.composer.composer_lock.as_ref().is_none_or(|existing| {
+                existing.content_hash != lock.content_hash || !same_locked_versions(existing, &lock)
+            });
+
+        // Only write lock file if there were changes
+        if lock_outdated && !dry_run {
+            log::debug!("Writing lock file");
+            lock.write(working_dir.join("composer.lock")).map_err(|e| {
+                std::io::Error::new(e.kind(), format!("Failed to write composer.lock: {}", e))
+            })?;
+        }
+        event_stream::emit(&Event::Lock { updated: lock_outdated && !dry_run });
+
+        if update_lock_only {
+             spinner.finish_and_clear();
+             if lock_outdated {
+                 println!("{} Lock file updated", style("Success:").green().bold());
+             } else {
+                 println!("{} Lock file is up to date", style("Info:").cyan());
+             }
+             return Ok(0);
+        }
+
+        log::debug!("Installing dependencies from lock file");
+        log::info!("Package operations: {} installs, {} updates, {} removals",
+            install_count, update_count, removal_count);
+
+        // A package required by both a dev and a non-dev package is a production package
+        let (packages, dev_package_names): (Vec<Package>, Vec<String>) = if no_dev {
+            (prod_packages.into_iter().cloned().collect(), Vec::new())
+        } else {
+            (packages, lock.packages_dev.iter().map(|p| p.name.clone()).collect())
+        };
+
+        let manager = &self.composer.installation_manager;
+        let mut result = manager.install_packages(&packages).await?;
+        result.removed = manager.remove_unlisted_packages(&packages).await?;
+        manager.write_installed_repository(&packages, &dev_package_names).await?;
+
+        spinner.finish_and_clear();
+
+        let actually_installed: Vec<_> = result.installed.iter()
+            .filter(|p| !is_platform_package(&p.name))
+            .collect();
+
+        for pkg in &actually_installed {
+            log::debug!("Installed {} ({})", pkg.name, pkg.version);
+            println!("  {} {} ({})", style("-").green(), style(&pkg.name).white().bold(), style(&pkg.version).yellow());
+        }
+        print_removals(&result);
+        emit_operations(&result);
+
+        if !dry_run {
+             println!("{} Generating autoload files", style("Info:").cyan());
+             
+             let aliases_map: HashMap<String, Vec<String>> = HashMap::new();
+             let dev_mode = !no_dev;
+
+             let package_autoloads = locked_package_autoloads(&lock, &aliases_map, manager.config());
+
+             let autoload_config = AutoloadConfig {
+                 vendor_dir: manager.config().vendor_dir.clone(),
+                 base_dir: working_dir.clone(),
+                 optimize: optimize_autoloader,
+                 suffix: Some(lock.content_hash.clone()),
+                 dev_mode,
+                 ..Default::default()
+             };
+
+             let generator = AutoloadGenerator::new(autoload_config);
+
+             let root_autoload = Some(root_autoload(composer_json, dev_mode));
+
+             let root_package = create_root_package_info(
+                 composer_json,
+                 &root_version,
+                 working_dir,
+                 Vec::new(),
+                 dev_mode,
+             );
+
+             generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package))?;
+             event_stream::emit(&Event::Autoload);
+
+             // Dispatch post-autoload-dump event (runs scripts and plugins)
+             let arc_packages: Vec<Arc<Package>> = packages.iter().map(|p| Arc::new(p.clone())).collect();
+             let event = PostAutoloadDumpEvent::new(arc_packages, !no_dev, optimize_autoloader);
+             let exit_code = self.composer.dispatch(&event).map_err(ComposerError::Event)?;
+             if exit_code != 0 {
+                 return Ok(exit_code);
+             }
+        }
+
+        let total_changed = actually_installed.len() + result.updated.len();
+        if total_changed > 0 || lock_file_changed {
+            println!("{} {} packages updated", style("Success:").green().bold(), total_changed);
+        } else {
+            println!("{} Nothing to update.", style("Info:").cyan());
+        }
+
+        if !dry_run {
+            self.audit_abandoned_packages(&packages);
+            warn_unsupported_plugins(&packages);
+            if !self.no_suggest {
+                report_suggestions(&actually_installed, &packages, platform_packages);
+            }
+        }
+
+        // Dispatch post-update event
+        if !dry_run {
+            let exit_code = self.composer.dispatch(&PostUpdateEvent::new(!no_dev)).map_err(ComposerError::Event)?;
+            if exit_code != 0 {
+                return Ok(exit_code);
+            }
+        }
+
+        Ok(0)
+    }
+
+    /// Check whether `name` at `constraint` could be installed together with the root
+    /// requirements, without changing anything
+    ///
+    /// The requirement replaces any existing root requirement of the package. Returns the
+    /// failure explaining what prevents it, or `None` if it would install.
+    pub async fn why_not(&self, name: &str, constraint: &str) -> Option<SolveFailure> {
+        let mut composer_json = self.composer.composer_json.clone();
+        composer_json.require.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+        composer_json.require_dev.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+        composer_json.require.insert(name.to_string(), constraint.to_string());
+
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} {msg}")
+                .unwrap(),
+        );
+        spinner.enable_steady_tick(Duration::from_millis(100));
         spinner.set_message("Resolving dependencies...");
 
+        let UpdatePool { pool, mut request, promoted, .. } =
+            self.load_update_pool(&composer_json, &spinner).await;
+        // Promoted suggestions are optional, so they shouldn't be what blocks the package
+        for promoted_name in &promoted {
+            request.requires.shift_remove(promoted_name);
+        }
+
+        let policy = Policy::new()
+            .prefer_lowest(self.composer.installation_manager.config().prefer_lowest)
+            .prefer_newer_by_time(self.composer.config.prefer_newer_by_time);
+        let result = Solver::new(&pool, &policy).solve(&request);
+        spinner.finish_and_clear();
+
+        result.err()
+    }
+
+    /// Load every package the root requirements of `composer_json` could resolve to into a
+    /// pool, and build the request to solve against it
+    async fn load_update_pool(&self, composer_json: &ComposerJson, spinner: &ProgressBar) -> UpdatePool {
+        let working_dir = &self.composer.working_dir;
+        let platform_packages = &self.composer.platform_packages;
+        let repo_manager = self.composer.repository_manager.clone();
+
         // Get minimum stability (default to "stable" if not specified)
         let minimum_stability: Stability = composer_json.minimum_stability
             .as_deref()
@@ -79,7 +477,7 @@ impl Installer {
         // Build package pool
         let mut pool = Pool::with_minimum_stability(minimum_stability);
 
-        // Add root package to pool (for replace/provide/conflict handling)
+        // Add root package to pool (for replace/provide handling)
         // Use add_platform_package to bypass stability filtering (root is always installed)
         let root_pkg = create_root_package(composer_json, &root_version);
         if !root_pkg.replace.is_empty() || !root_pkg.provide.is_empty() {
@@ -116,22 +514,11 @@ impl Installer {
             );
         }
 
-        // Add stability flags - sort for deterministic order
-        let mut sorted_require: Vec<_> = composer_json.require.iter().collect();
-        sorted_require.sort_by(|a, b| a.0.cmp(b.0));
-        for (name, constraint) in sorted_require {
-            if let Some(stability) = extract_stability_flag(constraint) {
-                pool.add_stability_flag(name, stability);
-                log::trace!("Stability flag for {}: {:?}", name, stability);
-            }
-        }
-        let mut sorted_require_dev: Vec<_> = composer_json.require_dev.iter().collect();
-        sorted_require_dev.sort_by(|a, b| a.0.cmp(b.0));
-        for (name, constraint) in sorted_require_dev {
-            if let Some(stability) = extract_stability_flag(constraint) {
-                pool.add_stability_flag(name, stability);
-                log::trace!("Stability flag for {}: {:?}", name, stability);
-            }
+        // Add stability flags from root requirements like `^1.0@dev`
+        let stability_flags = root_stability_flags(composer_json);
+        for (name, stability) in &stability_flags {
+            pool.add_stability_flag(name, *stability);
+            log::trace!("Stability flag for {}: {:?}", name, stability);
         }
 
         // Add platform packages (bypass stability filtering - these are fixed system packages)
@@ -163,22 +550,38 @@ impl Installer {
                 pending_packages.insert(name_lower, constraint.clone());
             }
         }
-        if !no_dev {
-            let mut sorted_require_dev: Vec<_> = composer_json.require_dev.iter().collect();
-            sorted_require_dev.sort_by(|a, b| a.0.cmp(b.0));
-            for (name, constraint) in sorted_require_dev {
-                if !is_platform_package(name) && !root_replaced.contains(&name.to_lowercase()) {
-                    let name_lower = name.to_lowercase();
-                    // Merge constraints if already present
-                    if let Some(existing) = pending_packages.get(&name_lower) {
-                        pending_packages.insert(name_lower, format!("{} || {}", existing, constraint));
-                    } else {
-                        pending_packages.insert(name_lower, constraint.clone());
-                    }
+        // require-dev is resolved even with --no-dev, like Composer, so the lock file stays
+        // complete; --no-dev only leaves the dev packages out of vendor and the autoloader
+        let mut sorted_require_dev: Vec<_> = composer_json.require_dev.iter().collect();
+        sorted_require_dev.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, constraint) in sorted_require_dev {
+            if !is_platform_package(name) && !root_replaced.contains(&name.to_lowercase()) {
+                let name_lower = name.to_lowercase();
+                // Merge constraints if already present
+                if let Some(existing) = pending_packages.get(&name_lower) {
+                    pending_packages.insert(name_lower, format!("{} || {}", existing, constraint));
+                } else {
+                    pending_packages.insert(name_lower, constraint.clone());
                 }
             }
         }
 
+        // Suggestions of installed packages that the config promotes to requires
+        let mut request = Request::from_project(composer_json, None, true);
+        for name in &self.composer.config.promote_suggestions {
+            request.promote_suggestion(name);
+        }
+        let installed_packages = self.load_installed_packages();
+        let promoted = request.apply_promoted_suggestions(installed_packages.iter().map(|p| p.as_ref()));
+        for name in &promoted {
+            log::debug!("Promoting suggestion {} to a requirement", name);
+            if !root_replaced.contains(name) {
+                pending_packages.entry(name.clone()).or_insert_with(|| "*".to_string());
+            }
+        }
+
+        let fetch_timer = profile::start(Phase::RepoFetch);
+
         // Process packages in parallel batches for performance
         // Determinism is ensured by:
         // 1. Processing batches in sorted order
@@ -220,307 +623,123 @@ impl Installer {
                 });
             }
 
-            // Collect results and process dependencies
-            let mut batch_packages: Vec<Arc<Package>> = Vec::new();
-            let mut new_deps: Vec<(String, String)> = Vec::new();
-
-            while let Some(result) = tasks.join_next().await {
-                if let Ok((name, packages)) = result {
-                    log::trace!("HTTP: {} ({} versions)", name, packages.len());
-                    for pkg in packages {
-                        // Collect dependencies
-                        for (dep_name, dep_constraint) in &pkg.require {
-                            if !is_platform_package(dep_name) {
-                                let dep_lower = dep_name.to_lowercase();
-                                if !loaded_packages.contains(&dep_lower) {
-                                    log::trace!("Adding dependency {} {} from {} {}", dep_name, dep_constraint, pkg.name, pkg.version);
-                                    new_deps.push((dep_lower, dep_constraint.clone()));
-                                }
-                            }
-                        }
-                        batch_packages.push(pkg);
-                    }
-                }
-            }
-
-            // Merge new dependencies into pending (after parallel fetch completes)
-            // Sort first for deterministic merging
-            new_deps.sort_by(|a, b| a.0.cmp(&b.0));
-            for (dep_name, dep_constraint) in new_deps {
-                if !loaded_packages.contains(&dep_name) {
-                    if let Some(existing) = pending_packages.get(&dep_name) {
-                        pending_packages.insert(
-                            dep_name,
-                            format!("{} || {}", existing, dep_constraint),
-                        );
-                    } else {
-                        pending_packages.insert(dep_name, dep_constraint);
-                    }
-                }
-            }
-
-            all_packages.extend(batch_packages);
-        }
-
-        // Sort packages by name and version for deterministic pool order
-        all_packages.sort_by(|a, b| {
-            match a.name.cmp(&b.name) {
-                std::cmp::Ordering::Equal => a.version.cmp(&b.version),
-                other => other,
-            }
-        });
-
-        // Add sorted packages to pool
-        for pkg in all_packages {
-            pool.add_package_arc(pkg, None);
-        }
-
-        log::info!("Loaded {} packages ({} HTTP requests) in {:?}",
-            pool.len(), http_request_count, load_start.elapsed());
-        log::debug!("Pool has {} packages after loading", pool.len());
-
-        // Solver Request - sort for deterministic order
-        let mut request = Request::new();
-        let mut sorted_require: Vec<_> = composer_json.require.iter().collect();
-        sorted_require.sort_by(|a, b| a.0.cmp(b.0));
-        for (name, constraint) in sorted_require {
-            if !is_platform_package(name) {
-                request.require(name, constraint);
-            }
-        }
-        if !no_dev {
-            let mut sorted_require_dev: Vec<_> = composer_json.require_dev.iter().collect();
-            sorted_require_dev.sort_by(|a, b| a.0.cmp(b.0));
-            for (name, constraint) in sorted_require_dev {
-                if !is_platform_package(name) {
-                    request.require(name, constraint);
-                }
-            }
-        }
-
-        // Add root package as fixed if it has replace/provide
-        // This ensures the solver knows the root package is always installed
-        // and its replaced/provided packages are available
-        let root_pkg = create_root_package(composer_json, &root_version);
-        if !root_pkg.replace.is_empty() || !root_pkg.provide.is_empty() {
-            request.fix(root_pkg);
-        }
-
-        let preferred_versions = match (&update_packages, &self.composer.composer_lock) {
-            (Some(packages_to_update), Some(lock)) if !packages_to_update.is_empty() => {
-                let update_allowlist: HashSet<String> = packages_to_update
-                    .iter()
-                    .map(|p| p.to_lowercase())
-                    .collect();
-
-                let mut preferred = HashMap::new();
-                for pkg in lock.packages.iter().chain(lock.packages_dev.iter()) {
-                    let pkg_name_lower = pkg.name.to_lowercase();
-                    if !update_allowlist.contains(&pkg_name_lower) {
-                        preferred.insert(pkg_name_lower, pkg.version.clone());
-                    }
-                }
-                log::debug!("Partial update: using {} preferred versions from lock file", preferred.len());
-                preferred
-            }
-            _ => {
-                log::debug!("Full update: no preferred versions, updating all packages");
-                HashMap::new()
-            }
-        };
-
-        let policy = Policy::new()
-            .prefer_lowest(prefer_lowest)
-            .preferred_versions(preferred_versions);
-        let solver = Solver::new(&pool, &policy).with_optimization(true);
-
-        let solver_result = match solver.solve(&request) {
-            Ok(result) => result,
-            Err(problems) => {
-                spinner.finish_and_clear();
-                eprintln!("{} Could not resolve dependencies", style("Error:").red().bold());
-                for problem in problems.problems() {
-                    eprintln!("  {}", problem.describe(&pool));
-                }
-                return Ok(1);
-            }
-        };
-
-        spinner.set_message("Installing packages...");
-
-        let present_packages = self.load_installed_packages();
-        let transaction = Transaction::from_packages(
-            present_packages,
-            solver_result.packages.clone(),
-            solver_result.aliases,
-        );
-
-        let packages: Vec<Package> = solver_result.packages.iter()
-            .map(|p| p.as_ref().clone())
-            .filter(|p| !is_platform_package(&p.name))
-            .collect();
-
-        let summary = transaction.summary();
-        let lock_file_changed = summary.installs > 0 || summary.updates > 0 || summary.uninstalls > 0;
-
-        let non_dev_roots: HashSet<String> = composer_json.require.keys()
-            .filter(|k| !is_platform_package(k))
-            .map(|k| k.to_lowercase())
-            .collect();
-
-        let non_dev_packages = find_transitive_dependencies(&packages, &non_dev_roots);
-        let (prod_packages, dev_packages): (Vec<_>, Vec<_>) = packages.iter()
-            .partition(|p| non_dev_packages.contains(&p.name.to_lowercase()));
-
-        let install_count = packages.len();
-        let update_count = 0; // TODO: track updates vs installs properly
-        let removal_count = 0; // TODO: track removals
-        log::info!("Lock file operations: {} installs, {} updates, {} removals",
-            install_count, update_count, removal_count);
-
-        // Extract platform requirements while preserving order from composer.json
-        let platform_reqs: IndexMap<String, String> = composer_json.require.iter()
-            .filter(|(name, _)| is_platform_package(name))
-            .map(|(name, constraint)| (name.clone(), constraint.clone()))
-            .collect();
-
-        let platform_dev_reqs: IndexMap<String, String> = composer_json.require_dev.iter()
-            .filter(|(name, _)| is_platform_package(name))
-            .map(|(name, constraint)| (name.clone(), constraint.clone()))
-            .collect();
-
-        let lock = ComposerLock {
-            content_hash: crate::util::compute_content_hash(&serde_json::to_string(composer_json).unwrap_or_default()),
-            packages: prod_packages.iter().map(|p| LockedPackage::from(*p)).collect(),
-            packages_dev: dev_packages.iter().map(|p| LockedPackage::from(*p)).collect(),
-            minimum_stability: composer_json.minimum_stability.clone().unwrap_or_else(|| "stable".to_string()),
-            prefer_stable: composer_json.prefer_stable.unwrap_or(false),
-            prefer_lowest,
-            platform: platform_reqs,
-            platform_dev: platform_dev_reqs,
-            plugin_api_version: "2.9.0".to_string(),
-            ..Default::default()
-        };
-
-        // Only write lock file if there were changes
-        if lock_file_changed && !dry_run {
-            log::debug!("Writing lock file");
-            let mut lock_content = serde_json::to_string_pretty(&lock).context("Failed to serialize composer.lock")?;
-            // Add trailing newline to match Composer's format
-            lock_content.push('\n');
-            std::fs::write(working_dir.join("composer.lock"), lock_content).context("Failed to write composer.lock")?;
-        }
-
-        if update_lock_only {
-             spinner.finish_and_clear();
-             if lock_file_changed {
-                 println!("{} Lock file updated", style("Success:").green().bold());
-             } else {
-                 println!("{} Lock file is up to date", style("Info:").cyan());
-             }
-             return Ok(0);
-        }
-
-        log::debug!("Installing dependencies from lock file");
-        log::info!("Package operations: {} installs, {} updates, {} removals",
-            install_count, update_count, removal_count);
-
-        let manager = &self.composer.installation_manager;
-        let result = manager.install_packages(&packages).await
-            .map_err(|e| anyhow::anyhow!("Failed to install packages: {}", e))?;
-
-        spinner.finish_and_clear();
-
-        let actually_installed: Vec<_> = result.installed.iter()
-            .filter(|p| !is_platform_package(&p.name))
-            .collect();
-
-        for pkg in &actually_installed {
-            log::debug!("Installed {} ({})", pkg.name, pkg.version);
-            println!("  {} {} ({})", style("-").green(), style(&pkg.name).white().bold(), style(&pkg.version).yellow());
-        }
-
-        if !dry_run {
-             println!("{} Generating autoload files", style("Info:").cyan());
-             
-             let aliases_map: HashMap<String, Vec<String>> = HashMap::new();
-             let dev_mode = !no_dev;
-
-             let mut package_autoloads: Vec<PackageAutoload> = lock.packages.iter()
-                .map(|lp| locked_package_to_autoload(lp, false, &aliases_map))
-                .collect();
-             if dev_mode {
-                 package_autoloads.extend(lock.packages_dev.iter().map(|lp| locked_package_to_autoload(lp, true, &aliases_map)));
-             }
+            // Collect results and process dependencies
+            let mut batch_packages: Vec<Arc<Package>> = Vec::new();
+            let mut new_deps: Vec<(String, String)> = Vec::new();
 
-             let autoload_config = AutoloadConfig {
-                 vendor_dir: manager.config().vendor_dir.clone(),
-                 base_dir: working_dir.clone(),
-                 optimize: optimize_autoloader,
-                 suffix: Some(lock.content_hash.clone()),
-                 ..Default::default()
-             };
+            while let Some(result) = tasks.join_next().await {
+                if let Ok((name, packages)) = result {
+                    log::trace!("HTTP: {} ({} versions)", name, packages.len());
+                    for pkg in packages {
+                        // Collect dependencies
+                        for (dep_name, dep_constraint) in &pkg.require {
+                            if !is_platform_package(dep_name) {
+                                let dep_lower = dep_name.to_lowercase();
+                                if !loaded_packages.contains(&dep_lower) {
+                                    log::trace!("Adding dependency {} {} from {} {}", dep_name, dep_constraint, pkg.name, pkg.version);
+                                    new_deps.push((dep_lower, dep_constraint.clone()));
+                                }
+                            }
+                        }
+                        batch_packages.push(pkg);
+                    }
+                }
+            }
 
-             let generator = AutoloadGenerator::new(autoload_config);
+            // Merge new dependencies into pending (after parallel fetch completes)
+            // Sort first for deterministic merging
+            new_deps.sort_by(|a, b| a.0.cmp(&b.0));
+            for (dep_name, dep_constraint) in new_deps {
+                if !loaded_packages.contains(&dep_name) {
+                    if let Some(existing) = pending_packages.get(&dep_name) {
+                        pending_packages.insert(
+                            dep_name,
+                            format!("{} || {}", existing, dep_constraint),
+                        );
+                    } else {
+                        pending_packages.insert(dep_name, dep_constraint);
+                    }
+                }
+            }
 
-             let root_autoload: Option<Autoload> = Some(composer_json.autoload.clone().into());
+            all_packages.extend(batch_packages);
+        }
+        drop(fetch_timer);
 
-             let root_package = create_root_package_info(
-                 composer_json,
-                 &root_version,
-                 working_dir,
-                 Vec::new(),
-                 dev_mode,
-             );
+        let pool_timer = profile::start(Phase::PoolBuild);
 
-             generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package))
-                 .context("Failed to generate autoloader")?;
+        // Sort packages by name and version for deterministic pool order
+        all_packages.sort_by(|a, b| {
+            match a.name.cmp(&b.name) {
+                std::cmp::Ordering::Equal => a.version.cmp(&b.version),
+                other => other,
+            }
+        });
 
-             // Dispatch post-autoload-dump event (runs scripts and plugins)
-             let arc_packages: Vec<Arc<Package>> = packages.iter().map(|p| Arc::new(p.clone())).collect();
-             let event = PostAutoloadDumpEvent::new(arc_packages, !no_dev, optimize_autoloader);
-             let exit_code = self.composer.dispatch(&event)?;
-             if exit_code != 0 {
-                 return Ok(exit_code);
-             }
+        // Add sorted packages to pool
+        for pkg in all_packages {
+            pool.add_package_arc(pkg, None);
         }
+        drop(pool_timer);
 
-        let total_changed = actually_installed.len() + result.updated.len();
-        if total_changed > 0 || lock_file_changed {
-            println!("{} {} packages updated", style("Success:").green().bold(), total_changed);
-        } else {
-            println!("{} Nothing to update.", style("Info:").cyan());
-        }
+        log::info!("Loaded {} packages ({} HTTP requests) in {:?}",
+            pool.len(), http_request_count, load_start.elapsed());
+        log::debug!("Pool has {} packages after loading", pool.len());
 
-        if !dry_run {
-            self.audit_abandoned_packages(&packages);
-        }
+        // Solver Request - the lock only pins partial updates, which `update` adds itself.
+        // A pool with a package provider loads what the request needs before solving.
+        pool.load_request(&request);
 
-        // Dispatch post-update event
-        if !dry_run {
-            let exit_code = self.composer.dispatch(&PostUpdateEvent::new(!no_dev))?;
-            if exit_code != 0 {
-                return Ok(exit_code);
+        // Promoted suggestions are optional, so ones no repository provides are skipped
+        for name in &promoted {
+            if pool.what_provides_lazy(name, None).is_empty() {
+                spinner.suspend(|| eprintln!(
+                    "{} Skipping promoted suggestion {}: no matching package found",
+                    style("Warning:").yellow(),
+                    name
+                ));
+                request.requires.shift_remove(name);
             }
         }
 
-        Ok(0)
+        // Add root package as fixed if it has replace/provide
+        // This ensures the solver knows the root package is always installed
+        // and its replaced/provided packages are available
+        let root_pkg = create_root_package(composer_json, &root_version);
+        if !root_pkg.replace.is_empty() || !root_pkg.provide.is_empty() {
+            request.fix(root_pkg);
+        }
+
+        UpdatePool { pool, request, promoted, installed_packages, root_version }
     }
 
-    pub async fn install(&self, no_scripts: bool, optimize_autoloader: bool, _classmap_authoritative: bool, _apcu_autoloader: bool, _ignore_platform_reqs: bool) -> Result<i32> {
+    pub async fn install(&self, no_scripts: bool, optimize_autoloader: bool, _classmap_authoritative: bool, _apcu_autoloader: bool, ignore_platform_reqs: bool) -> Result<i32> {
         let composer_json = &self.composer.composer_json;
         let working_dir = &self.composer.working_dir;
         let install_config = self.composer.installation_manager.config();
         let dry_run = install_config.dry_run;
         let no_dev = install_config.no_dev;
-        let lock = self.composer.composer_lock.as_ref().context("No composer.lock file found")?;
+        let lock = self.composer.composer_lock.as_ref()
+            .ok_or_else(|| ComposerError::InstallationFailed("No composer.lock file found".to_string()))?;
+
+        let missing = missing_locked_requirements(composer_json, lock, no_dev);
+        if !missing.is_empty() {
+            return Err(ComposerError::LockFileOutOfSync { missing });
+        }
+
+        if !ignore_platform_reqs {
+            let unmet = unmet_platform_requirements(lock, &self.composer.platform_packages, &self.composer.config.platform, no_dev);
+            if !unmet.is_empty() {
+                return Err(ComposerError::PlatformUnmet(unmet));
+            }
+        }
 
         // Detect root package version
         let root_version = get_root_version(working_dir, composer_json);
 
         // Dispatch pre-install event
         if !no_scripts {
-             let exit_code = self.composer.dispatch(&PreInstallEvent::new(!no_dev))?;
+             let exit_code = self.composer.dispatch(&PreInstallEvent::new(!no_dev)).map_err(ComposerError::Event)?;
              if exit_code != 0 { return Ok(exit_code); }
         }
 
@@ -543,7 +762,14 @@ impl Installer {
         progress.enable_steady_tick(Duration::from_millis(100));
 
         let manager = &self.composer.installation_manager;
-        let result = manager.install_packages(&packages).await.context("Failed to install packages")?;
+        let mut result = manager.install_packages(&packages).await?;
+        result.removed = manager.remove_unlisted_packages(&packages).await?;
+        let dev_package_names: Vec<String> = if no_dev {
+            Vec::new()
+        } else {
+            lock.packages_dev.iter().map(|p| p.name.clone()).collect()
+        };
+        manager.write_installed_repository(&packages, &dev_package_names).await?;
 
         progress.finish_and_clear();
 
@@ -552,11 +778,13 @@ impl Installer {
                  println!("  {} {} ({})", style("-").green(), style(&pkg.name).white().bold(), style(&pkg.version).yellow());
              }
         }
+        print_removals(&result);
+        emit_operations(&result);
 
         if !dry_run {
              // Dispatch pre-autoload-dump event
              if !no_scripts {
-                 let exit_code = self.composer.dispatch(&PreAutoloadDumpEvent::new(!no_dev, optimize_autoloader))?;
+                 let exit_code = self.composer.dispatch(&PreAutoloadDumpEvent::new(!no_dev, optimize_autoloader)).map_err(ComposerError::Event)?;
                  if exit_code != 0 { return Ok(exit_code); }
              }
 
@@ -567,24 +795,20 @@ impl Installer {
                  aliases_map.entry(alias.package.clone()).or_default().push(alias.alias.clone());
              }
              let dev_mode = !no_dev;
-             let mut package_autoloads: Vec<PackageAutoload> = lock.packages.iter()
-                 .map(|lp| locked_package_to_autoload(lp, false, &aliases_map))
-                 .collect();
-             if dev_mode {
-                 package_autoloads.extend(lock.packages_dev.iter().map(|lp| locked_package_to_autoload(lp, true, &aliases_map)));
-             }
-             
+             let package_autoloads = locked_package_autoloads(lock, &aliases_map, manager.config());
+
              let autoload_config = AutoloadConfig {
                  vendor_dir: manager.config().vendor_dir.clone(),
                  base_dir: working_dir.clone(),
                  optimize: optimize_autoloader,
                  suffix: if !lock.content_hash.is_empty() { Some(lock.content_hash.clone()) } else { None },
+                 dev_mode,
                  ..Default::default()
              };
 
              let generator = AutoloadGenerator::new(autoload_config);
              // Root autoload from json
-             let root_autoload: Option<Autoload> = Some(composer_json.autoload.clone().into());
+             let root_autoload = Some(root_autoload(composer_json, dev_mode));
              let root_aliases = aliases_map
                  .get(&composer_json.name.clone().unwrap_or_default())
                  .cloned()
@@ -597,13 +821,14 @@ impl Installer {
                  dev_mode,
              );
 
-             generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package)).context("Failed to generate autoloader")?;
+             generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package))?;
+             event_stream::emit(&Event::Autoload);
 
              // Dispatch post-autoload-dump event (runs scripts and plugins)
              if !no_scripts {
                  let arc_packages: Vec<Arc<Package>> = packages.iter().map(|p| Arc::new(p.clone())).collect();
                  let event = PostAutoloadDumpEvent::new(arc_packages, dev_mode, optimize_autoloader);
-                 let exit_code = self.composer.dispatch(&event)?;
+                 let exit_code = self.composer.dispatch(&event).map_err(ComposerError::Event)?;
                  if exit_code != 0 { return Ok(exit_code); }
              }
         }
@@ -612,11 +837,16 @@ impl Installer {
 
         if !dry_run {
             self.audit_abandoned_packages(&packages);
+            warn_unsupported_plugins(&packages);
+            if !self.no_suggest {
+                let new_packages: Vec<&Package> = result.installed.iter().collect();
+                report_suggestions(&new_packages, &packages, &self.composer.platform_packages);
+            }
         }
 
         // Dispatch post-install event
         if !no_scripts && !dry_run {
-             let exit_code = self.composer.dispatch(&PostInstallEvent::new(!no_dev))?;
+             let exit_code = self.composer.dispatch(&PostInstallEvent::new(!no_dev)).map_err(ComposerError::Event)?;
              if exit_code != 0 { return Ok(exit_code); }
         }
 
@@ -644,13 +874,8 @@ impl Installer {
                 aliases_map.entry(alias.package.clone()).or_default().push(alias.alias.clone());
             }
             
-            package_autoloads = lock.packages.iter()
-                .map(|lp| locked_package_to_autoload(lp, false, &aliases_map))
-                .collect();
-            if dev_mode {
-                package_autoloads.extend(lock.packages_dev.iter().map(|lp| locked_package_to_autoload(lp, true, &aliases_map)));
-            }
-            
+            package_autoloads = locked_package_autoloads(lock, &aliases_map, manager.config());
+
             all_installed_packages = lock.packages.iter().map(Package::from).collect();
             if dev_mode {
                 all_installed_packages.extend(lock.packages_dev.iter().map(Package::from));
@@ -668,12 +893,12 @@ impl Installer {
             authoritative,
             apcu,
             suffix,
-            ..Default::default()
+            dev_mode,
         };
 
         let generator = AutoloadGenerator::new(autoload_config);
         // Root autoload from json
-        let root_autoload: Option<Autoload> = Some(composer_json.autoload.clone().into());
+        let root_autoload = Some(root_autoload(composer_json, dev_mode));
         let root_aliases = aliases_map
             .get(&composer_json.name.clone().unwrap_or_default())
             .cloned()
@@ -686,12 +911,12 @@ impl Installer {
             dev_mode,
         );
 
-        generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package)).context("Failed to generate autoloader")?;
+        generator.generate(&package_autoloads, root_autoload.as_ref(), Some(&root_package))?;
 
         // Dispatch post-autoload-dump event (runs scripts and plugins)
         let arc_packages: Vec<Arc<Package>> = all_installed_packages.iter().map(|p| Arc::new(p.clone())).collect();
         let event = PostAutoloadDumpEvent::new(arc_packages, dev_mode, optimize || authoritative);
-        self.composer.dispatch(&event)?;
+        self.composer.dispatch(&event).map_err(ComposerError::Event)?;
 
         if optimize || authoritative {
             println!("{} Generated optimized autoload files", style("Success:").green().bold());
@@ -708,17 +933,10 @@ impl Installer {
             return Vec::new();
         };
 
-        let no_dev = self.composer.installation_manager.config().no_dev;
-
-        let mut packages: Vec<Arc<Package>> = lock.packages.iter()
+        lock.packages.iter()
+            .chain(lock.packages_dev.iter())
             .map(|lp| Arc::new(Package::from(lp)))
-            .collect();
-
-        if !no_dev {
-            packages.extend(lock.packages_dev.iter().map(|lp| Arc::new(Package::from(lp))));
-        }
-
-        packages
+            .collect()
     }
 
     fn audit_abandoned_packages(&self, packages: &[Package]) {
@@ -753,6 +971,110 @@ impl Installer {
 
 // Helpers
 
+/// Print the packages an install removed
+fn print_removals(result: &InstallResult) {
+    for pkg in &result.removed {
+        println!("  {} Removing {} ({})", style("-").red(), style(&pkg.name).white().bold(), style(&pkg.version).yellow());
+    }
+}
+
+/// Report the package operations of an install to the event stream
+fn emit_operations(result: &InstallResult) {
+    for pkg in result.installed.iter().filter(|p| !is_platform_package(&p.name)) {
+        event_stream::emit(&Event::Install { package: pkg.name.clone(), version: pkg.version.clone() });
+    }
+    for (from, to) in &result.updated {
+        event_stream::emit(&Event::Update { package: to.name.clone(), from: from.version.clone(), to: to.version.clone() });
+    }
+    for pkg in &result.removed {
+        event_stream::emit(&Event::Remove { package: pkg.name.clone(), version: pkg.version.clone() });
+    }
+}
+
+/// Warn about installed Composer plugins that pox neither ports nor makes redundant
+fn warn_unsupported_plugins(packages: &[Package]) {
+    let mut plugins: Vec<&str> = packages
+        .iter()
+        .filter(|p| p.is_composer_plugin() && plugin_support(&p.name) == PluginSupport::Unsupported)
+        .map(|p| p.pretty_name())
+        .collect();
+
+    if plugins.is_empty() {
+        return;
+    }
+
+    plugins.sort_unstable();
+    plugins.dedup();
+
+    eprintln!();
+    eprintln!(
+        "{} The following Composer plugins are installed but not executed by pox, their hooks will not run:",
+        style("Warning:").yellow()
+    );
+    for plugin in plugins {
+        eprintln!("  - {}", plugin);
+    }
+}
+
+/// Suggestions of newly installed packages, grouped by suggesting package
+///
+/// Suggested packages that are already installed, provided or replaced by an installed package,
+/// or present on the platform are left out. A package suggested by several packages is only
+/// listed under the first suggester.
+fn collect_suggestions<'a>(
+    new_packages: &[&'a Package],
+    installed: &[Package],
+    platform: &[Package],
+) -> Vec<(&'a str, Vec<(&'a str, &'a str)>)> {
+    let mut present: HashSet<String> = HashSet::new();
+    for package in installed.iter().chain(platform) {
+        present.extend(package.get_names(true).into_iter().map(|n| n.to_lowercase()));
+    }
+
+    let mut suggesters: Vec<&Package> = new_packages.to_vec();
+    suggesters.sort_by(|a, b| a.name.cmp(&b.name));
+    suggesters.dedup_by(|a, b| a.name == b.name);
+
+    let mut suggested: HashSet<String> = HashSet::new();
+    let mut groups = Vec::new();
+    for package in suggesters {
+        let suggestions: Vec<(&str, &str)> = package
+            .suggest
+            .iter()
+            .filter(|(target, _)| {
+                let target = target.to_lowercase();
+                !present.contains(&target) && suggested.insert(target)
+            })
+            .map(|(target, reason)| (target.as_str(), reason.as_str()))
+            .collect();
+        if !suggestions.is_empty() {
+            groups.push((package.pretty_name(), suggestions));
+        }
+    }
+    groups
+}
+
+/// Print the suggestions of newly installed packages
+fn report_suggestions(new_packages: &[&Package], installed: &[Package], platform: &[Package]) {
+    let groups = collect_suggestions(new_packages, installed, platform);
+    if groups.is_empty() {
+        return;
+    }
+
+    println!();
+    for (suggester, suggestions) in groups {
+        println!("{} suggests:", style(suggester).white().bold());
+        for (target, reason) in suggestions {
+            if reason.is_empty() {
+                println!(" - {}", style(target).green());
+            } else {
+                println!(" - {}: {}", style(target).green(), reason);
+            }
+        }
+        println!();
+    }
+}
+
 /// Detects and returns the root package version with logging.
 ///
 /// This handles:
@@ -779,8 +1101,9 @@ fn get_root_version(working_dir: &std::path::Path, composer_json: &ComposerJson)
 
 /// Creates a root package that can be added to the solver pool.
 ///
-/// This creates a Package with the root's replace/provide/conflict declarations
-/// so the solver knows what virtual packages the root provides.
+/// This creates a Package with the root's replace/provide declarations
+/// so the solver knows what virtual packages the root provides. Root conflicts
+/// are part of the request instead.
 fn create_root_package(composer_json: &ComposerJson, root_version: &RootVersion) -> Package {
     let name = composer_json
         .name
@@ -791,10 +1114,9 @@ fn create_root_package(composer_json: &ComposerJson, root_version: &RootVersion)
     pkg.pretty_version = Some(root_version.pretty_version.clone());
     pkg.package_type = composer_json.package_type.clone();
 
-    // Copy replace/provide/conflict from composer.json
+    // Copy replace/provide from composer.json
     pkg.replace = composer_json.replace.clone();
     pkg.provide = composer_json.provide.clone();
-    pkg.conflict = composer_json.conflict.clone();
 
     // Replace self.version with the actual root version
     pkg.replace_self_version();
@@ -824,60 +1146,170 @@ fn create_root_package_info(
     }
 }
 
-fn extract_stability_flag(constraint: &str) -> Option<Stability> {
-    if let Some(at_pos) = constraint.rfind('@') {
-        let stability_str = &constraint[at_pos + 1..];
-        let stability: Stability = stability_str.parse().ok()?;
-        if stability != Stability::Stable {
-            return Some(stability);
+fn print_relaxation_advice(advice: &RelaxationAdvice) {
+    match advice {
+        RelaxationAdvice::Single(relaxation) => {
+            eprintln!("{} The requirements become installable if you {}", style("Hint:").yellow(), relaxation);
+        }
+        RelaxationAdvice::Combined(relaxations) => {
+            eprintln!("{} A single relaxation isn't enough; the requirements become installable if you change these together:", style("Hint:").yellow());
+            for relaxation in relaxations {
+                eprintln!("  - {}", relaxation);
+            }
+        }
+        RelaxationAdvice::NotFound => {
+            eprintln!("{} No relaxation of the root constraints makes the requirements installable", style("Hint:").yellow());
         }
     }
-    None
 }
 
-fn find_transitive_dependencies(packages: &[Package], roots: &HashSet<String>) -> HashSet<String> {
-    let pkg_map: HashMap<String, &Package> = packages.iter()
-        .map(|p| (p.name.to_lowercase(), p))
-        .collect();
+/// Root requirements that no locked package satisfies, because composer.json was
+/// edited without updating the lock file
+fn missing_locked_requirements(composer_json: &ComposerJson, lock: &ComposerLock, no_dev: bool) -> Vec<String> {
+    let dev_requires = if no_dev { None } else { Some(&composer_json.require_dev) };
+    let locked: Vec<&LockedPackage> = if no_dev { lock.packages.iter().collect() } else { lock.all_packages().collect() };
+
+    composer_json.require.keys()
+        .chain(dev_requires.into_iter().flat_map(|requires| requires.keys()))
+        .filter(|name| !is_platform_package(name))
+        .filter(|name| !composer_json.replace.keys().chain(composer_json.provide.keys()).any(|n| n.eq_ignore_ascii_case(name)))
+        .filter(|name| !locked.iter().any(|package| {
+            package.name.eq_ignore_ascii_case(name)
+                || package.provide.keys().chain(package.replace.keys()).any(|n| n.eq_ignore_ascii_case(name))
+        }))
+        .cloned()
+        .collect()
+}
 
-    let mut result: HashSet<String> = HashSet::new();
-    let mut queue: VecDeque<String> = roots.iter().cloned().collect();
+/// Describe the php and extension requirements of the lock file that the platform
+/// doesn't satisfy
+///
+/// The `config.platform` overrides replace the detected versions, and `false` removes a
+/// package. A locked package that provides or replaces a requirement at a matching
+/// constraint, like a polyfill, satisfies it. Nothing is checked when no platform packages
+/// are known.
+fn unmet_platform_requirements(
+    lock: &ComposerLock,
+    platform: &[Package],
+    overrides: &HashMap<String, String>,
+    no_dev: bool,
+) -> Vec<String> {
+    if platform.is_empty() {
+        return Vec::new();
+    }
 
-    while let Some(name) = queue.pop_front() {
-        if result.contains(&name) {
+    let parser = pox_semver::VersionParser::new();
+    let mut platform: Vec<Package> = platform.to_vec();
+    for (name, version) in overrides {
+        let name = name.to_lowercase();
+        platform.retain(|package| package.name != name);
+        if version != "false" && !version.is_empty() {
+            let mut package = Package::new(&name, parser.normalize(version).unwrap_or_else(|_| version.clone()));
+            package.pretty_version = Some(version.clone());
+            platform.push(package);
+        }
+    }
+
+    let root_requires = lock.platform.iter()
+        .chain(lock.platform_dev.iter().filter(|_| !no_dev))
+        .map(|(name, constraint)| ("Root composer.json", name, constraint));
+    let locked: Vec<&LockedPackage> = if no_dev { lock.packages.iter().collect() } else { lock.all_packages().collect() };
+    let package_requires = locked.iter()
+        .flat_map(|package| package.require.iter().map(|(name, constraint)| (package.name.as_str(), name, constraint)));
+
+    let mut unmet = Vec::new();
+    for (source, name, constraint) in root_requires.chain(package_requires) {
+        let name = name.to_lowercase();
+        if name != "php" && !name.starts_with("ext-") {
             continue;
         }
 
-        if let Some(pkg) = pkg_map.get(&name) {
-            result.insert(name.clone());
-            for (dep_name, _) in &pkg.require {
-                if !is_platform_package(dep_name) {
-                    let dep_lower = dep_name.to_lowercase();
-                    if !result.contains(&dep_lower) {
-                        queue.push_back(dep_lower);
-                    }
-                }
+        let parsed = parser.parse_constraints(constraint).ok();
+        let available = platform.iter().find(|package| package.name == name);
+        let satisfied = available.is_some_and(|package| {
+            parsed.as_ref().map_or(true, |parsed| parsed.allows(&package.version))
+        });
+        let provided = || lock.providers_of(&name, no_dev).iter().any(|(_, provided)| {
+            match (&parsed, parser.parse_constraints(provided)) {
+                (Some(parsed), Ok(provided)) => parsed.matches(provided.as_ref()),
+                _ => true,
+            }
+        });
+        if !satisfied && !provided() {
+            let found = match available {
+                Some(package) => format!("your {} version ({}) does not satisfy that requirement", name, package.pretty_version()),
+                None if name == "php" => "it is missing from your system".to_string(),
+                None => format!("it is missing from your system. Install or enable PHP's {} extension", name.trim_start_matches("ext-")),
+            };
+            let message = format!("{} requires {} {} -> {}", source, name, constraint, found);
+            if !unmet.contains(&message) {
+                unmet.push(message);
             }
-        } else {
-            result.insert(name);
         }
     }
-    result
+
+    unmet
+}
+
+/// Check if two lock files pin the same package versions
+fn same_locked_versions(a: &ComposerLock, b: &ComposerLock) -> bool {
+    fn versions(packages: &[LockedPackage]) -> Vec<(String, &str)> {
+        let mut versions: Vec<(String, &str)> = packages.iter()
+            .map(|p| (p.name.to_lowercase(), p.version.as_str()))
+            .collect();
+        versions.sort();
+        versions
+    }
+
+    versions(&a.packages) == versions(&b.packages) && versions(&a.packages_dev) == versions(&b.packages_dev)
+}
+
+/// Autoload information for all locked packages, with the dev packages marked as such
+fn locked_package_autoloads(
+    lock: &ComposerLock,
+    aliases_map: &HashMap<String, Vec<String>>,
+    install_config: &InstallConfig,
+) -> Vec<PackageAutoload> {
+    lock.packages.iter()
+        .map(|lp| locked_package_to_autoload(lp, false, aliases_map, install_config))
+        .chain(lock.packages_dev.iter().map(|lp| locked_package_to_autoload(lp, true, aliases_map, install_config)))
+        .collect()
+}
+
+/// Autoload rules of the root package, with `autoload-dev` added in dev mode
+fn root_autoload(composer_json: &ComposerJson, dev_mode: bool) -> Autoload {
+    let mut autoload: Autoload = composer_json.autoload.clone().into();
+    if dev_mode {
+        autoload.merge(composer_json.autoload_dev.clone().into());
+    }
+    autoload
 }
 
-fn locked_package_to_autoload(lp: &LockedPackage, is_dev: bool, aliases_map: &HashMap<String, Vec<String>>) -> PackageAutoload {
+fn locked_package_to_autoload(
+    lp: &LockedPackage,
+    is_dev: bool,
+    aliases_map: &HashMap<String, Vec<String>>,
+    install_config: &InstallConfig,
+) -> PackageAutoload {
     let autoload = Autoload::from(&lp.autoload);
     let requires: Vec<String> = lp.require.keys().filter(|k| !is_platform_package(k)).cloned().collect();
     let reference = lp.source.as_ref().map(|s| s.reference.clone()).or_else(|| lp.dist.as_ref().and_then(|d| d.reference.clone()));
     let aliases = aliases_map.get(&lp.name).cloned().unwrap_or_default();
-    
+    // installed.php carries the normalized version next to the pretty one, like Composer
+    let version = pox_semver::VersionParser::new().normalize(&lp.version).unwrap_or_else(|_| lp.version.clone());
+
     PackageAutoload {
         name: lp.name.clone(),
         autoload,
-        install_path: lp.name.clone(),
+        install_path: install_config.installer_paths.vendor_relative_path(
+            &install_config.vendor_dir,
+            &lp.name,
+            &lp.package_type,
+            lp.extra.as_ref(),
+        ),
         requires,
         pretty_version: Some(lp.version.clone()),
-        version: Some(lp.version.clone()),
+        version: Some(version),
         reference,
         package_type: lp.package_type.clone(),
         dev_requirement: is_dev,
@@ -887,3 +1319,310 @@ fn locked_package_to_autoload(lp: &LockedPackage, is_dev: bool, aliases_map: &Ha
     }
 }
 
+/// Locked packages outside `allowlist` that the solver picked a different version of, as
+/// `name (locked => new)`
+///
+/// Locked packages missing from the result are fine: nothing requires them anymore.
+fn changed_outside_allowlist(lock: &ComposerLock, packages: &[Arc<Package>], allowlist: &HashSet<String>) -> Vec<String> {
+    let parser = pox_semver::VersionParser::new();
+    let normalize = |version: &str| parser.normalize(version).unwrap_or_else(|_| version.to_lowercase());
+
+    let mut changed = Vec::new();
+    for locked in lock.packages.iter().chain(lock.packages_dev.iter()) {
+        let name = locked.name.to_lowercase();
+        if allowlist.contains(&name) {
+            continue;
+        }
+        let Some(package) = packages.iter().find(|p| p.name.eq_ignore_ascii_case(&name)) else {
+            continue;
+        };
+        let version = package.pretty_version.as_deref().unwrap_or(&package.version);
+        if normalize(version) != normalize(&locked.version) {
+            changed.push(format!("{} ({} => {})", locked.name, locked.version, version));
+        }
+    }
+    changed
+}
+
+/// Expand the packages listed for a partial update with the ones they may drag along
+///
+/// Dependencies are found by walking the `require` of locked packages, matching packages
+/// by name, `provide` and `replace`. Dependents are only walked for
+/// [`UpdateAllowTransitive::All`], starting from the listed packages: walking them from a
+/// widely used dependency would unfreeze most of the lock file.
+fn expand_update_allowlist(
+    lock: &ComposerLock,
+    packages: &[String],
+    root_requires: &HashSet<String>,
+    transitive: UpdateAllowTransitive,
+) -> HashSet<String> {
+    let listed: HashSet<String> = packages.iter().map(|p| p.to_lowercase()).collect();
+    if transitive == UpdateAllowTransitive::ListedOnly {
+        return listed;
+    }
+
+    let locked: Vec<&LockedPackage> = lock.packages.iter().chain(lock.packages_dev.iter()).collect();
+
+    // Locked packages satisfying each name, and the dependencies of each package
+    let mut providers: HashMap<String, Vec<String>> = HashMap::new();
+    for pkg in &locked {
+        let name = pkg.name.to_lowercase();
+        for target in std::iter::once(&pkg.name).chain(pkg.provide.keys()).chain(pkg.replace.keys()) {
+            providers.entry(target.to_lowercase()).or_default().push(name.clone());
+        }
+    }
+    let dependencies: HashMap<String, Vec<String>> = locked
+        .iter()
+        .map(|pkg| {
+            let deps = pkg.require.keys()
+                .filter(|dep| !is_platform_package(dep))
+                .flat_map(|dep| providers.get(&dep.to_lowercase()).cloned().unwrap_or_default())
+                .collect();
+            (pkg.name.to_lowercase(), deps)
+        })
+        .collect();
+
+    let mut allowed = listed.clone();
+
+    let mut queue: VecDeque<String> = listed.iter().cloned().collect();
+    while let Some(name) = queue.pop_front() {
+        for dep in dependencies.get(&name).into_iter().flatten() {
+            let skip_root_require = transitive == UpdateAllowTransitive::DependenciesNoRootRequire
+                && root_requires.contains(dep);
+            if !skip_root_require && allowed.insert(dep.clone()) {
+                queue.push_back(dep.clone());
+            }
+        }
+    }
+
+    if transitive == UpdateAllowTransitive::All {
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (name, deps) in &dependencies {
+            for dep in deps {
+                dependents.entry(dep.as_str()).or_default().push(name.as_str());
+            }
+        }
+
+        let mut queue: VecDeque<&str> = listed.iter().map(String::as_str).collect();
+        let mut visited: HashSet<&str> = queue.iter().copied().collect();
+        while let Some(name) = queue.pop_front() {
+            for dependent in dependents.get(name).into_iter().flatten() {
+                if visited.insert(dependent) {
+                    allowed.insert(dependent.to_string());
+                    queue.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_with_suggests(name: &str, suggests: &[(&str, &str)]) -> Package {
+        let mut package = Package::new(name, "1.0.0");
+        for (target, reason) in suggests {
+            package.suggest.insert(target.to_string(), reason.to_string());
+        }
+        package
+    }
+
+    #[test]
+    fn test_locked_package_autoloads() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "packages": [
+                {"name": "acme/bundle", "version": "v1.2.0", "type": "symfony-bundle"}
+            ],
+            "packages-dev": [
+                {"name": "acme/tools", "version": "1.x-dev"}
+            ]
+        })).unwrap();
+
+        let autoloads = locked_package_autoloads(&lock, &HashMap::new(), &InstallConfig::default());
+
+        assert_eq!(autoloads[0].package_type, "symfony-bundle");
+        assert_eq!(autoloads[0].pretty_version.as_deref(), Some("v1.2.0"));
+        assert_eq!(autoloads[0].version.as_deref(), Some("1.2.0.0"));
+        assert!(!autoloads[0].dev_requirement);
+        assert_eq!(autoloads[1].package_type, "library");
+        assert_eq!(autoloads[1].version.as_deref(), Some("1.9999999.9999999.9999999-dev"));
+        assert!(autoloads[1].dev_requirement);
+    }
+
+    #[test]
+    fn test_same_locked_versions() {
+        let lock = |packages: serde_json::Value| -> ComposerLock {
+            serde_json::from_value(serde_json::json!({"content-hash": "", "packages": packages})).unwrap()
+        };
+
+        let a = lock(serde_json::json!([{"name": "a/a", "version": "1.0.0"}, {"name": "b/b", "version": "2.0.0"}]));
+        let b = lock(serde_json::json!([{"name": "B/B", "version": "2.0.0"}, {"name": "a/a", "version": "1.0.0"}]));
+        assert!(same_locked_versions(&a, &b));
+
+        let c = lock(serde_json::json!([{"name": "a/a", "version": "1.0.1"}, {"name": "b/b", "version": "2.0.0"}]));
+        assert!(!same_locked_versions(&a, &c));
+        assert!(!same_locked_versions(&a, &lock(serde_json::json!([]))));
+    }
+
+    #[test]
+    fn test_changed_outside_allowlist() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [
+                {"name": "acme/allowed", "version": "1.0.0"},
+                {"name": "acme/fixed", "version": "v2.0.0"},
+                {"name": "acme/moved", "version": "3.0.0"},
+                {"name": "acme/removed", "version": "4.0.0"}
+            ]
+        })).unwrap();
+        let packages = vec![
+            Arc::new(Package::new("acme/allowed", "1.1.0")),
+            Arc::new(Package::new("acme/fixed", "2.0.0")),
+            Arc::new(Package::new("acme/moved", "3.0.1")),
+            Arc::new(Package::new("acme/new", "1.0.0")),
+        ];
+        let allowlist: HashSet<String> = ["acme/allowed".to_string()].into_iter().collect();
+
+        assert_eq!(
+            changed_outside_allowlist(&lock, &packages, &allowlist),
+            vec!["acme/moved (3.0.0 => 3.0.1)"]
+        );
+    }
+
+    #[test]
+    fn test_missing_locked_requirements() {
+        let composer_json: ComposerJson = serde_json::from_value(serde_json::json!({
+            "require": {"php": ">=8.1", "Monolog/Monolog": "^3.0", "psr/log-implementation": "*", "acme/new": "^1.0"},
+            "require-dev": {"phpunit/phpunit": "^10.0"}
+        })).unwrap();
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [{"name": "monolog/monolog", "version": "3.5.0", "provide": {"psr/log-implementation": "3.0.0"}}]
+        })).unwrap();
+
+        assert_eq!(missing_locked_requirements(&composer_json, &lock, true), vec!["acme/new"]);
+        assert_eq!(missing_locked_requirements(&composer_json, &lock, false), vec!["acme/new", "phpunit/phpunit"]);
+    }
+
+    #[test]
+    fn test_unmet_platform_requirements() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [{"name": "acme/lib", "version": "1.0.0", "require": {"php": ">=8.1", "ext-intl": "*", "psr/log": "^3.0"}}],
+            "platform": {"php": "^8.2"},
+            "platform-dev": {"ext-xdebug": "*"}
+        })).unwrap();
+        let platform = vec![Package::new("php", "8.1.0"), Package::new("ext-intl", "8.1.0")];
+
+        let no_overrides = HashMap::new();
+
+        assert_eq!(
+            unmet_platform_requirements(&lock, &platform, &no_overrides, false),
+            vec![
+                "Root composer.json requires php ^8.2 -> your php version (8.1.0) does not satisfy that requirement",
+                "Root composer.json requires ext-xdebug * -> it is missing from your system. Install or enable PHP's xdebug extension",
+            ]
+        );
+        assert_eq!(unmet_platform_requirements(&lock, &platform, &no_overrides, true).len(), 1);
+        assert!(unmet_platform_requirements(&lock, &[], &no_overrides, false).is_empty());
+
+        // config.platform replaces the detected versions
+        let overrides: HashMap<String, String> = [("php".to_string(), "8.2.5".to_string()), ("ext-intl".to_string(), "false".to_string())]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            unmet_platform_requirements(&lock, &platform, &overrides, true),
+            vec!["acme/lib requires ext-intl * -> it is missing from your system. Install or enable PHP's intl extension"]
+        );
+    }
+
+    #[test]
+    fn test_unmet_platform_requirements_provided_by_locked_packages() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [
+                {"name": "acme/lib", "version": "1.0.0", "require": {"ext-mbstring": "*", "ext-intl": "^2.0", "ext-iconv": "*"}},
+                {"name": "symfony/polyfill-mbstring", "version": "v1.29.0", "provide": {"ext-mbstring": "*"}},
+                {"name": "acme/intl", "version": "1.0.0", "replace": {"ext-intl": "self.version"}}
+            ],
+            "packages-dev": [{"name": "acme/iconv", "version": "1.0.0", "provide": {"ext-iconv": "*"}}]
+        })).unwrap();
+        let platform = vec![Package::new("php", "8.3.0")];
+
+        assert_eq!(
+            unmet_platform_requirements(&lock, &platform, &HashMap::new(), false),
+            vec!["acme/lib requires ext-intl ^2.0 -> it is missing from your system. Install or enable PHP's intl extension"]
+        );
+        assert_eq!(unmet_platform_requirements(&lock, &platform, &HashMap::new(), true).len(), 2);
+    }
+
+    #[test]
+    fn test_collect_suggestions() {
+        let console = package_with_suggests(
+            "symfony/console",
+            &[("psr/log", "For using the console logger"), ("symfony/lock", ""), ("ext-intl", "")],
+        );
+        let monolog = package_with_suggests(
+            "monolog/monolog",
+            &[("symfony/lock", "Duplicate"), ("ext-mongodb", "For MongoDB"), ("acme/old", "")],
+        );
+        let mut replacer = Package::new("acme/new", "1.0.0");
+        replacer.replace.insert("acme/old".to_string(), "*".to_string());
+        let psr_log = Package::new("psr/log", "3.0.0");
+
+        let installed = vec![console.clone(), monolog.clone(), replacer, psr_log];
+        let platform = vec![Package::new("ext-intl", "8.3.0")];
+        let groups = collect_suggestions(&[&console, &monolog], &installed, &platform);
+
+        assert_eq!(
+            groups,
+            vec![
+                ("monolog/monolog", vec![("symfony/lock", "Duplicate"), ("ext-mongodb", "For MongoDB")]),
+            ]
+        );
+    }
+
+    fn allowlist(lock: &ComposerLock, packages: &[&str], transitive: UpdateAllowTransitive) -> Vec<String> {
+        let packages: Vec<String> = packages.iter().map(|p| p.to_string()).collect();
+        let root_requires = HashSet::from(["acme/app-lib".to_string(), "psr/log".to_string()]);
+        let mut allowed: Vec<String> = expand_update_allowlist(lock, &packages, &root_requires, transitive)
+            .into_iter()
+            .collect();
+        allowed.sort();
+        allowed
+    }
+
+    #[test]
+    fn test_expand_update_allowlist() {
+        // acme/app-lib -> acme/http -> acme/psr7-impl (provides psr/http-message-implementation)
+        //                           -> psr/log
+        // acme/other -> acme/http
+        // acme/unrelated -> psr/log
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [
+                {"name": "acme/app-lib", "version": "1.0.0", "require": {"php": ">=8.1", "acme/http": "^1.0"}},
+                {"name": "acme/http", "version": "1.0.0", "require": {"psr/http-message-implementation": "*", "psr/log": "^3.0"}},
+                {"name": "acme/psr7-impl", "version": "1.0.0", "provide": {"psr/http-message-implementation": "1.0"}},
+                {"name": "acme/other", "version": "1.0.0", "require": {"acme/http": "^1.0"}},
+                {"name": "acme/unrelated", "version": "1.0.0", "require": {"psr/log": "^3.0"}},
+                {"name": "psr/log", "version": "3.0.0"},
+            ],
+            "packages-dev": [],
+        }))
+        .unwrap();
+
+        assert_eq!(allowlist(&lock, &["Acme/HTTP"], UpdateAllowTransitive::ListedOnly), vec!["acme/http"]);
+        assert_eq!(
+            allowlist(&lock, &["acme/http"], UpdateAllowTransitive::DependenciesNoRootRequire),
+            vec!["acme/http", "acme/psr7-impl"]
+        );
+        assert_eq!(
+            allowlist(&lock, &["acme/http"], UpdateAllowTransitive::All),
+            vec!["acme/app-lib", "acme/http", "acme/other", "acme/psr7-impl", "psr/log"]
+        );
+    }
+}
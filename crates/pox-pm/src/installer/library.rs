@@ -3,7 +3,8 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::downloader::{DownloadManager, DownloadResult};
+use crate::downloader::{DistManifest, DownloadManager, DownloadResult};
+use super::paths::InstallerPaths;
 use crate::package::Package;
 use crate::Result;
 
@@ -11,6 +12,7 @@ use crate::Result;
 pub struct LibraryInstaller {
     download_manager: Arc<DownloadManager>,
     vendor_dir: PathBuf,
+    installer_paths: InstallerPaths,
 }
 
 impl LibraryInstaller {
@@ -19,12 +21,19 @@ impl LibraryInstaller {
         Self {
             download_manager,
             vendor_dir: vendor_dir.into(),
+            installer_paths: InstallerPaths::default(),
         }
     }
 
+    /// Route packages matching `extra.installer-paths` to their custom locations
+    pub fn with_installer_paths(mut self, installer_paths: InstallerPaths) -> Self {
+        self.installer_paths = installer_paths;
+        self
+    }
+
     /// Get the install path for a package
     pub fn get_install_path(&self, package: &Package) -> PathBuf {
-        self.vendor_dir.join(&package.name)
+        self.installer_paths.install_path(&self.vendor_dir, &package.name, &package.package_type, package.extra.as_ref())
     }
 
     /// Check if a package is installed
@@ -61,6 +70,19 @@ impl LibraryInstaller {
         self.download_manager.download(to).await
     }
 
+    /// Remove and install a package again with its currently installed version
+    ///
+    /// A package installed from source (a git checkout) is cloned again; other packages are
+    /// installed the way a fresh install would.
+    pub async fn reinstall(&self, package: &Package) -> Result<DownloadResult> {
+        let from_source = self.get_install_path(package).join(".git").exists();
+
+        self.uninstall(package).await?;
+
+        let use_source = from_source || self.download_manager.should_use_source(package);
+        self.download_manager.download_with(package, use_source).await
+    }
+
     /// Uninstall a package
     pub async fn uninstall(&self, package: &Package) -> Result<()> {
         let install_path = self.get_install_path(package);
@@ -68,6 +90,7 @@ impl LibraryInstaller {
         if install_path.exists() {
             tokio::fs::remove_dir_all(&install_path).await?;
         }
+        DistManifest::remove(&self.vendor_dir, &package.name)?;
 
         Ok(())
     }
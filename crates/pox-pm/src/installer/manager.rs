@@ -1,19 +1,23 @@
 //! Installation manager - orchestrates package installation.
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use futures_util::stream::{self, StreamExt};
 
+use crate::config::PreferredInstall;
 use crate::downloader::{DownloadConfig, DownloadManager};
 use crate::http::HttpClient;
 use crate::package::Package;
+use crate::repository::{InstalledRepository, Repository, WritableRepository};
 use crate::solver::{Operation, Transaction};
 use crate::Result;
 
 use super::binary::BinaryInstaller;
 use super::library::LibraryInstaller;
 use super::metapackage::MetapackageInstaller;
+use super::paths::InstallerPaths;
 
 /// Installation configuration
 #[derive(Debug, Clone)]
@@ -28,12 +32,18 @@ pub struct InstallConfig {
     pub prefer_source: bool,
     /// Prefer dist over source
     pub prefer_dist: bool,
+    /// Per-package install method by package name pattern, first match wins
+    pub package_preferences: Vec<(String, PreferredInstall)>,
     /// Run in dry-run mode (no actual changes)
     pub dry_run: bool,
     /// Skip dev dependencies
     pub no_dev: bool,
     /// Prefer lowest versions (useful for testing compatibility)
     pub prefer_lowest: bool,
+    /// Prefer stable versions, recorded in the lock file next to `prefer_lowest`
+    pub prefer_stable: bool,
+    /// Custom install paths from the root package's `extra.installer-paths`
+    pub installer_paths: InstallerPaths,
 }
 
 impl Default for InstallConfig {
@@ -46,9 +56,12 @@ impl Default for InstallConfig {
                 .join("cache"),
             prefer_source: false,
             prefer_dist: true,
+            package_preferences: Vec::new(),
             dry_run: false,
             no_dev: false,
             prefer_lowest: false,
+            prefer_stable: false,
+            installer_paths: InstallerPaths::default(),
         }
     }
 }
@@ -84,19 +97,22 @@ impl InstallationManager {
             cache_dir: config.cache_dir.clone(),
             prefer_source: config.prefer_source,
             prefer_dist: config.prefer_dist,
+            package_preferences: config.package_preferences.clone(),
+            installer_paths: config.installer_paths.clone(),
+            ..Default::default()
         };
 
         let download_manager = Arc::new(DownloadManager::new(http_client, download_config));
 
-        let library_installer = Arc::new(LibraryInstaller::new(
-            download_manager,
-            config.vendor_dir.clone(),
-        ));
+        let library_installer = Arc::new(
+            LibraryInstaller::new(download_manager, config.vendor_dir.clone())
+                .with_installer_paths(config.installer_paths.clone()),
+        );
 
-        let binary_installer = Arc::new(BinaryInstaller::new(
-            config.bin_dir.clone(),
-            config.vendor_dir.clone(),
-        ));
+        let binary_installer = Arc::new(
+            BinaryInstaller::new(config.bin_dir.clone(), config.vendor_dir.clone())
+                .with_installer_paths(config.installer_paths.clone()),
+        );
 
         let metapackage_installer = MetapackageInstaller::new();
 
@@ -288,8 +304,10 @@ impl InstallationManager {
             }
         }
 
+        // Metapackages have nothing to download but still count as installed
         for package in metapackages {
             self.metapackage_installer.install(package).await?;
+            result.installed.push(package.clone());
         }
 
         // Install regular packages in parallel
@@ -318,6 +336,102 @@ impl InstallationManager {
         Ok(result)
     }
 
+    /// Reinstall packages at their given versions without resolving dependencies
+    ///
+    /// Each package's files and binaries are removed and installed again, from source when
+    /// the package is currently a source checkout. Metapackages have no files and are skipped.
+    pub async fn reinstall_packages(&self, packages: &[Package]) -> Result<InstallResult> {
+        let mut result = InstallResult {
+            installed: Vec::new(),
+            updated: Vec::new(),
+            removed: Vec::new(),
+            binaries: Vec::new(),
+        };
+
+        let packages: Vec<&Package> = packages
+            .iter()
+            .filter(|p| !p.is_platform_package() && !p.is_metapackage())
+            .collect();
+
+        if self.config.dry_run {
+            result.installed = packages.into_iter().cloned().collect();
+            return Ok(result);
+        }
+
+        let reinstall_results: Vec<_> = stream::iter(packages.iter())
+            .map(|package| {
+                let library_installer = self.library_installer.clone();
+                let binary_installer = self.binary_installer.clone();
+                async move {
+                    binary_installer.uninstall(package).await?;
+                    library_installer.reinstall(package).await?;
+                    let bins = binary_installer.install(package).await?;
+                    Ok::<_, crate::ComposerError>(((*package).clone(), bins))
+                }
+            })
+            .buffer_unordered(MAX_CONCURRENT_INSTALLS)
+            .collect()
+            .await;
+
+        for reinstall_result in reinstall_results {
+            let (pkg, bins) = reinstall_result?;
+            result.installed.push(pkg);
+            result.binaries.extend(bins);
+        }
+
+        Ok(result)
+    }
+
+    /// Uninstall the packages recorded in `vendor/composer/installed.json` that are not part of
+    /// `packages`, such as dev packages when installing with `--no-dev`
+    pub async fn remove_unlisted_packages(&self, packages: &[Package]) -> Result<Vec<Package>> {
+        let repository = InstalledRepository::new(self.config.vendor_dir.clone());
+        repository.load().await.map_err(crate::ComposerError::Repository)?;
+
+        let listed: HashSet<String> = packages.iter().map(|p| p.name.to_lowercase()).collect();
+        let mut unlisted: Vec<Package> = repository.get_packages().await
+            .into_iter()
+            .filter(|p| !p.is_platform_package() && !listed.contains(&p.name.to_lowercase()))
+            .map(|p| p.as_ref().clone())
+            .collect();
+        unlisted.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if self.config.dry_run {
+            return Ok(unlisted);
+        }
+
+        for package in &unlisted {
+            if package.is_metapackage() {
+                self.metapackage_installer.uninstall(package).await?;
+            } else {
+                self.binary_installer.uninstall(package).await?;
+                self.uninstall_package(package).await?;
+            }
+        }
+
+        Ok(unlisted)
+    }
+
+    /// Record the installed packages in `vendor/composer/installed.json`
+    ///
+    /// Metapackages are recorded like any other package, so their presence keeps
+    /// satisfying dependents even though nothing exists on disk for them. `dev_package_names`
+    /// lists the packages only required through `require-dev`.
+    pub async fn write_installed_repository(&self, packages: &[Package], dev_package_names: &[String]) -> Result<()> {
+        if self.config.dry_run {
+            return Ok(());
+        }
+
+        let mut repository = InstalledRepository::new(self.config.vendor_dir.clone());
+        for package in packages.iter().filter(|p| !p.is_platform_package()) {
+            repository.add_package(package.clone()).await;
+        }
+        repository.set_dev_packages(!self.config.no_dev, dev_package_names.to_vec()).await;
+        repository.write().await?;
+
+        Ok(())
+    }
+
     /// Get the config
     pub fn config(&self) -> &InstallConfig {
         &self.config
@@ -396,4 +510,105 @@ mod tests {
         assert!(result.updated.is_empty());
         assert!(result.removed.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_install_metapackage() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let http_client = Arc::new(HttpClient::new().unwrap());
+        let config = InstallConfig {
+            vendor_dir: vendor_dir.clone(),
+            bin_dir: vendor_dir.join("bin"),
+            cache_dir: temp_dir.path().join("cache"),
+            ..Default::default()
+        };
+        let manager = InstallationManager::new(http_client, config);
+
+        // No source or dist: downloading would fail
+        let mut metapackage = Package::new("acme/bundle", "1.0.0");
+        metapackage.package_type = "metapackage".to_string();
+        metapackage.require.insert("acme/lib".to_string(), "^1.0".to_string());
+
+        let result = manager.install_packages(std::slice::from_ref(&metapackage)).await.unwrap();
+        assert_eq!(result.installed.len(), 1);
+        assert!(!vendor_dir.join("acme/bundle").exists());
+
+        manager.write_installed_repository(&[metapackage, Package::new("php", "8.3.0")], &[]).await.unwrap();
+        let repository = InstalledRepository::new(vendor_dir);
+        repository.load().await.unwrap();
+        let installed = repository.get_packages().await;
+        assert_eq!(installed.len(), 1);
+        assert!(installed[0].is_metapackage());
+        assert_eq!(installed[0].require["acme/lib"], "^1.0");
+    }
+
+    #[tokio::test]
+    async fn test_remove_unlisted_packages() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let vendor_dir = temp_dir.path().join("vendor");
+        let http_client = Arc::new(HttpClient::new().unwrap());
+        let config = InstallConfig {
+            vendor_dir: vendor_dir.clone(),
+            bin_dir: vendor_dir.join("bin"),
+            cache_dir: temp_dir.path().join("cache"),
+            ..Default::default()
+        };
+        let manager = InstallationManager::new(http_client, config);
+
+        let mut packages = Vec::new();
+        for name in ["acme/lib", "acme/dev-tool"] {
+            let source_dir = temp_dir.path().join("packages").join(name);
+            std::fs::create_dir_all(&source_dir).unwrap();
+            std::fs::write(source_dir.join("Lib.php"), "<?php").unwrap();
+
+            let mut package = Package::new(name, "1.0.0");
+            let options = std::collections::HashMap::from([("symlink".to_string(), serde_json::json!(false))]);
+            package.dist = Some(crate::package::Dist::new("path", source_dir.to_string_lossy()).with_transport_options(options));
+            packages.push(package);
+        }
+        manager.install_packages(&packages).await.unwrap();
+        manager.write_installed_repository(&packages, &["acme/dev-tool".to_string()]).await.unwrap();
+
+        let repository = InstalledRepository::new(vendor_dir.clone());
+        repository.load().await.unwrap();
+        assert_eq!(repository.dev_package_names().await, vec!["acme/dev-tool"]);
+        let installed_json = std::fs::read_to_string(repository.installed_json_path()).unwrap();
+        assert!(installed_json.contains("\"dev-package-names\""));
+
+        let removed = manager.remove_unlisted_packages(&packages[..1]).await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].name, "acme/dev-tool");
+        assert!(vendor_dir.join("acme/lib/Lib.php").exists());
+        assert!(!vendor_dir.join("acme/dev-tool").exists());
+    }
+
+    #[tokio::test]
+    async fn test_reinstall_restores_package_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("packages/lib");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("Lib.php"), "<?php").unwrap();
+
+        let vendor_dir = temp_dir.path().join("vendor");
+        let http_client = Arc::new(HttpClient::new().unwrap());
+        let config = InstallConfig {
+            vendor_dir: vendor_dir.clone(),
+            bin_dir: vendor_dir.join("bin"),
+            cache_dir: temp_dir.path().join("cache"),
+            ..Default::default()
+        };
+        let manager = InstallationManager::new(http_client, config);
+
+        let mut package = Package::new("acme/lib", "1.0.0");
+        let options = std::collections::HashMap::from([("symlink".to_string(), serde_json::json!(false))]);
+        package.dist = Some(crate::package::Dist::new("path", source_dir.to_string_lossy()).with_transport_options(options));
+
+        manager.install_packages(std::slice::from_ref(&package)).await.unwrap();
+        let installed_file = vendor_dir.join("acme/lib/Lib.php");
+        std::fs::remove_file(&installed_file).unwrap();
+
+        let result = manager.reinstall_packages(std::slice::from_ref(&package)).await.unwrap();
+        assert_eq!(result.installed.len(), 1);
+        assert!(installed_file.exists());
+    }
 }
@@ -7,10 +7,14 @@ mod binary;
 mod library;
 mod manager;
 mod metapackage;
+mod paths;
+mod process_lock;
 mod installer;
 
 pub use binary::BinaryInstaller;
 pub use library::LibraryInstaller;
-pub use manager::{InstallConfig, InstallationManager};
+pub use manager::{InstallConfig, InstallResult, InstallationManager};
 pub use metapackage::{MetapackageInstaller, MetapackageResult};
+pub use paths::InstallerPaths;
+pub use process_lock::{ProcessLock, LOCK_FILE};
 pub use installer::Installer;
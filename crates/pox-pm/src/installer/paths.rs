@@ -0,0 +1,177 @@
+//! Custom install locations from `extra.installer-paths`.
+//!
+//! Mirrors the `installer-paths` option of `composer/installers`: the root package maps
+//! path templates to package selectors, and matching packages are installed there instead
+//! of into the vendor directory.
+
+use std::path::{Path, PathBuf};
+
+/// Custom install paths configured by the root package
+#[derive(Debug, Clone, Default)]
+pub struct InstallerPaths {
+    /// Directory relative templates are resolved against (the project root)
+    base_dir: PathBuf,
+    /// Path templates with their selectors, in declaration order
+    paths: Vec<(String, Vec<String>)>,
+}
+
+impl InstallerPaths {
+    /// Read `installer-paths` from the root package's `extra`
+    ///
+    /// Each entry maps a path template to selectors: a package name, `type:<type>` or
+    /// `vendor:<vendor>`.
+    pub fn new(base_dir: impl Into<PathBuf>, extra: &serde_json::Value) -> Self {
+        let paths = crate::json::installer_paths(extra)
+            .map(|paths| paths.into_iter().collect())
+            .unwrap_or_default();
+
+        Self {
+            base_dir: base_dir.into(),
+            paths,
+        }
+    }
+
+    /// Check if no custom paths are configured
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Find the custom install path of a package, if a selector matches it
+    ///
+    /// The first path with a matching selector wins. `{$name}`, `{$vendor}` and `{$type}`
+    /// are substituted, where `{$name}` honours the package's `extra.installer-name` and
+    /// `{$type}` drops the framework prefix (`wordpress-plugin` becomes `plugin`).
+    pub fn resolve(&self, name: &str, package_type: &str, extra: Option<&serde_json::Value>) -> Option<PathBuf> {
+        // Metapackages have no files to place
+        if package_type == "metapackage" {
+            return None;
+        }
+
+        let name = name.to_lowercase();
+        let (vendor, short_name) = name.split_once('/').unwrap_or(("", name.as_str()));
+
+        let template = self.paths.iter().find_map(|(path, selectors)| {
+            selectors
+                .iter()
+                .any(|selector| {
+                    let selector = selector.to_lowercase();
+                    match selector.split_once(':') {
+                        Some(("type", t)) => t == package_type,
+                        Some(("vendor", v)) => v == vendor,
+                        _ => selector == name,
+                    }
+                })
+                .then_some(path)
+        })?;
+
+        let installer_name = extra
+            .and_then(|e| e.get("installer-name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(short_name);
+        let type_name = package_type.split_once('-').map_or(package_type, |(_, t)| t);
+
+        let path = template
+            .replace("{$name}", installer_name)
+            .replace("{$vendor}", vendor)
+            .replace("{$type}", type_name);
+
+        Some(self.base_dir.join(path.trim_end_matches('/')))
+    }
+
+    /// Get the install path of a package, falling back to `<vendor_dir>/<name>`
+    pub fn install_path(&self, vendor_dir: &Path, name: &str, package_type: &str, extra: Option<&serde_json::Value>) -> PathBuf {
+        self.resolve(name, package_type, extra)
+            .unwrap_or_else(|| vendor_dir.join(name))
+    }
+
+    /// Get the install path of a package relative to the vendor directory, as the autoloader
+    /// expects it (`../web/plugins/foo` for a custom path)
+    pub fn vendor_relative_path(&self, vendor_dir: &Path, name: &str, package_type: &str, extra: Option<&serde_json::Value>) -> String {
+        self.resolve(name, package_type, extra)
+            .and_then(|path| pathdiff::diff_paths(path, vendor_dir))
+            .map(|path| {
+                path.components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn installer_paths() -> InstallerPaths {
+        InstallerPaths::new(
+            "/app",
+            &json!({
+                "installer-paths": {
+                    "web/modules/custom/{$name}/": ["acme/special"],
+                    "web/plugins/{$vendor}-{$name}/": ["type:wordpress-plugin", "vendor:plugins"],
+                    "web/{$type}s/{$name}/": "type:drupal-module"
+                }
+            }),
+        )
+    }
+
+    #[test]
+    fn test_resolve_by_selector() {
+        let paths = installer_paths();
+
+        assert_eq!(
+            paths.resolve("acme/special", "wordpress-plugin", None),
+            Some(PathBuf::from("/app/web/modules/custom/special"))
+        );
+        assert_eq!(
+            paths.resolve("wpackagist/akismet", "wordpress-plugin", None),
+            Some(PathBuf::from("/app/web/plugins/wpackagist-akismet"))
+        );
+        assert_eq!(
+            paths.resolve("Plugins/Foo", "library", None),
+            Some(PathBuf::from("/app/web/plugins/plugins-foo"))
+        );
+        assert_eq!(
+            paths.resolve("drupal/token", "drupal-module", None),
+            Some(PathBuf::from("/app/web/modules/token"))
+        );
+        assert_eq!(paths.resolve("acme/lib", "library", None), None);
+        assert_eq!(paths.resolve("plugins/meta", "metapackage", None), None);
+    }
+
+    #[test]
+    fn test_resolve_uses_installer_name() {
+        let paths = installer_paths();
+        let extra = json!({"installer-name": "akismet-anti-spam"});
+
+        assert_eq!(
+            paths.resolve("wpackagist/akismet", "wordpress-plugin", Some(&extra)),
+            Some(PathBuf::from("/app/web/plugins/wpackagist-akismet-anti-spam"))
+        );
+    }
+
+    #[test]
+    fn test_vendor_relative_path() {
+        let paths = installer_paths();
+        let vendor_dir = Path::new("/app/vendor");
+
+        assert_eq!(
+            paths.vendor_relative_path(vendor_dir, "drupal/token", "drupal-module", None),
+            "../web/modules/token"
+        );
+        assert_eq!(paths.vendor_relative_path(vendor_dir, "acme/lib", "library", None), "acme/lib");
+        assert_eq!(
+            paths.install_path(vendor_dir, "acme/lib", "library", None),
+            PathBuf::from("/app/vendor/acme/lib")
+        );
+    }
+
+    #[test]
+    fn test_no_installer_paths() {
+        let paths = InstallerPaths::new("/app", &json!({}));
+        assert!(paths.is_empty());
+        assert_eq!(paths.resolve("acme/lib", "wordpress-plugin", None), None);
+    }
+}
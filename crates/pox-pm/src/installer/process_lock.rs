@@ -0,0 +1,214 @@
+//! Lock that keeps concurrent installs and updates out of the same vendor directory.
+//!
+//! The lock is an advisory file lock (`flock` on Unix) on a file in the vendor directory,
+//! so the operating system releases it when the holding process dies. The holder writes
+//! its pid to the file, which is shown to waiting processes and, on filesystems without
+//! file locking, checked to tell a running holder from a killed one.
+//!
+//! Install and update take the lock before reading composer.json and composer.lock, so a
+//! run that waited for another one works on the state that one left behind.
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::{ComposerError, Result};
+
+/// Name of the lock file in the vendor directory
+pub const LOCK_FILE: &str = ".pox-install.lock";
+
+/// How often the pid is checked again when the filesystem has no file locking
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lock of a vendor directory held by this process, released when dropped
+#[derive(Debug)]
+pub struct ProcessLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl ProcessLock {
+    /// Lock `dir` for this process, creating it if needed
+    ///
+    /// While another process holds the lock, this blocks until it is released when `wait`
+    /// is set and fails with [`ComposerError::Locked`] otherwise.
+    pub fn acquire(dir: &Path, wait: bool) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(LOCK_FILE);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let holder = read_pid(&mut file);
+                if !wait {
+                    return Err(ComposerError::Locked { dir: dir.to_path_buf(), pid: holder });
+                }
+                eprintln!("Waiting for another pox process{} running in {} to finish", describe_pid(holder), dir.display());
+                file.lock()?;
+            }
+            Err(TryLockError::Error(e)) if e.kind() == io::ErrorKind::Unsupported => {
+                wait_for_recorded_holder(&mut file, dir, wait)?;
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+
+        Ok(Self { file, path })
+    }
+
+    /// [`ProcessLock::acquire`] on the blocking thread pool, for async callers
+    pub async fn acquire_async(dir: PathBuf, wait: bool) -> Result<Self> {
+        tokio::task::spawn_blocking(move || Self::acquire(&dir, wait))
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ProcessLock {
+    fn drop(&mut self) {
+        // The file lock goes with the file, the pid must not outlive it
+        let _ = self.file.set_len(0);
+    }
+}
+
+/// Wait until the pid recorded in the lock file is no longer running, for filesystems
+/// without file locking
+///
+/// The pid is all there is to go by; one that is no longer running was left behind by a
+/// killed process.
+fn wait_for_recorded_holder(file: &mut File, dir: &Path, wait: bool) -> Result<()> {
+    let mut waiting = false;
+    while let Some(pid) = read_pid(file).filter(|pid| *pid != std::process::id() && is_running(*pid)) {
+        if !wait {
+            return Err(ComposerError::Locked { dir: dir.to_path_buf(), pid: Some(pid) });
+        }
+        if !waiting {
+            eprintln!("Waiting for another pox process{} running in {} to finish", describe_pid(Some(pid)), dir.display());
+            waiting = true;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// The pid written to the lock file by its holder
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
+
+fn describe_pid(pid: Option<u32>) -> String {
+    pid.map(|pid| format!(" (pid {})", pid)).unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists; EPERM means it runs as another user
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_acquire_fails_without_wait() {
+        let temp = tempfile::tempdir().unwrap();
+        let vendor = temp.path().join("vendor");
+
+        let lock = ProcessLock::acquire(&vendor, false).unwrap();
+        assert_eq!(fs::read_to_string(lock.path()).unwrap(), std::process::id().to_string());
+
+        match ProcessLock::acquire(&vendor, false) {
+            Err(ComposerError::Locked { pid, .. }) => assert_eq!(pid, Some(std::process::id())),
+            other => panic!("expected the lock to be held, got {:?}", other),
+        }
+
+        drop(lock);
+        assert_eq!(fs::read_to_string(vendor.join(LOCK_FILE)).unwrap(), "");
+        assert!(ProcessLock::acquire(&vendor, false).is_ok());
+    }
+
+    #[test]
+    fn test_leftover_pid_is_overwritten() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join(LOCK_FILE), "4194305").unwrap();
+
+        let lock = ProcessLock::acquire(temp.path(), false).unwrap();
+        assert_eq!(fs::read_to_string(lock.path()).unwrap(), std::process::id().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async() {
+        let temp = tempfile::tempdir().unwrap();
+
+        let lock = ProcessLock::acquire_async(temp.path().to_path_buf(), false).await.unwrap();
+        assert!(matches!(
+            ProcessLock::acquire_async(temp.path().to_path_buf(), false).await,
+            Err(ComposerError::Locked { .. })
+        ));
+        drop(lock);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_recorded_holder() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(LOCK_FILE);
+        let open = || OpenOptions::new().read(true).open(&path).unwrap();
+
+        // A killed process, this process and no pid at all don't hold the lock
+        for content in ["4194305".to_string(), std::process::id().to_string(), String::new()] {
+            fs::write(&path, content).unwrap();
+            assert!(wait_for_recorded_holder(&mut open(), temp.path(), false).is_ok());
+        }
+
+        let mut child = std::process::Command::new("sleep").arg("1").spawn().unwrap();
+        fs::write(&path, child.id().to_string()).unwrap();
+        match wait_for_recorded_holder(&mut open(), temp.path(), false) {
+            Err(ComposerError::Locked { pid, .. }) => assert_eq!(pid, Some(child.id())),
+            other => panic!("expected the lock to be held, got {:?}", other),
+        }
+
+        // Waiting returns once the holder is gone; reaping it keeps it from lingering as a zombie
+        let reaper = thread::spawn(move || child.wait());
+        assert!(wait_for_recorded_holder(&mut open(), temp.path(), true).is_ok());
+        reaper.join().unwrap().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_running() {
+        assert!(is_running(std::process::id()));
+        // Above the largest pid Linux hands out
+        assert!(!is_running(4194305));
+    }
+}
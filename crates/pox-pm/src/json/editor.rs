@@ -0,0 +1,445 @@
+//! Format-preserving editing of JSON documents such as composer.json, config.json and auth.json.
+//!
+//! Edits are applied to an order-preserving document tree and written back using the
+//! indentation detected in the original file, so unrelated keys keep their position and
+//! the file keeps its look after `config`, `add` or `remove` touch it.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{ComposerError, Result};
+use crate::util::is_platform_package;
+
+const DEFAULT_INDENT: &str = "    ";
+
+/// An editable JSON document that remembers the formatting of its source
+#[derive(Debug, Clone)]
+pub struct JsonEditor {
+    root: Value,
+    indent: String,
+    trailing_newline: bool,
+}
+
+impl Default for JsonEditor {
+    fn default() -> Self {
+        Self {
+            root: Value::Object(Map::new()),
+            indent: DEFAULT_INDENT.to_string(),
+            trailing_newline: true,
+        }
+    }
+}
+
+impl JsonEditor {
+    /// Create an empty document (`{}`) using Composer's default 4-space indentation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a document, detecting its indentation and trailing newline
+    pub fn parse(content: &str) -> Result<Self> {
+        let root: Value = if content.trim().is_empty() {
+            Value::Object(Map::new())
+        } else {
+            serde_json::from_str(content)?
+        };
+
+        if !root.is_object() {
+            return Err(ComposerError::InvalidManifest {
+                message: "the JSON document root must be an object".to_string(),
+            });
+        }
+
+        Ok(Self {
+            root,
+            indent: detect_indent(content).unwrap_or_else(|| DEFAULT_INDENT.to_string()),
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        })
+    }
+
+    /// Load a document from disk, starting from an empty one if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// The whole document
+    pub fn root(&self) -> &Value {
+        &self.root
+    }
+
+    /// The indentation unit used when writing the document
+    pub fn indent(&self) -> &str {
+        &self.indent
+    }
+
+    /// Get the value at a key path, e.g. `["config", "platform", "php"]`
+    pub fn get(&self, path: &[&str]) -> Option<&Value> {
+        let mut current = &self.root;
+        for key in path {
+            current = current.as_object()?.get(*key)?;
+        }
+        Some(current)
+    }
+
+    /// Set the value at a key path, creating intermediate objects as needed.
+    ///
+    /// Existing keys keep their position; new keys are appended to their parent object.
+    pub fn set(&mut self, path: &[&str], value: Value) -> Result<()> {
+        let Some((last, parents)) = path.split_last() else {
+            if !value.is_object() {
+                return Err(ComposerError::InvalidManifest {
+                    message: "the JSON document root must be an object".to_string(),
+                });
+            }
+            self.root = value;
+            return Ok(());
+        };
+
+        let mut current = &mut self.root;
+        for (depth, key) in parents.iter().enumerate() {
+            let object = current
+                .as_object_mut()
+                .ok_or_else(|| not_an_object(&path[..depth]))?;
+            let entry = object
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            // Composer writes empty maps as `[]`, treat those as empty objects
+            if entry.as_array().is_some_and(|a| a.is_empty()) {
+                *entry = Value::Object(Map::new());
+            }
+            current = entry;
+        }
+
+        let object = current
+            .as_object_mut()
+            .ok_or_else(|| not_an_object(parents))?;
+        object.insert(last.to_string(), value);
+        Ok(())
+    }
+
+    /// Remove the value at a key path, returning it if it existed.
+    ///
+    /// Parent objects that become empty through the removal are removed as well.
+    pub fn remove(&mut self, path: &[&str]) -> Option<Value> {
+        let (last, parents) = path.split_last()?;
+
+        let mut current = &mut self.root;
+        for key in parents {
+            current = current.as_object_mut()?.get_mut(*key)?;
+        }
+        let removed = current.as_object_mut()?.shift_remove(*last)?;
+
+        for depth in (1..path.len()).rev() {
+            let is_empty = self
+                .get(&path[..depth])
+                .and_then(|v| v.as_object())
+                .is_some_and(|o| o.is_empty());
+            if !is_empty {
+                break;
+            }
+            let (key, parents) = path[..depth].split_last()?;
+            let mut parent = &mut self.root;
+            for k in parents {
+                parent = parent.as_object_mut()?.get_mut(*k)?;
+            }
+            parent.as_object_mut()?.shift_remove(*key);
+        }
+
+        Some(removed)
+    }
+
+    /// Add a package link to a section such as `require` or `require-dev`, or update its constraint.
+    ///
+    /// An existing entry, matched case-insensitively, keeps its name and position and new
+    /// entries are appended. With `sort_packages` the section is sorted like Composer's
+    /// `config.sort-packages` instead.
+    pub fn add_link(&mut self, section: &str, name: &str, constraint: &str, sort_packages: bool) -> Result<()> {
+        let key = self
+            .get(&[section])
+            .and_then(|v| v.as_object())
+            .and_then(|links| links.keys().find(|k| k.eq_ignore_ascii_case(name)).cloned())
+            .unwrap_or_else(|| name.to_string());
+        self.set(&[section, &key], Value::String(constraint.to_string()))?;
+
+        if sort_packages {
+            if let Some(links) = self.root.get_mut(section).and_then(|v| v.as_object_mut()) {
+                let mut entries: Vec<(String, Value)> = std::mem::take(links).into_iter().collect();
+                entries.sort_by(|(a, _), (b, _)| compare_links(a, b));
+                links.extend(entries);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a package link from a section, matching its name case-insensitively.
+    ///
+    /// Unlike [`JsonEditor::remove`] the section is kept when it becomes empty, as Composer does.
+    pub fn remove_link(&mut self, section: &str, name: &str) -> Option<Value> {
+        let links = self.root.get_mut(section)?.as_object_mut()?;
+        let key = links.keys().find(|k| k.eq_ignore_ascii_case(name))?.clone();
+        links.shift_remove(&key)
+    }
+
+    /// Serialize the document with the detected indentation
+    pub fn to_json_string(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(self.indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        self.root.serialize(&mut serializer)?;
+
+        let mut content = String::from_utf8(buf).map_err(|e| ComposerError::InvalidManifest {
+            message: e.to_string(),
+        })?;
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    /// Write the document to disk, creating parent directories if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        fs::write(path, self.to_json_string()?)?;
+        Ok(())
+    }
+}
+
+fn not_an_object(path: &[&str]) -> ComposerError {
+    ComposerError::InvalidManifest {
+        message: format!("\"{}\" is not an object", path.join(".")),
+    }
+}
+
+/// Order of package links with `config.sort-packages`, matching Composer's `JsonManipulator`.
+///
+/// Platform packages come first, grouped as `php*`, `hhvm*`, `ext-*`, `lib-*` and the
+/// remaining ones such as `composer-runtime-api`, followed by all other packages. Within
+/// a group names are compared case-insensitively in natural order, so `ext-a2` sorts
+/// before `ext-a10`.
+fn compare_links(a: &str, b: &str) -> Ordering {
+    fn sort_key(name: &str) -> String {
+        let name = name.to_lowercase();
+        let group = if !is_platform_package(&name) {
+            '5'
+        } else if name.starts_with("php") {
+            '0'
+        } else if name.starts_with("hhvm") {
+            '1'
+        } else if name.starts_with("ext") {
+            '2'
+        } else if name.starts_with("lib") {
+            '3'
+        } else {
+            '4'
+        };
+        format!("{}-{}", group, name)
+    }
+
+    natural_cmp(&sort_key(a), &sort_key(b))
+}
+
+/// Compare strings like PHP's `strnatcmp`, treating runs of digits as numbers
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                        digits.push(c);
+                    }
+                    digits.trim_start_matches('0').to_string()
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Detect the indentation unit from the first indented line of a document
+fn detect_indent(content: &str) -> Option<String> {
+    content
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect::<String>()
+        })
+        .filter(|indent| !indent.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_indent() {
+        assert_eq!(detect_indent("{\n  \"a\": 1\n}"), Some("  ".to_string()));
+        assert_eq!(detect_indent("{\n\t\"a\": 1\n}"), Some("\t".to_string()));
+        assert_eq!(detect_indent("{\"a\": 1}"), None);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_order_and_indent() {
+        let content =
+            "{\n  \"name\": \"vendor/pkg\",\n  \"require\": {\n    \"php\": \">=8.1\"\n  }\n}\n";
+        let editor = JsonEditor::parse(content).unwrap();
+        assert_eq!(editor.to_json_string().unwrap(), content);
+    }
+
+    #[test]
+    fn test_missing_trailing_newline_is_kept() {
+        let content = "{\n    \"name\": \"vendor/pkg\"\n}";
+        let editor = JsonEditor::parse(content).unwrap();
+        assert_eq!(editor.to_json_string().unwrap(), content);
+    }
+
+    #[test]
+    fn test_set_nested_creates_objects() {
+        let mut editor = JsonEditor::parse("{\n    \"name\": \"vendor/pkg\"\n}\n").unwrap();
+        editor
+            .set(&["config", "platform", "php"], json!("8.2.0"))
+            .unwrap();
+
+        assert_eq!(
+            editor.get(&["config", "platform", "php"]),
+            Some(&json!("8.2.0"))
+        );
+        assert_eq!(
+            editor.to_json_string().unwrap(),
+            "{\n    \"name\": \"vendor/pkg\",\n    \"config\": {\n        \"platform\": {\n            \"php\": \"8.2.0\"\n        }\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_set_keeps_existing_key_position() {
+        let mut editor = JsonEditor::parse(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+        editor.set(&["b"], json!(20)).unwrap();
+
+        let keys: Vec<&String> = editor.root().as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_set_through_scalar_fails() {
+        let mut editor = JsonEditor::parse(r#"{"config": "oops"}"#).unwrap();
+        assert!(editor.set(&["config", "vendor-dir"], json!("lib")).is_err());
+    }
+
+    #[test]
+    fn test_set_replaces_empty_array_with_object() {
+        let mut editor = JsonEditor::parse(r#"{"extra": []}"#).unwrap();
+        editor.set(&["extra", "foo"], json!("bar")).unwrap();
+        assert_eq!(editor.get(&["extra", "foo"]), Some(&json!("bar")));
+    }
+
+    #[test]
+    fn test_remove_prunes_empty_parents() {
+        let mut editor =
+            JsonEditor::parse(r#"{"name": "vendor/pkg", "config": {"platform": {"php": "8.1"}}}"#)
+                .unwrap();
+
+        assert_eq!(
+            editor.remove(&["config", "platform", "php"]),
+            Some(json!("8.1"))
+        );
+        assert!(editor.get(&["config"]).is_none());
+        assert!(editor.get(&["name"]).is_some());
+    }
+
+    #[test]
+    fn test_remove_keeps_non_empty_parents() {
+        let mut editor =
+            JsonEditor::parse(r#"{"config": {"vendor-dir": "lib", "sort-packages": true}}"#)
+                .unwrap();
+
+        editor.remove(&["config", "sort-packages"]);
+        assert_eq!(editor.get(&["config", "vendor-dir"]), Some(&json!("lib")));
+        assert!(editor.remove(&["config", "missing"]).is_none());
+    }
+
+    #[test]
+    fn test_non_object_root_is_rejected() {
+        assert!(JsonEditor::parse("[1, 2]").is_err());
+        assert!(JsonEditor::parse("").is_ok());
+    }
+
+    #[test]
+    fn test_add_link_appends_or_updates_in_place() {
+        let mut editor =
+            JsonEditor::parse(r#"{"require": {"symfony/console": "^6.0", "Acme/Lib": "^1.0"}}"#).unwrap();
+        editor.add_link("require", "psr/log", "^3.0", false).unwrap();
+        editor.add_link("require", "acme/lib", "^2.0", false).unwrap();
+
+        let keys: Vec<&String> = editor.get(&["require"]).unwrap().as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["symfony/console", "Acme/Lib", "psr/log"]);
+        assert_eq!(editor.get(&["require", "Acme/Lib"]), Some(&json!("^2.0")));
+
+        editor.add_link("require-dev", "phpunit/phpunit", "^11.0", false).unwrap();
+        assert_eq!(editor.get(&["require-dev", "phpunit/phpunit"]), Some(&json!("^11.0")));
+    }
+
+    #[test]
+    fn test_add_link_sorts_packages() {
+        let mut editor = JsonEditor::parse(
+            r#"{"require": {"symfony/console": "^6.0", "ext-json": "*", "php": ">=8.1", "composer-runtime-api": "^2.2"}}"#,
+        )
+        .unwrap();
+        editor.add_link("require", "Monolog/Monolog", "^3.0", true).unwrap();
+        editor.add_link("require", "ext-ctype", "*", true).unwrap();
+        editor.add_link("require", "lib-pcre", "*", true).unwrap();
+
+        let keys: Vec<&String> = editor.get(&["require"]).unwrap().as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            vec!["php", "ext-ctype", "ext-json", "lib-pcre", "composer-runtime-api", "Monolog/Monolog", "symfony/console"]
+        );
+    }
+
+    #[test]
+    fn test_remove_link_keeps_section() {
+        let mut editor = JsonEditor::parse(r#"{"require": {"Psr/Log": "^3.0"}}"#).unwrap();
+        assert_eq!(editor.remove_link("require", "psr/log"), Some(json!("^3.0")));
+        assert_eq!(editor.get(&["require"]), Some(&json!({})));
+        assert!(editor.remove_link("require-dev", "psr/log").is_none());
+    }
+
+    #[test]
+    fn test_compare_links_natural_order() {
+        assert_eq!(compare_links("ext-a2", "ext-a10"), Ordering::Less);
+        assert_eq!(compare_links("acme/lib", "ACME/lib"), Ordering::Equal);
+        assert_eq!(compare_links("php-64bit", "ext-json"), Ordering::Less);
+        assert_eq!(compare_links("zend/lib", "composer-plugin-api"), Ordering::Greater);
+    }
+}
@@ -0,0 +1,279 @@
+//! Typed access to well-known keys of a package's `extra` section.
+//!
+//! Plugins and installers are configured through `extra`, which composer.json leaves
+//! untyped. Each key is parsed into its own struct here. A key with an unexpected shape
+//! is treated like a missing one and logged at debug level, so a broken block only
+//! disables the feature it configures instead of failing the whole command.
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+use super::ComposerJson;
+
+/// Parse `extra[key]` into `T`, or `None` if the key is missing or malformed
+pub fn extra_section<T: DeserializeOwned>(extra: &serde_json::Value, key: &str) -> Option<T> {
+    let value = extra.get(key)?;
+    match T::deserialize(value) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            log::debug!("Ignoring malformed extra.{}: {}", key, e);
+            None
+        }
+    }
+}
+
+/// A single string or a list of strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl From<StringOrList> for Vec<String> {
+    fn from(value: StringOrList) -> Self {
+        match value {
+            StringOrList::Single(s) => vec![s],
+            StringOrList::Multiple(list) => list,
+        }
+    }
+}
+
+fn deserialize_string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    StringOrList::deserialize(deserializer).map(Vec::from)
+}
+
+/// Parse `extra.installer-paths` into path templates and their selectors
+pub(crate) fn installer_paths(extra: &serde_json::Value) -> Option<IndexMap<String, Vec<String>>> {
+    let paths: IndexMap<String, StringOrList> = extra_section(extra, "installer-paths")?;
+    Some(paths.into_iter().map(|(path, selectors)| (path, selectors.into())).collect())
+}
+
+/// Configuration for the bin plugin from composer.json extra.bamarni-bin
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BinConfig {
+    /// Whether to create bin links in the main vendor/bin directory
+    pub bin_links: bool,
+    /// Target directory for bin namespaces (default: vendor-bin)
+    pub target_directory: String,
+    /// Whether to forward install/update commands to all namespaces
+    pub forward_command: bool,
+}
+
+impl Default for BinConfig {
+    fn default() -> Self {
+        Self {
+            bin_links: false,  // Default to false in 2.x behavior
+            target_directory: "vendor-bin".to_string(),
+            forward_command: false,
+        }
+    }
+}
+
+impl BinConfig {
+    /// Parse config from composer.json extra field, falling back to the defaults
+    pub fn from_extra(extra: &serde_json::Value) -> Self {
+        extra_section(extra, "bamarni-bin").unwrap_or_default()
+    }
+}
+
+/// Configuration for the merge plugin from composer.json extra.merge-plugin
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MergeConfig {
+    /// Glob patterns of files to merge, relative to the including file
+    #[serde(deserialize_with = "deserialize_string_or_list")]
+    pub include: Vec<String>,
+    /// Like `include`, but each pattern must match at least one file
+    #[serde(deserialize_with = "deserialize_string_or_list")]
+    pub require: Vec<String>,
+    /// Whether included files may declare further includes
+    pub recurse: bool,
+    /// Whether `require-dev` and `autoload-dev` are merged
+    pub merge_dev: bool,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            require: Vec::new(),
+            recurse: true,
+            merge_dev: true,
+        }
+    }
+}
+
+/// Symfony Flex settings from composer.json extra.symfony
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SymfonyConfig {
+    /// Constraint all `symfony/*` packages are restricted to, e.g. `7.1.*`
+    pub require: Option<String>,
+    /// Whether recipes from the contrib repository may be installed
+    pub allow_contrib: bool,
+    /// Whether recipes may add Docker configuration
+    pub docker: Option<bool>,
+    /// Recipe endpoints to use instead of the default ones
+    #[serde(deserialize_with = "deserialize_string_or_list")]
+    pub endpoint: Vec<String>,
+    /// Directory the Symfony application lives in, relative to composer.json
+    pub root_dir: Option<String>,
+}
+
+/// Symfony Runtime settings from composer.json extra.runtime
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeConfig {
+    /// Whether `vendor/autoload_runtime.php` is generated (`"runtime": false` disables it)
+    #[serde(skip, default = "enabled")]
+    pub enabled: bool,
+    /// Runtime class to instantiate
+    pub class: Option<String>,
+    /// Template to generate `autoload_runtime.php` from instead of the built-in one
+    pub autoload_template: Option<String>,
+    /// Every other key, passed to the runtime as options
+    #[serde(flatten)]
+    pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            class: None,
+            autoload_template: None,
+            options: serde_json::Map::new(),
+        }
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// extra.runtime is either a boolean toggle or the runtime options
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuntimeSetting {
+    Toggle(bool),
+    Options(RuntimeConfig),
+}
+
+impl ComposerJson {
+    /// Get extra.branch-alias as written, mapping source branches to alias versions
+    pub fn extra_branch_alias(&self) -> Option<IndexMap<String, String>> {
+        extra_section(&self.extra, "branch-alias")
+    }
+
+    /// Get extra.installer-paths, mapping path templates to package selectors
+    pub fn extra_installer_paths(&self) -> Option<IndexMap<String, Vec<String>>> {
+        installer_paths(&self.extra)
+    }
+
+    /// Get the bin plugin configuration from extra.bamarni-bin
+    pub fn extra_bamarni_bin(&self) -> Option<BinConfig> {
+        extra_section(&self.extra, "bamarni-bin")
+    }
+
+    /// Get the merge plugin configuration from extra.merge-plugin
+    pub fn extra_merge_plugin(&self) -> Option<MergeConfig> {
+        extra_section(&self.extra, "merge-plugin")
+    }
+
+    /// Get the Symfony Flex settings from extra.symfony
+    pub fn extra_symfony(&self) -> Option<SymfonyConfig> {
+        extra_section(&self.extra, "symfony")
+    }
+
+    /// Get the Symfony Runtime settings from extra.runtime
+    pub fn extra_runtime(&self) -> Option<RuntimeConfig> {
+        match extra_section(&self.extra, "runtime")? {
+            RuntimeSetting::Toggle(enabled) => Some(RuntimeConfig { enabled, ..Default::default() }),
+            RuntimeSetting::Options(config) => Some(config),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn composer_json(extra: serde_json::Value) -> ComposerJson {
+        ComposerJson { extra, ..Default::default() }
+    }
+
+    #[test]
+    fn test_plugin_sections() {
+        let json = composer_json(json!({
+            "branch-alias": {"dev-main": "2.x-dev"},
+            "installer-paths": {
+                "web/modules/{$name}": ["type:drupal-module"],
+                "web/core": "drupal/core"
+            },
+            "bamarni-bin": {"bin-links": true},
+            "merge-plugin": {"include": "composer.local.json", "merge-dev": false},
+            "symfony": {"require": "7.1.*", "allow-contrib": true, "endpoint": ["https://example.com"]}
+        }));
+
+        assert_eq!(json.extra_branch_alias().unwrap()["dev-main"], "2.x-dev");
+
+        let paths = json.extra_installer_paths().unwrap();
+        assert_eq!(paths["web/modules/{$name}"], vec!["type:drupal-module"]);
+        assert_eq!(paths["web/core"], vec!["drupal/core"]);
+
+        let bin = json.extra_bamarni_bin().unwrap();
+        assert!(bin.bin_links);
+        assert_eq!(bin.target_directory, "vendor-bin");
+
+        let merge = json.extra_merge_plugin().unwrap();
+        assert_eq!(merge.include, vec!["composer.local.json"]);
+        assert!(merge.recurse);
+        assert!(!merge.merge_dev);
+
+        let symfony = json.extra_symfony().unwrap();
+        assert_eq!(symfony.require.as_deref(), Some("7.1.*"));
+        assert!(symfony.allow_contrib);
+        assert_eq!(symfony.endpoint, vec!["https://example.com"]);
+
+        assert!(json.extra_runtime().is_none());
+    }
+
+    #[test]
+    fn test_runtime_section() {
+        let disabled = composer_json(json!({"runtime": false}));
+        assert!(!disabled.extra_runtime().unwrap().enabled);
+
+        let runtime = composer_json(json!({
+            "runtime": {"class": "App\\Runtime", "env_var_name": "APP_ENV"}
+        })).extra_runtime().unwrap();
+        assert!(runtime.enabled);
+        assert_eq!(runtime.class.as_deref(), Some("App\\Runtime"));
+        assert_eq!(runtime.options.keys().collect::<Vec<_>>(), vec!["env_var_name"]);
+    }
+
+    #[test]
+    fn test_malformed_sections_are_none() {
+        let json = composer_json(json!({
+            "branch-alias": ["dev-main"],
+            "installer-paths": {"web/core": 1},
+            "bamarni-bin": {"bin-links": "yes"},
+            "merge-plugin": "composer.local.json",
+            "symfony": {"allow-contrib": "true"},
+            "runtime": "App\\Runtime"
+        }));
+
+        assert!(json.extra_branch_alias().is_none());
+        assert!(json.extra_installer_paths().is_none());
+        assert!(json.extra_bamarni_bin().is_none());
+        assert!(json.extra_merge_plugin().is_none());
+        assert!(json.extra_symfony().is_none());
+        assert!(json.extra_runtime().is_none());
+        assert_eq!(BinConfig::from_extra(&json.extra).target_directory, "vendor-bin");
+    }
+}
@@ -76,7 +76,7 @@ pub fn validate_composer_json(json: &ComposerJson) -> Result<(), Vec<String>> {
 }
 
 /// Check if a package name is valid
-fn is_valid_package_name(name: &str) -> bool {
+pub(crate) fn is_valid_package_name(name: &str) -> bool {
     // Must contain exactly one /
     let parts: Vec<&str> = name.split('/').collect();
     if parts.len() != 2 {
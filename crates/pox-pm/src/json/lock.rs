@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use indexmap::IndexMap;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde::ser::SerializeSeq;
 
 /// Deserializes a HashMap that might be represented as an empty array in JSON.
 /// Composer outputs `[]` for empty maps like stability-flags, platform-dev, etc.
@@ -130,7 +131,7 @@ pub struct ComposerLock {
     pub minimum_stability: String,
 
     /// Per-package stability flags
-    #[serde(default, deserialize_with = "deserialize_map_or_empty_array")]
+    #[serde(default, deserialize_with = "deserialize_map_or_empty_array", serialize_with = "serialize_sorted_map")]
     pub stability_flags: HashMap<String, u8>,
 
     /// Whether to prefer stable versions
@@ -142,11 +143,11 @@ pub struct ComposerLock {
     pub prefer_lowest: bool,
 
     /// Platform requirements
-    #[serde(default, skip_serializing_if = "IndexMap::is_empty", deserialize_with = "deserialize_indexmap_or_empty_array")]
+    #[serde(default, deserialize_with = "deserialize_indexmap_or_empty_array", serialize_with = "serialize_map_or_empty_array")]
     pub platform: IndexMap<String, String>,
 
     /// Platform dev requirements
-    #[serde(default, skip_serializing_if = "IndexMap::is_empty", deserialize_with = "deserialize_indexmap_or_empty_array")]
+    #[serde(default, deserialize_with = "deserialize_indexmap_or_empty_array", serialize_with = "serialize_map_or_empty_array")]
     pub platform_dev: IndexMap<String, String>,
 
     /// Platform overrides from config
@@ -158,6 +159,28 @@ pub struct ComposerLock {
     pub plugin_api_version: String,
 }
 
+/// Write stability flags sorted by name, and as `[]` when there are none like Composer does
+fn serialize_sorted_map<S>(map: &HashMap<String, u8>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if map.is_empty() {
+        return serializer.serialize_seq(Some(0))?.end();
+    }
+    map.iter().collect::<std::collections::BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Write an empty map as `[]`, which is how PHP encodes an empty array
+fn serialize_map_or_empty_array<S>(map: &IndexMap<String, String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if map.is_empty() {
+        return serializer.serialize_seq(Some(0))?.end();
+    }
+    map.serialize(serializer)
+}
+
 fn default_readme() -> Vec<String> {
     vec![
         "This file locks the dependencies of your project to a known state".to_string(),
@@ -228,6 +251,10 @@ pub struct LockedPackage {
     #[serde(default, skip_serializing_if = "IndexMap::is_empty", deserialize_with = "deserialize_indexmap_or_empty_array")]
     pub suggest: IndexMap<String, String>,
 
+    /// Default branch flag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<bool>,
+
     /// Binary executables
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub bin: Vec<String>,
@@ -240,6 +267,10 @@ pub struct LockedPackage {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub extra: Option<serde_json::Value>,
 
+    /// Installation source preference (never written, like Composer)
+    #[serde(default, skip_serializing)]
+    pub installation_source: Option<String>,
+
     /// Autoload configuration
     #[serde(default, skip_serializing_if = "LockAutoload::is_empty")]
     pub autoload: LockAutoload,
@@ -252,6 +283,10 @@ pub struct LockedPackage {
     #[serde(default, rename = "notification-url", skip_serializing_if = "Option::is_none")]
     pub notification_url: Option<String>,
 
+    /// Archive exclusions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<LockArchive>,
+
     /// License(s)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub license: Vec<String>,
@@ -280,25 +315,13 @@ pub struct LockedPackage {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub funding: Vec<LockFunding>,
 
-    /// Release time
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub time: Option<String>,
-
     /// Whether this is an abandoned package
     #[serde(default, skip_serializing_if = "is_null_or_false")]
     pub abandoned: serde_json::Value,
 
-    /// Archive exclusions
+    /// Release time (always last, like Composer)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub archive: Option<LockArchive>,
-
-    /// Installation source preference
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub installation_source: Option<String>,
-
-    /// Default branch flag
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub default_branch: Option<bool>,
+    pub time: Option<String>,
 }
 
 fn is_null_or_false(v: &serde_json::Value) -> bool {
@@ -422,11 +445,11 @@ pub struct LockFunding {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockArchive {
     /// Name for the archive
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
     /// Paths to exclude from archives
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub exclude: Vec<String>,
 }
 
@@ -460,9 +483,42 @@ impl ComposerLock {
             .map_err(|e| LockLoadError::Parse(e))
     }
 
-    /// Serialize to JSON string
+    /// Serialize to JSON string, formatted the way Composer writes composer.lock
+    ///
+    /// Packages are sorted by name and version, their links and suggestions by key and
+    /// their keywords alphabetically, and the output is indented by four spaces. This keeps
+    /// diffs clean when Composer and pox write the same lock file.
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(self)
+        let mut lock = self.clone();
+        for packages in [&mut lock.packages, &mut lock.packages_dev] {
+            packages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
+            for package in packages.iter_mut() {
+                for links in [
+                    &mut package.require,
+                    &mut package.conflict,
+                    &mut package.provide,
+                    &mut package.replace,
+                    &mut package.require_dev,
+                    &mut package.suggest,
+                ] {
+                    links.sort_keys();
+                }
+                package.keywords.sort();
+            }
+        }
+
+        let mut out = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+        lock.serialize(&mut serializer)?;
+        String::from_utf8(out).map_err(serde::ser::Error::custom)
+    }
+
+    /// Write to a composer.lock file, with the trailing newline Composer adds
+    pub fn write(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let mut content = self.to_json()?;
+        content.push('\n');
+        std::fs::write(path, content)
     }
 
     /// Get all packages (both prod and dev)
@@ -472,9 +528,51 @@ impl ComposerLock {
 
     /// Find a package by name
     pub fn find_package(&self, name: &str) -> Option<&LockedPackage> {
-        let name_lower = name.to_lowercase();
         self.all_packages()
-            .find(|p| p.name.to_lowercase() == name_lower)
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Find the root alias (`dev-main as 1.0.x-dev`) of a locked package, if any
+    pub fn find_alias(&self, name: &str) -> Option<&LockAlias> {
+        let package = self.find_package(name)?;
+        self.aliases.iter().find(|alias| {
+            alias.package.eq_ignore_ascii_case(name) && alias.version.eq_ignore_ascii_case(&package.version)
+        })
+    }
+
+    /// Get the version a requirement on a locked package is matched against
+    ///
+    /// This is the root alias when there is one, then the branch alias of a dev
+    /// version, and otherwise the locked version itself.
+    pub fn locked_version(&self, name: &str) -> Option<&str> {
+        if let Some(alias) = self.find_alias(name) {
+            return Some(&alias.alias);
+        }
+
+        let package = self.find_package(name)?;
+        let branch_alias = package.version.starts_with("dev-")
+            .then(|| package.extra.as_ref()?.get("branch-alias")?.get(&package.version)?.as_str())
+            .flatten();
+
+        Some(branch_alias.unwrap_or(&package.version))
+    }
+
+    /// Find the locked packages that provide or replace `name`, with the constraint they do so at
+    ///
+    /// A `self.version` constraint is resolved to the version of the providing package. With
+    /// `no_dev`, dev packages are left out.
+    pub fn providers_of(&self, name: &str, no_dev: bool) -> Vec<(&LockedPackage, String)> {
+        let dev_packages: &[LockedPackage] = if no_dev { &[] } else { &self.packages_dev };
+        self.packages.iter()
+            .chain(dev_packages)
+            .filter_map(|package| {
+                let (_, constraint) = package.provide.iter()
+                    .chain(package.replace.iter())
+                    .find(|(provided, _)| provided.eq_ignore_ascii_case(name))?;
+                let constraint = if constraint == "self.version" { &package.version } else { constraint };
+                Some((package, constraint.clone()))
+            })
+            .collect()
     }
 
     /// Check if a package is a dev dependency
@@ -627,6 +725,76 @@ mod tests {
         assert!(lock.is_dev_package("vendor/dev"));
     }
 
+    #[test]
+    fn test_find_package_case_variants() {
+        let json = r#"{
+            "content-hash": "abc",
+            "packages": [{"name": "monolog/monolog", "version": "3.5.0"}]
+        }"#;
+
+        let lock = ComposerLock::from_str(json).unwrap();
+
+        for name in ["monolog/monolog", "Monolog/Monolog", "MONOLOG/monolog"] {
+            assert_eq!(lock.find_package(name).map(|p| p.name.as_str()), Some("monolog/monolog"));
+            assert_eq!(lock.locked_version(name), Some("3.5.0"));
+        }
+        assert!(lock.find_alias("Monolog/Monolog").is_none());
+    }
+
+    #[test]
+    fn test_locked_version_aliases() {
+        let json = r#"{
+            "content-hash": "abc",
+            "packages": [
+                {"name": "vendor/root-aliased", "version": "dev-main"},
+                {"name": "vendor/branch-aliased", "version": "dev-master", "extra": {"branch-alias": {"dev-master": "2.1.x-dev"}}},
+                {"name": "vendor/unaliased", "version": "dev-feature"}
+            ],
+            "packages-dev": [{"name": "vendor/dev", "version": "1.0.0"}],
+            "aliases": [
+                {"package": "Vendor/Root-Aliased", "version": "dev-main", "alias": "1.4.0", "alias_normalized": "1.4.0.0"},
+                {"package": "vendor/dev", "version": "dev-other", "alias": "9.0.0", "alias_normalized": "9.0.0.0"}
+            ]
+        }"#;
+
+        let lock = ComposerLock::from_str(json).unwrap();
+
+        assert_eq!(lock.find_alias("vendor/root-aliased").map(|a| a.alias.as_str()), Some("1.4.0"));
+        assert_eq!(lock.locked_version("VENDOR/ROOT-ALIASED"), Some("1.4.0"));
+        assert_eq!(lock.locked_version("vendor/branch-aliased"), Some("2.1.x-dev"));
+        assert_eq!(lock.locked_version("vendor/unaliased"), Some("dev-feature"));
+
+        // An alias of a version that is not the locked one does not apply
+        assert!(lock.find_alias("vendor/dev").is_none());
+        assert_eq!(lock.locked_version("vendor/dev"), Some("1.0.0"));
+        assert_eq!(lock.locked_version("vendor/missing"), None);
+    }
+
+    #[test]
+    fn test_providers_of() {
+        let json = r#"{
+            "content-hash": "abc",
+            "packages": [
+                {"name": "symfony/polyfill-mbstring", "version": "v1.29.0", "provide": {"ext-mbstring": "*"}},
+                {"name": "acme/intl", "version": "2.0.0", "replace": {"EXT-INTL": "self.version"}}
+            ],
+            "packages-dev": [{"name": "acme/dev-polyfill", "version": "1.0.0", "provide": {"ext-mbstring": "^8.0"}}]
+        }"#;
+
+        let lock = ComposerLock::from_str(json).unwrap();
+        let providers = |name: &str, no_dev: bool| -> Vec<(String, String)> {
+            lock.providers_of(name, no_dev).into_iter().map(|(p, c)| (p.name.clone(), c)).collect()
+        };
+
+        assert_eq!(providers("ext-mbstring", false), vec![
+            ("symfony/polyfill-mbstring".to_string(), "*".to_string()),
+            ("acme/dev-polyfill".to_string(), "^8.0".to_string()),
+        ]);
+        assert_eq!(providers("ext-mbstring", true).len(), 1);
+        assert_eq!(providers("ext-intl", true), vec![("acme/intl".to_string(), "2.0.0".to_string())]);
+        assert!(providers("ext-redis", false).is_empty());
+    }
+
     #[test]
     fn test_abandoned_package() {
         let json = r#"{
@@ -704,4 +872,90 @@ mod tests {
         assert!(pkg.replace.is_empty());
         assert!(pkg.suggest.is_empty());
     }
+
+    #[test]
+    fn test_to_json_matches_composer_format() {
+        let json = r#"{
+            "content-hash": "abc",
+            "packages": [
+                {
+                    "name": "vendor/b",
+                    "version": "1.0.0",
+                    "time": "2024-01-01T00:00:00+00:00",
+                    "type": "library",
+                    "keywords": ["z", "a"],
+                    "require": {"vendor/a": "^1.0", "php": ">=8.1"},
+                    "installation-source": "dist",
+                    "dist": {"type": "zip", "url": "https://example.com/b.zip", "reference": "abc"},
+                    "default-branch": true
+                },
+                {"name": "vendor/a", "version": "1.0.0"}
+            ],
+            "packages-dev": [],
+            "stability-flags": [],
+            "platform": []
+        }"#;
+
+        let lock = ComposerLock::from_str(json).unwrap();
+        let expected = r#"{
+    "_readme": [
+        "This file locks the dependencies of your project to a known state",
+        "Read more about it at https://getcomposer.org/doc/01-basic-usage.md#installing-dependencies",
+        "This file is @generated automatically"
+    ],
+    "content-hash": "abc",
+    "packages": [
+        {
+            "name": "vendor/a",
+            "version": "1.0.0",
+            "type": "library"
+        },
+        {
+            "name": "vendor/b",
+            "version": "1.0.0",
+            "dist": {
+                "type": "zip",
+                "url": "https://example.com/b.zip",
+                "reference": "abc",
+                "shasum": ""
+            },
+            "require": {
+                "php": ">=8.1",
+                "vendor/a": "^1.0"
+            },
+            "default-branch": true,
+            "type": "library",
+            "keywords": [
+                "a",
+                "z"
+            ],
+            "time": "2024-01-01T00:00:00+00:00"
+        }
+    ],
+    "packages-dev": [],
+    "aliases": [],
+    "minimum-stability": "",
+    "stability-flags": [],
+    "prefer-stable": false,
+    "prefer-lowest": false,
+    "platform": [],
+    "platform-dev": []
+}"#;
+        assert_eq!(lock.to_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_write_adds_trailing_newline() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("composer.lock");
+        let lock = ComposerLock {
+            plugin_api_version: "2.6.0".to_string(),
+            ..Default::default()
+        };
+
+        lock.write(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with("\"plugin-api-version\": \"2.6.0\"\n}\n"));
+        assert_eq!(ComposerLock::from_str(&content).unwrap().plugin_api_version, "2.6.0");
+    }
 }
@@ -1,7 +1,13 @@
 mod schema;
 mod loader;
 mod lock;
+mod editor;
+mod extra;
+mod validate;
 
 pub use schema::*;
 pub use loader::*;
 pub use lock::*;
+pub use editor::JsonEditor;
+pub use extra::*;
+pub use validate::*;
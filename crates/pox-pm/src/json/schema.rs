@@ -347,42 +347,102 @@ pub enum Repository {
         url: String,
         #[serde(default, skip_serializing_if = "RepositoryOptions::is_empty")]
         options: RepositoryOptions,
+        /// Require metadata and dist downloads of the repository to be signed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<RepositorySignature>,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     Vcs {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     Git {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     #[serde(rename = "github")]
     GitHub {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     #[serde(rename = "gitlab")]
     GitLab {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     #[serde(rename = "bitbucket")]
     Bitbucket {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     Path {
         url: String,
         #[serde(default, skip_serializing_if = "PathRepositoryOptions::is_empty")]
         options: PathRepositoryOptions,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     Artifact {
         url: String,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     Package {
         /// Package can be a single object or an array of package objects
         package: serde_json::Value,
+        #[serde(flatten)]
+        filter: RepositoryFilter,
     },
     /// Disable a repository by name
     #[serde(untagged)]
     Disabled(bool),
 }
 
+impl Repository {
+    /// The package filter of the repository, `None` for disabled repositories
+    pub fn filter(&self) -> Option<&RepositoryFilter> {
+        match self {
+            Repository::Composer { filter, .. }
+            | Repository::Vcs { filter, .. }
+            | Repository::Git { filter, .. }
+            | Repository::GitHub { filter, .. }
+            | Repository::GitLab { filter, .. }
+            | Repository::Bitbucket { filter, .. }
+            | Repository::Path { filter, .. }
+            | Repository::Artifact { filter, .. }
+            | Repository::Package { filter, .. } => Some(filter),
+            Repository::Disabled(_) => None,
+        }
+    }
+}
+
+/// Which packages a repository provides, from its `only`, `exclude` and `canonical` keys
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct RepositoryFilter {
+    /// Package name patterns (`*` as wildcard) the repository is limited to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Package name patterns (`*` as wildcard) the repository never provides
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Whether a package found in this repository is not looked up in lower priority
+    /// repositories (the default). A non-canonical repository only adds versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<bool>,
+}
+
+impl RepositoryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.exclude.is_empty() && self.canonical.is_none()
+    }
+}
+
 /// Repository options
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RepositoryOptions {
@@ -396,6 +456,20 @@ impl RepositoryOptions {
     }
 }
 
+/// Signature verification of a composer repository
+///
+/// Metadata and dist downloads from the repository's origin must have an Ed25519 signature
+/// at their URL plus `suffix`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositorySignature {
+    /// Base64 encoded Ed25519 public key
+    #[serde(rename = "public-key")]
+    pub public_key: String,
+    /// Appended to a file URL to get its signature URL, `.sig` by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+}
+
 /// SSL options
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SslOptions {
@@ -0,0 +1,293 @@
+//! Validation of composer.json with structured diagnostics.
+//!
+//! Each problem carries a stable code and the JSON Pointer (RFC 6901) of the value it is
+//! about, so tools can annotate the offending line. Errors make the file invalid, warnings
+//! point at things that work but are likely mistakes or block publishing.
+
+use std::path::Path;
+
+use pox_semver::VersionParser;
+use pox_spdx::SpdxLicenses;
+use serde::Serialize;
+
+use super::loader::is_valid_package_name;
+use super::lock::ComposerLock;
+use super::schema::{Autoload, ComposerJson};
+use crate::util::{compute_content_hash, is_platform_package};
+
+/// Version far above any real release, allowed only by constraints without an upper bound
+const UNBOUNDED_PROBE: &str = "99999.0.0";
+
+/// One problem found in composer.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Stable identifier of the check, e.g. `invalid-constraint`
+    pub code: &'static str,
+    pub message: String,
+    /// JSON Pointer to the value, empty for the whole document
+    pub path: String,
+}
+
+impl Diagnostic {
+    fn new(code: &'static str, path: &[&str], message: String) -> Self {
+        Self { code, message, path: json_pointer(path) }
+    }
+}
+
+/// Everything [`validate_manifest`] found
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+    /// Whether the package can be published, which needs a name and description
+    pub publishable: bool,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Report an error if the lock file was not generated from this composer.json
+    pub fn check_lock(&mut self, content: &str, lock: &ComposerLock) {
+        if lock.content_hash != compute_content_hash(content) {
+            self.errors.push(Diagnostic::new(
+                "lock-outdated",
+                &[],
+                "The lock file is not up to date with the latest changes in composer.json, run `update --lock` to fix it".to_string(),
+            ));
+        }
+    }
+}
+
+/// Validate the content of a composer.json file
+///
+/// With `base_dir`, autoload paths are checked to exist relative to it.
+pub fn validate_manifest(content: &str, base_dir: Option<&Path>) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let json: ComposerJson = match serde_json::from_str(content) {
+        Ok(json) => json,
+        Err(e) => {
+            let code = if e.is_data() { "invalid-schema" } else { "invalid-json" };
+            report.errors.push(Diagnostic::new(code, &[], format!("composer.json could not be parsed: {}", e)));
+            return report;
+        }
+    };
+
+    check_metadata(&json, &mut report);
+    check_links(&json, &mut report);
+    for (key, autoload) in [("autoload", &json.autoload), ("autoload-dev", &json.autoload_dev)] {
+        check_autoload(key, autoload, base_dir, &mut report);
+    }
+
+    report.publishable = report.errors.is_empty() && json.name.is_some() && json.description.is_some();
+    report
+}
+
+fn check_metadata(json: &ComposerJson, report: &mut ValidationReport) {
+    match &json.name {
+        Some(name) if !is_valid_package_name(name) => report.errors.push(Diagnostic::new(
+            "invalid-name",
+            &["name"],
+            format!("Invalid package name \"{}\", it must be lowercase and have a vendor/package format", name),
+        )),
+        Some(_) => {}
+        None => report.warnings.push(Diagnostic::new(
+            "missing-name",
+            &["name"],
+            "No name property found, it is required to publish the package".to_string(),
+        )),
+    }
+
+    if json.description.is_none() {
+        report.warnings.push(Diagnostic::new(
+            "missing-description",
+            &["description"],
+            "No description property found, it is required to publish the package".to_string(),
+        ));
+    }
+
+    if let Some(version) = &json.version {
+        if VersionParser::new().normalize(version).is_err() {
+            report.errors.push(Diagnostic::new(
+                "invalid-version",
+                &["version"],
+                format!("Invalid version \"{}\"", version),
+            ));
+        } else {
+            report.warnings.push(Diagnostic::new(
+                "version-field",
+                &["version"],
+                "The version field is present, it is recommended to leave it out if the package is published on Packagist".to_string(),
+            ));
+        }
+    }
+
+    if let Some(stability) = &json.minimum_stability {
+        if !["dev", "alpha", "beta", "rc", "stable"].contains(&stability.to_lowercase().as_str()) {
+            report.errors.push(Diagnostic::new(
+                "invalid-minimum-stability",
+                &["minimum-stability"],
+                format!("Invalid minimum-stability \"{}\", it must be one of dev, alpha, beta, rc or stable", stability),
+            ));
+        }
+    }
+
+    let licenses = json.license.as_vec();
+    if licenses.is_empty() {
+        report.warnings.push(Diagnostic::new(
+            "missing-license",
+            &["license"],
+            "No license specified, it is recommended to do so. For closed-source software you may use \"proprietary\" as license".to_string(),
+        ));
+    }
+    let spdx = SpdxLicenses::new();
+    for (index, license) in licenses.iter().enumerate() {
+        if license != "proprietary" && !spdx.validate(license) {
+            let index = index.to_string();
+            let path: Vec<&str> = if licenses.len() == 1 { vec!["license"] } else { vec!["license", &index] };
+            report.warnings.push(Diagnostic::new(
+                "invalid-license",
+                &path,
+                format!("License \"{}\" is not a valid SPDX license identifier, see https://spdx.org/licenses/", license),
+            ));
+        }
+    }
+}
+
+fn check_links(json: &ComposerJson, report: &mut ValidationReport) {
+    let parser = VersionParser::new();
+    let sections = [
+        ("require", &json.require),
+        ("require-dev", &json.require_dev),
+        ("conflict", &json.conflict),
+        ("replace", &json.replace),
+        ("provide", &json.provide),
+    ];
+
+    for (section, links) in sections {
+        for (name, constraint) in links {
+            if constraint == "self.version" {
+                continue;
+            }
+            let Ok(parsed) = parser.parse_constraints_cached(constraint) else {
+                report.errors.push(Diagnostic::new(
+                    "invalid-constraint",
+                    &[section, name],
+                    format!("Invalid version constraint \"{}\" for {}", constraint, name),
+                ));
+                continue;
+            };
+
+            if section == "require" && !is_platform_package(&name.to_lowercase()) && parsed.satisfies(UNBOUNDED_PROBE) {
+                report.warnings.push(Diagnostic::new(
+                    "unbound-constraint",
+                    &[section, name],
+                    format!("Unbound version constraint \"{}\" for {} should be avoided", constraint, name),
+                ));
+            }
+        }
+    }
+
+    for name in json.require_dev.keys().filter(|name| json.require.contains_key(*name)) {
+        report.warnings.push(Diagnostic::new(
+            "require-overlap",
+            &["require-dev", name],
+            format!("{} is required both in require and require-dev, this can lead to unexpected behavior", name),
+        ));
+    }
+}
+
+fn check_autoload(key: &str, autoload: &Autoload, base_dir: Option<&Path>, report: &mut ValidationReport) {
+    for (standard, mappings) in [("psr-4", &autoload.psr4), ("psr-0", &autoload.psr0)] {
+        for (namespace, paths) in mappings {
+            if standard == "psr-4" && !namespace.is_empty() && !namespace.ends_with('\\') {
+                report.errors.push(Diagnostic::new(
+                    "invalid-psr4-prefix",
+                    &[key, standard, namespace],
+                    format!("A non-empty PSR-4 prefix must end with a namespace separator, \"{}\" should be \"{}\\\\\"", namespace, namespace),
+                ));
+            }
+
+            let Some(base_dir) = base_dir else { continue };
+            for path in paths.as_vec() {
+                if !base_dir.join(&path).exists() {
+                    report.warnings.push(Diagnostic::new(
+                        "missing-autoload-path",
+                        &[key, standard, namespace],
+                        format!("The {} path \"{}\" of \"{}\" does not exist", standard, path, namespace),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Build a JSON Pointer from unescaped segments
+fn json_pointer(segments: &[&str]) -> String {
+    segments.iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|diagnostic| diagnostic.code).collect()
+    }
+
+    #[test]
+    fn test_valid_manifest() {
+        let report = validate_manifest(r#"{
+            "name": "acme/lib",
+            "description": "A library",
+            "license": "MIT",
+            "require": {"php": ">=8.1", "acme/other": "^1.0"},
+            "autoload": {"psr-4": {"Acme\\Lib\\": "src/"}}
+        }"#, None);
+
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert!(report.publishable);
+    }
+
+    #[test]
+    fn test_diagnostics_carry_codes_and_paths() {
+        let report = validate_manifest(r#"{
+            "name": "Acme/Lib",
+            "license": "Not-A-License",
+            "require": {"acme/a": "not a constraint", "acme/b": ">=1.0"},
+            "require-dev": {"acme/b": "^1.0"},
+            "autoload": {"psr-4": {"Acme\\Lib": "src/"}}
+        }"#, None);
+
+        assert_eq!(codes(&report.errors), vec!["invalid-name", "invalid-constraint", "invalid-psr4-prefix"]);
+        assert_eq!(report.errors[1].path, "/require/acme~1a");
+        assert_eq!(report.errors[2].path, "/autoload/psr-4/Acme\\Lib");
+        assert_eq!(codes(&report.warnings), vec!["missing-description", "invalid-license", "unbound-constraint", "require-overlap"]);
+        assert_eq!(report.warnings[3].path, "/require-dev/acme~1b");
+        assert!(!report.publishable);
+    }
+
+    #[test]
+    fn test_parse_errors_and_lock() {
+        let report = validate_manifest("{\"name\": ", None);
+        assert_eq!(codes(&report.errors), vec!["invalid-json"]);
+
+        let report = validate_manifest(r#"{"require": ["acme/lib"]}"#, None);
+        assert_eq!(codes(&report.errors), vec!["invalid-schema"]);
+
+        let content = r#"{"name": "acme/lib", "description": "A library", "license": "MIT"}"#;
+        let mut report = validate_manifest(content, None);
+        let mut lock = ComposerLock { content_hash: compute_content_hash(content), ..Default::default() };
+        report.check_lock(content, &lock);
+        assert!(report.is_valid());
+
+        lock.content_hash = "outdated".to_string();
+        report.check_lock(content, &lock);
+        assert_eq!(codes(&report.errors), vec!["lock-outdated"]);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod archiver;
+pub mod audit;
 pub mod autoload;
 pub mod cache;
 pub mod composer;
@@ -6,11 +8,13 @@ pub mod dependency_graph;
 pub mod downloader;
 pub mod error;
 pub mod event;
+pub mod event_stream;
 pub mod http;
 pub mod installer;
 pub mod json;
 pub mod package;
 pub mod plugin;
+pub mod profile;
 pub mod repository;
 pub mod scripts;
 pub mod solver;
@@ -26,11 +30,12 @@ pub use installer::{InstallationManager, InstallConfig};
 pub use autoload::{AutoloadGenerator, AutoloadConfig};
 pub use plugin::{register_plugins, BinConfig};
 pub use composer::{Composer, ComposerBuilder};
-pub use dependency_graph::{get_dependents, find_packages_with_replacers_and_providers, DependencyResult};
+pub use dependency_graph::{get_dependents, get_blocking_dependents, find_packages_with_replacers_and_providers, DependencyResult};
 pub use event::{
     ComposerEvent, EventDispatcher, EventListener, EventType,
     PostAutoloadDumpEvent, PostInstallEvent, PostUpdateEvent,
     PreAutoloadDumpEvent, PreInstallEvent, PreUpdateEvent,
 };
-pub use util::{is_platform_package, compute_content_hash};
+pub use util::{is_platform_package, is_network_disabled, compute_content_hash};
 #[cfg(test)] mod test_content_hash;
+#[cfg(test)] mod test_server;
@@ -3,7 +3,7 @@
 use pox_semver::VersionParser;
 use indexmap::IndexMap;
 
-use super::{Autoload, AutoloadPath, Author, Dist, Funding, Package, Source, Support};
+use super::{parse_release_time, Autoload, AutoloadPath, Author, Dist, Funding, Package, Source, Support};
 use crate::json::{
     LockAutoload, LockAuthor, LockDist, LockFunding, LockSource, LockedPackage,
 };
@@ -76,9 +76,7 @@ impl From<&LockedPackage> for Package {
         }
 
         if let Some(ref time_str) = lp.time {
-            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(time_str) {
-                pkg.time = Some(dt.with_timezone(&chrono::Utc));
-            }
+            pkg.time = parse_release_time(time_str);
         }
 
         pkg
@@ -18,8 +18,8 @@ pub use alias::{
 pub use autoload::{Autoload, AutoloadPath};
 pub use link::{Link, LinkType};
 pub use package::{
-    package_type, Abandoned, ArchiveConfig, Author, Funding, Package, ScriptHandler, Scripts,
-    Stability, Support,
+    package_type, parse_release_time, Abandoned, ArchiveConfig, Author, Funding, Package,
+    ScriptHandler, Scripts, Stability, Support,
 };
-pub use root_version::{detect_root_version, get_git_branch, RootVersion, RootVersionSource};
+pub use root_version::{detect_root_version, get_git_branch, get_nearest_git_tag, RootVersion, RootVersionSource};
 pub use source::{Dist, Mirror, Source};
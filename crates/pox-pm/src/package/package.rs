@@ -1,6 +1,6 @@
 use super::{Autoload, Dist, Link, Source};
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 use indexmap::IndexMap;
 
 /// Package stability levels
@@ -281,7 +281,7 @@ pub struct Package {
     pub installation_source: Option<String>,
 
     /// Release date
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_release_time")]
     pub time: Option<DateTime<Utc>>,
 
     // CompletePackage fields
@@ -294,7 +294,7 @@ pub struct Package {
     pub homepage: Option<String>,
 
     /// License identifiers (SPDX format)
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[serde(skip_serializing_if = "Vec::is_empty", default, deserialize_with = "deserialize_license")]
     pub license: Vec<String>,
 
     /// Keywords
@@ -332,8 +332,19 @@ pub struct Package {
     /// Transport options for downloading
     #[serde(rename = "transport-options", skip_serializing_if = "Option::is_none")]
     pub transport_options: Option<serde_json::Value>,
+
+    /// composer.json fields not covered by the model, kept by
+    /// [`from_composer_json`](Package::from_composer_json)
+    #[serde(skip)]
+    pub unknown_fields: serde_json::Map<String, serde_json::Value>,
 }
 
+/// Name used for a composer.json without a `name` field, as in Composer
+const ROOT_PACKAGE_NAME: &str = "__root__";
+
+/// Version used for a composer.json without a `version` field, as in Composer
+const NO_VERSION_SET: &str = "1.0.0+no-version-set";
+
 /// Archive configuration
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct ArchiveConfig {
@@ -349,6 +360,41 @@ fn default_package_type() -> String {
     "library".to_string()
 }
 
+/// Parse the `time` of a package
+///
+/// Repositories write RFC 3339 (`2024-01-15T10:30:00+00:00`), older metadata and
+/// composer.json files also use `2024-01-15 10:30:00` and plain dates. Times without an
+/// offset are taken as UTC.
+pub fn parse_release_time(time: &str) -> Option<DateTime<Utc>> {
+    let time = time.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(time) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%z") {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(time, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(time, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+}
+
+/// A `time` that can't be parsed is left out rather than failing the whole package
+fn deserialize_release_time<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.as_deref().and_then(parse_release_time))
+}
+
+/// A `license` can be a single identifier as well as a list of them
+fn deserialize_license<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(crate::json::License::deserialize(deserializer)?.as_vec())
+}
+
 /// Package type constants
 pub mod package_type {
     /// Standard library package (default)
@@ -404,6 +450,78 @@ impl Package {
             archive: None,
             default_branch: None,
             transport_options: None,
+            unknown_fields: serde_json::Map::new(),
+        }
+    }
+
+    /// Load a package from the contents of a composer.json file
+    ///
+    /// Fields the model does not cover, or that would not be written back as they were
+    /// (like an empty `require` or a single `license` string), are kept in `unknown_fields`. A missing `name` or
+    /// `version` is filled in with Composer's `__root__` and `1.0.0+no-version-set`
+    /// placeholders, which [`to_composer_json`](Package::to_composer_json) leaves out again.
+    pub fn from_composer_json(json: &str) -> crate::Result<Self> {
+        let serde_json::Value::Object(mut fields) = serde_json::from_str(json)? else {
+            return Err(crate::ComposerError::InvalidManifest {
+                message: "composer.json must contain a JSON object".to_string(),
+            });
+        };
+
+        let original = fields.clone();
+        fields.entry("name").or_insert_with(|| ROOT_PACKAGE_NAME.into());
+        fields.entry("version").or_insert_with(|| NO_VERSION_SET.into());
+
+        let mut package: Package = serde_json::from_value(serde_json::Value::Object(fields))?;
+        let serde_json::Value::Object(known) = serde_json::to_value(&package)? else {
+            unreachable!("a package always serializes to an object");
+        };
+
+        package.unknown_fields = original
+            .into_iter()
+            .filter(|(key, value)| known.get(key) != Some(value))
+            .collect();
+
+        Ok(package)
+    }
+
+    /// Serialize the package as composer.json contents
+    ///
+    /// `type` is always written, even when it was left to default to `library`. Fields kept
+    /// by [`from_composer_json`](Package::from_composer_json) are written as they were read,
+    /// unless the model's value for them has changed since.
+    pub fn to_composer_json(&self) -> crate::Result<String> {
+        let serde_json::Value::Object(mut fields) = serde_json::to_value(self)? else {
+            unreachable!("a package always serializes to an object");
+        };
+
+        if fields.get("name").and_then(|v| v.as_str()) == Some(ROOT_PACKAGE_NAME) {
+            fields.remove("name");
+        }
+        if fields.get("version").and_then(|v| v.as_str()) == Some(NO_VERSION_SET) {
+            fields.remove("version");
+        }
+
+        for (key, value) in &self.unknown_fields {
+            if fields.get(key) == Self::loaded_field(key, value).as_ref() {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+
+        Ok(serde_json::to_string_pretty(&fields)?)
+    }
+
+    /// How the model writes back the composer.json field `key` when it was read as `value`,
+    /// or `None` when it leaves it out
+    fn loaded_field(key: &str, value: &serde_json::Value) -> Option<serde_json::Value> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("name".to_string(), ROOT_PACKAGE_NAME.into());
+        fields.insert("version".to_string(), NO_VERSION_SET.into());
+        fields.insert(key.to_string(), value.clone());
+
+        let package: Package = serde_json::from_value(serde_json::Value::Object(fields)).ok()?;
+        match serde_json::to_value(&package).ok()? {
+            serde_json::Value::Object(mut written) => written.remove(key),
+            _ => None,
         }
     }
 
@@ -486,6 +604,21 @@ impl Package {
         self.stability() == Stability::Dev
     }
 
+    /// Whether this is the default branch of its repository
+    pub fn is_default_branch(&self) -> bool {
+        self.default_branch.unwrap_or(false)
+    }
+
+    /// Order by release time, newest first, with packages without a time after all others
+    pub fn compare_release_time(&self, other: &Package) -> std::cmp::Ordering {
+        match (self.time, other.time) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+    }
+
     /// Returns true if the package is abandoned
     pub fn is_abandoned(&self) -> bool {
         self.abandoned.is_some()
@@ -628,6 +761,124 @@ impl std::fmt::Display for Package {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_release_time() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-15T10:30:00Z").unwrap().with_timezone(&Utc);
+        for time in [
+            "2024-01-15T10:30:00+00:00",
+            "2024-01-15T12:30:00+02:00",
+            "2024-01-15T10:30:00Z",
+            "2024-01-15 10:30:00",
+            "2024-01-15T10:30:00",
+            "2024-01-15 10:30:00+0000",
+        ] {
+            assert_eq!(parse_release_time(time), Some(expected), "{}", time);
+        }
+        assert_eq!(parse_release_time("2024-01-15").unwrap().to_rfc3339(), "2024-01-15T00:00:00+00:00");
+        assert_eq!(parse_release_time("last tuesday"), None);
+
+        let package: Package = serde_json::from_str(r#"{"name": "acme/lib", "version": "1.0.0", "time": "2024-01-15 10:30:00"}"#).unwrap();
+        assert_eq!(package.time, Some(expected));
+        let package: Package = serde_json::from_str(r#"{"name": "acme/lib", "version": "1.0.0", "time": "soon"}"#).unwrap();
+        assert_eq!(package.time, None);
+    }
+
+    #[test]
+    fn test_compare_release_time() {
+        let mut old = Package::new("acme/lib", "1.0.0");
+        old.time = parse_release_time("2023-01-01");
+        let mut new = Package::new("acme/lib", "2.0.0");
+        new.time = parse_release_time("2024-01-01");
+        let untimed = Package::new("acme/lib", "dev-main");
+
+        let mut packages = [&untimed, &old, &new];
+        packages.sort_by(|a, b| a.compare_release_time(b));
+        let versions: Vec<&str> = packages.iter().map(|p| p.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.0", "1.0.0", "dev-main"]);
+    }
+
+    #[test]
+    fn test_composer_json_round_trip() {
+        let json = r#"{
+            "name": "acme/framework",
+            "description": "A framework",
+            "type": "library",
+            "license": ["MIT"],
+            "require": {"php": ">=8.1", "psr/log": "^3.0"},
+            "require-dev": {"phpunit/phpunit": "^10.0"},
+            "provide": {"psr/log-implementation": "1.0|2.0|3.0"},
+            "replace": {"acme/support": "self.version"},
+            "conflict": {"acme/legacy": "<2.0"},
+            "autoload": {
+                "psr-4": {"Acme\\": ["src/", "lib/"], "Acme\\Support\\": "support/"},
+                "files": ["src/helpers.php"],
+                "exclude-from-classmap": ["/Tests/"]
+            },
+            "bin": ["bin/acme"],
+            "scripts": {"test": "phpunit", "check": ["@test", "phpstan"]},
+            "extra": {"branch-alias": {"dev-main": "2.x-dev"}, "laravel": {"providers": ["Acme\\Provider"]}},
+            "funding": [{"type": "github", "url": "https://github.com/acme"}, {"type": "patreon", "url": "https://patreon.com/acme"}],
+            "support": {"issues": "https://github.com/acme/framework/issues", "source": "https://github.com/acme/framework"},
+            "minimum-stability": "dev",
+            "prefer-stable": true,
+            "config": {"sort-packages": true},
+            "repositories": [{"type": "path", "url": "../packages/*"}],
+            "suggest": {}
+        }"#;
+
+        let package = Package::from_composer_json(json).unwrap();
+        assert_eq!(package.name, "acme/framework");
+        assert_eq!(package.replace["acme/support"], "self.version");
+        assert_eq!(package.funding.len(), 2);
+        assert_eq!(
+            package.unknown_fields.keys().collect::<Vec<_>>(),
+            vec!["minimum-stability", "prefer-stable", "config", "repositories", "suggest"]
+        );
+
+        let written = package.to_composer_json().unwrap();
+        let original: serde_json::Value = serde_json::from_str(json).unwrap();
+        let written: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(written, original);
+        assert_eq!(Package::from_composer_json(&written.to_string()).unwrap(), package);
+    }
+
+    #[test]
+    fn test_composer_json_keeps_differently_written_fields() {
+        let json = r#"{"name": "acme/lib", "license": "MIT", "require": {}}"#;
+        let mut package = Package::from_composer_json(json).unwrap();
+        assert_eq!(package.license, vec!["MIT".to_string()]);
+        assert_eq!(package.unknown_fields.keys().collect::<Vec<_>>(), vec!["license", "require"]);
+
+        let written: serde_json::Value = serde_json::from_str(&package.to_composer_json().unwrap()).unwrap();
+        assert_eq!(written["license"], "MIT");
+        assert_eq!(written["require"], serde_json::json!({}));
+
+        package.license = vec!["MIT".to_string(), "GPL-3.0-or-later".to_string()];
+        package.require.insert("php".to_string(), "^8.2".to_string());
+        let written: serde_json::Value = serde_json::from_str(&package.to_composer_json().unwrap()).unwrap();
+        assert_eq!(written["license"], serde_json::json!(["MIT", "GPL-3.0-or-later"]));
+        assert_eq!(written["require"], serde_json::json!({"php": "^8.2"}));
+    }
+
+    #[test]
+    fn test_composer_json_without_name_and_version() {
+        let package = Package::from_composer_json(r#"{"type": "project", "require": {"php": "^8.2"}}"#).unwrap();
+        assert_eq!(package.name, "__root__");
+        assert_eq!(package.version, "1.0.0+no-version-set");
+
+        let written: serde_json::Value = serde_json::from_str(&package.to_composer_json().unwrap()).unwrap();
+        assert_eq!(written, serde_json::json!({"type": "project", "require": {"php": "^8.2"}}));
+    }
+
+    #[test]
+    fn test_composer_json_must_be_object() {
+        assert!(matches!(
+            Package::from_composer_json("[]"),
+            Err(crate::ComposerError::InvalidManifest { .. })
+        ));
+        assert!(matches!(Package::from_composer_json("{"), Err(crate::ComposerError::JsonParse(_))));
+    }
+
     #[test]
     fn test_package_creation() {
         let package = Package::new("vendor/package", "1.0.0");
@@ -1,13 +1,14 @@
 //! Root package version detection.
 //!
 //! This module handles detecting the version of the root package (the project itself)
-//! based on git branch, branch-alias configuration, and environment variables.
+//! based on git tags, git branch, branch-alias configuration, and environment variables.
 //!
 //! The priority order is:
 //! 1. COMPOSER_ROOT_VERSION environment variable
 //! 2. Explicit version in composer.json
-//! 3. Branch alias matching the current git branch
-//! 4. Git branch name converted to a dev version
+//! 3. Version tag pointing at a clean HEAD
+//! 4. Branch alias matching the current git branch
+//! 5. Git branch name converted to a dev version
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -32,6 +33,8 @@ pub enum RootVersionSource {
     Environment,
     /// From explicit version field in composer.json
     ComposerJson,
+    /// From a version tag on HEAD
+    GitTag,
     /// From branch-alias matching the current git branch
     BranchAlias,
     /// From git branch name (converted to dev-* version)
@@ -45,6 +48,7 @@ impl std::fmt::Display for RootVersionSource {
         match self {
             RootVersionSource::Environment => write!(f, "COMPOSER_ROOT_VERSION env"),
             RootVersionSource::ComposerJson => write!(f, "composer.json version field"),
+            RootVersionSource::GitTag => write!(f, "git tag"),
             RootVersionSource::BranchAlias => write!(f, "branch-alias"),
             RootVersionSource::GitBranch => write!(f, "git branch"),
             RootVersionSource::Default => write!(f, "default"),
@@ -57,9 +61,13 @@ impl std::fmt::Display for RootVersionSource {
 /// Priority order:
 /// 1. COMPOSER_ROOT_VERSION environment variable
 /// 2. Explicit version in composer.json
-/// 3. Branch alias matching the current git branch
-/// 4. Git branch name converted to a dev version
-/// 5. Default "dev-main"
+/// 3. Version tag pointing at HEAD, unless the working tree is dirty
+/// 4. Branch alias matching the current git branch
+/// 5. Git branch name converted to a dev version
+/// 6. Default "dev-main"
+///
+/// In detached HEAD state the branch is one containing HEAD, so a checked out commit
+/// still reports `dev-<branch>`. A shallow clone without tags falls through to the branch.
 ///
 /// # Arguments
 /// * `working_dir` - The project root directory (where composer.json is)
@@ -69,9 +77,21 @@ pub fn detect_root_version(
     working_dir: &Path,
     composer_version: Option<&str>,
     branch_aliases: &HashMap<String, (String, String)>,
+) -> RootVersion {
+    let env_version = std::env::var("COMPOSER_ROOT_VERSION").ok();
+    detect_root_version_with_env(working_dir, env_version.as_deref(), composer_version, branch_aliases)
+}
+
+/// [`detect_root_version`] with the value of COMPOSER_ROOT_VERSION passed in, so tests
+/// don't depend on the process environment
+fn detect_root_version_with_env(
+    working_dir: &Path,
+    env_version: Option<&str>,
+    composer_version: Option<&str>,
+    branch_aliases: &HashMap<String, (String, String)>,
 ) -> RootVersion {
     // 1. Check COMPOSER_ROOT_VERSION environment variable
-    if let Ok(env_version) = std::env::var("COMPOSER_ROOT_VERSION") {
+    if let Some(env_version) = env_version {
         let env_version = env_version.trim();
         if !env_version.is_empty() {
             let (version, pretty_version) = normalize_version(env_version);
@@ -106,8 +126,19 @@ pub fn detect_root_version(
         }
     }
 
-    // 3. Try to get git branch and match against branch-alias
-    if let Some(branch) = get_git_branch(working_dir) {
+    // 3. Use the tag of the checked out commit
+    if let Some(tag) = get_git_tag_version(working_dir) {
+        let (version, pretty_version) = normalize_version(&tag);
+        log::debug!("Root version from git tag: {} (normalized: {})", tag, version);
+        return RootVersion {
+            version,
+            pretty_version,
+            source: RootVersionSource::GitTag,
+        };
+    }
+
+    // 4. Try to get git branch and match against branch-alias
+    if let Some(branch) = get_git_branch(working_dir).or_else(|| get_detached_branch(working_dir)) {
         log::debug!("Current git branch: {}", branch);
 
         // Normalize the branch to a dev version for matching
@@ -132,7 +163,7 @@ pub fn detect_root_version(
             };
         }
 
-        // 4. Use git branch as version
+        // 5. Use git branch as version
         let (version, pretty_version) = normalize_version(&dev_branch);
         log::debug!(
             "Root version from git branch: {} (normalized: {})",
@@ -146,7 +177,7 @@ pub fn detect_root_version(
         };
     }
 
-    // 5. Default fallback
+    // 6. Default fallback
     log::debug!("Root version defaulting to dev-main");
     RootVersion {
         version: "dev-main".to_string(),
@@ -185,6 +216,90 @@ pub fn get_git_branch(path: &Path) -> Option<String> {
     None
 }
 
+/// Gets the version tag pointing at HEAD, as reported by `git describe --tags`.
+///
+/// Returns None if HEAD is not tagged, the tag isn't a version, or the working tree has
+/// uncommitted changes, since the checkout then no longer is that release.
+fn get_git_tag_version(path: &Path) -> Option<String> {
+    if !path.join(".git").exists() {
+        return None;
+    }
+
+    let output = std::process::Command::new("git")
+        .args(["describe", "--tags", "--exact-match", "--dirty"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if tag.is_empty() || tag.ends_with("-dirty") || VersionParser::new().normalize(&tag).is_err() {
+        return None;
+    }
+    Some(tag)
+}
+
+/// Gets a local branch containing HEAD when HEAD is detached.
+///
+/// Prefers the default branch names, so a commit on both `main` and a feature branch
+/// reports `dev-main`.
+fn get_detached_branch(path: &Path) -> Option<String> {
+    if !path.join(".git").exists() {
+        return None;
+    }
+
+    let output = std::process::Command::new("git")
+        .args(["branch", "--contains", "HEAD", "--format=%(refname:short)"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The detached HEAD itself is listed as "(HEAD detached at ...)"
+    let branches: Vec<&str> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty() && !branch.starts_with('('))
+        .collect();
+
+    branches
+        .iter()
+        .find(|branch| ["main", "master", "trunk"].contains(branch))
+        .or_else(|| branches.first())
+        .map(|branch| branch.to_string())
+}
+
+/// Gets the nearest tag reachable from HEAD that is a valid version.
+///
+/// Returns None if not in a git repository or no version tag precedes HEAD. Tags that
+/// aren't versions are skipped, so a `nightly` tag doesn't hide the last release.
+pub fn get_nearest_git_tag(path: &Path) -> Option<String> {
+    if !path.join(".git").exists() {
+        return None;
+    }
+
+    let output = std::process::Command::new("git")
+        .args(["tag", "--merged", "HEAD", "--sort=-creatordate"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let parser = VersionParser::new();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|tag| !tag.is_empty() && parser.normalize(tag).is_ok())
+        .map(String::from)
+}
+
 /// Normalizes a branch name to a dev version string.
 ///
 /// Examples:
@@ -250,9 +365,7 @@ mod tests {
 
     #[test]
     fn test_detect_root_version_from_env() {
-        std::env::set_var("COMPOSER_ROOT_VERSION", "1.2.3");
-        let result = detect_root_version(Path::new("/nonexistent"), None, &HashMap::new());
-        std::env::remove_var("COMPOSER_ROOT_VERSION");
+        let result = detect_root_version_with_env(Path::new("/nonexistent"), Some("1.2.3"), Some("2.0.0"), &HashMap::new());
 
         assert_eq!(result.source, RootVersionSource::Environment);
         assert_eq!(result.pretty_version, "1.2.3");
@@ -260,7 +373,7 @@ mod tests {
 
     #[test]
     fn test_detect_root_version_from_composer_json() {
-        let result = detect_root_version(Path::new("/nonexistent"), Some("2.0.0"), &HashMap::new());
+        let result = detect_root_version_with_env(Path::new("/nonexistent"), None, Some("2.0.0"), &HashMap::new());
 
         assert_eq!(result.source, RootVersionSource::ComposerJson);
         assert_eq!(result.pretty_version, "2.0.0");
@@ -268,7 +381,7 @@ mod tests {
 
     #[test]
     fn test_detect_root_version_default() {
-        let result = detect_root_version(Path::new("/nonexistent"), None, &HashMap::new());
+        let result = detect_root_version_with_env(Path::new("/nonexistent"), None, None, &HashMap::new());
 
         assert_eq!(result.source, RootVersionSource::Default);
         assert_eq!(result.version, "dev-main");
@@ -284,6 +397,7 @@ mod tests {
             RootVersionSource::ComposerJson.to_string(),
             "composer.json version field"
         );
+        assert_eq!(RootVersionSource::GitTag.to_string(), "git tag");
         assert_eq!(RootVersionSource::BranchAlias.to_string(), "branch-alias");
         assert_eq!(RootVersionSource::GitBranch.to_string(), "git branch");
         assert_eq!(RootVersionSource::Default.to_string(), "default");
@@ -316,6 +430,73 @@ mod tests {
         assert_eq!(pretty, "6.7.x-dev");
     }
 
+    #[test]
+    fn test_get_nearest_git_tag() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.email=test@test.com", "-c", "user.name=Test", "-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+
+        assert_eq!(get_nearest_git_tag(temp.path()), None);
+
+        git(&["init"]);
+        git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+        assert_eq!(get_nearest_git_tag(temp.path()), None);
+
+        git(&["tag", "v1.2.0"]);
+        git(&["commit", "--allow-empty", "-m", "Next commit"]);
+        git(&["tag", "nightly"]);
+        assert_eq!(get_nearest_git_tag(temp.path()).as_deref(), Some("v1.2.0"));
+    }
+
+    #[test]
+    fn test_detect_root_version_from_git() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.email=test@test.com", "-c", "user.name=Test", "-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        let detect = || detect_root_version_with_env(temp.path(), None, None, &HashMap::new());
+
+        git(&["init", "-b", "main"]);
+        std::fs::write(temp.path().join("README.md"), "v1").unwrap();
+        git(&["add", "README.md"]);
+        git(&["commit", "-m", "Initial commit"]);
+        git(&["tag", "v1.2.0"]);
+
+        let result = detect();
+        assert_eq!(result.source, RootVersionSource::GitTag);
+        assert_eq!(result.pretty_version, "v1.2.0");
+        assert_eq!(result.version, "1.2.0.0");
+
+        // The version field still wins over the tag
+        let result = detect_root_version_with_env(temp.path(), None, Some("2.0.0"), &HashMap::new());
+        assert_eq!(result.source, RootVersionSource::ComposerJson);
+
+        // A dirty checkout of a tag is not that release
+        std::fs::write(temp.path().join("README.md"), "changed").unwrap();
+        git(&["checkout", "--detach"]);
+        let result = detect();
+        assert_eq!(result.source, RootVersionSource::GitBranch);
+        assert_eq!(result.pretty_version, "dev-main");
+
+        git(&["checkout", "--", "README.md"]);
+        git(&["checkout", "main"]);
+        git(&["commit", "--allow-empty", "-m", "Next commit"]);
+        let result = detect();
+        assert_eq!(result.source, RootVersionSource::GitBranch);
+        assert_eq!(result.pretty_version, "dev-main");
+    }
+
     #[test]
     fn test_numeric_branch_normalization() {
         // Test version-like branches (common in release branches)
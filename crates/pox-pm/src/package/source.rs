@@ -1,3 +1,7 @@
+use std::sync::OnceLock;
+
+use md5::{Digest, Md5};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -74,6 +78,17 @@ impl Source {
 
         urls
     }
+
+    /// Returns all URLs for `package_name`, with mirror URL templates expanded
+    ///
+    /// Mirrors are only supported for git and hg sources. See [`process_source_mirror_url`].
+    pub fn mirror_urls(&self, package_name: &str) -> Vec<String> {
+        let supports_mirrors = matches!(self.source_type.as_str(), "git" | "hg");
+
+        collect_urls(self.url.clone(), self.mirrors.as_deref().filter(|_| supports_mirrors), |template| {
+            process_source_mirror_url(template, package_name, &self.url, &self.source_type)
+        })
+    }
 }
 
 impl Default for Source {
@@ -195,6 +210,30 @@ impl Dist {
 
         urls
     }
+
+    /// Returns all URLs for a package version, with mirror URL templates expanded
+    ///
+    /// `version` is the normalized version. See [`process_dist_mirror_url`].
+    pub fn mirror_urls(&self, package_name: &str, version: &str, pretty_version: Option<&str>) -> Vec<String> {
+        let process = |template: &str| {
+            process_dist_mirror_url(
+                template,
+                package_name,
+                version,
+                self.reference.as_deref(),
+                &self.dist_type,
+                pretty_version,
+            )
+        };
+
+        let primary = if self.url.contains('%') {
+            process(&self.url)
+        } else {
+            self.url.clone()
+        };
+
+        collect_urls(primary, self.mirrors.as_deref(), process)
+    }
 }
 
 impl Default for Dist {
@@ -240,6 +279,96 @@ impl Mirror {
     }
 }
 
+/// Expand a dist mirror URL template
+///
+/// Replaces `%package%`, `%version%`, `%reference%`, `%type%` and `%prettyVersion%`. A
+/// reference that isn't a hex commit hash, or a version containing `/`, is replaced by its
+/// MD5 hash so the result stays a valid URL path.
+pub fn process_dist_mirror_url(
+    template: &str,
+    package_name: &str,
+    version: &str,
+    reference: Option<&str>,
+    dist_type: &str,
+    pretty_version: Option<&str>,
+) -> String {
+    let reference = match reference {
+        Some(r) if r.is_empty() || r == "%reference%" || r.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) => {
+            r.to_string()
+        }
+        Some(r) => md5_hex(r),
+        None => String::new(),
+    };
+    let version = if version.contains('/') {
+        md5_hex(version)
+    } else {
+        version.to_string()
+    };
+
+    let mut url = template
+        .replace("%package%", package_name)
+        .replace("%version%", &version)
+        .replace("%reference%", &reference)
+        .replace("%type%", dist_type);
+    if let Some(pretty_version) = pretty_version {
+        url = url.replace("%prettyVersion%", pretty_version);
+    }
+    url
+}
+
+/// Expand a git or hg source mirror URL template
+///
+/// Replaces `%package%`, `%type%` and `%normalizedUrl%`, where the normalized URL is
+/// `gh-vendor/repo` for GitHub, `bb-vendor/repo` for Bitbucket and the URL with every
+/// character outside `[a-z0-9_.-]` replaced by `-` otherwise.
+pub fn process_source_mirror_url(template: &str, package_name: &str, url: &str, source_type: &str) -> String {
+    static GITHUB: OnceLock<Regex> = OnceLock::new();
+    static BITBUCKET: OnceLock<Regex> = OnceLock::new();
+    static UNSAFE: OnceLock<Regex> = OnceLock::new();
+
+    let github = GITHUB.get_or_init(|| {
+        Regex::new(r"^(?:(?:https?|git)://github\.com/|git@github\.com:)([^/]+)/(.+?)(?:\.git)?$").unwrap()
+    });
+    let bitbucket = BITBUCKET.get_or_init(|| Regex::new(r"^https://bitbucket\.org/([^/]+)/(.+?)(?:\.git)?/?$").unwrap());
+
+    let normalized = if let Some(caps) = github.captures(url) {
+        format!("gh-{}/{}", &caps[1], &caps[2])
+    } else if let Some(caps) = bitbucket.captures(url) {
+        format!("bb-{}/{}", &caps[1], &caps[2])
+    } else {
+        let unsafe_chars = UNSAFE.get_or_init(|| Regex::new(r"(?i)[^a-z0-9_.-]").unwrap());
+        unsafe_chars.replace_all(url.trim_matches('/'), "-").into_owned()
+    };
+
+    template
+        .replace("%package%", package_name)
+        .replace("%normalizedUrl%", &normalized)
+        .replace("%type%", source_type)
+}
+
+/// Combine a primary URL with expanded mirror URLs, preferred mirrors first
+fn collect_urls(primary: String, mirrors: Option<&[Mirror]>, process: impl Fn(&str) -> String) -> Vec<String> {
+    let mut urls = vec![primary];
+
+    for mirror in mirrors.unwrap_or_default() {
+        let url = process(&mirror.url);
+        if urls.contains(&url) {
+            continue;
+        }
+        if mirror.preferred {
+            urls.insert(0, url);
+        } else {
+            urls.push(url);
+        }
+    }
+
+    urls
+}
+
+fn md5_hex(input: &str) -> String {
+    format!("{:x}", Md5::digest(input.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,4 +430,55 @@ mod tests {
         assert_eq!(urls[1], "https://example.com/package.zip");
         assert_eq!(urls[2], "https://mirror2.example.com/package.zip");
     }
+
+    #[test]
+    fn test_dist_mirror_urls_expand_templates() {
+        let dist = Dist::zip("https://example.com/package.zip")
+            .with_reference("0123abcd")
+            .with_mirrors(vec![
+                Mirror::preferred("https://mirror.example.com/%package%/%version%/%reference%.%type%"),
+                Mirror::fallback("https://backup.example.com/%package%/%prettyVersion%.zip"),
+            ]);
+
+        let urls = dist.mirror_urls("vendor/pkg", "1.2.0.0", Some("v1.2.0"));
+        assert_eq!(
+            urls,
+            vec![
+                "https://mirror.example.com/vendor/pkg/1.2.0.0/0123abcd.zip",
+                "https://example.com/package.zip",
+                "https://backup.example.com/vendor/pkg/v1.2.0.zip",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dist_mirror_urls_hash_unsafe_values() {
+        let url = process_dist_mirror_url("%version%/%reference%", "vendor/pkg", "dev-feature/x", Some("v1.0"), "zip", None);
+        assert_eq!(url, format!("{}/{}", md5_hex("dev-feature/x"), md5_hex("v1.0")));
+    }
+
+    #[test]
+    fn test_dist_mirror_urls_skip_duplicates() {
+        let dist = Dist::zip("https://example.com/vendor/pkg.zip")
+            .with_mirrors(vec![Mirror::preferred("https://example.com/%package%.zip")]);
+
+        assert_eq!(dist.mirror_urls("vendor/pkg", "1.0.0.0", None), vec!["https://example.com/vendor/pkg.zip"]);
+    }
+
+    #[test]
+    fn test_source_mirror_urls_normalize_url() {
+        let source = Source::git("https://github.com/vendor/repo.git", "abc123")
+            .with_mirrors(vec![Mirror::fallback("https://git.example.com/%normalizedUrl%.%type%")]);
+        assert_eq!(
+            source.mirror_urls("vendor/repo"),
+            vec!["https://github.com/vendor/repo.git", "https://git.example.com/gh-vendor/repo.git"]
+        );
+
+        let url = process_source_mirror_url("%normalizedUrl%", "vendor/repo", "https://git.example.org/repo/", "git");
+        assert_eq!(url, "https---git.example.org-repo");
+
+        let svn = Source::svn("https://svn.example.com/repo", "123")
+            .with_mirrors(vec![Mirror::fallback("https://mirror.example.com/%package%")]);
+        assert_eq!(svn.mirror_urls("vendor/repo"), vec!["https://svn.example.com/repo"]);
+    }
 }
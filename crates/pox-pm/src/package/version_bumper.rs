@@ -1,3 +1,4 @@
+use pox_semver::{ConstraintKind, VersionParser};
 use regex::Regex;
 
 pub fn bump_requirement(constraint: &str, installed_version: &str) -> String {
@@ -33,6 +34,7 @@ fn clean_version(version: &str) -> String {
     let version = version.strip_prefix('V').unwrap_or(version);
     let version = version.strip_suffix("-dev").unwrap_or(version);
     let version = version
+        .trim_end_matches(".x")
         .trim_end_matches(".0")
         .trim_end_matches(".9999999");
 
@@ -194,7 +196,11 @@ fn compute_replacement(old_constraint: &str, version: &str) -> String {
         ""
     };
 
-    if old.starts_with('~') {
+    let kind = VersionParser::new()
+        .parse_constraint_kind(old)
+        .unwrap_or(ConstraintKind::Exact);
+
+    if kind == ConstraintKind::Tilde {
         if old_dot_count >= 2 && !old.contains('*') && !old.contains('x') {
             let version_parts: Vec<&str> = clean_version.split('.').collect();
             let mut result_parts: Vec<&str> = version_parts.clone();
@@ -209,7 +215,7 @@ fn compute_replacement(old_constraint: &str, version: &str) -> String {
         return format!("^{}{}", clean_version, suffix);
     }
 
-    if old.starts_with('^') {
+    if kind == ConstraintKind::Caret {
         if old_dot_count >= 2 {
             let version_parts: Vec<&str> = clean_version.split('.').collect();
             let mut result_parts: Vec<&str> = version_parts.clone();
@@ -225,18 +231,10 @@ fn compute_replacement(old_constraint: &str, version: &str) -> String {
         return format!("^{}{}", clean_version, suffix);
     }
 
-    if old.starts_with(">=") {
+    if kind == ConstraintKind::Comparison || old == "*" {
         return format!(">={}{}", clean_version, suffix);
     }
 
-    if old == "*" {
-        return format!(">={}{}", clean_version, suffix);
-    }
-
-    if old.contains('*') || old.contains('x') || old.contains('X') {
-        return format!("^{}{}", clean_version, suffix);
-    }
-
     format!("^{}{}", clean_version, suffix)
 }
 
@@ -424,6 +422,12 @@ mod tests {
         assert_eq!(clean_version("1.0.0"), "1");
         assert_eq!(clean_version("1.2.3-dev"), "1.2.3");
         assert_eq!(clean_version("1.2.3.9999999-dev"), "1.2.3");
+        assert_eq!(clean_version("3.3.x-dev"), "3.3");
+    }
+
+    #[test]
+    fn test_upgrade_to_branch_alias() {
+        assert_eq!(bump_requirement("^3.2", "3.3.x-dev"), "^3.3");
     }
 
     #[test]
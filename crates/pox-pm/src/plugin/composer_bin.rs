@@ -17,50 +17,6 @@ use crate::Result;
 /// The package name that triggers this plugin.
 pub const PACKAGE_NAME: &str = "bamarni/composer-bin-plugin";
 
-/// Configuration for the bin plugin from composer.json extra.bamarni-bin
-#[derive(Debug, Clone)]
-pub struct BinConfig {
-    /// Whether to create bin links in the main vendor/bin directory
-    pub bin_links: bool,
-    /// Target directory for bin namespaces (default: vendor-bin)
-    pub target_directory: String,
-    /// Whether to forward install/update commands to all namespaces
-    pub forward_command: bool,
-}
-
-impl Default for BinConfig {
-    fn default() -> Self {
-        Self {
-            bin_links: false,  // Default to false in 2.x behavior
-            target_directory: "vendor-bin".to_string(),
-            forward_command: false,
-        }
-    }
-}
-
-impl BinConfig {
-    /// Parse config from composer.json extra field
-    pub fn from_extra(extra: &serde_json::Value) -> Self {
-        let bamarni_bin = extra.get("bamarni-bin");
-
-        let mut config = Self::default();
-
-        if let Some(obj) = bamarni_bin.and_then(|v| v.as_object()) {
-            if let Some(bin_links) = obj.get("bin-links").and_then(|v| v.as_bool()) {
-                config.bin_links = bin_links;
-            }
-            if let Some(target_dir) = obj.get("target-directory").and_then(|v| v.as_str()) {
-                config.target_directory = target_dir.to_string();
-            }
-            if let Some(forward) = obj.get("forward-command").and_then(|v| v.as_bool()) {
-                config.forward_command = forward;
-            }
-        }
-
-        config
-    }
-}
-
 /// Composer bin plugin - implements EventListener directly.
 pub struct ComposerBinPlugin;
 
@@ -103,7 +59,7 @@ impl ComposerBinPlugin {
         composer_json: &ComposerJson,
         _installed_packages: &[Arc<Package>],
     ) -> Result<()> {
-        let config = BinConfig::from_extra(&composer_json.extra);
+        let config = composer_json.extra_bamarni_bin().unwrap_or_default();
 
         // Only act if forward-command is enabled
         if !config.forward_command {
@@ -204,7 +160,7 @@ fn create_bin_links(namespace_dir: &Path, main_bin_dir: &Path) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::json::BinConfig;
 
     #[test]
     fn test_bin_config_default() {
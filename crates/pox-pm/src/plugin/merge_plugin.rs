@@ -0,0 +1,450 @@
+//! Merge plugin - combine several composer.json files into the root package.
+//!
+//! This is a native Rust port of wikimedia/composer-merge-plugin.
+//! The files matched by `extra.merge-plugin.include` contribute their
+//! requirements, repositories and autoload rules to the root package
+//! before dependencies are resolved.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use pox_semver::VersionParser;
+
+use crate::json::{Autoload, AutoloadPath, ComposerJson, MergeConfig, Repositories, Repository};
+use crate::{ComposerError, Result};
+
+/// The package name that triggers this plugin.
+pub const PACKAGE_NAME: &str = "wikimedia/composer-merge-plugin";
+
+/// Merge the files included through `extra.merge-plugin` into the root package
+///
+/// Only runs when the root package requires the plugin, like Composer would only run
+/// it once installed. Returns the merged files.
+///
+/// A package required by several files must be satisfiable by all of their constraints,
+/// which are combined; constraints that exclude each other are an error.
+pub fn merge_includes(base_dir: &Path, root: &mut ComposerJson) -> Result<Vec<PathBuf>> {
+    let requires_plugin = root
+        .require
+        .keys()
+        .chain(root.require_dev.keys())
+        .any(|name| name.eq_ignore_ascii_case(PACKAGE_NAME));
+    if !requires_plugin {
+        return Ok(Vec::new());
+    }
+    let Some(config) = root.extra_merge_plugin() else {
+        return Ok(Vec::new());
+    };
+
+    let mut merger = Merger {
+        base_dir,
+        merge_dev: config.merge_dev,
+        recurse: config.recurse,
+        sources: HashMap::new(),
+        merged: Vec::new(),
+        seen: HashSet::new(),
+    };
+    for name in root.require.keys().chain(root.require_dev.keys()) {
+        merger.sources.insert(name.to_lowercase(), "composer.json".to_string());
+    }
+    merger.seen.insert(canonical(&base_dir.join("composer.json")));
+
+    merger.merge_patterns(root, base_dir, &config)?;
+
+    Ok(merger.merged)
+}
+
+/// State of one merge run
+struct Merger<'a> {
+    base_dir: &'a Path,
+    merge_dev: bool,
+    recurse: bool,
+    /// File that introduced each required package, for conflict messages
+    sources: HashMap<String, String>,
+    merged: Vec<PathBuf>,
+    /// Files already merged, so overlapping globs and include cycles merge once
+    seen: HashSet<PathBuf>,
+}
+
+impl Merger<'_> {
+    fn merge_patterns(&mut self, root: &mut ComposerJson, dir: &Path, config: &MergeConfig) -> Result<()> {
+        let required = config.require.iter().map(|p| (p, true));
+        let optional = config.include.iter().map(|p| (p, false));
+
+        for (pattern, is_required) in required.chain(optional) {
+            let full_pattern = dir.join(pattern);
+            let mut paths: Vec<PathBuf> = glob::glob(&full_pattern.to_string_lossy())
+                .map_err(|e| ComposerError::InvalidManifest {
+                    message: format!("Invalid merge-plugin pattern \"{}\": {}", pattern, e),
+                })?
+                .filter_map(|entry| entry.ok())
+                .filter(|path| path.is_file())
+                .collect();
+            paths.sort();
+
+            if is_required && paths.is_empty() {
+                return Err(ComposerError::InvalidManifest {
+                    message: format!("merge-plugin: no files match the required pattern \"{}\"", pattern),
+                });
+            }
+
+            for path in paths {
+                self.merge_file(root, &path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_file(&mut self, root: &mut ComposerJson, path: &Path) -> Result<()> {
+        if !self.seen.insert(canonical(path)) {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let include: ComposerJson = serde_json::from_str(&content).map_err(|e| ComposerError::InvalidManifest {
+            message: format!("Failed to parse {}: {}", path.display(), e),
+        })?;
+
+        let source = relative_to(self.base_dir, path);
+        let dir = path.parent().unwrap_or(self.base_dir).to_path_buf();
+        let prefix = relative_to(self.base_dir, &dir);
+
+        self.merge_requires(&mut root.require, &include.require, &source)?;
+        merge_repositories(&mut root.repositories, &include.repositories, &prefix, &source);
+        merge_autoload(&mut root.autoload, &include.autoload, &prefix);
+        if self.merge_dev {
+            self.merge_requires(&mut root.require_dev, &include.require_dev, &source)?;
+            merge_autoload(&mut root.autoload_dev, &include.autoload_dev, &prefix);
+        }
+        self.merged.push(path.to_path_buf());
+
+        if self.recurse {
+            if let Some(config) = include.extra_merge_plugin() {
+                self.merge_patterns(root, &dir, &config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_requires(
+        &mut self,
+        target: &mut IndexMap<String, String>,
+        requires: &IndexMap<String, String>,
+        source: &str,
+    ) -> Result<()> {
+        for (name, constraint) in requires {
+            let key = name.to_lowercase();
+            let existing = target.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name));
+
+            match existing {
+                None => {
+                    target.insert(name.clone(), constraint.clone());
+                    self.sources.insert(key, source.to_string());
+                }
+                Some((_, current)) if current.trim() == constraint.trim() => {}
+                Some((_, current)) => {
+                    let other = self.sources.get(&key).cloned().unwrap_or_default();
+                    *current = combine_constraints(name, current, &other, constraint, source)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Combine two constraints on the same package, failing if no version can satisfy both
+fn combine_constraints(name: &str, current: &str, current_source: &str, new: &str, new_source: &str) -> Result<String> {
+    let parser = VersionParser::new();
+    let parse = |constraint: &str, source: &str| {
+        parser.parse_constraints(constraint).map_err(|e| ComposerError::InvalidManifest {
+            message: format!("merge-plugin: invalid constraint \"{}\" for {} in {}: {}", constraint, name, source, e),
+        })
+    };
+
+    let combined = parse(current, current_source)?.intersect(parse(new, new_source)?.as_ref());
+    if combined.is_match_none() {
+        return Err(ComposerError::InvalidManifest {
+            message: format!(
+                "merge-plugin: conflicting requirements for {}: \"{}\" in {} and \"{}\" in {} cannot both be satisfied",
+                name, current, current_source, new, new_source
+            ),
+        });
+    }
+
+    // `,` binds tighter than `||`, so joining two disjunctions needs the AND distributed
+    // over every pair of their branches; pairs no version satisfies are left out
+    let mut branches = Vec::new();
+    for left in or_branches(current) {
+        for right in or_branches(new) {
+            let disjoint = match (parser.parse_constraints(left), parser.parse_constraints(right)) {
+                (Ok(left), Ok(right)) => left.intersect(right.as_ref()).is_match_none(),
+                _ => false,
+            };
+            if !disjoint {
+                branches.push(format!("{}, {}", left, right));
+            }
+        }
+    }
+
+    Ok(branches.join(" || "))
+}
+
+/// The `||` separated alternatives of a constraint
+fn or_branches(constraint: &str) -> Vec<&str> {
+    constraint.split('|').map(str::trim).filter(|branch| !branch.is_empty()).collect()
+}
+
+/// Append repositories that are not declared yet, rebasing relative path repositories
+fn merge_repositories(target: &mut Repositories, repositories: &Repositories, prefix: &str, source: &str) {
+    let mut existing: Vec<serde_json::Value> = target
+        .as_vec()
+        .iter()
+        .filter_map(|r| serde_json::to_value(r).ok())
+        .collect();
+
+    for (index, repository) in repositories.as_vec().into_iter().enumerate() {
+        let repository = match repository {
+            Repository::Path { url, options, filter } => Repository::Path {
+                url: rebase(prefix, &url),
+                options,
+                filter,
+            },
+            other => other,
+        };
+
+        let Ok(value) = serde_json::to_value(&repository) else {
+            continue;
+        };
+        if existing.contains(&value) {
+            continue;
+        }
+        existing.push(value);
+
+        match target {
+            Repositories::None => *target = Repositories::Array(vec![repository]),
+            Repositories::Array(list) => list.push(repository),
+            Repositories::Object(map) => {
+                map.insert(format!("{}#{}", source, index), repository);
+            }
+        }
+    }
+}
+
+/// Merge autoload rules, rebasing paths onto the root and skipping ones already declared
+fn merge_autoload(target: &mut Autoload, autoload: &Autoload, prefix: &str) {
+    let merge_namespaces = |target: &mut IndexMap<String, AutoloadPath>, namespaces: &IndexMap<String, AutoloadPath>| {
+        for (namespace, paths) in namespaces {
+            let entry = target.entry(namespace.clone()).or_default();
+            let mut merged = entry.as_vec();
+            for path in paths.as_vec() {
+                let path = rebase(prefix, &path);
+                if !merged.iter().any(|p| same_path(p, &path)) {
+                    merged.push(path);
+                }
+            }
+            *entry = match merged.len() {
+                1 => AutoloadPath::Single(merged.remove(0)),
+                _ => AutoloadPath::Multiple(merged),
+            };
+        }
+    };
+    merge_namespaces(&mut target.psr4, &autoload.psr4);
+    merge_namespaces(&mut target.psr0, &autoload.psr0);
+
+    let merge_list = |target: &mut Vec<String>, paths: &[String]| {
+        for path in paths {
+            let path = rebase(prefix, path);
+            if !target.iter().any(|p| same_path(p, &path)) {
+                target.push(path);
+            }
+        }
+    };
+    merge_list(&mut target.classmap, &autoload.classmap);
+    merge_list(&mut target.files, &autoload.files);
+    merge_list(&mut target.exclude_from_classmap, &autoload.exclude_from_classmap);
+}
+
+/// Make a path relative to an included file's directory relative to the root instead
+///
+/// `..` segments are collapsed, so `../../src` from `packages/a` becomes `src`.
+fn rebase(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() || Path::new(path).is_absolute() {
+        return path.to_string();
+    }
+
+    let mut segments: Vec<&str> = prefix.split('/').collect();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|s| *s != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let rebased = segments.join("/");
+    match (rebased.is_empty(), path.ends_with('/') || path == ".") {
+        (true, _) => ".".to_string(),
+        (false, true) => format!("{}/", rebased),
+        (false, false) => rebased,
+    }
+}
+
+/// Compare autoload paths, ignoring `./` prefixes and trailing slashes
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| p.trim_start_matches("./").trim_end_matches('/').to_string();
+    normalize(a) == normalize(b)
+}
+
+fn relative_to(base: &Path, path: &Path) -> String {
+    pathdiff::diff_paths(path, base)
+        .unwrap_or_else(|| path.to_path_buf())
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn write_json(path: &Path, value: serde_json::Value) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, serde_json::to_string(&value).unwrap()).unwrap();
+    }
+
+    fn root_json(include: serde_json::Value) -> ComposerJson {
+        serde_json::from_value(json!({
+            "require": {"wikimedia/composer-merge-plugin": "^2.1", "psr/log": "^3.0"},
+            "autoload": {"psr-4": {"App\\": "src/"}},
+            "extra": {"merge-plugin": {"include": include}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_includes() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        write_json(
+            &base.join("packages/a/composer.json"),
+            json!({
+                "require": {"symfony/console": "^6.0", "psr/log": "^3.0"},
+                "require-dev": {"phpunit/phpunit": "^10.0"},
+                "repositories": [{"type": "path", "url": "../b"}],
+                "autoload": {"psr-4": {"A\\": "src/"}, "files": ["helpers.php"]}
+            }),
+        );
+        write_json(
+            &base.join("packages/b/composer.json"),
+            json!({
+                "require": {"symfony/console": ">=6.2"},
+                "autoload": {"psr-4": {"App\\": ["src/", "../../src"]}}
+            }),
+        );
+
+        let mut root = root_json(json!("packages/*/composer.json"));
+        let merged = merge_includes(base, &mut root).unwrap();
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(root.require["symfony/console"], "^6.0, >=6.2");
+        assert_eq!(root.require["psr/log"], "^3.0");
+        assert_eq!(root.require_dev["phpunit/phpunit"], "^10.0");
+
+        let repositories = root.repositories.as_vec();
+        assert!(matches!(&repositories[..], [Repository::Path { url, .. }] if url == "packages/b"));
+
+        assert_eq!(root.autoload.psr4["A\\"].as_vec(), vec!["packages/a/src/"]);
+        assert_eq!(root.autoload.files, vec!["packages/a/helpers.php"]);
+        // `../../src` from packages/b is the root's own `src/`
+        assert_eq!(root.autoload.psr4["App\\"].as_vec(), vec!["src/", "packages/b/src/"]);
+    }
+
+    #[test]
+    fn test_disjunctive_constraints_are_distributed() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        write_json(&base.join("a.json"), json!({"require": {"symfony/console": "^6.0 || ^7.0"}}));
+        write_json(&base.join("b.json"), json!({"require": {"symfony/console": ">=6.2"}}));
+        write_json(&base.join("c.json"), json!({"require": {"symfony/console": "^6.4 | ^7.1"}}));
+
+        let mut root = root_json(json!(["a.json", "b.json", "c.json"]));
+        merge_includes(base, &mut root).unwrap();
+
+        let merged = &root.require["symfony/console"];
+        assert_eq!(merged, "^6.0, >=6.2, ^6.4 || ^7.0, >=6.2, ^7.1");
+
+        let constraint = VersionParser::new().parse_constraints_cached(merged).unwrap();
+        assert!(!constraint.satisfies("6.1.0"));
+        assert!(!constraint.satisfies("6.3.0"));
+        assert!(constraint.satisfies("6.4.0"));
+        assert!(!constraint.satisfies("7.0.0"));
+        assert!(constraint.satisfies("7.1.0"));
+    }
+
+    #[test]
+    fn test_identical_autoload_roots_are_deduplicated() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        write_json(&base.join("a.json"), json!({"autoload": {"psr-4": {"App\\": "./src"}, "classmap": ["lib/"]}}));
+        write_json(&base.join("b.json"), json!({"autoload": {"psr-4": {"App\\": "src/"}, "classmap": ["lib"]}}));
+
+        let mut root = root_json(json!(["a.json", "b.json"]));
+        merge_includes(base, &mut root).unwrap();
+
+        assert_eq!(root.autoload.psr4["App\\"].as_vec(), vec!["src/"]);
+        assert_eq!(root.autoload.classmap, vec!["lib/"]);
+    }
+
+    #[test]
+    fn test_conflicting_constraints_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        write_json(&base.join("modules/a/composer.json"), json!({"require": {"psr/log": "^1.0"}}));
+
+        let mut root = root_json(json!("modules/*/composer.json"));
+        let err = merge_includes(base, &mut root).unwrap_err().to_string();
+
+        assert!(err.contains("psr/log"), "{}", err);
+        assert!(err.contains("\"^3.0\" in composer.json"), "{}", err);
+        assert!(err.contains("\"^1.0\" in modules/a/composer.json"), "{}", err);
+    }
+
+    #[test]
+    fn test_required_pattern_must_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut root: ComposerJson = serde_json::from_value(json!({
+            "require": {"wikimedia/composer-merge-plugin": "^2.1"},
+            "extra": {"merge-plugin": {"require": ["missing/composer.json"]}}
+        }))
+        .unwrap();
+
+        assert!(merge_includes(temp_dir.path(), &mut root).is_err());
+    }
+
+    #[test]
+    fn test_skipped_without_plugin_requirement() {
+        let temp_dir = TempDir::new().unwrap();
+        write_json(&temp_dir.path().join("a.json"), json!({"require": {"acme/lib": "^1.0"}}));
+
+        let mut root: ComposerJson = serde_json::from_value(json!({
+            "extra": {"merge-plugin": {"include": ["a.json"]}}
+        }))
+        .unwrap();
+
+        assert!(merge_includes(temp_dir.path(), &mut root).unwrap().is_empty());
+        assert!(root.require.is_empty());
+    }
+}
@@ -5,12 +5,16 @@
 //! ported and registered as event listeners.
 //!
 //! Each plugin implements `EventListener` directly and checks if its
-//! corresponding package is installed before taking action.
+//! corresponding package is installed before taking action. The merge
+//! plugin is the exception: it changes the root package itself, so it runs
+//! while the root composer.json is loaded.
 
 mod composer_bin;
+mod merge_plugin;
 mod phpstan_extension_installer;
 mod registry;
 mod symfony_runtime;
 
-pub use composer_bin::BinConfig;
-pub use registry::register_plugins;
+pub use crate::json::{BinConfig, MergeConfig};
+pub use merge_plugin::merge_includes;
+pub use registry::{plugin_support, register_plugins, PluginSupport};
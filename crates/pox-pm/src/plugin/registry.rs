@@ -7,9 +7,47 @@ use std::sync::Arc;
 
 use crate::event::{EventListener, EventType, EventDispatcher};
 
-use super::composer_bin::ComposerBinPlugin;
-use super::phpstan_extension_installer::PhpstanExtensionInstallerPlugin;
-use super::symfony_runtime::SymfonyRuntimePlugin;
+use super::composer_bin::{self, ComposerBinPlugin};
+use super::merge_plugin;
+use super::phpstan_extension_installer::{self, PhpstanExtensionInstallerPlugin};
+use super::symfony_runtime::{self, SymfonyRuntimePlugin};
+
+/// Plugins with a native port registered by [`register_plugins`]
+const PORTED_PLUGINS: &[&str] = &[
+    composer_bin::PACKAGE_NAME,
+    merge_plugin::PACKAGE_NAME,
+    phpstan_extension_installer::PACKAGE_NAME,
+    symfony_runtime::PACKAGE_NAME,
+];
+
+/// Plugins whose behavior needs no port
+const UNNEEDED_PLUGINS: &[&str] = &[
+    // Falls back to `Composer\InstalledVersions`, which is generated with the autoloader
+    "composer/package-versions-deprecated",
+];
+
+/// How a package of type `composer-plugin` is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginSupport {
+    /// A native port runs in place of the plugin
+    Ported,
+    /// The plugin's work is already done by pox itself, so skipping it is harmless
+    NotNeeded,
+    /// The plugin's hooks will not run
+    Unsupported,
+}
+
+/// Look up how a Composer plugin package is handled
+pub fn plugin_support(package_name: &str) -> PluginSupport {
+    let name = package_name.to_lowercase();
+    if PORTED_PLUGINS.contains(&name.as_str()) {
+        PluginSupport::Ported
+    } else if UNNEEDED_PLUGINS.contains(&name.as_str()) {
+        PluginSupport::NotNeeded
+    } else {
+        PluginSupport::Unsupported
+    }
+}
 
 /// Register all plugins with the event dispatcher.
 ///
@@ -20,3 +58,17 @@ pub fn register_plugins(dispatcher: &mut EventDispatcher) {
     dispatcher.add_listener(EventType::PostAutoloadDump, Arc::new(PhpstanExtensionInstallerPlugin) as Arc<dyn EventListener>);
     dispatcher.add_listener(EventType::PostAutoloadDump, Arc::new(SymfonyRuntimePlugin) as Arc<dyn EventListener>);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_support() {
+        assert_eq!(plugin_support("symfony/runtime"), PluginSupport::Ported);
+        assert_eq!(plugin_support("Bamarni/Composer-Bin-Plugin"), PluginSupport::Ported);
+        assert_eq!(plugin_support("wikimedia/composer-merge-plugin"), PluginSupport::Ported);
+        assert_eq!(plugin_support("composer/package-versions-deprecated"), PluginSupport::NotNeeded);
+        assert_eq!(plugin_support("symfony/flex"), PluginSupport::Unsupported);
+    }
+}
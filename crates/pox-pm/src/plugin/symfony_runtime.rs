@@ -8,7 +8,7 @@ use std::path::Path;
 
 use crate::composer::Composer;
 use crate::event::{ComposerEvent, EventListener, EventType, PostAutoloadDumpEvent};
-use crate::json::ComposerJson;
+use crate::json::{ComposerJson, RuntimeConfig};
 use crate::Result;
 
 /// The package name that triggers this plugin.
@@ -93,21 +93,14 @@ impl SymfonyRuntimePlugin {
             return Ok(());
         }
 
-        // Get runtime configuration from extra.runtime
-        let runtime_extra = composer_json.extra.get("runtime");
-
-        // If runtime is explicitly set to false, skip generation
-        if let Some(serde_json::Value::Bool(false)) = runtime_extra {
+        // Get runtime configuration from extra.runtime, skipping generation when it is false
+        let runtime = composer_json.extra_runtime().unwrap_or_default();
+        if !runtime.enabled {
             return Ok(());
         }
 
-        // Get the runtime options (or empty object)
-        let extra = runtime_extra
-            .cloned()
-            .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-
         // Check for custom autoload_template
-        let autoload_template_path = extra.get("autoload_template").and_then(|v| v.as_str());
+        let autoload_template_path = runtime.autoload_template.as_deref();
 
         let template_content = if let Some(template_path) = autoload_template_path {
             // Resolve the template path
@@ -132,13 +125,12 @@ impl SymfonyRuntimePlugin {
         let project_dir_code = self.calculate_project_dir_code(vendor_dir, project_dir)?;
 
         // Get runtime class (default: Symfony\Component\Runtime\SymfonyRuntime)
-        let runtime_class = extra
-            .get("class")
-            .and_then(|v| v.as_str())
+        let runtime_class = runtime.class
+            .as_deref()
             .unwrap_or("Symfony\\Component\\Runtime\\SymfonyRuntime");
 
         // Build runtime options, excluding 'class' and 'autoload_template'
-        let runtime_options = self.build_runtime_options(&extra, &project_dir_code);
+        let runtime_options = self.build_runtime_options(&runtime, &project_dir_code);
 
         // Replace placeholders in template
         let code = template_content
@@ -203,19 +195,12 @@ impl SymfonyRuntimePlugin {
     }
 
     /// Build the runtime options PHP array code.
-    fn build_runtime_options(&self, extra: &serde_json::Value, project_dir_code: &str) -> String {
+    fn build_runtime_options(&self, runtime: &RuntimeConfig, project_dir_code: &str) -> String {
         let mut options = Vec::new();
 
-        if let Some(obj) = extra.as_object() {
-            for (key, value) in obj {
-                // Skip 'class' and 'autoload_template' as they're not runtime options
-                if key == "class" || key == "autoload_template" {
-                    continue;
-                }
-
-                let value_str = php_var_export(value);
-                options.push(format!("  '{}' => {},", key, value_str));
-            }
+        for (key, value) in &runtime.options {
+            let value_str = php_var_export(value);
+            options.push(format!("  '{}' => {},", key, value_str));
         }
 
         // Always add project_dir
@@ -302,14 +287,14 @@ mod tests {
     fn test_build_runtime_options() {
         let plugin = SymfonyRuntimePlugin;
 
-        let extra = serde_json::json!({
+        let runtime: RuntimeConfig = serde_json::from_value(serde_json::json!({
             "class": "SomeClass",
             "autoload_template": "template.php",
             "env_var_name": "APP_ENV",
             "debug_var_name": "APP_DEBUG"
-        });
+        })).unwrap();
 
-        let result = plugin.build_runtime_options(&extra, "dirname(__DIR__, 1)");
+        let result = plugin.build_runtime_options(&runtime, "dirname(__DIR__, 1)");
 
         assert!(result.contains("'env_var_name' => 'APP_ENV'"));
         assert!(result.contains("'debug_var_name' => 'APP_DEBUG'"));
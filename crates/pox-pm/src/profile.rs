@@ -0,0 +1,196 @@
+//! Wall-clock profiling of the top-level phases of a command.
+//!
+//! Profiling is off by default; `--profile` enables it. While enabled, each phase records
+//! the interval it ran in. Phases that run concurrently (downloads, extraction) report the
+//! wall-clock time covered by their intervals rather than the sum of them.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SPANS: Mutex<Vec<(Phase, Instant, Instant)>> = Mutex::new(Vec::new());
+
+/// A top-level phase of an install or update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+    ConfigLoad,
+    RepoFetch,
+    PoolBuild,
+    Solve,
+    Download,
+    Extract,
+    AutoloadDump,
+}
+
+impl Phase {
+    /// All phases in execution order
+    pub const ALL: [Phase; 7] = [
+        Phase::ConfigLoad,
+        Phase::RepoFetch,
+        Phase::PoolBuild,
+        Phase::Solve,
+        Phase::Download,
+        Phase::Extract,
+        Phase::AutoloadDump,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::ConfigLoad => "config load",
+            Phase::RepoFetch => "repo fetch",
+            Phase::PoolBuild => "pool build",
+            Phase::Solve => "solve",
+            Phase::Download => "download",
+            Phase::Extract => "extract",
+            Phase::AutoloadDump => "autoload dump",
+        }
+    }
+}
+
+/// Wall-clock time spent in a phase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub duration: Duration,
+    /// Number of times the phase was entered
+    pub count: usize,
+}
+
+/// Records the time between its creation and drop as a span of a phase
+#[must_use = "the phase is recorded when the timer is dropped"]
+pub struct PhaseTimer {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl Drop for PhaseTimer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            if let Ok(mut spans) = SPANS.lock() {
+                spans.push((self.phase, start, Instant::now()));
+            }
+        }
+    }
+}
+
+/// Enable profiling for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Check whether profiling is enabled
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Start timing a phase; the span ends when the returned timer is dropped
+pub fn start(phase: Phase) -> PhaseTimer {
+    PhaseTimer {
+        phase,
+        start: is_enabled().then(Instant::now),
+    }
+}
+
+/// Timings of all recorded phases, in execution order
+pub fn timings() -> Vec<PhaseTiming> {
+    let spans = match SPANS.lock() {
+        Ok(spans) => spans.clone(),
+        Err(_) => return Vec::new(),
+    };
+
+    Phase::ALL
+        .iter()
+        .filter_map(|phase| {
+            let intervals: Vec<(Instant, Instant)> = spans
+                .iter()
+                .filter(|(p, _, _)| p == phase)
+                .map(|(_, start, end)| (*start, *end))
+                .collect();
+
+            if intervals.is_empty() {
+                return None;
+            }
+
+            Some(PhaseTiming {
+                phase: *phase,
+                duration: wall_clock(intervals.clone()),
+                count: intervals.len(),
+            })
+        })
+        .collect()
+}
+
+/// Format the recorded timings as a table, with `total` as the overall runtime
+pub fn report(total: Duration) -> String {
+    let mut out = String::from("Profile:\n");
+    for timing in timings() {
+        let calls = if timing.count > 1 {
+            format!(" ({} spans)", timing.count)
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "  {:<15}{:>9.3}s{}\n",
+            timing.phase.as_str(),
+            timing.duration.as_secs_f64(),
+            calls
+        ));
+    }
+    out.push_str(&format!("  {:<15}{:>9.3}s\n", "total", total.as_secs_f64()));
+    out
+}
+
+/// Length of the union of a set of intervals
+fn wall_clock(mut intervals: Vec<(Instant, Instant)>) -> Duration {
+    intervals.sort();
+
+    let mut total = Duration::ZERO;
+    let mut current: Option<(Instant, Instant)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((cur_start, cur_end)) if start <= cur_end => Some((cur_start, cur_end.max(end))),
+            Some((cur_start, cur_end)) => {
+                total += cur_end - cur_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some((start, end)) = current {
+        total += end - start;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wall_clock_merges_overlapping_intervals() {
+        let base = Instant::now();
+        let at = |ms: u64| base + Duration::from_millis(ms);
+
+        let intervals = vec![(at(0), at(100)), (at(50), at(150)), (at(300), at(400))];
+        assert_eq!(wall_clock(intervals), Duration::from_millis(250));
+
+        assert_eq!(wall_clock(Vec::new()), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_wall_clock_nested_intervals() {
+        let base = Instant::now();
+        let at = |ms: u64| base + Duration::from_millis(ms);
+
+        let intervals = vec![(at(0), at(500)), (at(100), at(200))];
+        assert_eq!(wall_clock(intervals), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_phase_names() {
+        assert_eq!(Phase::ConfigLoad.as_str(), "config load");
+        assert_eq!(Phase::AutoloadDump.as_str(), "autoload dump");
+        assert_eq!(Phase::ALL.len(), 7);
+    }
+}
@@ -12,8 +12,9 @@ use regex::Regex;
 use super::traits::{Repository, SearchMode, SearchResult, ProviderInfo};
 use crate::cache::{RepoCache, CacheMetadata};
 use crate::config::AuthConfig;
-use crate::package::{Package, Dist, Source, Autoload, AutoloadPath, Stability};
-use pox_semver::{Constraint, Operator, VersionParser};
+use crate::http::{HttpClient, SignedOrigin, default_client};
+use crate::package::{Package, Dist, Source, Mirror, Autoload, AutoloadPath, Stability, parse_release_time};
+use pox_semver::VersionParser;
 
 /// Default TTL for cached metadata (10 minutes, matching Composer)
 const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(600);
@@ -78,6 +79,20 @@ where
     deserialize_maybe_unset(deserializer)
 }
 
+/// Deserialize a flag that might be "__unset"
+///
+/// Unlike other fields, an unset flag must not be inherited from the previous version, so
+/// it is read as `false`.
+fn deserialize_flag_maybe_unset<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) if s == "__unset" => Ok(Some(false)),
+        value => Option::<bool>::deserialize(value.unwrap_or(Value::Null)).map_err(serde::de::Error::custom),
+    }
+}
+
 /// Composer repository (Packagist-compatible)
 pub struct ComposerRepository {
     /// Repository name/identifier
@@ -88,8 +103,8 @@ pub struct ComposerRepository {
     base_url: String,
     /// In-memory package cache
     packages: RwLock<HashMap<String, Vec<Arc<Package>>>>,
-    /// HTTP client for API requests
-    client: reqwest::Client,
+    /// HTTP client for API requests, sharing the TLS, proxy and timeout settings
+    http: Arc<HttpClient>,
     /// File-based cache for HTTP responses
     file_cache: Option<RepoCache>,
     /// Cache TTL
@@ -124,6 +139,8 @@ pub struct ComposerRepository {
     degraded_mode: RwLock<bool>,
     /// Packages that returned 404 (don't re-fetch)
     packages_not_found: RwLock<HashSet<String>>,
+    /// Verifier for the metadata, if the repository is signed
+    signed_origin: Option<SignedOrigin>,
 }
 
 impl ComposerRepository {
@@ -148,10 +165,7 @@ impl ComposerRepository {
             base_url,
             packages: RwLock::new(HashMap::new()),
             loading_locks: RwLock::new(HashMap::new()),
-            client: reqwest::Client::builder()
-                .user_agent("pox-composer/0.1.0")
-                .build()
-                .unwrap_or_default(),
+            http: default_client(),
             file_cache: None,
             cache_ttl: DEFAULT_CACHE_TTL,
             auth: None,
@@ -168,6 +182,7 @@ impl ComposerRepository {
             root_loaded: RwLock::new(false),
             degraded_mode: RwLock::new(false),
             packages_not_found: RwLock::new(HashSet::new()),
+            signed_origin: None,
         }
     }
 
@@ -193,6 +208,28 @@ impl ComposerRepository {
         self.cache_ttl = ttl;
     }
 
+    /// Fetch metadata through `http`, so it uses the configured certificates and timeouts
+    pub fn set_http_client(&mut self, http: Arc<HttpClient>) {
+        self.http = http;
+    }
+
+    /// Require package metadata to be signed
+    pub fn set_signed_origin(&mut self, origin: SignedOrigin) {
+        self.signed_origin = Some(origin);
+    }
+
+    /// Check the signature of metadata fetched from `url`, if the repository is signed
+    async fn verify_signature(&self, url: &str, body: &[u8]) -> Result<(), String> {
+        let Some(origin) = self.signed_origin.as_ref().filter(|origin| origin.matches(url)) else {
+            return Ok(());
+        };
+
+        self.http
+            .verify_signed(origin, url, body, self.auth.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// Get the repository URL
     pub fn url(&self) -> &str {
         &self.url
@@ -203,37 +240,18 @@ impl ComposerRepository {
         self.auth = Some(Arc::new(auth));
     }
 
-    /// Apply authentication to a request builder
-    fn apply_auth(&self, mut request: reqwest::RequestBuilder, url: &str) -> reqwest::RequestBuilder {
-        if let Some(ref auth) = self.auth {
-            match auth.find_for_url(url) {
-                crate::config::AuthMatch::HttpBasic(creds) => {
-                    request = request.basic_auth(&creds.username, Some(&creds.password));
-                }
-                crate::config::AuthMatch::Bearer(token) => {
-                    request = request.bearer_auth(token);
-                }
-                crate::config::AuthMatch::GitHubOAuth(token) => {
-                    request = request.bearer_auth(token);
-                }
-                crate::config::AuthMatch::GitLabToken(token) => {
-                    request = request.header("PRIVATE-TOKEN", token);
-                }
-                crate::config::AuthMatch::BitbucketOAuth(creds) => {
-                    request = request.basic_auth(&creds.consumer_key, Some(&creds.consumer_secret));
-                }
-                crate::config::AuthMatch::None => {}
-            }
-        }
-        request
-    }
-
     /// Generate cache key for a package
     fn cache_key(package_name: &str) -> String {
         // Convert vendor/package to vendor~package for safe filesystem use
         format!("provider-{}.json", package_name.replace('/', "~"))
     }
 
+    /// Cache keys of the stable and dev metadata of a package, as written to the repo cache
+    pub fn package_cache_keys(package_name: &str) -> [String; 2] {
+        let name = package_name.to_lowercase();
+        [Self::cache_key(&name), Self::cache_key(&format!("{}~dev", name))]
+    }
+
     fn canonicalize_url(&self, url: &str) -> String {
         if url.starts_with('/') {
             if let Some(pos) = self.base_url.find("://") {
@@ -571,10 +589,9 @@ impl ComposerRepository {
     }
 
     async fn fetch_if_modified(&self, url: &str, last_modified: &str) -> Result<FetchResult, String> {
-        let request = self.client
-            .get(url)
+        let request = self.http
+            .get_request(url, self.auth.as_deref())
             .header("If-Modified-Since", last_modified);
-        let request = self.apply_auth(request, url);
         let response = request
             .send()
             .await
@@ -596,6 +613,7 @@ impl ComposerRepository {
 
         let body = response.text().await
             .map_err(|e| format!("Failed to read response body: {}", e))?;
+        self.verify_signature(url, body.as_bytes()).await?;
 
         let metadata = CacheMetadata {
             last_modified: new_last_modified,
@@ -609,8 +627,7 @@ impl ComposerRepository {
         log::debug!("HTTP GET {}", url);
         let start = std::time::Instant::now();
 
-        let request = self.client.get(url);
-        let request = self.apply_auth(request, url);
+        let request = self.http.get_request(url, self.auth.as_deref());
         let response = request
             .send()
             .await
@@ -634,6 +651,7 @@ impl ComposerRepository {
 
         let body = response.text().await
             .map_err(|e| format!("Failed to read response body: {}", e))?;
+        self.verify_signature(url, body.as_bytes()).await?;
 
         log::debug!("HTTP 200 {} ({} bytes) in {:?}", url, body.len(), start.elapsed());
 
@@ -655,11 +673,14 @@ impl ComposerRepository {
 
         let mut result = Vec::new();
         let notify_batch = self.notify_batch.read().await.clone();
+        let dist_mirrors = self.dist_mirrors.read().await.clone();
+        let source_mirrors = self.source_mirrors.read().await.clone();
 
         if let Some(versions) = data.packages.get(name) {
             let expanded_versions = Self::expand_minified_versions(versions);
             for expanded_data in &expanded_versions {
-                let pkg = self.convert_to_package(name, expanded_data, notify_batch.as_deref());
+                let mut pkg = self.convert_to_package(name, expanded_data, notify_batch.as_deref());
+                Self::apply_mirrors(&mut pkg, &dist_mirrors, &source_mirrors);
                 result.push(Arc::new(pkg));
             }
         }
@@ -714,6 +735,7 @@ impl ComposerRepository {
                 autoload: Self::apply_delta_opt(&version_data.autoload, &prev.autoload),
                 autoload_dev: Self::apply_delta_opt(&version_data.autoload_dev, &prev.autoload_dev),
                 time: Self::apply_delta_opt(&version_data.time, &prev.time),
+                default_branch: Self::apply_delta_opt(&version_data.default_branch, &prev.default_branch),
                 notification_url: Self::apply_delta_opt(&version_data.notification_url, &prev.notification_url),
                 support: Self::apply_delta_opt(&version_data.support, &prev.support),
                 funding: Self::apply_delta_opt(&version_data.funding, &prev.funding),
@@ -735,6 +757,21 @@ impl ComposerRepository {
         current.clone().or_else(|| prev.clone())
     }
 
+    /// Attach the repository's mirrors to a package's dist and source
+    fn apply_mirrors(pkg: &mut Package, dist_mirrors: &[DistMirror], source_mirrors: &HashMap<String, Vec<SourceMirror>>) {
+        if let Some(dist) = pkg.dist.as_mut() {
+            if !dist_mirrors.is_empty() {
+                dist.mirrors = Some(dist_mirrors.iter().map(|m| Mirror::new(&m.url, m.preferred)).collect());
+            }
+        }
+
+        if let Some(source) = pkg.source.as_mut() {
+            if let Some(mirrors) = source_mirrors.get(&source.source_type).filter(|m| !m.is_empty()) {
+                source.mirrors = Some(mirrors.iter().map(|m| Mirror::new(&m.url, m.preferred)).collect());
+            }
+        }
+    }
+
     fn convert_to_package(&self, package_name: &str, data: &PackagistVersion, notify_batch: Option<&str>) -> Package {
         let version = data.version_normalized.as_ref()
             .unwrap_or(&data.version);
@@ -792,10 +829,8 @@ impl ComposerRepository {
             pkg.autoload_dev = Some(Self::convert_autoload(al));
         }
 
-        let time = data.time.as_ref();
-        if let Some(t) = time {
-            pkg.time = chrono::DateTime::parse_from_rfc3339(t).ok().map(|dt| dt.with_timezone(&chrono::Utc));
-        }
+        pkg.time = data.time.as_deref().and_then(parse_release_time);
+        pkg.default_branch = data.default_branch.filter(|default| *default);
 
         pkg.notification_url = data.notification_url.clone()
             .or_else(|| notify_batch.map(|s| s.to_string()));
@@ -994,17 +1029,7 @@ impl Repository for ComposerRepository {
         };
 
         packages.into_iter()
-            .filter(|pkg| {
-                let normalized = parser.normalize(&pkg.version)
-                    .unwrap_or_else(|_| pkg.version.clone());
-
-                let version_constraint = match Constraint::new(Operator::Equal, normalized) {
-                    Ok(c) => c,
-                    Err(_) => return true,
-                };
-
-                parsed_constraint.matches(&version_constraint)
-            })
+            .filter(|pkg| parsed_constraint.allows(&pkg.version))
             .collect()
     }
 
@@ -1018,6 +1043,10 @@ impl Repository for ComposerRepository {
         Vec::new()
     }
 
+    async fn package_names(&self) -> Vec<String> {
+        self.get_package_names(None).await
+    }
+
     async fn search(&self, query: &str, mode: SearchMode) -> Vec<SearchResult> {
         self.load_root_server_file().await.ok();
 
@@ -1032,7 +1061,7 @@ impl Repository for ComposerRepository {
                     format!("{}/search.json?q={}", self.url, urlencoding::encode(query))
                 };
 
-                let response = match self.client.get(&url).send().await {
+                let response = match self.http.get_request(&url, self.auth.as_deref()).send().await {
                     Ok(r) => r,
                     Err(_) => return Vec::new(),
                 };
@@ -1131,8 +1160,7 @@ impl Repository for ComposerRepository {
         if let Some(ref providers_url) = *self.providers_api_url.read().await {
             let url = providers_url.replace("%package%", package_name);
 
-            let request = self.client.get(&url);
-            let request = self.apply_auth(request, &url);
+            let request = self.http.get_request(&url, self.auth.as_deref());
 
             let response = match request.send().await {
                 Ok(r) => r,
@@ -1226,14 +1254,7 @@ impl Repository for ComposerRepository {
                     pkgs
                 } else if let Ok(parsed_constraint) = parser.parse_constraints(c) {
                     pkgs.into_iter()
-                        .filter(|pkg| {
-                            let normalized = parser.normalize(&pkg.version)
-                                .unwrap_or_else(|_| pkg.version.clone());
-                            match Constraint::new(Operator::Equal, normalized) {
-                                Ok(vc) => parsed_constraint.matches(&vc),
-                                Err(_) => true,
-                            }
-                        })
+                        .filter(|pkg| parsed_constraint.allows(&pkg.version))
                         .collect()
                 } else {
                     pkgs
@@ -1300,6 +1321,8 @@ struct PackagistVersion {
     autoload_dev: Option<PackagistAutoload>,
     #[serde(default, deserialize_with = "deserialize_maybe_unset")]
     time: Option<String>,
+    #[serde(rename = "default-branch", default, deserialize_with = "deserialize_flag_maybe_unset")]
+    default_branch: Option<bool>,
     #[serde(rename = "notification-url", default, deserialize_with = "deserialize_maybe_unset")]
     notification_url: Option<String>,
     #[serde(default, deserialize_with = "deserialize_maybe_unset")]
@@ -1689,6 +1712,39 @@ mod tests {
         assert_eq!(expanded[1].description.as_ref().unwrap(), "Has requirements");
     }
 
+    /// The default branch flag is set on one version and unset on the next
+    #[test]
+    fn test_expand_minified_default_branch() {
+        let json = r#"[
+            {
+                "version": "dev-main",
+                "version_normalized": "dev-main",
+                "default-branch": true,
+                "time": "2024-03-01T08:00:00+00:00"
+            },
+            {
+                "version": "dev-feature",
+                "version_normalized": "dev-feature",
+                "default-branch": "__unset",
+                "time": "2024-02-01 08:00:00"
+            },
+            {
+                "version": "1.0.0",
+                "version_normalized": "1.0.0.0"
+            }
+        ]"#;
+
+        let versions: Vec<PackagistVersion> = serde_json::from_str(json).unwrap();
+        let expanded = ComposerRepository::expand_minified_versions(&versions);
+        let repo = ComposerRepository::new("test", "https://repo.example.org");
+        let packages: Vec<Package> = expanded.iter().map(|data| repo.convert_to_package("acme/lib", data, None)).collect();
+
+        let default_branches: Vec<bool> = packages.iter().map(|p| p.is_default_branch()).collect();
+        assert_eq!(default_branches, vec![true, false, false]);
+        assert_eq!(packages[0].time.unwrap().to_rfc3339(), "2024-03-01T08:00:00+00:00");
+        assert_eq!(packages[1].time.unwrap().to_rfc3339(), "2024-02-01T08:00:00+00:00");
+    }
+
     /// Test the full parse flow with a mock response
     #[test]
     fn test_parse_packagist_response_isolates_packages() {
@@ -2100,12 +2156,44 @@ mod tests {
     // Tests for cache key generation
     // ============================================================================
 
+    #[test]
+    fn test_apply_mirrors_to_package() {
+        let mut pkg = Package::new("vendor/pkg", "1.0.0.0");
+        pkg.dist = Some(Dist::zip("https://example.org/pkg.zip"));
+        pkg.source = Some(Source::git("https://github.com/vendor/pkg.git", "abc123"));
+
+        let dist_mirrors = vec![DistMirror {
+            url: "https://mirror.example.org/%package%/%version%.%type%".to_string(),
+            preferred: true,
+        }];
+        let mut source_mirrors = HashMap::new();
+        source_mirrors.insert("hg".to_string(), vec![SourceMirror {
+            url: "https://mirror.example.org/%package%".to_string(),
+            preferred: true,
+        }]);
+
+        ComposerRepository::apply_mirrors(&mut pkg, &dist_mirrors, &source_mirrors);
+
+        let dist = pkg.dist.as_ref().unwrap();
+        assert_eq!(dist.mirrors, Some(vec![Mirror::preferred("https://mirror.example.org/%package%/%version%.%type%")]));
+        assert_eq!(dist.mirror_urls(&pkg.name, &pkg.version, None)[0], "https://mirror.example.org/vendor/pkg/1.0.0.0.zip");
+        assert_eq!(pkg.source.as_ref().unwrap().mirrors, None);
+    }
+
     #[test]
     fn test_cache_key_simple_package() {
         let key = ComposerRepository::cache_key("vendor/package");
         assert_eq!(key, "provider-vendor~package.json");
     }
 
+    #[test]
+    fn test_package_cache_keys() {
+        assert_eq!(ComposerRepository::package_cache_keys("Vendor/Package"), [
+            "provider-vendor~package.json".to_string(),
+            "provider-vendor~package~dev.json".to_string(),
+        ]);
+    }
+
     #[test]
     fn test_cache_key_nested_vendor() {
         let key = ComposerRepository::cache_key("vendor/sub/package");
@@ -2172,4 +2260,36 @@ mod tests {
         let key = ComposerRepository::cache_key("vendor/package~dev");
         assert_eq!(key, "provider-vendor~package~dev.json");
     }
+
+    #[tokio::test]
+    async fn test_signed_metadata() {
+        use base64::Engine;
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+        use crate::http::{Ed25519Verifier, Verifier};
+        use crate::test_server::serve;
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = base64::engine::general_purpose::STANDARD.encode(key_pair.public_key().as_ref());
+        let verifier: Arc<dyn Verifier> = Arc::new(Ed25519Verifier::from_base64(&public_key).unwrap());
+
+        let base = serve(vec![
+            ("/p2/acme/signed.json", b"{\"packages\":{}}".to_vec()),
+            ("/p2/acme/signed.json.sig", key_pair.sign(b"{\"packages\":{}}").as_ref().to_vec()),
+            ("/p2/acme/tampered.json", b"{\"packages\":[]}".to_vec()),
+            ("/p2/acme/tampered.json.sig", key_pair.sign(b"{\"packages\":{}}").as_ref().to_vec()),
+            ("/p2/acme/unsigned.json", b"{}".to_vec()),
+        ]);
+
+        let mut repo = ComposerRepository::new("signed", base.clone());
+        repo.set_signed_origin(SignedOrigin::new(base.clone(), verifier));
+
+        let (body, _) = repo.fetch_fresh(&format!("{}/p2/acme/signed.json", base)).await.unwrap();
+        assert_eq!(body, "{\"packages\":{}}");
+
+        let err = repo.fetch_fresh(&format!("{}/p2/acme/tampered.json", base)).await.unwrap_err();
+        assert!(err.contains("signature does not match"), "{}", err);
+        let err = repo.fetch_fresh(&format!("{}/p2/acme/unsigned.json", base)).await.unwrap_err();
+        assert!(err.contains("is not signed"), "{}", err);
+    }
 }
@@ -0,0 +1,196 @@
+//! Filter repository - limits which packages another repository provides.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use regex::Regex;
+
+use super::traits::{LoadResult, Repository, SearchMode, SearchResult, ProviderInfo};
+use crate::json::RepositoryFilter;
+use crate::package::Package;
+
+/// Wraps a repository to apply the `only`, `exclude` and `canonical` keys of its definition
+///
+/// Packages outside the filter are hidden entirely, so lower priority repositories are
+/// asked for them. A non-canonical repository still provides its packages, but doesn't
+/// stop lower priority repositories from adding more versions of them.
+///
+/// ```json
+/// {
+///     "repositories": [
+///         {
+///             "type": "composer",
+///             "url": "https://packages.example.org",
+///             "only": ["example/*"],
+///             "canonical": false
+///         }
+///     ]
+/// }
+/// ```
+pub struct FilterRepository {
+    inner: Arc<dyn Repository>,
+    only: Vec<Regex>,
+    exclude: Vec<Regex>,
+    canonical: bool,
+}
+
+impl FilterRepository {
+    /// Create a filter around a repository
+    pub fn new(inner: Arc<dyn Repository>, filter: &RepositoryFilter) -> Self {
+        Self {
+            inner,
+            only: filter.only.iter().filter_map(|pattern| pattern_to_regex(pattern)).collect(),
+            exclude: filter.exclude.iter().filter_map(|pattern| pattern_to_regex(pattern)).collect(),
+            canonical: filter.canonical.unwrap_or(true),
+        }
+    }
+
+    /// Check if the repository may provide a package
+    pub fn is_allowed(&self, name: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|re| re.is_match(name)))
+            && !self.exclude.iter().any(|re| re.is_match(name))
+    }
+
+    /// Whether packages found here hide the same packages in lower priority repositories
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    fn filter_packages(&self, packages: Vec<Arc<Package>>) -> Vec<Arc<Package>> {
+        packages.into_iter().filter(|package| self.is_allowed(&package.name)).collect()
+    }
+}
+
+/// Turn a package name pattern where `*` matches any characters into a case-insensitive regex
+fn pattern_to_regex(pattern: &str) -> Option<Regex> {
+    Regex::new(&format!("(?i)^{}$", regex::escape(pattern).replace(r"\*", ".*"))).ok()
+}
+
+#[async_trait]
+impl Repository for FilterRepository {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn has_package(&self, name: &str) -> bool {
+        self.is_allowed(name) && self.inner.has_package(name).await
+    }
+
+    async fn find_packages(&self, name: &str) -> Vec<Arc<Package>> {
+        if !self.is_allowed(name) {
+            return Vec::new();
+        }
+        self.filter_packages(self.inner.find_packages(name).await)
+    }
+
+    async fn find_package(&self, name: &str, version: &str) -> Option<Arc<Package>> {
+        if !self.is_allowed(name) {
+            return None;
+        }
+        self.inner.find_package(name, version).await
+    }
+
+    async fn find_packages_with_constraint(&self, name: &str, constraint: &str) -> Vec<Arc<Package>> {
+        if !self.is_allowed(name) {
+            return Vec::new();
+        }
+        self.filter_packages(self.inner.find_packages_with_constraint(name, constraint).await)
+    }
+
+    async fn get_packages(&self) -> Vec<Arc<Package>> {
+        self.filter_packages(self.inner.get_packages().await)
+    }
+
+    async fn search(&self, query: &str, mode: SearchMode) -> Vec<SearchResult> {
+        self.inner.search(query, mode).await
+            .into_iter()
+            .filter(|result| self.is_allowed(&result.name))
+            .collect()
+    }
+
+    async fn get_providers(&self, package_name: &str) -> Vec<ProviderInfo> {
+        self.inner.get_providers(package_name).await
+            .into_iter()
+            .filter(|provider| self.is_allowed(&provider.name))
+            .collect()
+    }
+
+    async fn package_names(&self) -> Vec<String> {
+        self.inner.package_names().await
+            .into_iter()
+            .filter(|name| self.is_allowed(name))
+            .collect()
+    }
+
+    async fn load_packages_batch(&self, packages: &[(String, Option<String>)]) -> LoadResult {
+        let allowed: Vec<(String, Option<String>)> = packages.iter()
+            .filter(|(name, _)| self.is_allowed(name))
+            .cloned()
+            .collect();
+        if allowed.is_empty() {
+            return LoadResult { packages: Vec::new(), names_found: Vec::new() };
+        }
+
+        let mut result = self.inner.load_packages_batch(&allowed).await;
+        result.packages = self.filter_packages(result.packages);
+        if !self.canonical {
+            result.names_found.clear();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PackageRepository;
+
+    fn repository(filter: RepositoryFilter) -> FilterRepository {
+        let dist = serde_json::json!({"type": "zip", "url": "https://example.com/package.zip"});
+        let packages = serde_json::json!([
+            {"name": "acme/lib", "version": "1.0.0", "dist": dist},
+            {"name": "acme/tool", "version": "1.0.0", "dist": dist},
+            {"name": "other/lib", "version": "2.0.0", "dist": dist}
+        ]);
+        FilterRepository::new(Arc::new(PackageRepository::new(&packages).unwrap()), &filter)
+    }
+
+    #[tokio::test]
+    async fn test_only_and_exclude() {
+        let repo = repository(RepositoryFilter {
+            only: vec!["ACME/*".to_string()],
+            exclude: vec!["acme/tool".to_string()],
+            canonical: None,
+        });
+
+        assert_eq!(repo.package_names().await, vec!["acme/lib"]);
+        assert!(repo.has_package("acme/lib").await);
+        assert!(!repo.has_package("acme/tool").await);
+        assert!(repo.find_packages("other/lib").await.is_empty());
+        assert!(repo.find_package("other/lib", "2.0.0").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_canonical_controls_names_found() {
+        let batch = vec![
+            ("acme/lib".to_string(), None),
+            ("other/lib".to_string(), None),
+        ];
+
+        let canonical = repository(RepositoryFilter {
+            only: vec!["acme/*".to_string()],
+            ..Default::default()
+        });
+        let result = canonical.load_packages_batch(&batch).await;
+        assert_eq!(result.packages.len(), 1);
+        assert_eq!(result.names_found, vec!["acme/lib"]);
+
+        let supplementing = repository(RepositoryFilter {
+            canonical: Some(false),
+            ..Default::default()
+        });
+        let result = supplementing.load_packages_batch(&batch).await;
+        assert_eq!(result.packages.len(), 2);
+        assert!(result.names_found.is_empty());
+    }
+}
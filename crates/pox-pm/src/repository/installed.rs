@@ -14,6 +14,10 @@ pub struct InstalledRepository {
     vendor_dir: PathBuf,
     /// Installed packages
     packages: RwLock<HashMap<String, Arc<Package>>>,
+    /// Whether dev requirements are installed
+    dev_mode: RwLock<bool>,
+    /// Names of the installed packages that are only required through `require-dev`
+    dev_package_names: RwLock<Vec<String>>,
     /// Whether the repository has been modified
     dirty: RwLock<bool>,
 }
@@ -24,6 +28,8 @@ impl InstalledRepository {
         Self {
             vendor_dir: vendor_dir.into(),
             packages: RwLock::new(HashMap::new()),
+            dev_mode: RwLock::new(true),
+            dev_package_names: RwLock::new(Vec::new()),
             dirty: RwLock::new(false),
         }
     }
@@ -53,10 +59,26 @@ impl InstalledRepository {
             let package = Package::from_installed_json(&pkg_data);
             packages.insert(package.name.clone(), Arc::new(package));
         }
+        *self.dev_mode.write().await = data.dev;
+        *self.dev_package_names.write().await = data.dev_package_names;
 
         Ok(())
     }
 
+    /// Record whether dev requirements are installed and which packages are only required
+    /// through them
+    pub async fn set_dev_packages(&self, dev_mode: bool, mut names: Vec<String>) {
+        names.sort();
+        names.dedup();
+        *self.dev_mode.write().await = dev_mode;
+        *self.dev_package_names.write().await = names;
+    }
+
+    /// Names of the installed packages that are only required through `require-dev`
+    pub async fn dev_package_names(&self) -> Vec<String> {
+        self.dev_package_names.read().await.clone()
+    }
+
     /// Get the vendor directory path
     pub fn vendor_dir(&self) -> &Path {
         &self.vendor_dir
@@ -168,10 +190,13 @@ impl WritableRepository for InstalledRepository {
     async fn write(&self) -> std::io::Result<()> {
         let packages = self.packages.read().await;
 
+        let mut sorted: Vec<&Arc<Package>> = packages.values().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
         let installed = InstalledJson {
-            packages: packages.values().map(|p| p.to_installed_json()).collect(),
-            dev: true,
-            dev_package_names: vec![],
+            packages: sorted.into_iter().map(|p| p.to_installed_json()).collect(),
+            dev: *self.dev_mode.read().await,
+            dev_package_names: self.dev_package_names.read().await.clone(),
         };
 
         let content = serde_json::to_string_pretty(&installed)
@@ -193,12 +218,16 @@ impl WritableRepository for InstalledRepository {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct InstalledJson {
     packages: Vec<InstalledPackage>,
-    #[serde(default)]
+    #[serde(default = "default_dev")]
     dev: bool,
-    #[serde(default)]
+    #[serde(rename = "dev-package-names", alias = "dev_package_names", default)]
     dev_package_names: Vec<String>,
 }
 
+fn default_dev() -> bool {
+    true
+}
+
 /// Package entry in installed.json
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InstalledPackage {
@@ -6,13 +6,17 @@ use super::PlatformRepository;
 use super::path::{PathRepository, PathRepositoryOptions};
 use super::package::PackageRepository;
 use super::artifact::ArtifactRepository;
+use super::filter::FilterRepository;
 use super::vcs::{VcsRepository, VcsType};
+use crate::http::{HttpClient, SignedOrigin};
 use crate::package::Package;
 
 /// Manages multiple repositories with priority ordering
 pub struct RepositoryManager {
     /// Repositories in priority order (first = highest priority)
     repositories: Vec<Arc<dyn Repository>>,
+    /// Client given to the Composer repositories this manager creates
+    http_client: Option<Arc<HttpClient>>,
 }
 
 impl RepositoryManager {
@@ -20,9 +24,15 @@ impl RepositoryManager {
     pub fn new() -> Self {
         Self {
             repositories: Vec::new(),
+            http_client: None,
         }
     }
 
+    /// Fetch the metadata of the Composer repositories created from now on through `client`
+    pub fn set_http_client(&mut self, client: Arc<HttpClient>) {
+        self.http_client = Some(client);
+    }
+
     /// Add a repository (will be added with lowest priority)
     pub fn add_repository(&mut self, repo: Arc<dyn Repository>) {
         self.repositories.push(repo);
@@ -56,6 +66,37 @@ impl RepositoryManager {
         packages
     }
 
+    /// Find packages by name across all repositories, with the name of the repository each came from
+    ///
+    /// A version offered by several repositories is only returned from the one with the highest priority.
+    pub async fn find_packages_by_repository(&self, name: &str) -> Vec<(String, Arc<Package>)> {
+        let mut packages = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for repo in &self.repositories {
+            for pkg in repo.find_packages(name).await {
+                let key = format!("{}@{}", pkg.name, pkg.version);
+                if !seen.contains(&key) {
+                    seen.insert(key);
+                    packages.push((repo.name().to_string(), pkg));
+                }
+            }
+        }
+
+        packages
+    }
+
+    /// Get the names of all packages available in any repository, sorted and deduplicated
+    pub async fn package_names(&self) -> Vec<String> {
+        let mut names = std::collections::BTreeSet::new();
+
+        for repo in &self.repositories {
+            names.extend(repo.package_names().await.into_iter().map(|n| n.to_lowercase()));
+        }
+
+        names.into_iter().collect()
+    }
+
     /// Find a specific package version
     pub async fn find_package(&self, name: &str, version: &str) -> Option<Arc<Package>> {
         for repo in &self.repositories {
@@ -190,11 +231,26 @@ impl RepositoryManager {
         use crate::json::Repository as JsonRepo;
 
         let result: Option<Arc<dyn Repository>> = match repo {
-            JsonRepo::Composer { url, .. } => {
+            JsonRepo::Composer { url, signature, .. } => {
                 let name = extract_repo_name(url);
-                Some(Arc::new(ComposerRepository::new(name, url)))
+                let mut repo = ComposerRepository::new(name, url);
+                if let Some(client) = &self.http_client {
+                    repo.set_http_client(client.clone());
+                }
+                // A repository that can't be verified must not be used unverified
+                match signature.as_ref().map(|signature| SignedOrigin::from_repository(url, signature)) {
+                    Some(Err(e)) => {
+                        eprintln!("Warning: Skipping repository {}: {}", url, e);
+                        None
+                    }
+                    Some(Ok(origin)) => {
+                        repo.set_signed_origin(origin);
+                        Some(Arc::new(repo))
+                    }
+                    None => Some(Arc::new(repo)),
+                }
             }
-            JsonRepo::Path { url, options } => {
+            JsonRepo::Path { url, options, .. } => {
                 let path_options = PathRepositoryOptions {
                     symlink: options.symlink,
                     relative: false,
@@ -203,7 +259,7 @@ impl RepositoryManager {
                 };
                 Some(Arc::new(PathRepository::new(url, path_options)))
             }
-            JsonRepo::Package { package } => {
+            JsonRepo::Package { package, .. } => {
                 match PackageRepository::new(package) {
                     Ok(repo) => Some(Arc::new(repo)),
                     Err(e) => {
@@ -212,22 +268,22 @@ impl RepositoryManager {
                     }
                 }
             }
-            JsonRepo::Vcs { url } => {
+            JsonRepo::Vcs { url, .. } => {
                 Some(Arc::new(VcsRepository::new(url, VcsType::Vcs)))
             }
-            JsonRepo::Git { url } => {
+            JsonRepo::Git { url, .. } => {
                 Some(Arc::new(VcsRepository::new(url, VcsType::Git)))
             }
-            JsonRepo::GitHub { url } => {
+            JsonRepo::GitHub { url, .. } => {
                 Some(Arc::new(VcsRepository::new(url, VcsType::GitHub)))
             }
-            JsonRepo::GitLab { url } => {
+            JsonRepo::GitLab { url, .. } => {
                 Some(Arc::new(VcsRepository::new(url, VcsType::GitLab)))
             }
-            JsonRepo::Bitbucket { url } => {
+            JsonRepo::Bitbucket { url, .. } => {
                 Some(Arc::new(VcsRepository::new(url, VcsType::Bitbucket)))
             }
-            JsonRepo::Artifact { url } => {
+            JsonRepo::Artifact { url, .. } => {
                 Some(Arc::new(ArtifactRepository::new(url)))
             }
             JsonRepo::Disabled(_) => {
@@ -236,8 +292,14 @@ impl RepositoryManager {
             }
         };
 
-        if let Some(repo) = result {
-            self.add_repository(repo);
+        if let Some(created) = result {
+            // only/exclude/canonical keys wrap the repository in a filter
+            match repo.filter() {
+                Some(filter) if !filter.is_empty() => {
+                    self.add_repository(Arc::new(FilterRepository::new(created, filter)));
+                }
+                _ => self.add_repository(created),
+            }
         }
     }
 
@@ -276,3 +338,72 @@ impl Default for RepositoryManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_repo(packages: &[(&str, &str)]) -> Arc<dyn Repository> {
+        let config: Vec<_> = packages
+            .iter()
+            .map(|(name, version)| serde_json::json!({
+                "name": name,
+                "version": version,
+                "dist": {"url": format!("https://example.com/{}-{}.zip", name, version), "type": "zip"},
+            }))
+            .collect();
+        Arc::new(PackageRepository::new(&serde_json::Value::Array(config)).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_find_packages_by_repository() {
+        let mut manager = RepositoryManager::new();
+        manager.add_repository(package_repo(&[("vendor/package", "2.0.0"), ("vendor/package", "1.0.0")]));
+        manager.add_repository(package_repo(&[("vendor/package", "1.0.0"), ("vendor/package", "0.9.0"), ("vendor/other", "1.0.0")]));
+
+        let found: Vec<(String, String)> = manager
+            .find_packages_by_repository("vendor/package")
+            .await
+            .into_iter()
+            .map(|(repo, pkg)| (repo, pkg.version.clone()))
+            .collect();
+
+        assert_eq!(found, vec![
+            ("package repo (2 packages)".to_string(), "2.0.0".to_string()),
+            ("package repo (2 packages)".to_string(), "1.0.0".to_string()),
+            ("package repo (3 packages)".to_string(), "0.9.0".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_package_names() {
+        let mut manager = RepositoryManager::new();
+        manager.add_repository(package_repo(&[("vendor/zeta", "1.0.0")]));
+        manager.add_repository(package_repo(&[("Vendor/Alpha", "1.0.0"), ("vendor/zeta", "2.0.0")]));
+
+        assert_eq!(manager.package_names().await, vec!["vendor/alpha", "vendor/zeta"]);
+    }
+
+    #[tokio::test]
+    async fn test_json_repository_filter() {
+        let definition = serde_json::json!({
+            "type": "package",
+            "package": [
+                {"name": "acme/lib", "version": "1.0.0", "dist": {"url": "https://example.com/lib.zip", "type": "zip"}},
+                {"name": "other/lib", "version": "1.0.0", "dist": {"url": "https://example.com/other.zip", "type": "zip"}}
+            ],
+            "only": ["acme/*"],
+            "canonical": false
+        });
+        let repository: crate::json::Repository = serde_json::from_value(definition.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&repository).unwrap(), definition);
+
+        let mut manager = RepositoryManager::new();
+        manager.add_from_json_repository(&repository);
+        manager.add_repository(package_repo(&[("acme/lib", "2.0.0"), ("other/lib", "2.0.0")]));
+
+        let versions = |packages: Vec<Arc<Package>>| packages.iter().map(|p| p.version.clone()).collect::<Vec<_>>();
+        assert_eq!(versions(manager.find_packages("acme/lib").await), vec!["1.0.0", "2.0.0"]);
+        assert_eq!(versions(manager.find_packages("other/lib").await), vec!["2.0.0"]);
+    }
+}
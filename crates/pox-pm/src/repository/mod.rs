@@ -6,6 +6,7 @@ mod installed;
 mod path;
 mod package;
 mod artifact;
+mod filter;
 mod utils;
 pub mod vcs;
 
@@ -17,5 +18,6 @@ pub use installed::*;
 pub use path::*;
 pub use package::*;
 pub use artifact::*;
+pub use filter::*;
 pub use utils::*;
 pub use vcs::{VcsRepository, VcsType, GitDriver, GitHubDriver, GitLabDriver, BitbucketDriver, get_head_commit};
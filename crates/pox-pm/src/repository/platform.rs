@@ -4,7 +4,7 @@ use async_trait::async_trait;
 
 use super::traits::{Repository, SearchMode, SearchResult, ProviderInfo};
 use crate::package::{Package, Stability};
-use pox_semver::{Constraint, Operator, VersionParser};
+use pox_semver::VersionParser;
 
 /// Platform repository - provides PHP version and extensions
 pub struct PlatformRepository {
@@ -180,20 +180,7 @@ impl Repository for PlatformRepository {
 
         // Filter packages by constraint
         packages.into_iter()
-            .filter(|pkg| {
-                // Normalize the package version
-                let normalized = parser.normalize(&pkg.version)
-                    .unwrap_or_else(|_| pkg.version.clone());
-
-                // Create a version constraint (== normalized_version)
-                let version_constraint = match Constraint::new(Operator::Equal, normalized) {
-                    Ok(c) => c,
-                    Err(_) => return true, // Be permissive
-                };
-
-                // Check if the version matches the constraint
-                parsed_constraint.matches(&version_constraint)
-            })
+            .filter(|pkg| parsed_constraint.allows(&pkg.version))
             .collect()
     }
 
@@ -79,6 +79,11 @@ pub trait Repository: Send + Sync {
     /// Get packages that provide a virtual package
     async fn get_providers(&self, package_name: &str) -> Vec<ProviderInfo>;
 
+    /// Get the names of all packages in the repository
+    async fn package_names(&self) -> Vec<String> {
+        self.get_packages().await.iter().map(|p| p.name.clone()).collect()
+    }
+
     /// Get the number of packages in the repository
     async fn count(&self) -> usize {
         self.get_packages().await.len()
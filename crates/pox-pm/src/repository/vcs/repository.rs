@@ -3,7 +3,6 @@
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 
 use super::driver::{VcsDriver, VcsDriverError, normalize_tag, normalize_branch};
 use super::git::GitDriver;
@@ -11,7 +10,7 @@ use super::github::GitHubDriver;
 use super::gitlab::GitLabDriver;
 use super::bitbucket::BitbucketDriver;
 use crate::config::AuthConfig;
-use crate::package::{Package, Dist, Source, Autoload, AutoloadPath};
+use crate::package::{Package, Dist, Source, Autoload, AutoloadPath, parse_release_time};
 use crate::repository::traits::{Repository, SearchMode, SearchResult, ProviderInfo};
 
 /// Type of VCS driver to use
@@ -180,9 +179,14 @@ impl VcsRepository {
         }
 
         if let Ok(branches) = driver.get_branches() {
+            // Drivers identify the default branch by its name or by its head commit
+            let root_identifier = driver.get_root_identifier().ok();
             for (branch, identifier) in branches {
                 let version = normalize_branch(&branch);
-                if let Ok(pkg) = self.create_package_from_ref(&*driver, &branch, &identifier, &version, true) {
+                if let Ok(mut pkg) = self.create_package_from_ref(&*driver, &branch, &identifier, &version, true) {
+                    if root_identifier.as_deref().is_some_and(|root| root == branch || root == identifier) {
+                        pkg.default_branch = Some(true);
+                    }
                     new_packages.push(Arc::new(pkg));
                 }
             }
@@ -240,9 +244,7 @@ impl VcsRepository {
         }
 
         if let Some(time_str) = info.time {
-            if let Ok(time) = DateTime::parse_from_rfc3339(&time_str) {
-                pkg.time = Some(time.with_timezone(&Utc));
-            }
+            pkg.time = parse_release_time(&time_str);
         }
 
         if let Some(desc) = json.get("description").and_then(|v| v.as_str()) {
@@ -3,10 +3,13 @@
 use anyhow::{Context, Result};
 use console::style;
 use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::io::IsTerminal;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
+use crate::config::Config;
 use crate::json::ComposerJson;
 
 /// Default process timeout in seconds (same as Composer)
@@ -39,6 +42,18 @@ impl ScriptContext {
         }
     }
 
+    /// Create a context with the project's `process-timeout` config, which also honours
+    /// `COMPOSER_PROCESS_TIMEOUT`
+    pub fn for_project(working_dir: &Path) -> Self {
+        match Config::build(Some(working_dir), true) {
+            Ok(config) => Self {
+                env_vars: HashMap::new(),
+                process_timeout: (config.process_timeout > 0).then_some(config.process_timeout),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+
     /// Disable the process timeout
     pub fn disable_timeout(&mut self) {
         self.process_timeout = None;
@@ -112,7 +127,7 @@ pub fn run_event_script(
         );
     }
 
-    let mut ctx = ScriptContext::new();
+    let mut ctx = ScriptContext::for_project(working_dir);
 
     for cmd in commands {
         if !quiet {
@@ -162,7 +177,7 @@ pub fn run_script(
         commands.len()
     );
 
-    let mut ctx = ScriptContext::new();
+    let mut ctx = ScriptContext::for_project(working_dir);
 
     for cmd in commands {
         println!("{} {}", style(">").green(), style(cmd).dim());
@@ -275,20 +290,10 @@ pub fn run_command(
 }
 
 /// Execute a shell command with optional timeout
+///
+/// Output goes straight to the terminal as it is written. Stdin is only passed on when it
+/// is a terminal, so scripts can prompt interactively but never wait for input in CI.
 fn execute_shell_command(cmd: &str, working_dir: &Path, ctx: &ScriptContext) -> Result<i32> {
-    // Prepend vendor/bin to PATH so scripts can find vendored binaries
-    let vendor_bin = working_dir.join("vendor").join("bin");
-    let path_env = if vendor_bin.exists() {
-        let current_path = std::env::var("PATH").unwrap_or_default();
-        #[cfg(unix)]
-        let new_path = format!("{}:{}", vendor_bin.display(), current_path);
-        #[cfg(windows)]
-        let new_path = format!("{};{}", vendor_bin.display(), current_path);
-        Some(new_path)
-    } else {
-        None
-    };
-
     #[cfg(unix)]
     let mut command = Command::new("sh");
     #[cfg(unix)]
@@ -300,11 +305,8 @@ fn execute_shell_command(cmd: &str, working_dir: &Path, ctx: &ScriptContext) ->
     command.arg("/C").arg(cmd);
 
     command.current_dir(working_dir);
-
-    // Add vendor/bin to PATH
-    if let Some(ref path) = path_env {
-        command.env("PATH", path);
-    }
+    command.envs(script_env(working_dir));
+    command.stdin(if std::io::stdin().is_terminal() { Stdio::inherit() } else { Stdio::null() });
 
     // Add custom environment variables
     for (key, value) in &ctx.env_vars {
@@ -336,14 +338,13 @@ fn execute_shell_command(cmd: &str, working_dir: &Path, ctx: &ScriptContext) ->
             }
             Ok(None) => {
                 if start.elapsed() > timeout {
-                    // Kill the process
                     let _ = child.kill();
-                    eprintln!(
-                        "{} Process timed out after {} seconds. Use Composer\\Config::disableProcessTimeout to disable.",
-                        style("Error:").red().bold(),
+                    let _ = child.wait();
+                    anyhow::bail!(
+                        "The process \"{}\" exceeded the timeout of {} seconds. Use Composer\\Config::disableProcessTimeout or set process-timeout to 0 to disable it.",
+                        cmd,
                         timeout_secs
                     );
-                    return Ok(1);
                 }
                 // Sleep briefly before checking again
                 std::thread::sleep(Duration::from_millis(100));
@@ -355,6 +356,48 @@ fn execute_shell_command(cmd: &str, working_dir: &Path, ctx: &ScriptContext) ->
     }
 }
 
+/// Environment Composer gives scripts and vendored binaries, so they can call other
+/// vendored tools and Composer itself
+///
+/// The bin-dir is put in front of PATH (unless it is already on it) and exposed as
+/// `COMPOSER_RUNTIME_BIN_DIR`, and `COMPOSER_BINARY` points at the running binary.
+pub fn script_env(working_dir: &Path) -> Vec<(&'static str, OsString)> {
+    let bin_dir = Config::build(Some(working_dir), true)
+        .map(|config| config.get_bin_dir())
+        .unwrap_or_else(|_| working_dir.join("vendor").join("bin"));
+
+    let mut env = Vec::new();
+    if bin_dir.is_dir() {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        env.push(("PATH", prepend_path(&bin_dir, &path)));
+        env.push(("COMPOSER_RUNTIME_BIN_DIR", bin_dir.into_os_string()));
+    }
+    if let Ok(binary) = std::env::current_exe() {
+        env.push(("COMPOSER_BINARY", binary.into_os_string()));
+    }
+    env
+}
+
+/// Put `dir` in front of a PATH-style variable, unless it is already one of its entries
+pub fn prepend_path(dir: &Path, path: &OsStr) -> OsString {
+    if path.is_empty() {
+        return dir.as_os_str().to_os_string();
+    }
+    if is_on_path(dir, path) {
+        return path.to_os_string();
+    }
+    std::env::join_paths(std::iter::once(dir.to_path_buf()).chain(std::env::split_paths(path)))
+        .unwrap_or_else(|_| path.to_os_string())
+}
+
+/// Check if a directory is one of the entries of a PATH-style variable
+pub fn is_on_path(dir: &Path, path: &OsStr) -> bool {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    std::env::split_paths(path).any(|entry| {
+        entry.canonicalize().unwrap_or(entry) == dir
+    })
+}
+
 /// List available scripts
 pub fn list_scripts(composer_json: &ComposerJson) -> Result<i32> {
     let scripts = collect_scripts(composer_json);
@@ -423,3 +466,75 @@ pub fn list_scripts(composer_json: &ComposerJson) -> Result<i32> {
 
     Ok(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_is_on_path() {
+        let path = std::env::join_paths([
+            PathBuf::from("/usr/bin"),
+            PathBuf::from("/home/user/.composer/vendor/bin"),
+        ]).unwrap();
+
+        assert!(is_on_path(Path::new("/home/user/.composer/vendor/bin"), &path));
+        assert!(is_on_path(Path::new("/usr/bin"), &path));
+        assert!(!is_on_path(Path::new("/home/user/.config/composer/vendor/bin"), &path));
+        assert!(!is_on_path(Path::new("/usr/bin"), OsStr::new("")));
+    }
+
+    #[test]
+    fn test_prepend_path() {
+        let bin = Path::new("/app/vendor/bin");
+        let path = std::env::join_paths([PathBuf::from("/usr/local/bin"), PathBuf::from("/usr/bin")]).unwrap();
+
+        let prepended = prepend_path(bin, &path);
+        assert_eq!(
+            std::env::split_paths(&prepended).collect::<Vec<_>>(),
+            vec![PathBuf::from("/app/vendor/bin"), PathBuf::from("/usr/local/bin"), PathBuf::from("/usr/bin")]
+        );
+        assert_eq!(prepend_path(bin, &prepended), prepended);
+        assert_eq!(prepend_path(bin, OsStr::new("")), OsString::from("/app/vendor/bin"));
+    }
+
+    #[test]
+    fn test_context_for_project_reads_process_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("composer.json"), r#"{"config": {"process-timeout": 0}}"#).unwrap();
+        assert_eq!(ScriptContext::for_project(dir.path()).process_timeout, None);
+
+        std::fs::write(dir.path().join("composer.json"), r#"{"config": {"process-timeout": 20}}"#).unwrap();
+        assert_eq!(ScriptContext::for_project(dir.path()).process_timeout, Some(20));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_shell_command_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ctx = ScriptContext::new();
+        ctx.process_timeout = Some(1);
+
+        let start = Instant::now();
+        let error = execute_shell_command("sleep 10", dir.path(), &ctx).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(error.to_string().starts_with("The process \"sleep 10\" exceeded the timeout of 1 seconds."), "{}", error);
+
+        assert_eq!(execute_shell_command("exit 3", dir.path(), &ctx).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_script_env() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(script_env(dir.path()).iter().all(|(key, _)| *key == "COMPOSER_BINARY"));
+
+        let bin_dir = dir.path().join("vendor").join("bin");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let env: HashMap<&str, OsString> = script_env(dir.path()).into_iter().collect();
+
+        assert_eq!(env["COMPOSER_RUNTIME_BIN_DIR"], bin_dir.as_os_str());
+        assert_eq!(std::env::split_paths(&env["PATH"]).next(), Some(bin_dir));
+        assert!(env.contains_key("COMPOSER_BINARY"));
+    }
+}
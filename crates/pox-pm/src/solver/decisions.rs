@@ -245,6 +245,18 @@ impl Decisions {
         &self.decision_queue
     }
 
+    /// Iterate over the decisions in the order they were made
+    ///
+    /// Yields `(package_id, installed, level, rule_id)`. Reverted decisions are dropped from
+    /// the queue on backtracking, so this only reflects the current assignment.
+    pub fn iter(&self) -> impl Iterator<Item = (PackageId, bool, u32, Option<u32>)> + '_ {
+        self.decision_queue.iter().map(|&(literal, rule_id)| {
+            let package_id = literal.unsigned_abs() as PackageId;
+            let level = self.decision_map[package_id as usize].unsigned_abs() - 1;
+            (package_id, literal > 0, level, rule_id)
+        })
+    }
+
     /// Get decisions at a specific level
     pub fn decisions_at_level(&self, level: u32) -> Vec<Literal> {
         self.decision_queue
@@ -406,4 +418,22 @@ mod tests {
         assert_eq!(decisions.decision_rule(1), Some(42));
         assert_eq!(decisions.decision_rule(2), None);
     }
+
+    #[test]
+    fn test_decisions_iter_after_revert() {
+        let mut decisions = Decisions::new();
+        decisions.decide(1, Some(0));
+        decisions.increment_level();
+        decisions.decide(-2, None);
+        decisions.increment_level();
+        decisions.decide(3, Some(7));
+
+        decisions.revert_to_level(1);
+        decisions.decide(-3, Some(9));
+
+        assert_eq!(
+            decisions.iter().collect::<Vec<_>>(),
+            vec![(1, true, 0, Some(0)), (2, false, 1, None), (3, false, 1, Some(9))]
+        );
+    }
 }
@@ -0,0 +1,129 @@
+//! Export of the generated SAT problem for debugging.
+//!
+//! The rules are written as DIMACS CNF, which external SAT solvers read, next to a JSON
+//! sidecar mapping each variable to the package version it stands for. Both are streamed,
+//! since rule sets of large projects run into millions of clauses.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::pool::Pool;
+use super::rule::{Literal, Rule};
+use super::rule_set::RuleSet;
+
+/// Write the rules to `path` in DIMACS CNF, and the literal mapping to `<path>.json`
+///
+/// Returns the path of the JSON sidecar.
+pub fn dump_problem(path: &Path, rules: &RuleSet, pool: &Pool) -> io::Result<PathBuf> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_dimacs(&mut writer, rules, pool)?;
+    writer.flush()?;
+
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".json");
+    let sidecar = PathBuf::from(sidecar);
+    let mut writer = BufWriter::new(File::create(&sidecar)?);
+    write_literal_map(&mut writer, pool)?;
+    writer.flush()?;
+
+    Ok(sidecar)
+}
+
+/// Write the enabled rules as DIMACS CNF
+///
+/// Variables are pool package IDs. Multi-conflict rules ("at most one of") are not a
+/// single clause, so they are expanded into pairwise conflicts.
+pub fn write_dimacs<W: Write>(writer: &mut W, rules: &RuleSet, pool: &Pool) -> io::Result<()> {
+    let clause_count: usize = rules
+        .iter()
+        .filter(|rule| !rule.is_disabled())
+        .map(|rule| if rule.is_multi_conflict() { rule.len() * rule.len().saturating_sub(1) / 2 } else { 1 })
+        .sum();
+
+    writeln!(writer, "c pox dependency resolution problem")?;
+    writeln!(writer, "c variables are package ids, see the .json sidecar for their versions")?;
+    writeln!(writer, "p cnf {} {}", pool.len(), clause_count)?;
+
+    for rule in rules.iter().filter(|rule| !rule.is_disabled()) {
+        write_rule(writer, rule)?;
+    }
+
+    Ok(())
+}
+
+fn write_rule<W: Write>(writer: &mut W, rule: &Rule) -> io::Result<()> {
+    let literals = rule.literals();
+    if rule.is_multi_conflict() {
+        for (i, a) in literals.iter().enumerate() {
+            for b in &literals[i + 1..] {
+                write_clause(writer, &[*a, *b])?;
+            }
+        }
+        return Ok(());
+    }
+    write_clause(writer, literals)
+}
+
+fn write_clause<W: Write>(writer: &mut W, literals: &[Literal]) -> io::Result<()> {
+    for literal in literals {
+        write!(writer, "{} ", literal)?;
+    }
+    writeln!(writer, "0")
+}
+
+/// Write a JSON object mapping each variable to `name@version`
+pub fn write_literal_map<W: Write>(writer: &mut W, pool: &Pool) -> io::Result<()> {
+    write!(writer, "{{")?;
+    let mut first = true;
+    for id in pool.all_package_ids() {
+        let Some(package) = pool.package(id) else {
+            continue;
+        };
+        let label = format!("{}@{}", package.name, package.pretty_version());
+        write!(
+            writer,
+            "{}\n    \"{}\": {}",
+            if first { "" } else { "," },
+            id,
+            serde_json::to_string(&label)?
+        )?;
+        first = false;
+    }
+    writeln!(writer, "{}}}", if first { "" } else { "\n" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Package;
+
+    #[test]
+    fn test_write_dimacs() {
+        let mut pool = Pool::new();
+        let a = pool.add_package(Package::new("vendor/a", "1.0.0"));
+        let b1 = pool.add_package(Package::new("vendor/b", "1.0.0"));
+        let b2 = pool.add_package(Package::new("vendor/b", "2.0.0"));
+        let b3 = pool.add_package(Package::new("vendor/b", "3.0.0"));
+
+        let mut rules = RuleSet::new();
+        rules.add(Rule::root_require(vec![a]));
+        rules.add(Rule::requires(a, vec![b1, b2]));
+        rules.add(Rule::multi_conflict(vec![b1, b2, b3]));
+
+        let mut out = Vec::new();
+        write_dimacs(&mut out, &rules, &pool).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let clauses: Vec<&str> = out.lines().filter(|l| !l.starts_with('c')).collect();
+        assert_eq!(
+            clauses,
+            vec!["p cnf 4 5", "1 0", "-1 2 3 0", "-2 -3 0", "-2 -4 0", "-3 -4 0"]
+        );
+
+        let mut out = Vec::new();
+        write_literal_map(&mut out, &pool).unwrap();
+        let map: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(map["1"], "vendor/a@1.0.0");
+        assert_eq!(map["4"], "vendor/b@3.0.0");
+    }
+}
@@ -40,7 +40,7 @@
 //!
 //! match solver.solve(&request) {
 //!     Ok(transaction) => println!("Solution found!"),
-//!     Err(problems) => println!("No solution: {:?}", problems),
+//!     Err(failure) => println!("No solution:\n{}", failure),
 //! }
 //!
 //! // To disable pool optimization:
@@ -60,18 +60,24 @@ mod solver;
 mod problem;
 mod transaction;
 mod policy;
+mod dump;
+mod stats;
+mod relax;
 
 #[cfg(test)]
 mod tests;
 
-pub use pool::{Pool, PoolBuilder, PoolEntry, PackageId};
+pub use pool::{PackageProvider, Pool, PoolBuilder, PoolEntry, PackageId};
 pub use pool_builder::PoolBuilder as LazyPoolBuilder;
 pub use pool_optimizer::PoolOptimizer;
-pub use request::Request;
+pub use request::{Request, UpdateAllowTransitive, root_stability_flags};
 pub use rule::{Rule, RuleType, Literal};
 pub use rule_set::RuleSet;
 pub use decisions::Decisions;
-pub use solver::{Solver, SolverResult};
-pub use problem::Problem;
-pub use transaction::{Transaction, Operation};
+pub use solver::{Decision, SolveError, Solver, SolverResult};
+pub use problem::{Problem, ProblemSet, SolveFailure};
+pub use transaction::{Transaction, Operation, LockChange, LockDiff, diff_against_lock, non_dev_package_names};
 pub use policy::Policy;
+pub use dump::{dump_problem, write_dimacs, write_literal_map};
+pub use stats::{PackageStats, SolverStats};
+pub use relax::{suggest_relaxations, Relaxation, RelaxationAdvice};
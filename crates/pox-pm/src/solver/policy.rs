@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
 use super::pool::{Pool, PackageId};
+use crate::util::is_platform_package;
 
 /// Policy for selecting between candidate packages.
 ///
@@ -17,6 +18,9 @@ pub struct Policy {
     /// Preferred versions for specific packages (package name -> normalized version)
     /// When a preferred version is available, it will be selected over newer versions
     pub preferred_versions: HashMap<String, String>,
+    /// Among candidates with equal versions, like two dev branches, prefer the default
+    /// branch and then the most recent release time
+    pub prefer_newer_by_time: bool,
 }
 
 impl Policy {
@@ -27,6 +31,7 @@ impl Policy {
             prefer_lowest: false,
             prefer_dev_over_prerelease: false,
             preferred_versions: HashMap::new(),
+            prefer_newer_by_time: false,
         }
     }
 
@@ -55,6 +60,12 @@ impl Policy {
         self
     }
 
+    /// Set preference for the default branch and newer releases among equal versions
+    pub fn prefer_newer_by_time(mut self, prefer: bool) -> Self {
+        self.prefer_newer_by_time = prefer;
+        self
+    }
+
     /// Add a preferred version for a specific package
     pub fn with_preferred_version(mut self, package: &str, version: &str) -> Self {
         self.preferred_versions.insert(package.to_lowercase(), version.to_string());
@@ -66,10 +77,12 @@ impl Policy {
     /// Returns the candidates sorted by preference (best first).
     /// This implements Composer's package selection logic:
     /// 1. Prefer aliases over non-aliases (for same package name)
-    /// 2. Prefer original packages over replacers
+    /// 2. Prefer platform packages over polyfills, and original packages over replacers
     /// 3. Prefer same vendor as the required package
     /// 4. Prefer by version (highest/lowest based on policy)
-    /// 5. Fall back to package ID (pool insertion order)
+    /// 5. With `prefer_newer_by_time`, prefer the default branch, then newer release times
+    /// 6. Break ties by name, normalized version, repository and references
+    /// 7. Fall back to package ID (pool insertion order)
     pub fn select_preferred(&self, pool: &Pool, candidates: &[PackageId]) -> Vec<PackageId> {
         self.select_preferred_for_requirement(pool, candidates, None)
     }
@@ -148,6 +161,16 @@ impl Policy {
                 }
 
                 if !ignore_replace {
+                    // Prefer a real platform package (an installed extension) over polyfills providing it
+                    let a_is_platform = is_platform_package(&pa.name);
+                    let b_is_platform = is_platform_package(&pb.name);
+                    if a_is_platform && !b_is_platform {
+                        return std::cmp::Ordering::Less; // prefer a
+                    }
+                    if !a_is_platform && b_is_platform {
+                        return std::cmp::Ordering::Greater; // prefer b
+                    }
+
                     // Prefer original packages over replacers
                     // If a replaces b's name, prefer b (the original)
                     if self.replaces(pa, &pb.name) {
@@ -231,8 +254,17 @@ impl Policy {
                     return version_result;
                 }
 
-                // Fall back to package ID (pool insertion order)
-                a.cmp(&b)
+                if self.prefer_newer_by_time {
+                    let by_time = pb.is_default_branch().cmp(&pa.is_default_branch())
+                        .then_with(|| pa.compare_release_time(pb));
+                    if by_time != std::cmp::Ordering::Equal {
+                        return by_time;
+                    }
+                }
+
+                self.tie_break(pool, a, b)
+                    // Fall back to package ID (pool insertion order)
+                    .then_with(|| a.cmp(&b))
             }
             (Some(_), None) => std::cmp::Ordering::Less,
             (None, Some(_)) => std::cmp::Ordering::Greater,
@@ -240,6 +272,22 @@ impl Policy {
         }
     }
 
+    /// Order otherwise equal candidates by what they are rather than where they sit in the pool,
+    /// so the choice does not depend on the order repositories were loaded in.
+    fn tie_break(&self, pool: &Pool, a: PackageId, b: PackageId) -> std::cmp::Ordering {
+        let (Some(pa), Some(pb)) = (pool.package(a), pool.package(b)) else {
+            return std::cmp::Ordering::Equal;
+        };
+        let source_reference = |p: &crate::package::Package| p.source.as_ref().map(|s| s.reference.clone());
+        let dist_reference = |p: &crate::package::Package| p.dist.as_ref().and_then(|d| d.reference.clone());
+
+        pa.name.to_lowercase().cmp(&pb.name.to_lowercase())
+            .then_with(|| pa.version.cmp(&pb.version))
+            .then_with(|| pool.get_repository(a).cmp(&pool.get_repository(b)))
+            .then_with(|| source_reference(pa).cmp(&source_reference(pb)))
+            .then_with(|| dist_reference(pa).cmp(&dist_reference(pb)))
+    }
+
     /// Check if source package replaces target package name.
     fn replaces(&self, source: &crate::package::Package, target_name: &str) -> bool {
         source.replace.keys().any(|replaced| replaced.eq_ignore_ascii_case(target_name))
@@ -856,6 +904,32 @@ mod tests {
         assert_eq!(selected[1], id_a2);
     }
 
+    #[test]
+    fn test_prefer_newer_by_time_among_dev_branches() {
+        use crate::package::{parse_release_time, Stability};
+
+        let branch = |version: &str, time: Option<&str>, default_branch: bool| {
+            let mut package = Package::new("a", version);
+            package.time = time.and_then(parse_release_time);
+            package.default_branch = default_branch.then_some(true);
+            package
+        };
+
+        let mut pool = Pool::with_minimum_stability(Stability::Dev);
+        let untimed = pool.add_package(branch("dev-old", None, false));
+        let older = pool.add_package(branch("dev-hotfix", Some("2024-01-01 00:00:00"), false));
+        let newest = pool.add_package(branch("dev-feature", Some("2024-03-01T00:00:00+00:00"), false));
+        let default = pool.add_package(branch("dev-main", Some("2024-02-01T00:00:00+00:00"), true));
+        let candidates = [untimed, older, newest, default];
+
+        // Without the option, equal versions are ordered by what they are
+        let selected = Policy::new().select_preferred(&pool, &candidates);
+        assert_eq!(selected, vec![newest, older, default, untimed]);
+
+        let selected = Policy::new().prefer_newer_by_time(true).select_preferred(&pool, &candidates);
+        assert_eq!(selected, vec![default, newest, older, untimed]);
+    }
+
     /// Port of Composer's testSelectLocalReposFirst
     /// Tests that root package aliases are preferred over other aliases
     #[test]
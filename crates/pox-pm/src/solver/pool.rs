@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::cell::RefCell;
 
 use crate::package::{AliasPackage, Package, Stability};
+use crate::util::is_platform_package;
+use super::request::Request;
 use pox_semver::{Constraint, ConstraintInterface, Operator, VersionParser};
 
 /// A literal represents a package decision in the SAT solver.
@@ -74,6 +76,24 @@ impl PoolEntry {
     }
 }
 
+/// Source of package metadata that a pool loads from on demand.
+///
+/// Repositories too large to load up front (like all of Packagist) implement this
+/// so the pool only holds the packages it is asked about.
+pub trait PackageProvider: Send {
+    /// Load every version of the package with the given (lowercase) name
+    fn load_packages(&mut self, name: &str) -> Vec<Package>;
+}
+
+impl<F> PackageProvider for F
+where
+    F: FnMut(&str) -> Vec<Package> + Send,
+{
+    fn load_packages(&mut self, name: &str) -> Vec<Package> {
+        self(name)
+    }
+}
+
 /// Pool of all available packages for dependency resolution.
 ///
 /// The pool indexes packages by ID (1-based) and by name for efficient lookup.
@@ -116,6 +136,12 @@ pub struct Pool {
     /// Per-package stability overrides (package name -> stability)
     /// Allows specific packages to have a lower stability than minimum_stability
     stability_flags: HashMap<String, Stability>,
+
+    /// Loads packages by name on demand, if the pool is lazy
+    provider: Option<Box<dyn PackageProvider>>,
+
+    /// Names already requested from the provider (lowercase)
+    provider_loaded: HashSet<String>,
 }
 
 impl std::fmt::Debug for Pool {
@@ -129,6 +155,7 @@ impl std::fmt::Debug for Pool {
             .field("alias_map", &self.alias_map)
             .field("minimum_stability", &self.minimum_stability)
             .field("stability_flags", &self.stability_flags)
+            .field("provider", &self.provider.is_some())
             .finish()
     }
 }
@@ -155,9 +182,78 @@ impl Pool {
             alias_map: HashMap::new(),
             minimum_stability,
             stability_flags: HashMap::new(),
+            provider: None,
+            provider_loaded: HashSet::new(),
+        }
+    }
+
+    /// Load packages from `provider` on demand instead of requiring them up front
+    pub fn set_package_provider(&mut self, provider: Box<dyn PackageProvider>) {
+        self.provider = Some(provider);
+    }
+
+    /// Load all versions of a package from the package provider, if it wasn't asked yet
+    ///
+    /// Each name is requested at most once; later calls hit the packages already in
+    /// the pool. Returns the IDs of the packages that were added.
+    pub fn load_package(&mut self, name: &str) -> Vec<PackageId> {
+        let name_lower = name.to_lowercase();
+        if is_platform_package(&name_lower) || self.provider.is_none() || !self.provider_loaded.insert(name_lower.clone()) {
+            return Vec::new();
+        }
+
+        let packages = self.provider.as_mut()
+            .map(|provider| provider.load_packages(&name_lower))
+            .unwrap_or_default();
+
+        packages.into_iter()
+            .map(|package| self.add_package(package))
+            .filter(|&id| id != 0)
+            .collect()
+    }
+
+    /// Load a set of packages and, transitively, everything they require
+    ///
+    /// The solver only sees packages already in the pool, so a lazy pool must load
+    /// the root requirements this way before solving.
+    pub fn load_requirements<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        let mut queue: Vec<String> = names.into_iter().map(|name| name.to_lowercase()).collect();
+
+        while let Some(name) = queue.pop() {
+            for id in self.load_package(&name) {
+                if let Some(package) = self.package(id) {
+                    queue.extend(package.require.keys()
+                        .map(|dep| dep.to_lowercase())
+                        .filter(|dep| !self.provider_loaded.contains(dep)));
+                }
+            }
         }
     }
 
+    /// Load everything a request refers to from the package provider before solving
+    ///
+    /// The solver only sees packages already in the pool, so this loads the required,
+    /// conflicting, locked and fixed packages and, transitively, their requirements.
+    /// Does nothing for a pool without a provider.
+    pub fn load_request(&mut self, request: &Request) {
+        if self.provider.is_none() {
+            return;
+        }
+
+        let names: Vec<String> = request.all_requires().map(|(name, _)| name.clone())
+            .chain(request.conflicts.keys().cloned())
+            .chain(request.promoted_suggestions.iter().cloned())
+            .chain(request.locked_packages.iter().chain(&request.fixed_packages).map(|package| package.name.to_lowercase()))
+            .collect();
+        self.load_requirements(names.iter().map(String::as_str));
+    }
+
+    /// Like [`Pool::what_provides`], but loads the package from the provider first
+    pub fn what_provides_lazy(&mut self, name: &str, constraint: Option<&str>) -> Vec<PackageId> {
+        self.load_package(name);
+        self.what_provides(name, constraint)
+    }
+
     /// Set the minimum stability for packages
     pub fn set_minimum_stability(&mut self, stability: Stability) {
         self.minimum_stability = stability;
@@ -235,6 +331,18 @@ impl Pool {
             return 0; // Package filtered out due to stability
         }
 
+        // `self.version` stands for the package's own version; resolve it so provider
+        // constraints can be matched against requirements
+        let has_self_version = package.provide.values().chain(package.replace.values())
+            .any(|constraint| constraint == "self.version");
+        let package = if has_self_version {
+            let mut package = Arc::unwrap_or_clone(package);
+            package.replace_self_version();
+            Arc::new(package)
+        } else {
+            package
+        };
+
         let id = self.packages.len() as PackageId;
         let name = package.name.to_lowercase();
 
@@ -431,6 +539,9 @@ impl Pool {
     }
 
     /// Get all packages with a given name
+    ///
+    /// Only packages actually named `name` are returned. Use [`Pool::providers_of`] for
+    /// packages that provide or replace it, or [`Pool::what_provides`] for both.
     pub fn packages_by_name(&self, name: &str) -> Vec<PackageId> {
         self.packages_by_name
             .get(&name.to_lowercase())
@@ -438,6 +549,17 @@ impl Pool {
             .unwrap_or_default()
     }
 
+    /// Get all packages that provide or replace a given name, without the packages
+    /// of that name itself
+    ///
+    /// This is how virtual packages like `psr/log-implementation` resolve.
+    pub fn providers_of(&self, name: &str) -> Vec<PackageId> {
+        self.providers
+            .get(&name.to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Find all packages that provide a given name (including the name itself)
     ///
     /// This includes:
@@ -587,17 +709,7 @@ impl Pool {
 
         let Some(parsed_provided) = parsed_provided else {
             // If provided looks like a version (not a constraint), try as exact version
-            let normalized_version = match parser.normalize(provided_constraint_str) {
-                Ok(v) => v,
-                Err(_) => provided_constraint_str.to_string(),
-            };
-
-            let version_constraint = match Constraint::new(Operator::Equal, normalized_version) {
-                Ok(c) => c,
-                Err(_) => return true,
-            };
-
-            return parsed_required.matches(&version_constraint);
+            return parsed_required.allows(provided_constraint_str);
         };
 
         // Check if the constraints intersect (have any overlap)
@@ -708,6 +820,9 @@ impl Pool {
     }
 
     /// Get all package IDs
+    ///
+    /// Only packages already in the pool are included; the package provider of a lazy
+    /// pool is never consulted.
     pub fn all_package_ids(&self) -> impl Iterator<Item = PackageId> + '_ {
         1..self.packages.len() as PackageId
     }
@@ -883,6 +998,117 @@ mod tests {
         assert_eq!(Pool::id_to_literal(5, false), -5);
     }
 
+    #[test]
+    fn test_package_provider_loads_on_demand() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut pool = Pool::new();
+        pool.add_package(Package::new("vendor/local", "1.0.0"));
+        pool.set_package_provider(Box::new(move |name: &str| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            match name {
+                "vendor/a" => vec![
+                    Package::new("vendor/a", "1.0.0"),
+                    Package::new("vendor/a", "2.0.0"),
+                    Package::new("vendor/a", "3.0.0-beta1"),
+                ],
+                _ => Vec::new(),
+            }
+        }));
+
+        // Iterating never loads anything from the provider
+        assert_eq!(pool.all_package_ids().count(), 1);
+        assert!(pool.what_provides("vendor/a", None).is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Unstable versions are filtered like any other package
+        assert_eq!(pool.what_provides_lazy("Vendor/A", None).len(), 2);
+        assert_eq!(pool.what_provides_lazy("vendor/a", Some("^2.0")).len(), 1);
+        assert!(pool.what_provides_lazy("vendor/missing", None).is_empty());
+        assert!(pool.what_provides_lazy("vendor/missing", None).is_empty());
+        assert!(pool.what_provides_lazy("php", None).is_empty());
+
+        // Every name is requested once, platform packages never
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.all_package_ids().count(), 3);
+    }
+
+    #[test]
+    fn test_load_requirements_is_transitive() {
+        let mut pool = Pool::new();
+        pool.set_package_provider(Box::new(|name: &str| {
+            let mut package = Package::new(name, "1.0.0");
+            match name {
+                "vendor/a" => { package.require.insert("vendor/b".to_string(), "^1.0".to_string()); }
+                "vendor/b" => { package.require.insert("Vendor/C".to_string(), "^1.0".to_string()); }
+                "vendor/c" => { package.require.insert("vendor/a".to_string(), "^1.0".to_string()); }
+                _ => return Vec::new(),
+            }
+            vec![package]
+        }));
+
+        pool.load_requirements(["vendor/a"]);
+
+        assert_eq!(pool.len(), 3);
+        for name in ["vendor/a", "vendor/b", "vendor/c"] {
+            assert_eq!(pool.what_provides(name, None).len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_load_request_before_solving() {
+        use crate::solver::{Policy, Solver};
+
+        let mut pool = Pool::new();
+        pool.set_package_provider(Box::new(|name: &str| {
+            let mut package = Package::new(name, "1.0.0");
+            match name {
+                "vendor/app" => { package.require.insert("vendor/lib".to_string(), "^1.0".to_string()); }
+                "vendor/lib" | "vendor/locked" => {}
+                _ => return Vec::new(),
+            }
+            vec![package]
+        }));
+
+        let mut request = Request::new();
+        request.require("vendor/app", "^1.0");
+        request.lock(Package::new("vendor/locked", "1.0.0"));
+        pool.load_request(&request);
+        assert_eq!(pool.len(), 3);
+
+        let policy = Policy::new();
+        let result = Solver::new(&pool, &policy).solve(&request).unwrap();
+        let mut names: Vec<&str> = result.packages.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["vendor/app", "vendor/lib"]);
+    }
+
+    #[test]
+    fn test_providers_of() {
+        let mut pool = Pool::new();
+
+        let mut monolog = Package::new("monolog/monolog", "3.5.0");
+        monolog.provide.insert("psr/log-implementation".to_string(), "3.0.0".to_string());
+        let monolog_id = pool.add_package(monolog);
+
+        let mut fork = Package::new("acme/log", "1.0.0");
+        fork.replace.insert("PSR/Log".to_string(), "self.version".to_string());
+        let fork_id = pool.add_package(fork);
+        let psr_log_id = pool.add_package(Package::new("psr/log", "3.0.0"));
+
+        // A virtual package has no packages of its own, only providers
+        assert!(pool.packages_by_name("psr/log-implementation").is_empty());
+        assert_eq!(pool.providers_of("psr/log-implementation"), vec![monolog_id]);
+        assert_eq!(pool.what_provides("psr/log-implementation", Some("^3.0")), vec![monolog_id]);
+
+        // Replacers are providers too, while the real package is not one of them
+        assert_eq!(pool.packages_by_name("psr/log"), vec![psr_log_id]);
+        assert_eq!(pool.providers_of("psr/log"), vec![fork_id]);
+        assert!(pool.providers_of("monolog/monolog").is_empty());
+    }
+
     #[test]
     fn test_pool_builder() {
         let pool = Pool::builder()
@@ -1016,6 +1242,23 @@ mod tests {
         assert_eq!(matches.len(), 3);
     }
 
+    #[test]
+    fn test_replace_self_version() {
+        let mut pool = Pool::new();
+
+        let mut framework = Package::new("laravel/framework", "10.5.0.0");
+        framework.pretty_version = Some("v10.5.0".to_string());
+        framework.replace.insert("illuminate/support".to_string(), "self.version".to_string());
+        framework.provide.insert("psr/container-implementation".to_string(), "1.1|2.0".to_string());
+        let id = pool.add_package(framework);
+
+        assert_eq!(pool.package(id).unwrap().replace["illuminate/support"], "=10.5.0.0");
+        assert_eq!(pool.what_provides("illuminate/support", Some("^10")), vec![id]);
+        assert_eq!(pool.what_provides("illuminate/support", Some("10.5.0")), vec![id]);
+        assert!(pool.what_provides("illuminate/support", Some("^9")).is_empty());
+        assert!(pool.what_provides("illuminate/support", Some("^10.6")).is_empty());
+    }
+
     #[test]
     fn test_provide_wildcard_version() {
         let mut pool = Pool::new();
@@ -192,6 +192,9 @@ impl<'a> PoolOptimizer<'a> {
         for (name, constraint) in request.all_requires() {
             self.extract_require_constraint(name, constraint);
         }
+        for (name, constraint) in &request.conflicts {
+            self.extract_conflict_constraint(name, constraint);
+        }
 
         // First pass over all packages to extract constraints and build alias map
         for id in pool.all_package_ids() {
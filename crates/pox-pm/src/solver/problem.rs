@@ -2,7 +2,7 @@ use std::fmt;
 
 use pox_semver::VersionParser;
 
-use super::pool::{Pool, PackageId};
+use super::pool::{Pool, PackageId, PoolEntry};
 use super::rule::{Rule, RuleType};
 use crate::util::is_platform_package;
 
@@ -57,9 +57,7 @@ impl Problem {
 
     /// Add a rule to this problem, resolving source package name from the pool
     pub fn add_rule_with_pool(&mut self, rule: &Rule, pool: &Pool) {
-        let source_name = rule.source_package()
-            .and_then(|id| pool.package(id))
-            .map(|p| p.pretty_string());
+        let source_name = rule.source_package().and_then(|id| pretty_string(pool, id));
 
         self.rules.push(ProblemRule {
             rule_id: rule.id(),
@@ -106,12 +104,19 @@ impl Default for Problem {
 fn get_source_name(rule: &ProblemRule, pool: &Pool) -> String {
     rule.source_name.clone().unwrap_or_else(|| {
         rule.source
-            .and_then(|id| pool.package(id))
-            .map(|p| p.pretty_string())
+            .and_then(|id| pretty_string(pool, id))
             .unwrap_or_else(|| "unknown".to_string())
     })
 }
 
+/// Name and version of a package or alias in the pool
+fn pretty_string(pool: &Pool, id: PackageId) -> Option<String> {
+    match pool.entry(id)? {
+        PoolEntry::Alias(alias) => Some(alias.pretty_string()),
+        PoolEntry::Package(package) => Some(package.pretty_string()),
+    }
+}
+
 /// Check if a version satisfies a constraint
 fn version_satisfies(version: &str, constraint: &str) -> bool {
     let parser = VersionParser::new();
@@ -122,6 +127,23 @@ fn version_satisfies(version: &str, constraint: &str) -> bool {
     }
 }
 
+/// Format packages as `name[version, ...]`, grouped by name in pool order
+fn format_packages(pool: &Pool, ids: &[PackageId]) -> String {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for pkg in ids.iter().filter_map(|&id| pool.package(id)) {
+        let version = pkg.pretty_version.as_deref().unwrap_or(&pkg.version).to_string();
+        match groups.iter_mut().find(|(name, _)| *name == pkg.name) {
+            Some((_, versions)) => versions.push(version),
+            None => groups.push((pkg.name.clone(), vec![version])),
+        }
+    }
+
+    groups.iter()
+        .map(|(name, versions)| format!("{}[{}]", name, versions.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Describe a problem rule in human-readable form
 fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
     match rule.rule_type {
@@ -131,6 +153,13 @@ fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
             let packages = pool.packages_by_name(target);
 
             if packages.is_empty() {
+                let providers = pool.providers_of(target);
+                if !providers.is_empty() {
+                    return format!(
+                        "Root composer.json requires {} {}, it could not be found in any version, but the following packages provide it: {}\n    Consider requiring one of these to satisfy the {} requirement.",
+                        target, constraint, format_packages(pool, &providers), target
+                    );
+                }
                 return format!(
                     "Root composer.json requires {} {}, but no matching package was found",
                     target, constraint
@@ -186,7 +215,13 @@ fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
                         let providers = pool.what_provides(dep_name, Some(dep_constraint));
                         if providers.is_empty() {
                             let available = pool.packages_by_name(dep_name);
-                            if available.is_empty() {
+                            let dep_providers = pool.providers_of(dep_name);
+                            if available.is_empty() && !dep_providers.is_empty() {
+                                reasons.push(format!(
+                                    "{} {} requires {} {} -> it is provided by {} but not in a matching version",
+                                    target, version, dep_name, dep_constraint, format_packages(pool, &dep_providers)
+                                ));
+                            } else if available.is_empty() {
                                 reasons.push(format!(
                                     "{} {} requires {} {} -> no matching package found",
                                     target, version, dep_name, dep_constraint
@@ -243,10 +278,26 @@ fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
                 )
             }
         }
+        RuleType::RootConflict => {
+            let source = get_source_name(rule, pool);
+            let target = rule.target.as_deref().unwrap_or("unknown");
+            let constraint = rule.constraint.as_deref().unwrap_or("*");
+            format!("Root composer.json conflicts with {} {}, so {} cannot be installed", target, constraint, source)
+        }
         RuleType::Fixed => {
             let source = get_source_name(rule, pool);
             format!("{} is fixed and cannot be changed", source)
         }
+        RuleType::Locked => {
+            let source = get_source_name(rule, pool);
+            let target = rule.target.as_deref().unwrap_or("unknown");
+            let constraint = rule.constraint.as_deref().unwrap_or("*");
+            format!(
+                "{} is locked to version {} and an update of this package was not requested, so {} cannot be installed. \
+                 Add it to the packages to update, or use --with-dependencies to update it along with them",
+                target, constraint, source
+            )
+        }
         RuleType::PackageRequires => {
             let source = get_source_name(rule, pool);
             let target = rule.target.as_deref().unwrap_or("unknown");
@@ -254,7 +305,20 @@ fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
 
             let target_packages = pool.packages_by_name(target);
             if target_packages.is_empty() {
-                format!("{} requires {} {} -> no matching package found", source, target, constraint)
+                let providers = pool.providers_of(target);
+                if providers.is_empty() {
+                    format!("{} requires {} {} -> no matching package found", source, target, constraint)
+                } else if pool.what_provides(target, Some(constraint)).is_empty() {
+                    format!(
+                        "{} requires {} {} -> it is provided by {} but not in a matching version",
+                        source, target, constraint, format_packages(pool, &providers)
+                    )
+                } else {
+                    format!(
+                        "{} requires {} {} -> provided by {}",
+                        source, target, constraint, format_packages(pool, &providers)
+                    )
+                }
             } else {
                 let providers = pool.what_provides(target, Some(constraint));
                 if providers.is_empty() {
@@ -277,11 +341,24 @@ fn describe_rule(pool: &Pool, rule: &ProblemRule) -> String {
             let target = rule.target.as_deref().unwrap_or("unknown");
             format!("{} conflicts with {}", source, target)
         }
-        RuleType::PackageSameName | RuleType::MultiConflict => {
-            "Only one version of a package can be installed".to_string()
+        RuleType::PackageSameName | RuleType::MultiConflict => match rule.target.as_deref() {
+            Some(target) => format!("Only one version of {} can be installed", target),
+            None => "Only one version of a package can be installed".to_string(),
+        },
+        RuleType::PackageReplaced => {
+            let target = rule.target.as_deref().unwrap_or("a package");
+            format!("Only one version of {} can be installed, counting packages that replace it", target)
         }
-        RuleType::PackageAlias | RuleType::PackageInverseAlias => {
-            "Package alias constraint".to_string()
+        RuleType::PackageAlias => {
+            let source = get_source_name(rule, pool);
+            match rule.target.as_deref() {
+                Some(target) => format!("{} is an alias of {} and is installed with it", source, target),
+                None => format!("{} is an alias of a package that cannot be installed", source),
+            }
+        }
+        RuleType::PackageInverseAlias => {
+            let source = get_source_name(rule, pool);
+            format!("{} is installed together with its aliases", source)
         }
         RuleType::Learned => {
             "Learned constraint from conflict analysis".to_string()
@@ -345,9 +422,89 @@ impl fmt::Display for ProblemSet {
     }
 }
 
+/// The problems of a failed solve, for callers that render or act on them themselves.
+///
+/// Problems refer to packages by pool ID, so each one is described against the pool
+/// while it is still around. The failure stays describable after the solver is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct SolveFailure {
+    problems: Vec<Problem>,
+    descriptions: Vec<String>,
+}
+
+impl SolveFailure {
+    /// Describe each problem of the set against the pool that was solved
+    pub fn new(problems: ProblemSet, pool: &Pool) -> Self {
+        let descriptions = problems.problems.iter().map(|p| p.describe(pool)).collect();
+        Self {
+            problems: problems.problems,
+            descriptions,
+        }
+    }
+
+    /// Check if there are any problems
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Get the number of problems
+    pub fn len(&self) -> usize {
+        self.problems.len()
+    }
+
+    /// Get the raw problems, with their rules and package names
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    /// Get the description of each problem, in the same order as [`SolveFailure::problems`]
+    pub fn descriptions(&self) -> &[String] {
+        &self.descriptions
+    }
+
+    /// Get the descriptions of the problems caused by a platform requirement (PHP, its
+    /// extensions or libraries) that the platform doesn't satisfy
+    pub fn platform_descriptions(&self) -> Vec<&str> {
+        self.problems.iter()
+            .zip(&self.descriptions)
+            .filter(|(problem, _)| problem.rules.iter().any(|rule| {
+                rule.target.as_deref().is_some_and(is_platform_package)
+            }))
+            .map(|(_, description)| description.as_str())
+            .collect()
+    }
+
+    /// Turn into problems whose message is their full description
+    pub fn into_described_problems(self) -> Vec<Problem> {
+        self.problems.into_iter()
+            .zip(self.descriptions)
+            .map(|(problem, description)| problem.with_message(description))
+            .collect()
+    }
+}
+
+impl fmt::Display for SolveFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.descriptions.is_empty() {
+            return write!(f, "No problems found");
+        }
+        for (i, description) in self.descriptions.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+            write!(f, "Problem {}:\n{}", i + 1, description)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SolveFailure {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::package::Package;
 
     #[test]
     fn test_problem_new() {
@@ -381,6 +538,95 @@ mod tests {
         assert!(description.contains("^1.0"));
     }
 
+    #[test]
+    fn test_problem_describe_virtual_package() {
+        let mut pool = Pool::new();
+        let mut monolog = Package::new("monolog/monolog", "3.5.0");
+        monolog.provide.insert("psr/log-implementation".to_string(), "3.0.0".to_string());
+        let monolog_id = pool.add_package(monolog);
+        let mut app = Package::new("acme/app", "1.0.0");
+        app.require.insert("psr/log-implementation".to_string(), "^1.0".to_string());
+        let app_id = pool.add_package(app);
+
+        let mut problem = Problem::new();
+        problem.add_rule(&Rule::root_require(vec![])
+            .with_target("psr/log-implementation")
+            .with_constraint("*"));
+        problem.add_rule_with_pool(&Rule::requires(app_id, vec![])
+            .with_source(app_id)
+            .with_target("psr/log-implementation")
+            .with_constraint("^1.0"), &pool);
+        problem.add_rule_with_pool(&Rule::requires(app_id, vec![monolog_id])
+            .with_source(app_id)
+            .with_target("psr/log-implementation")
+            .with_constraint("^3.0"), &pool);
+
+        let description = problem.describe(&pool);
+        assert!(description.contains(
+            "Root composer.json requires psr/log-implementation *, it could not be found in any version, but the following packages provide it: monolog/monolog[3.5.0]"
+        ), "{}", description);
+        assert!(description.contains(
+            "acme/app 1.0.0 requires psr/log-implementation ^1.0 -> it is provided by monolog/monolog[3.5.0] but not in a matching version"
+        ), "{}", description);
+        assert!(description.contains(
+            "acme/app 1.0.0 requires psr/log-implementation ^3.0 -> provided by monolog/monolog[3.5.0]"
+        ), "{}", description);
+    }
+
+    #[test]
+    fn test_solve_failure_outlives_pool() {
+        let failure = {
+            let mut pool = Pool::new();
+            let app_id = pool.add_package(Package::new("acme/app", "1.0.0"));
+            pool.add_package(Package::new("acme/lib", "1.0.0"));
+
+            let mut problem = Problem::new();
+            problem.add_rule_with_pool(&Rule::requires(app_id, vec![])
+                .with_source(app_id)
+                .with_target("acme/lib")
+                .with_constraint("^2.0"), &pool);
+            let mut problems = ProblemSet::new();
+            problems.add(problem);
+            SolveFailure::new(problems, &pool)
+        };
+
+        assert_eq!(failure.len(), 1);
+        assert_eq!(failure.problems()[0].rules[0].source_name.as_deref(), Some("acme/app 1.0.0"));
+        assert_eq!(failure.problems()[0].rules[0].target.as_deref(), Some("acme/lib"));
+        assert!(failure.descriptions()[0].contains("acme/app 1.0.0 requires acme/lib ^2.0"), "{}", failure);
+        assert!(failure.to_string().starts_with("Problem 1:\n"));
+
+        let described = failure.into_described_problems();
+        assert!(described[0].message.as_deref().unwrap().contains("acme/lib ^2.0"));
+    }
+
+    #[test]
+    fn test_solve_failure_platform_descriptions() {
+        use crate::solver::{Policy, Request, Solver};
+
+        let mut pool = Pool::new();
+        pool.add_platform_package(Package::new("php", "7.4.0"));
+        let mut modern = Package::new("acme/modern", "2.0.0");
+        modern.require.insert("php".to_string(), ">=8.1".to_string());
+        pool.add_package(modern);
+        let mut legacy = Package::new("acme/legacy", "1.0.0");
+        legacy.require.insert("acme/missing".to_string(), "^1.0".to_string());
+        pool.add_package(legacy);
+
+        let policy = Policy::new();
+        let mut request = Request::new();
+        request.require("acme/modern", "^2.0");
+        let failure = Solver::new(&pool, &policy).solve(&request).unwrap_err();
+        let platform = failure.platform_descriptions();
+        assert_eq!(platform.len(), 1);
+        assert!(platform[0].contains("php"), "{}", failure);
+
+        let mut request = Request::new();
+        request.require("acme/legacy", "^1.0");
+        let failure = Solver::new(&pool, &policy).solve(&request).unwrap_err();
+        assert!(failure.platform_descriptions().is_empty(), "{}", failure);
+    }
+
     #[test]
     fn test_problem_set() {
         let mut problems = ProblemSet::new();
@@ -0,0 +1,255 @@
+//! Suggest loosening root constraints after a failed solve.
+//!
+//! Candidates are the root requirements involved in the problems. Each one is widened
+//! with a range covering versions it currently excludes, newest first, and the request is
+//! solved again. This is expensive, so callers only run it when asked to.
+
+use std::fmt;
+
+use pox_semver::{Comparator, VersionParser};
+
+use super::policy::Policy;
+use super::pool::Pool;
+use super::problem::SolveFailure;
+use super::request::Request;
+use super::solver::Solver;
+
+/// Upper bound on the number of solves one suggestion may run
+const MAX_ATTEMPTS: usize = 32;
+
+/// Upper bound on the number of constraints one suggestion may change
+const MAX_RELAXATIONS: usize = 3;
+
+/// A root constraint and the wider one to replace it with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relaxation {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl fmt::Display for Relaxation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "relax {}:{} to {}", self.name, self.from, self.to)
+    }
+}
+
+/// Outcome of looking for relaxations that make a failed request solvable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelaxationAdvice {
+    /// Relaxing this one constraint is enough
+    Single(Relaxation),
+    /// The problems are independent and all of these need relaxing together
+    Combined(Vec<Relaxation>),
+    /// No tried relaxation makes the request solvable
+    NotFound,
+}
+
+/// Find the smallest change to the root constraints implicated in `failure` that lets
+/// `request` be solved against `pool`
+///
+/// Every candidate relaxation of the implicated requirements is tried on its own first.
+/// When none is enough, the first one that moves the failure to other requirements is
+/// kept and the search continues from the new failure, up to [`MAX_RELAXATIONS`] changes
+/// and [`MAX_ATTEMPTS`] solves.
+pub fn suggest_relaxations(pool: &Pool, policy: &Policy, request: &Request, failure: &SolveFailure) -> RelaxationAdvice {
+    let mut request = request.clone();
+    let mut failure = failure.clone();
+    let mut applied: Vec<Relaxation> = Vec::new();
+    let mut attempts = 0;
+
+    while applied.len() < MAX_RELAXATIONS {
+        let candidates: Vec<Relaxation> = implicated_requirements(&request, &failure)
+            .into_iter()
+            .flat_map(|(name, constraint)| candidate_relaxations(pool, &name, &constraint))
+            .collect();
+
+        let mut next = None;
+        for relaxation in candidates {
+            if attempts == MAX_ATTEMPTS {
+                return RelaxationAdvice::NotFound;
+            }
+            attempts += 1;
+
+            let relaxed = relax(&request, &relaxation);
+            match Solver::new(pool, policy).solve(&relaxed) {
+                Ok(_) => {
+                    applied.push(relaxation);
+                    return match applied.len() {
+                        1 => RelaxationAdvice::Single(applied.remove(0)),
+                        _ => RelaxationAdvice::Combined(applied),
+                    };
+                }
+                Err(remaining) => {
+                    let moved_on = !implicated_requirements(&relaxed, &remaining)
+                        .iter()
+                        .any(|(name, _)| *name == relaxation.name);
+                    if next.is_none() && moved_on {
+                        next = Some((relaxation, relaxed, remaining));
+                    }
+                }
+            }
+        }
+
+        let Some((relaxation, relaxed, remaining)) = next else {
+            return RelaxationAdvice::NotFound;
+        };
+        applied.push(relaxation);
+        request = relaxed;
+        failure = remaining;
+    }
+
+    RelaxationAdvice::NotFound
+}
+
+fn relax(request: &Request, relaxation: &Relaxation) -> Request {
+    let mut relaxed = request.clone();
+    for requires in [&mut relaxed.requires, &mut relaxed.dev_requires] {
+        if let Some(constraint) = requires.get_mut(&relaxation.name) {
+            constraint.clone_from(&relaxation.to);
+        }
+    }
+    relaxed
+}
+
+/// Root requirements whose package is the target or the source of a problem rule
+fn implicated_requirements(request: &Request, failure: &SolveFailure) -> Vec<(String, String)> {
+    let names: Vec<String> = failure.problems().iter()
+        .flat_map(|problem| &problem.rules)
+        .flat_map(|rule| {
+            let source = rule.source_name.as_deref().and_then(|name| name.split(' ').next());
+            rule.target.as_deref().into_iter().chain(source)
+        })
+        .map(|name| name.to_lowercase())
+        .collect();
+
+    let mut implicated: Vec<(String, String)> = Vec::new();
+    for (name, constraint) in request.requires.iter().chain(request.dev_requires.iter()) {
+        if names.contains(&name.to_lowercase()) && !implicated.iter().any(|(n, _)| n == name) {
+            implicated.push((name.clone(), constraint.clone()));
+        }
+    }
+    implicated
+}
+
+/// Widen `constraint` with one range per major version (minor for 0.x) of the versions
+/// in the pool it doesn't allow, newest range first
+fn candidate_relaxations(pool: &Pool, name: &str, constraint: &str) -> Vec<Relaxation> {
+    let parser = VersionParser::new();
+    let Ok(parsed) = parser.parse_constraints_cached(constraint) else {
+        return Vec::new();
+    };
+
+    let mut versions: Vec<String> = pool.packages_by_name(name).into_iter()
+        .filter_map(|id| pool.package(id))
+        .filter(|package| !package.version.starts_with("dev-"))
+        .map(|package| package.version.clone())
+        .collect();
+    versions.sort_by(|a, b| {
+        if Comparator::compare(a, ">", b) {
+            std::cmp::Ordering::Less
+        } else if Comparator::compare(a, "<", b) {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    });
+    let lowest_allowed = versions.iter().rev().find(|version| parsed.satisfies(version));
+
+    let mut relaxations: Vec<Relaxation> = Vec::new();
+    for version in versions.iter().filter(|version| !parsed.satisfies(version)) {
+        let mut parts = version.split('.');
+        let major = parts.next().unwrap_or("0");
+        let minor = parts.next().unwrap_or("0");
+        let range = if major == "0" { format!("^0.{}", minor) } else { format!("^{}.0", major) };
+
+        // Keep the ranges in ascending order, like they would be written by hand
+        let to = match lowest_allowed {
+            Some(lowest) if Comparator::compare(version, "<", lowest) => format!("{} || {}", range, constraint),
+            _ => format!("{} || {}", constraint, range),
+        };
+        if !relaxations.iter().any(|relaxation| relaxation.to == to) {
+            relaxations.push(Relaxation {
+                name: name.to_string(),
+                from: constraint.to_string(),
+                to,
+            });
+        }
+    }
+    relaxations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Package;
+
+    fn pkg(name: &str, version: &str, requires: &[(&str, &str)]) -> Package {
+        let mut package = Package::new(name, version);
+        for (target, constraint) in requires {
+            package.require.insert(target.to_string(), constraint.to_string());
+        }
+        package
+    }
+
+    fn advice(pool: &Pool, request: &Request) -> RelaxationAdvice {
+        let policy = Policy::new();
+        let failure = Solver::new(pool, &policy).solve(request).unwrap_err();
+        suggest_relaxations(pool, &policy, request, &failure)
+    }
+
+    #[test]
+    fn test_single_relaxation() {
+        let mut pool = Pool::new();
+        pool.add_package(pkg("acme/a", "1.5.0", &[]));
+        pool.add_package(pkg("acme/a", "2.0.0", &[]));
+        pool.add_package(pkg("acme/b", "1.0.0", &[("acme/a", "^1.0")]));
+
+        let mut request = Request::new();
+        request.require("acme/a", "^2.0");
+        request.require("acme/b", "^1.0");
+
+        assert_eq!(advice(&pool, &request), RelaxationAdvice::Single(Relaxation {
+            name: "acme/a".to_string(),
+            from: "^2.0".to_string(),
+            to: "^1.0 || ^2.0".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_independent_conflicts_need_combined_relaxation() {
+        let mut pool = Pool::new();
+        pool.add_package(pkg("acme/a", "1.0.0", &[]));
+        pool.add_package(pkg("acme/a", "2.0.0", &[]));
+        pool.add_package(pkg("acme/b", "1.0.0", &[("acme/a", "^1.0")]));
+        pool.add_package(pkg("acme/c", "1.0.0", &[]));
+        pool.add_package(pkg("acme/c", "3.0.0", &[]));
+        pool.add_package(pkg("acme/d", "1.0.0", &[("acme/c", "^3.0")]));
+
+        let mut request = Request::new();
+        request.require("acme/a", "^2.0");
+        request.require("acme/b", "^1.0");
+        request.require("acme/c", "^1.0");
+        request.require("acme/d", "^1.0");
+
+        let RelaxationAdvice::Combined(relaxations) = advice(&pool, &request) else {
+            panic!("expected a combined relaxation");
+        };
+        let relaxations: Vec<String> = relaxations.iter().map(|r| r.to_string()).collect();
+        assert_eq!(relaxations, vec![
+            "relax acme/a:^2.0 to ^1.0 || ^2.0",
+            "relax acme/c:^1.0 to ^1.0 || ^3.0",
+        ]);
+    }
+
+    #[test]
+    fn test_no_relaxation_for_missing_package() {
+        let mut pool = Pool::new();
+        pool.add_package(pkg("acme/a", "1.0.0", &[("acme/missing", "^1.0")]));
+
+        let mut request = Request::new();
+        request.require("acme/a", "^1.0");
+
+        assert_eq!(advice(&pool, &request), RelaxationAdvice::NotFound);
+    }
+}
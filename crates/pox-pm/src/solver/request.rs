@@ -1,8 +1,23 @@
 use std::sync::Arc;
 
 use indexmap::IndexMap;
-
-use crate::package::Package;
+use pox_semver::VersionParser;
+
+use crate::json::{ComposerJson, ComposerLock};
+use crate::package::{Package, Stability};
+use crate::util::is_platform_package;
+
+/// Which packages besides the listed ones a partial update may change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateAllowTransitive {
+    /// Only the listed packages
+    #[default]
+    ListedOnly,
+    /// Also their dependencies, unless required by the root package (`--with-dependencies`)
+    DependenciesNoRootRequire,
+    /// Also all their dependencies and the packages depending on them (`--with-all-dependencies`)
+    All,
+}
 
 /// A request specifies what needs to be resolved.
 ///
@@ -17,6 +32,13 @@ pub struct Request {
     /// Uses IndexMap to preserve insertion order
     pub dev_requires: IndexMap<String, String>,
 
+    /// Platform requirements (php, ext-*, lib-*) -> constraint
+    /// These are checked against the platform rather than resolved
+    pub platform_requires: IndexMap<String, String>,
+
+    /// Root `conflict` constraints (name -> constraint), versions the solver must never select
+    pub conflicts: IndexMap<String, String>,
+
     /// Fixed packages that cannot be changed (e.g., platform packages)
     pub fixed_packages: Vec<Arc<Package>>,
 
@@ -26,6 +48,13 @@ pub struct Request {
     /// Packages that must be updated (for partial updates)
     pub update_allowlist: Vec<String>,
 
+    /// Suggested packages to require when a present package suggests them (lowercase names)
+    pub promoted_suggestions: Vec<String>,
+
+    /// Per-package minimum stability from root requirements like `^1.0@dev`
+    /// (name -> stability), overriding the global minimum stability for that package only
+    pub stability_flags: IndexMap<String, Stability>,
+
     /// Whether this is a dev install
     pub install_dev: bool,
 
@@ -42,15 +71,69 @@ impl Request {
         Self {
             requires: IndexMap::new(),
             dev_requires: IndexMap::new(),
+            platform_requires: IndexMap::new(),
+            conflicts: IndexMap::new(),
             fixed_packages: Vec::new(),
             locked_packages: Vec::new(),
             update_allowlist: Vec::new(),
+            promoted_suggestions: Vec::new(),
+            stability_flags: IndexMap::new(),
             install_dev: true,
             prefer_stable: true,
             prefer_lowest: false,
         }
     }
 
+    /// Create a request for a project's composer.json and lock file
+    ///
+    /// Requirements are added sorted by name for a deterministic solve, with `require-dev`
+    /// included when `dev` is set. Platform packages go to `platform_requires` and the root
+    /// `conflict` map to `conflicts`. All packages
+    /// of the lock file are locked; nothing is fixed. Stability flags are taken from both
+    /// `require` and `require-dev`, like Composer does.
+    pub fn from_project(composer_json: &ComposerJson, lock: Option<&ComposerLock>, dev: bool) -> Self {
+        let mut request = Self::new();
+        request.with_dev(dev);
+        request.stability_flags = root_stability_flags(composer_json);
+
+        let mut requires: Vec<_> = composer_json.require.iter().collect();
+        requires.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, constraint) in requires {
+            if is_platform_package(name) {
+                request.require_platform(name, constraint);
+            } else {
+                request.require(name, constraint);
+            }
+        }
+
+        if dev {
+            let mut dev_requires: Vec<_> = composer_json.require_dev.iter().collect();
+            dev_requires.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, constraint) in dev_requires {
+                if is_platform_package(name) {
+                    request.require_platform(name, constraint);
+                } else {
+                    request.require_dev(name, constraint);
+                }
+            }
+        }
+
+        let mut conflicts: Vec<_> = composer_json.conflict.iter().collect();
+        conflicts.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, constraint) in conflicts {
+            request.conflict(name, constraint);
+        }
+
+        if let Some(lock) = lock {
+            let dev_packages = if dev { lock.packages_dev.as_slice() } else { &[] };
+            for locked in lock.packages.iter().chain(dev_packages) {
+                request.lock(Package::from(locked));
+            }
+        }
+
+        request
+    }
+
     /// Add a requirement
     pub fn require(&mut self, name: impl Into<String>, constraint: impl Into<String>) -> &mut Self {
         self.requires.insert(name.into().to_lowercase(), constraint.into());
@@ -63,6 +146,18 @@ impl Request {
         self
     }
 
+    /// Add a platform requirement
+    pub fn require_platform(&mut self, name: impl Into<String>, constraint: impl Into<String>) -> &mut Self {
+        self.platform_requires.insert(name.into().to_lowercase(), constraint.into());
+        self
+    }
+
+    /// Forbid all versions of a package matching a constraint
+    pub fn conflict(&mut self, name: impl Into<String>, constraint: impl Into<String>) -> &mut Self {
+        self.conflicts.insert(name.into().to_lowercase(), constraint.into());
+        self
+    }
+
     /// Add a fixed package (cannot be changed)
     pub fn fix(&mut self, package: Package) -> &mut Self {
         self.fixed_packages.push(Arc::new(package));
@@ -75,6 +170,46 @@ impl Request {
         self
     }
 
+    /// Install a suggested package as if required, once a present package suggests it
+    pub fn promote_suggestion(&mut self, name: &str) -> &mut Self {
+        let name = name.to_lowercase();
+        if !self.promoted_suggestions.contains(&name) {
+            self.promoted_suggestions.push(name);
+        }
+        self
+    }
+
+    /// Require the promoted suggestions made by any of `packages`
+    ///
+    /// Platform packages cannot be installed and names that are already required are left
+    /// alone. Returns the names that were added, in promotion order.
+    pub fn apply_promoted_suggestions<'a>(&mut self, packages: impl IntoIterator<Item = &'a Package>) -> Vec<String> {
+        let suggested: std::collections::HashSet<String> = packages
+            .into_iter()
+            .flat_map(|p| p.suggest.keys().map(|name| name.to_lowercase()))
+            .collect();
+
+        let promoted: Vec<String> = self
+            .promoted_suggestions
+            .iter()
+            .filter(|name| suggested.contains(*name))
+            .filter(|name| !crate::util::is_platform_package(name))
+            .filter(|name| !self.requires.contains_key(*name) && !self.dev_requires.contains_key(*name))
+            .cloned()
+            .collect();
+
+        for name in &promoted {
+            self.require(name, "*");
+        }
+        promoted
+    }
+
+    /// Set the minimum stability of one package
+    pub fn stability_flag(&mut self, name: &str, stability: Stability) -> &mut Self {
+        self.stability_flags.insert(name.to_lowercase(), stability);
+        self
+    }
+
     /// Set packages to update (partial update)
     pub fn update(&mut self, packages: Vec<String>) -> &mut Self {
         self.update_allowlist = packages.into_iter().map(|s| s.to_lowercase()).collect();
@@ -137,6 +272,39 @@ impl Request {
     }
 }
 
+/// Extract the stability flags of the root requirements, sorted by package name
+///
+/// See [`VersionParser::extract_stability_flag`]; when `require` and `require-dev` both
+/// flag a package, the least stable flag wins.
+pub fn root_stability_flags(composer_json: &ComposerJson) -> IndexMap<String, Stability> {
+    let parser = VersionParser::new();
+    let minimum_stability = composer_json
+        .minimum_stability
+        .as_deref()
+        .and_then(|s| VersionParser::normalize_stability(s).ok())
+        .unwrap_or(pox_semver::Stability::Stable);
+
+    let mut requires: Vec<_> = composer_json
+        .require
+        .iter()
+        .chain(composer_json.require_dev.iter())
+        .map(|(name, constraint)| (name.to_lowercase(), constraint))
+        .collect();
+    requires.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut flags: IndexMap<String, Stability> = IndexMap::new();
+    for (name, constraint) in requires {
+        if let Some(flag) = parser.extract_stability_flag(constraint, minimum_stability) {
+            let flag: Stability = flag.as_str().parse().unwrap_or_default();
+            let entry = flags.entry(name).or_insert(flag);
+            if flag.priority() > entry.priority() {
+                *entry = flag;
+            }
+        }
+    }
+    flags
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +348,80 @@ mod tests {
         assert!(!request.is_fixed("ext-json"));
     }
 
+    #[test]
+    fn test_request_from_project() {
+        let composer_json: ComposerJson = serde_json::from_value(serde_json::json!({
+            "require": {"vendor/b": "^1.0", "php": ">=8.1", "vendor/a": "^2.0", "ext-json": "*"},
+            "require-dev": {"vendor/dev": "^3.0", "ext-xdebug": "*"}
+        }))
+        .unwrap();
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [{"name": "vendor/a", "version": "2.1.0"}],
+            "packages-dev": [{"name": "vendor/dev", "version": "3.0.0"}]
+        }))
+        .unwrap();
+
+        let request = Request::from_project(&composer_json, Some(&lock), true);
+        assert_eq!(request.requires.keys().collect::<Vec<_>>(), vec!["vendor/a", "vendor/b"]);
+        assert_eq!(request.dev_requires.keys().collect::<Vec<_>>(), vec!["vendor/dev"]);
+        assert_eq!(
+            request.platform_requires.keys().collect::<Vec<_>>(),
+            vec!["ext-json", "php", "ext-xdebug"]
+        );
+        assert!(request.get_locked("vendor/dev").is_some());
+        assert!(request.fixed_packages.is_empty());
+
+        let request = Request::from_project(&composer_json, Some(&lock), false);
+        assert!(!request.install_dev);
+        assert!(request.dev_requires.is_empty());
+        assert!(!request.platform_requires.contains_key("ext-xdebug"));
+        assert!(request.get_locked("vendor/a").is_some());
+        assert!(request.get_locked("vendor/dev").is_none());
+    }
+
+    #[test]
+    fn test_root_stability_flags() {
+        let composer_json: ComposerJson = serde_json::from_value(serde_json::json!({
+            "require": {"vendor/b": "^1.0@dev", "vendor/a": "^2.0", "vendor/c": "2.0.0-beta1 as 1.9.0", "vendor/d": "^1.0@beta"},
+            "require-dev": {"Vendor/D": "dev-main", "vendor/e": "^3.0@stable"}
+        }))
+        .unwrap();
+
+        let flags = root_stability_flags(&composer_json);
+        assert_eq!(
+            flags.keys().collect::<Vec<_>>(),
+            vec!["vendor/b", "vendor/c", "vendor/d", "vendor/e"]
+        );
+        assert_eq!(flags["vendor/b"], Stability::Dev);
+        assert_eq!(flags["vendor/c"], Stability::Beta);
+        // The least stable of require and require-dev wins
+        assert_eq!(flags["vendor/d"], Stability::Dev);
+        // Explicit flags are kept even when they match the minimum stability
+        assert_eq!(flags["vendor/e"], Stability::Stable);
+
+        let request = Request::from_project(&composer_json, None, false);
+        assert_eq!(request.stability_flags, flags);
+    }
+
+    #[test]
+    fn test_apply_promoted_suggestions() {
+        let mut request = Request::new();
+        request.require("acme/app-lib", "^1.0");
+        request.promote_suggestion("Acme/Redis").promote_suggestion("acme/app-lib").promote_suggestion("ext-redis");
+        request.promote_suggestion("acme/unsuggested");
+
+        let mut lib = Package::new("acme/lib", "1.0.0");
+        lib.suggest.insert("acme/redis".to_string(), "For caching".to_string());
+        lib.suggest.insert("acme/app-lib".to_string(), String::new());
+        lib.suggest.insert("ext-redis".to_string(), String::new());
+
+        assert_eq!(request.apply_promoted_suggestions([&lib]), vec!["acme/redis"]);
+        assert_eq!(request.requires.get("acme/redis").map(String::as_str), Some("*"));
+        assert_eq!(request.requires.get("acme/app-lib").map(String::as_str), Some("^1.0"));
+        assert!(!request.requires.contains_key("acme/unsuggested"));
+    }
+
     #[test]
     fn test_request_update_allowlist() {
         let mut request = Request::new();
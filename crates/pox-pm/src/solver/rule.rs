@@ -11,8 +11,12 @@ pub type Literal = i32;
 pub enum RuleType {
     /// Root composer.json requirement
     RootRequire,
+    /// Root composer.json conflict: the package must not be installed
+    RootConflict,
     /// Fixed package that must stay installed (e.g., platform packages)
     Fixed,
+    /// Locked package a partial update may not change: other versions must not be installed
+    Locked,
     /// Package dependency: if A is installed, then B|C|D must be installed
     PackageRequires,
     /// Package conflict: A and B cannot both be installed
@@ -22,6 +26,8 @@ pub enum RuleType {
     /// Multiple versions of same package: at most one can be installed (n-ary multi-conflict)
     /// This is more efficient than O(n²) binary conflicts for packages with many versions
     MultiConflict,
+    /// A package and the packages replacing it: at most one can be installed (n-ary multi-conflict)
+    PackageReplaced,
     /// Alias must require its target
     PackageAlias,
     /// Target must require its alias
@@ -34,11 +40,12 @@ impl RuleType {
     /// Get the priority of this rule type (lower = higher priority)
     pub fn priority(&self) -> u8 {
         match self {
-            RuleType::RootRequire | RuleType::Fixed => 1, // Request rules
+            RuleType::RootRequire | RuleType::RootConflict | RuleType::Fixed | RuleType::Locked => 1, // Request rules
             RuleType::PackageRequires
             | RuleType::PackageConflict
             | RuleType::PackageSameName
             | RuleType::MultiConflict
+            | RuleType::PackageReplaced
             | RuleType::PackageAlias
             | RuleType::PackageInverseAlias => 0, // Package rules
             RuleType::Learned => 4,
@@ -47,7 +54,7 @@ impl RuleType {
 
     /// Check if this is a multi-conflict rule type
     pub fn is_multi_conflict(&self) -> bool {
-        matches!(self, RuleType::MultiConflict)
+        matches!(self, RuleType::MultiConflict | RuleType::PackageReplaced)
     }
 }
 
@@ -126,6 +133,13 @@ impl Rule {
         Self::new(literals, RuleType::MultiConflict)
     }
 
+    /// Create a replace rule: at most one of a package's versions and the packages
+    /// replacing it can be installed
+    pub fn replaced(packages: Vec<PackageId>) -> Self {
+        let literals: Vec<_> = packages.into_iter().map(|p| -p).collect();
+        Self::new(literals, RuleType::PackageReplaced)
+    }
+
     /// Check if this is a multi-conflict rule
     pub fn is_multi_conflict(&self) -> bool {
         self.rule_type.is_multi_conflict()
@@ -136,11 +150,21 @@ impl Rule {
         Self::new(targets, RuleType::RootRequire)
     }
 
+    /// Create a root conflict rule: the package must not be installed
+    pub fn root_conflict(package: PackageId) -> Self {
+        Self::assertion(-package, RuleType::RootConflict)
+    }
+
     /// Create a fixed package rule
     pub fn fixed(package: PackageId) -> Self {
         Self::assertion(package, RuleType::Fixed)
     }
 
+    /// Create a rule forbidding a version of a package that is locked to another version
+    pub fn locked(package: PackageId) -> Self {
+        Self::assertion(-package, RuleType::Locked)
+    }
+
     /// Create a learned rule from conflict analysis
     pub fn learned(literals: Vec<Literal>) -> Self {
         Self::new(literals, RuleType::Learned)
@@ -288,11 +312,14 @@ impl Rule {
     fn rule_type_str(&self) -> &'static str {
         match self.rule_type {
             RuleType::RootRequire => "root-require",
+            RuleType::RootConflict => "root-conflict",
             RuleType::Fixed => "fixed",
+            RuleType::Locked => "locked",
             RuleType::PackageRequires => "requires",
             RuleType::PackageConflict => "conflict",
             RuleType::PackageSameName => "same-name",
             RuleType::MultiConflict => "multi-conflict",
+            RuleType::PackageReplaced => "replaced",
             RuleType::PackageAlias => "alias",
             RuleType::PackageInverseAlias => "inverse-alias",
             RuleType::Learned => "learned",
@@ -107,6 +107,14 @@ impl<'a> RuleGenerator<'a> {
         self.add_provider_conflict_rules();
         log::debug!("After provider conflict rules: {} rules", self.rules.len());
 
+        // Forbid the versions the root package conflicts with
+        self.add_root_conflict_rules(request);
+        log::debug!("After root conflict rules: {} rules", self.rules.len());
+
+        // Keep locked packages a partial update may not touch at their locked version
+        self.add_locked_rules(request);
+        log::debug!("After locked rules: {} rules", self.rules.len());
+
         log::info!("Rule generation stats: {} packages processed, {} unique package names, {} provider names tracked in {:?}",
             self.added_packages.len(),
             self.added_packages_by_name.len(),
@@ -198,16 +206,28 @@ impl<'a> RuleGenerator<'a> {
         // Check if this is an alias - if so, add alias-specific rules
         if let Some(entry) = self.pool.entry(package_id) {
             if let PoolEntry::Alias(alias) = entry {
-                // If alias is installed, base package must be installed
+                // An alias and its base are the same install: deciding one decides the other
                 if let Some(base_id) = self.pool.get_alias_base(package_id) {
-                    // Rule: alias -> base (if alias is installed, base must be installed)
-                    let rule = Rule::requires(package_id, vec![base_id])
+                    let base_name = self.pool.package(base_id)
+                        .map(|base| base.pretty_string())
+                        .unwrap_or_else(|| alias.name().to_string());
+                    let rule = Rule::new(vec![-package_id, base_id], RuleType::PackageAlias)
                         .with_source(package_id)
+                        .with_target(base_name);
+                    self.rules.add(rule);
+
+                    let rule = Rule::new(vec![-base_id, package_id], RuleType::PackageInverseAlias)
+                        .with_source(base_id)
                         .with_target(alias.name());
                     self.rules.add(rule);
 
                     // Also process the base package's rules
                     self.add_package_rules(base_id);
+                } else {
+                    // The base didn't make it into the pool (e.g. filtered by stability)
+                    let rule = Rule::new(vec![-package_id], RuleType::PackageAlias)
+                        .with_source(package_id);
+                    self.rules.add(rule);
                 }
 
                 // Process alias dependencies (may differ from base due to self.version replacement)
@@ -288,16 +308,20 @@ impl<'a> RuleGenerator<'a> {
             // Composer behavior: providers/replacers are only auto-selected if:
             // 1. There's also a direct package available, OR
             // 2. The dependency name is explicitly required by the user (root requirement)
-            //    or provided/replaced by a root-required package
+            //    or provided/replaced by a root-required package, OR
+            // 3. The dependency is a platform package, which polyfills may provide
+            //    when the extension itself is missing
             let direct_providers = self.pool.what_provides_direct_only(dep_name, Some(constraint));
             let has_direct = !direct_providers.is_empty();
             let is_root_required = self.root_required_names.contains(&dep_name.to_lowercase());
+            let is_platform = is_platform_package(dep_name);
 
             // Get all providers (direct + provide/replace)
             let all_providers = self.pool.what_provides(dep_name, Some(constraint));
 
-            // Include providers/replacers if there's a direct package OR this is a root requirement
-            let providers = if has_direct || is_root_required {
+            // Include providers/replacers if there's a direct package, this is a root requirement
+            // or a platform requirement
+            let providers = if has_direct || is_root_required || is_platform {
                 // Include all providers
                 all_providers
             } else {
@@ -369,11 +393,20 @@ impl<'a> RuleGenerator<'a> {
                 continue;
             }
 
+            // Packages replacing the name are grouped under it too, and may not be
+            // installed next to any of its versions
+            let has_replacers = non_alias_versions.iter().any(|&id| {
+                self.pool.package(id).is_some_and(|package| package.name.to_lowercase() != name)
+            });
+
             // Use a single multi-conflict rule instead of O(n²) pairwise conflicts
             // This is much more efficient for packages with many versions
-            let rule = Rule::multi_conflict(non_alias_versions)
-                .with_target(name);
-            self.rules.add(rule);
+            let rule = if has_replacers {
+                Rule::replaced(non_alias_versions)
+            } else {
+                Rule::multi_conflict(non_alias_versions)
+            };
+            self.rules.add(rule.with_target(name));
         }
     }
 
@@ -449,6 +482,68 @@ impl<'a> RuleGenerator<'a> {
             conflict_count, skipped_not_added);
     }
 
+    /// Add rules forbidding the packages matched by the root `conflict` map.
+    ///
+    /// Like package conflicts these also match packages providing or replacing the name,
+    /// and only packages reachable from the request get a rule.
+    fn add_root_conflict_rules(&mut self, request: &Request) {
+        for (conflict_name, constraint) in &request.conflicts {
+            for conflict_id in self.pool.what_provides(conflict_name, Some(constraint)) {
+                if !self.added_packages.contains(&conflict_id) {
+                    continue;
+                }
+
+                // An alias only conflicts by its own name, its target is matched on its own
+                if let Some(alias) = self.pool.entry(conflict_id).and_then(|entry| entry.as_alias()) {
+                    if alias.name().to_lowercase() != *conflict_name {
+                        continue;
+                    }
+                }
+
+                let rule = Rule::root_conflict(conflict_id)
+                    .with_source(conflict_id)
+                    .with_target(conflict_name)
+                    .with_constraint(constraint);
+                self.rules.add(rule);
+            }
+        }
+    }
+
+    /// Forbid every other version of the locked packages outside the update allow-list
+    ///
+    /// A locked package may still be removed when nothing requires it anymore. An alias is
+    /// kept when the version it aliases is the locked one.
+    fn add_locked_rules(&mut self, request: &Request) {
+        for locked in &request.locked_packages {
+            if request.is_update_allowed(&locked.name) {
+                continue;
+            }
+
+            let mut ids = self.pool.packages_by_name(&locked.name);
+            ids.sort();
+            for id in ids {
+                if !self.added_packages.contains(&id) {
+                    continue;
+                }
+
+                let version = match self.pool.entry(id) {
+                    Some(PoolEntry::Alias(alias)) => alias.alias_of().version.clone(),
+                    Some(entry) => entry.version().to_string(),
+                    None => continue,
+                };
+                if version == locked.version {
+                    continue;
+                }
+
+                let rule = Rule::locked(id)
+                    .with_source(id)
+                    .with_target(locked.name.to_lowercase())
+                    .with_constraint(locked.pretty_version.as_deref().unwrap_or(&locked.version));
+                self.rules.add(rule);
+            }
+        }
+    }
+
     /// Add conflict rules for packages that REPLACE the same name.
     ///
     /// Note: Packages that merely `provide` a virtual package do NOT conflict
@@ -501,7 +596,7 @@ impl<'a> RuleGenerator<'a> {
             }
 
             // Use multi-conflict rule: at most one replacer can be installed
-            let rule = Rule::multi_conflict(replacer_ids)
+            let rule = Rule::replaced(replacer_ids)
                 .with_target(&name);
             self.rules.add(rule);
         }
@@ -597,6 +692,31 @@ mod tests {
         assert!(!multi_conflict_rules.is_empty());
     }
 
+    #[test]
+    fn test_rule_generator_replaced() {
+        let mut pool = create_test_pool();
+        let mut d = Package::new("vendor/d", "1.0.0");
+        d.require.insert("vendor/b".to_string(), "^1.0".to_string());
+        d.require.insert("vendor/e".to_string(), "*".to_string());
+        pool.add_package(d);
+        let mut e = Package::new("vendor/e", "1.0.0");
+        e.replace.insert("vendor/b".to_string(), "1.0.0".to_string());
+        pool.add_package(e);
+
+        let mut request = Request::new();
+        request.require("vendor/d", "*");
+
+        let rules = RuleGenerator::new(&pool).generate(&request);
+
+        // The versions of vendor/b and its replacer share one rule, kept apart from plain same-name rules
+        let replaced_rules: Vec<_> = rules.rules_of_type(RuleType::PackageReplaced).collect();
+        assert_eq!(replaced_rules.len(), 1);
+        assert_eq!(replaced_rules[0].target_name(), Some("vendor/b"));
+        assert_eq!(replaced_rules[0].len(), 2);
+        assert!(replaced_rules[0].is_multi_conflict());
+        assert_eq!(rules.rules_of_type(RuleType::MultiConflict).count(), 0);
+    }
+
     #[test]
     fn test_rule_generator_package_requires() {
         let pool = create_test_pool();
@@ -165,11 +165,14 @@ impl RuleSet {
         for rule in &self.rules {
             match rule.rule_type() {
                 RuleType::RootRequire => stats.root_require += 1,
+                RuleType::RootConflict => stats.root_conflict += 1,
                 RuleType::Fixed => stats.fixed += 1,
+                RuleType::Locked => stats.locked += 1,
                 RuleType::PackageRequires => stats.requires += 1,
                 RuleType::PackageConflict => stats.conflict += 1,
                 RuleType::PackageSameName => stats.same_name += 1,
                 RuleType::MultiConflict => stats.multi_conflict += 1,
+                RuleType::PackageReplaced => stats.replaced += 1,
                 RuleType::PackageAlias => stats.alias += 1,
                 RuleType::PackageInverseAlias => stats.inverse_alias += 1,
                 RuleType::Learned => stats.learned += 1,
@@ -196,11 +199,14 @@ pub struct RuleSetStats {
     pub total: usize,
     pub assertions: usize,
     pub root_require: usize,
+    pub root_conflict: usize,
     pub fixed: usize,
+    pub locked: usize,
     pub requires: usize,
     pub conflict: usize,
     pub same_name: usize,
     pub multi_conflict: usize,
+    pub replaced: usize,
     pub alias: usize,
     pub inverse_alias: usize,
     pub learned: usize,
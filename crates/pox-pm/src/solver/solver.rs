@@ -1,14 +1,18 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::decisions::Decisions;
+use super::dump::dump_problem;
 use super::pool::{Pool, PackageId, PoolEntry};
 use super::pool_optimizer::PoolOptimizer;
 use super::policy::Policy;
-use super::problem::{Problem, ProblemSet};
+use super::problem::{Problem, ProblemSet, SolveFailure};
 use super::request::Request;
 use super::rule::{Literal, Rule, RuleType};
 use super::rule_generator::RuleGenerator;
 use super::rule_set::RuleSet;
+use super::stats::{SolverStats, StatsCollector};
 use super::watch_graph::{WatchGraph, Propagator, PropagateResult};
 
 use crate::package::{AliasPackage, Package};
@@ -24,6 +28,8 @@ pub struct SolverResult {
     pub packages: Vec<Arc<Package>>,
     /// Alias packages that should be marked as installed
     pub aliases: Vec<Arc<AliasPackage>>,
+    /// The final assignment of the solve, in the order decisions were made
+    pub decisions: Vec<Decision>,
 }
 
 impl SolverResult {
@@ -32,10 +38,49 @@ impl SolverResult {
         Self {
             packages: Vec::new(),
             aliases: Vec::new(),
+            decisions: Vec::new(),
         }
     }
 }
 
+/// Why a budgeted solve did not produce a result
+#[derive(Debug)]
+pub enum SolveError {
+    /// The request cannot be satisfied
+    Unsolvable(SolveFailure),
+    /// The solver took more steps (decisions and conflicts) than allowed. The problems
+    /// describe the choices that were still open when it gave up.
+    BudgetExceeded { steps: u64, problems: SolveFailure },
+}
+
+/// A failed solve before its problems are described against the pool
+enum SatFailure {
+    Unsolvable(ProblemSet),
+    BudgetExceeded { steps: u64, problems: ProblemSet },
+}
+
+impl From<ProblemSet> for SatFailure {
+    fn from(problems: ProblemSet) -> Self {
+        SatFailure::Unsolvable(problems)
+    }
+}
+
+/// A decision of the solver, for tooling that inspects how a solution was reached
+#[derive(Debug, Clone)]
+pub struct Decision {
+    /// ID of the package in the pool the solver ran on, which is the optimized pool
+    /// unless optimization is disabled
+    pub package_id: PackageId,
+    /// The package the decision is about
+    pub package: Arc<Package>,
+    /// Whether the package is installed (`false` means it was ruled out)
+    pub install: bool,
+    /// Decision level the assignment was made at
+    pub level: u32,
+    /// The rule that forced the decision, `None` for free choices made by the policy
+    pub rule_id: Option<u32>,
+}
+
 /// The main SAT solver for dependency resolution.
 ///
 /// Implements a CDCL (Conflict-Driven Clause Learning) algorithm
@@ -47,6 +92,12 @@ pub struct Solver<'a> {
     policy: &'a Policy,
     /// Whether to optimize the pool before solving
     optimize_pool: bool,
+    /// File to export the generated SAT problem to
+    dump_path: Option<PathBuf>,
+    /// Whether to count propagations, conflicts and backtracks
+    collect_stats: bool,
+    /// Counters of the last solve, when collected
+    stats: RefCell<Option<SolverStats>>,
 }
 
 impl<'a> Solver<'a> {
@@ -56,6 +107,9 @@ impl<'a> Solver<'a> {
             pool,
             policy,
             optimize_pool: true, // Pool optimization enabled
+            dump_path: None,
+            collect_stats: false,
+            stats: RefCell::new(None),
         }
     }
 
@@ -68,14 +122,64 @@ impl<'a> Solver<'a> {
         self
     }
 
+    /// Export the generated rules to a file before solving.
+    ///
+    /// The rules are written as DIMACS CNF with a `.json` sidecar mapping literals to
+    /// package versions, see [`dump_problem`].
+    pub fn with_dump(mut self, path: Option<PathBuf>) -> Self {
+        self.dump_path = path;
+        self
+    }
+
+    /// Count propagations, conflicts and backtracks per package while solving.
+    ///
+    /// Off by default as it adds bookkeeping to the hot loop. The counters of the last solve,
+    /// successful or not, are available from [`Solver::stats`].
+    pub fn with_stats(mut self, enabled: bool) -> Self {
+        self.collect_stats = enabled;
+        self
+    }
+
+    /// Counters of the last solve, if stats collection was enabled
+    pub fn stats(&self) -> Option<SolverStats> {
+        self.stats.borrow().clone()
+    }
+
     /// Solve the dependency resolution problem.
     ///
     /// Returns a SolverResult containing packages that should be installed,
-    /// or a ProblemSet explaining failures.
+    /// or a SolveFailure explaining failures.
     ///
     /// The caller should use Transaction::from_packages() to compare the result
     /// with currently installed packages and generate the actual operations.
-    pub fn solve(&self, request: &Request) -> Result<SolverResult, ProblemSet> {
+    pub fn solve(&self, request: &Request) -> Result<SolverResult, SolveFailure> {
+        self.solve_bounded(request, None).map_err(|e| match e {
+            SolveError::Unsolvable(problems) => problems,
+            SolveError::BudgetExceeded { problems, .. } => problems,
+        })
+    }
+
+    /// Solve like [`Solver::solve`], but give up after `max_steps` decisions and conflicts.
+    ///
+    /// Pathological pools can keep the solver busy for a very long time; this lets a caller
+    /// fail fast with [`SolveError::BudgetExceeded`] instead.
+    pub fn solve_with_budget(&self, request: &Request, max_steps: u64) -> Result<SolverResult, SolveError> {
+        self.solve_bounded(request, Some(max_steps))
+    }
+
+    fn solve_bounded(&self, request: &Request, budget: Option<u64>) -> Result<SolverResult, SolveError> {
+        // Problems are described against the full pool, which still knows the versions
+        // the optimizer dropped as unusable
+        self.solve_optimized(request, budget).map_err(|failure| match failure {
+            SatFailure::Unsolvable(problems) => SolveError::Unsolvable(SolveFailure::new(problems, self.pool)),
+            SatFailure::BudgetExceeded { steps, problems } => SolveError::BudgetExceeded {
+                steps,
+                problems: SolveFailure::new(problems, self.pool),
+            },
+        })
+    }
+
+    fn solve_optimized(&self, request: &Request, budget: Option<u64>) -> Result<SolverResult, SatFailure> {
         log::debug!("Building pool with {} packages", self.pool.len());
 
         if self.optimize_pool {
@@ -92,14 +196,14 @@ impl<'a> Solver<'a> {
             log::info!("Pool optimizer completed in {:.3} seconds", elapsed.as_secs_f64());
             log::info!("Found {} package versions referenced in dependency graph. {} ({}%) were optimized away",
                 original, removed, percent);
-            self.solve_with_pool(&optimized_pool, request)
+            self.solve_with_pool(&optimized_pool, request, budget)
         } else {
-            self.solve_with_pool(self.pool, request)
+            self.solve_with_pool(self.pool, request, budget)
         }
     }
 
     /// Internal solve method that works with any pool reference.
-    fn solve_with_pool(&self, pool: &Pool, request: &Request) -> Result<SolverResult, ProblemSet> {
+    fn solve_with_pool(&self, pool: &Pool, request: &Request, budget: Option<u64>) -> Result<SolverResult, SatFailure> {
         log::debug!("Generating rules");
         let start = std::time::Instant::now();
 
@@ -109,14 +213,30 @@ impl<'a> Solver<'a> {
 
         log::info!("Generated {} rules in {:?}", rules.len(), start.elapsed());
 
+        if let Some(path) = &self.dump_path {
+            match dump_problem(path, &rules, pool) {
+                Ok(sidecar) => log::info!("Wrote SAT problem to {} and {}", path.display(), sidecar.display()),
+                Err(e) => log::warn!("Failed to write SAT problem to {}: {}", path.display(), e),
+            }
+        }
+
         // Create solver state
         let mut state = SolverState::new(rules);
+        state.budget = budget;
+        if self.collect_stats {
+            state.stats = Some(StatsCollector::default());
+        }
 
         log::debug!("Resolving dependencies through SAT");
         let sat_start = std::time::Instant::now();
 
         // Run the SAT solver
-        match self.run_sat(&mut state, pool, request) {
+        let outcome = self.run_sat(&mut state, pool, request);
+        if let Some(collector) = state.stats.take() {
+            *self.stats.borrow_mut() = Some(collector.finish(pool));
+        }
+
+        match outcome {
             Ok(()) => {
                 let elapsed = sat_start.elapsed();
                 log::info!("Dependency resolution completed in {:.3} seconds", elapsed.as_secs_f64());
@@ -137,7 +257,7 @@ impl<'a> Solver<'a> {
     /// 2. Fulfill all unresolved rules
     /// 3. On conflict: use CDCL learning to backtrack
     /// 4. After solution found: minimization step to try alternatives
-    fn run_sat(&self, state: &mut SolverState, pool: &Pool, request: &Request) -> Result<(), ProblemSet> {
+    fn run_sat(&self, state: &mut SolverState, pool: &Pool, request: &Request) -> Result<(), SatFailure> {
         // Process assertion rules first (single-literal rules)
         self.process_assertions(state, pool)?;
 
@@ -151,7 +271,14 @@ impl<'a> Solver<'a> {
             if iterations > MAX_ITERATIONS {
                 let mut problems = ProblemSet::new();
                 problems.add(Problem::new().with_message("Solver exceeded maximum iterations"));
-                return Err(problems);
+                return Err(problems.into());
+            }
+
+            if state.budget.is_some_and(|budget| state.steps > budget) {
+                return Err(SatFailure::BudgetExceeded {
+                    steps: state.steps,
+                    problems: self.describe_open_branches(state),
+                });
             }
 
             // Step 1: Propagate all consequences of current decisions
@@ -161,13 +288,13 @@ impl<'a> Solver<'a> {
                     log::debug!("Conflict at level 1: rule {} is unsolvable", conflict_rule);
                     let mut problems = ProblemSet::new();
                     problems.add(self.analyze_unsolvable(state, pool, conflict_rule));
-                    return Err(problems);
+                    return Err(problems.into());
                 }
 
                 // Use CDCL learning to analyze conflict and backtrack
                 let level = self.analyze_and_backtrack(state, conflict_rule)?;
                 if level == 0 {
-                    return Err(ProblemSet::new());
+                    return Err(ProblemSet::new().into());
                 }
                 continue;
             }
@@ -177,7 +304,7 @@ impl<'a> Solver<'a> {
                 Some((candidates, name)) => {
                     let level = self.select_and_install(state, pool, &candidates, &name)?;
                     if level == 0 {
-                        return Err(ProblemSet::new());
+                        return Err(ProblemSet::new().into());
                     }
                 }
                 None => {
@@ -228,6 +355,7 @@ impl<'a> Solver<'a> {
         // Increment level and make decision
         state.decisions.increment_level();
         state.decisions.decide(selected, None);
+        state.steps += 1;
 
         // Propagate and handle any conflicts with CDCL
         loop {
@@ -255,6 +383,7 @@ impl<'a> Solver<'a> {
         state: &mut SolverState,
         conflict_rule: u32,
     ) -> Result<u32, ProblemSet> {
+        state.steps += 1;
         let (learned_literal, backtrack_level, learned_rule) =
             self.analyze_conflict(state, conflict_rule);
 
@@ -264,6 +393,7 @@ impl<'a> Solver<'a> {
         }
 
         // Backtrack to appropriate level
+        state.record_backtrack(backtrack_level);
         state.decisions.revert_to_level(backtrack_level);
         state.reset_propagate_index();
 
@@ -318,6 +448,7 @@ impl<'a> Solver<'a> {
         }
 
         // Revert to the branch level
+        state.record_backtrack(level);
         state.decisions.revert_to_level(level);
         state.reset_propagate_index();
 
@@ -327,6 +458,7 @@ impl<'a> Solver<'a> {
         // Try the alternative
         state.decisions.increment_level();
         state.decisions.decide(literal as PackageId, None);
+        state.steps += 1;
 
         // Propagate and handle conflicts
         loop {
@@ -347,6 +479,25 @@ impl<'a> Solver<'a> {
         }
     }
 
+    /// Describe the choices still open when a budgeted solve gives up, innermost first
+    fn describe_open_branches(&self, state: &SolverState) -> ProblemSet {
+        let mut problems = ProblemSet::new();
+        problems.add(Problem::new().with_message(format!(
+            "Gave up after {} decisions and conflicts at decision level {}",
+            state.steps,
+            state.decisions.level()
+        )));
+        for branch in state.branches.iter().rev().take(5) {
+            problems.add(Problem::new().with_message(format!(
+                "Still choosing {} at decision level {}, {} alternatives left",
+                branch.name,
+                branch.level,
+                branch.alternatives.len()
+            )));
+        }
+        problems
+    }
+
     /// Process assertion rules (single-literal rules that must be true)
     /// Also check for empty rules which indicate unsatisfiable requirements
     fn process_assertions(&self, state: &mut SolverState, pool: &Pool) -> Result<(), ProblemSet> {
@@ -378,9 +529,12 @@ impl<'a> Solver<'a> {
             let literal = literals[0];
 
             if state.decisions.conflict(literal) {
-                // Conflict with existing decision
+                // Conflict with existing decision, report the assertion that made it too
                 let mut problems = ProblemSet::new();
                 let mut problem = Problem::new();
+                if let Some(cause) = state.decisions.decision_rule(literal).and_then(|id| state.rules.get(id)) {
+                    problem.add_rule_with_pool(cause, pool);
+                }
                 problem.add_rule_with_pool(rule, pool);
                 problems.add(problem);
                 return Err(problems);
@@ -427,13 +581,18 @@ impl<'a> Solver<'a> {
                             if let Some(rule) = state.rules.get(rule_id) {
                                 log::debug!("Conflict in propagation: rule {:?} type {:?}", rule_id, rule.rule_type());
                             }
+                            state.record_conflict(rule_id);
                             return Err(rule_id);
                         }
                         if !state.decisions.satisfied(unit_lit) {
                             state.decisions.decide(unit_lit, Some(rule_id));
+                            if let Some(stats) = &mut state.stats {
+                                stats.propagation(unit_lit.unsigned_abs() as PackageId);
+                            }
                         }
                     }
                     PropagateResult::Conflict(rule_id) => {
+                        state.record_conflict(rule_id);
                         return Err(rule_id);
                     }
                 }
@@ -687,6 +846,8 @@ impl<'a> Solver<'a> {
     fn build_result(&self, state: &SolverState, pool: &Pool, request: &Request) -> SolverResult {
         let mut result = SolverResult::new();
         let mut seen_packages = std::collections::HashSet::new();
+        // An alias is reported once, whether decided itself or found through its base
+        let mut seen_aliases = std::collections::HashSet::new();
 
         let installed_pkgs: Vec<_> = state.decisions.installed_packages().collect();
         log::debug!("Building result from {} installed packages", installed_pkgs.len());
@@ -695,7 +856,9 @@ impl<'a> Solver<'a> {
             if let Some(entry) = pool.entry(pkg_id) {
                 match entry {
                     PoolEntry::Alias(alias) => {
-                        result.aliases.push(alias.clone());
+                        if seen_aliases.insert(pkg_id) {
+                            result.aliases.push(alias.clone());
+                        }
                         continue;
                     }
                     PoolEntry::Package(_) => {}
@@ -719,7 +882,9 @@ impl<'a> Solver<'a> {
                 for alias_id in aliases {
                     if let Some(entry) = pool.entry(alias_id) {
                         if let PoolEntry::Alias(alias) = entry {
-                            result.aliases.push(alias.clone());
+                            if seen_aliases.insert(alias_id) {
+                                result.aliases.push(alias.clone());
+                            }
                         }
                     }
                 }
@@ -728,6 +893,13 @@ impl<'a> Solver<'a> {
 
         result.packages.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
 
+        result.decisions = state.decisions.iter()
+            .filter_map(|(package_id, install, level, rule_id)| {
+                let package = pool.package(package_id)?.clone();
+                Some(Decision { package_id, package, install, level, rule_id })
+            })
+            .collect();
+
         result
     }
 }
@@ -744,6 +916,12 @@ struct SolverState {
     branches: Vec<Branch>,
     /// Index of next decision to propagate (avoids re-propagating)
     propagate_index: usize,
+    /// Counters, when enabled
+    stats: Option<StatsCollector>,
+    /// Decisions and conflicts taken so far
+    steps: u64,
+    /// Maximum number of steps before giving up
+    budget: Option<u64>,
 }
 
 impl SolverState {
@@ -756,6 +934,9 @@ impl SolverState {
             watch_graph,
             branches: Vec::new(),
             propagate_index: 0,
+            stats: None,
+            steps: 0,
+            budget: None,
         }
     }
 
@@ -763,10 +944,27 @@ impl SolverState {
     fn reset_propagate_index(&mut self) {
         self.propagate_index = self.decisions.len();
     }
+
+    /// Count a conflict for the packages of a rule
+    fn record_conflict(&mut self, rule_id: u32) {
+        if let (Some(stats), Some(rule)) = (&mut self.stats, self.rules.get(rule_id)) {
+            stats.conflict(rule.literals().iter().map(|l| l.unsigned_abs() as PackageId));
+        }
+    }
+
+    /// Count a backtrack for the packages whose decisions above `level` are about to be undone
+    fn record_backtrack(&mut self, level: u32) {
+        if let Some(stats) = &mut self.stats {
+            stats.backtrack(
+                self.decisions.iter()
+                    .filter(|&(_, _, decision_level, _)| decision_level > level)
+                    .map(|(package_id, _, _, _)| package_id),
+            );
+        }
+    }
 }
 
 /// A branch point for backtracking
-#[allow(dead_code)]
 struct Branch {
     /// Decision level at this branch
     level: u32,
@@ -0,0 +1,147 @@
+//! Instrumentation of the CDCL loop for diagnosing slow solves.
+//!
+//! When enabled, the solver counts propagations, conflicts and backtracks, both in total
+//! and per package name. A package whose versions keep ending up in conflicts is usually
+//! the one to pin.
+
+use std::collections::HashMap;
+
+use super::pool::{PackageId, Pool};
+
+/// Counters for one package name, summed over all its versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PackageStats {
+    /// Decisions on the package forced by unit propagation
+    pub propagations: u64,
+    /// Conflicting rules the package appeared in
+    pub conflicts: u64,
+    /// Decisions on the package undone by backtracking
+    pub backtracks: u64,
+}
+
+/// Counters collected during one solve
+#[derive(Debug, Clone, Default)]
+pub struct SolverStats {
+    pub propagations: u64,
+    pub conflicts: u64,
+    pub backtracks: u64,
+    /// Per-package counters, keyed by lowercase package name
+    pub packages: HashMap<String, PackageStats>,
+}
+
+impl SolverStats {
+    /// Packages involved in the most conflicts, most conflicting first
+    ///
+    /// Packages that never conflicted are left out.
+    pub fn most_conflicting(&self, limit: usize) -> Vec<(&str, &PackageStats)> {
+        let mut packages: Vec<(&str, &PackageStats)> = self
+            .packages
+            .iter()
+            .filter(|(_, stats)| stats.conflicts > 0)
+            .map(|(name, stats)| (name.as_str(), stats))
+            .collect();
+        packages.sort_by(|a, b| b.1.conflicts.cmp(&a.1.conflicts).then_with(|| a.0.cmp(b.0)));
+        packages.truncate(limit);
+        packages
+    }
+
+    /// Format the totals and the most conflicting packages
+    pub fn report(&self, limit: usize) -> String {
+        let mut out = format!(
+            "Solver: {} propagations, {} conflicts, {} backtracks\n",
+            self.propagations, self.conflicts, self.backtracks
+        );
+        for (name, stats) in self.most_conflicting(limit) {
+            out.push_str(&format!(
+                "  {} involved in {} conflicts ({} propagations, {} backtracks)\n",
+                name, stats.conflicts, stats.propagations, stats.backtracks
+            ));
+        }
+        out
+    }
+}
+
+/// Counters by package ID, as collected in the solver loop
+#[derive(Debug, Default)]
+pub(crate) struct StatsCollector {
+    propagations: u64,
+    conflicts: u64,
+    backtracks: u64,
+    packages: HashMap<PackageId, PackageStats>,
+}
+
+impl StatsCollector {
+    pub(crate) fn propagation(&mut self, package_id: PackageId) {
+        self.propagations += 1;
+        self.packages.entry(package_id).or_default().propagations += 1;
+    }
+
+    pub(crate) fn conflict(&mut self, package_ids: impl IntoIterator<Item = PackageId>) {
+        self.conflicts += 1;
+        for package_id in package_ids {
+            self.packages.entry(package_id).or_default().conflicts += 1;
+        }
+    }
+
+    pub(crate) fn backtrack(&mut self, package_ids: impl IntoIterator<Item = PackageId>) {
+        self.backtracks += 1;
+        for package_id in package_ids {
+            self.packages.entry(package_id).or_default().backtracks += 1;
+        }
+    }
+
+    /// Sum the counters of all versions of each package
+    pub(crate) fn finish(self, pool: &Pool) -> SolverStats {
+        let mut packages: HashMap<String, PackageStats> = HashMap::new();
+        for (package_id, counts) in self.packages {
+            let Some(package) = pool.package(package_id) else {
+                continue;
+            };
+            let entry = packages.entry(package.name.to_lowercase()).or_default();
+            entry.propagations += counts.propagations;
+            entry.conflicts += counts.conflicts;
+            entry.backtracks += counts.backtracks;
+        }
+
+        SolverStats {
+            propagations: self.propagations,
+            conflicts: self.conflicts,
+            backtracks: self.backtracks,
+            packages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Package;
+
+    #[test]
+    fn test_stats_by_package_name() {
+        let mut pool = Pool::new();
+        let a1 = pool.add_package(Package::new("vendor/a", "1.0.0"));
+        let a2 = pool.add_package(Package::new("vendor/a", "2.0.0"));
+        let b = pool.add_package(Package::new("vendor/b", "1.0.0"));
+        let c = pool.add_package(Package::new("vendor/c", "1.0.0"));
+
+        let mut collector = StatsCollector::default();
+        collector.propagation(c);
+        collector.conflict([a1, b]);
+        collector.conflict([a2, b]);
+        collector.conflict([a1]);
+        collector.backtrack([a2, c]);
+
+        let stats = collector.finish(&pool);
+        assert_eq!((stats.propagations, stats.conflicts, stats.backtracks), (1, 3, 1));
+        assert_eq!(
+            stats.most_conflicting(10),
+            vec![
+                ("vendor/a", &PackageStats { propagations: 0, conflicts: 3, backtracks: 1 }),
+                ("vendor/b", &PackageStats { propagations: 0, conflicts: 2, backtracks: 0 }),
+            ]
+        );
+        assert_eq!(stats.most_conflicting(1).len(), 1);
+        assert!(stats.report(1).contains("vendor/a involved in 3 conflicts"));
+    }
+}
@@ -127,6 +127,94 @@ fn test_solver_install_single() {
     ]);
 }
 
+#[test]
+fn test_solver_result_decisions() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0")]));
+    pool.add_package(pkg("b", "1.0.0"));
+    pool.add_package(pkg("b", "1.1.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy).with_optimization(false);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    let solver_result = solver.solve(&request).unwrap();
+    let installed: Vec<String> = solver_result.decisions.iter()
+        .filter(|d| d.install)
+        .map(|d| format!("{} {}", d.package.name, d.package.version))
+        .collect();
+    assert_eq!(installed, vec!["a 1.0.0", "b 1.1.0"]);
+
+    // The root requirement is forced by a rule, the version of b is a policy choice
+    let a = &solver_result.decisions[0];
+    assert_eq!((a.package_id, a.level), (1, 1));
+    assert!(a.rule_id.is_some());
+    let b = solver_result.decisions.iter().find(|d| d.install && d.package.name == "b").unwrap();
+    assert_eq!(b.rule_id, None);
+    assert!(b.level > a.level);
+
+    for decision in &solver_result.decisions {
+        assert_eq!(pool.package(decision.package_id).unwrap().version, decision.package.version);
+    }
+}
+
+#[test]
+fn test_solver_stats_only_when_enabled() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "1.0.0")]));
+    pool.add_package(pkg("b", "1.0.0"));
+
+    let policy = Policy::new();
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    let solver = Solver::new(&pool, &policy);
+    solver.solve(&request).unwrap();
+    assert!(solver.stats().is_none());
+
+    let solver = Solver::new(&pool, &policy).with_stats(true);
+    solver.solve(&request).unwrap();
+    let stats = solver.stats().unwrap();
+    assert_eq!(stats.conflicts, 0);
+    assert_eq!(stats.packages["b"].propagations, 1);
+}
+
+#[test]
+fn test_solver_budget_exceeded() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "*")]));
+    pool.add_package(pkg_with_requires("a", "1.1.0", vec![("b", "*")]));
+    pool.add_package(pkg("b", "1.0.0"));
+    pool.add_package(pkg("b", "2.0.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy).with_optimization(false);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    match solver.solve_with_budget(&request, 1) {
+        Err(SolveError::BudgetExceeded { steps, problems }) => {
+            assert_eq!(steps, 2);
+            let messages: Vec<_> = problems.problems().iter().filter_map(|p| p.message.clone()).collect();
+            assert_eq!(messages, vec![
+                "Gave up after 2 decisions and conflicts at decision level 3",
+                "Still choosing b at decision level 2, 1 alternatives left",
+                "Still choosing a at decision level 1, 1 alternatives left",
+            ]);
+        }
+        other => panic!("expected the budget to run out, got {:?}", other),
+    }
+
+    let solver_result = solver.solve_with_budget(&request, 100).unwrap();
+    check_solver_result(&solver_result, &request, vec![
+        ("install", "b", "2.0.0"),
+        ("install", "a", "1.1.0"),
+    ]);
+}
+
 #[test]
 fn test_solver_remove_if_not_requested() {
     let mut pool = Pool::new();
@@ -340,6 +428,52 @@ fn test_solver_conflict_between_requirements() {
     assert!(result.is_err(), "Solver should fail due to conflicting requirements");
 }
 
+#[test]
+fn test_solver_root_conflict_skips_forbidden_versions() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0")]));
+    pool.add_package(pkg("b", "1.0.0"));
+    pool.add_package(pkg("b", "1.1.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+    request.conflict("b", ">=1.1");
+
+    let solver_result = solver.solve(&request).expect("Solver should find a solution");
+    check_solver_result(&solver_result, &request, vec![
+        ("install", "b", "1.0.0"),
+        ("install", "a", "1.0.0"),
+    ]);
+}
+
+#[test]
+fn test_solver_root_conflict_with_requirement() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0")]));
+    pool.add_package(pkg("b", "1.0.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    // Required directly
+    let mut request = Request::new();
+    request.require("b", "^1.0");
+    request.conflict("b", "<2.0");
+    let failure = solver.solve(&request).unwrap_err().to_string();
+    assert!(failure.contains("Root composer.json conflicts with b <2.0, so b 1.0.0 cannot be installed"), "{}", failure);
+
+    // Required transitively
+    let mut request = Request::new();
+    request.require("a", "*");
+    request.conflict("b", "<2.0");
+    let failure = solver.solve(&request).unwrap_err().to_string();
+    assert!(failure.contains("Root composer.json conflicts with b <2.0"), "{}", failure);
+    assert!(failure.contains("a 1.0.0 requires b ^1.0"), "{}", failure);
+}
+
 // ============================================================================
 // Replace Tests
 // ============================================================================
@@ -439,6 +573,50 @@ fn test_skip_replaced_package_if_replacer_is_selected() {
     assert!(q_installed, "Q should be installed");
 }
 
+/// When A requires both B and C and C replaces B, only C is installed: it already
+/// satisfies the requirement on B and may not be installed next to it.
+#[test]
+fn test_replacer_dependency_suppresses_replaced_package() {
+    for replaced in ["1.1.0", "^1.0", "*"] {
+        let mut pool = Pool::new();
+        pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0"), ("c", "*")]));
+        pool.add_package(pkg("b", "1.0.0"));
+        pool.add_package(pkg("b", "1.1.0"));
+        pool.add_package(pkg_with_replaces("c", "1.0.0", vec![("b", replaced)]));
+
+        let policy = Policy::new();
+        let solver = Solver::new(&pool, &policy);
+
+        let mut request = Request::new();
+        request.require("a", "*");
+
+        let solver_result = solver.solve(&request).expect("Solver should find a solution");
+        check_solver_result(&solver_result, &request, vec![
+            ("install", "c", "1.0.0"),
+            ("install", "a", "1.0.0"),
+        ]);
+    }
+}
+
+/// A replacer conflicts with the replaced package even when it doesn't cover the
+/// required range, like in Composer
+#[test]
+fn test_replacer_outside_required_range_conflicts() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0"), ("c", "*")]));
+    pool.add_package(pkg("b", "1.0.0"));
+    pool.add_package(pkg_with_replaces("c", "1.0.0", vec![("b", "<1.0")]));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    let failure = solver.solve(&request).unwrap_err().to_string();
+    assert!(failure.contains("Only one version of b can be installed, counting packages that replace it"), "{}", failure);
+}
+
 // ============================================================================
 // Circular Dependency Tests
 // ============================================================================
@@ -523,6 +701,27 @@ fn test_solver_prefer_lowest() {
     assert_eq!(installed[0].version, "1.0.0", "Should prefer lowest version");
 }
 
+#[test]
+fn test_solver_prefer_lowest_respects_dependency_floor() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("app", "1.0.0", vec![("lib", "^1.2")]));
+    pool.add_package(pkg("lib", "1.0.0"));
+    pool.add_package(pkg("lib", "1.2.0"));
+    pool.add_package(pkg("lib", "1.5.0"));
+
+    let policy = Policy::new().prefer_lowest(true).prefer_stable(true);
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("app", "*");
+    request.require("lib", "*");
+
+    let solver_result = solver.solve(&request).unwrap();
+    let transaction = make_transaction(&solver_result, &request);
+    let lib = transaction.installs().find(|p| p.name == "lib").unwrap();
+    assert_eq!(lib.version, "1.2.0", "Should not go below the floor set by app");
+}
+
 #[test]
 fn test_solver_pick_older_if_newer_conflicts() {
     let mut pool = Pool::new();
@@ -972,6 +1171,70 @@ fn test_install_root_aliases_if_alias_of_is_installed() {
         "C's alias should be installed");
 }
 
+/// Pool with `a dev-main` aliased to 1.0.0, `a 0.9.0` and `b 1.0.0` requiring `a ^1.0`
+fn dev_branch_alias_pool(minimum_stability: crate::package::Stability) -> Pool {
+    let mut pool = Pool::with_minimum_stability(minimum_stability);
+    let base = Package::new("a", "dev-main");
+    pool.add_package(base.clone());
+    pool.add_package(pkg("a", "0.9.0"));
+    pool.add_alias_package(AliasPackage::new(Arc::new(base), "1.0.0.0".to_string(), "1.0.0".to_string()));
+    pool.add_package(pkg_with_requires("b", "1.0.0", vec![("a", "^1.0")]));
+    pool
+}
+
+/// Requiring the aliased version installs the base branch, recorded with its own version
+#[test]
+fn test_alias_requirement_installs_base() {
+    let pool = dev_branch_alias_pool(crate::package::Stability::Dev);
+    let policy = Policy::new();
+
+    let mut request = Request::new();
+    request.require("b", "*");
+
+    let solver_result = Solver::new(&pool, &policy).solve(&request).expect("Should find solution using alias");
+    check_solver_result(&solver_result, &request, vec![
+        ("install", "a", "dev-main"),
+        ("alias_install", "a", "1.0.0.0"),
+        ("install", "b", "1.0.0"),
+    ]);
+}
+
+/// Ruling out the alias rules out its base, and the other way around
+#[test]
+fn test_alias_and_base_are_decided_together() {
+    let pool = dev_branch_alias_pool(crate::package::Stability::Dev);
+    let policy = Policy::new();
+
+    let mut request = Request::new();
+    request.require("a", "*");
+    request.conflict("a", "1.0.0");
+    let solver_result = Solver::new(&pool, &policy).solve(&request).unwrap();
+    check_solver_result(&solver_result, &request, vec![("install", "a", "0.9.0")]);
+
+    let mut request = Request::new();
+    request.require("b", "*");
+    request.conflict("a", "dev-main");
+    let failure = Solver::new(&pool, &policy).solve(&request).unwrap_err().to_string();
+    assert!(failure.contains("is an alias of a dev-main"), "{}", failure);
+}
+
+/// An alias whose base was filtered out of the pool can't be installed on its own
+#[test]
+fn test_alias_without_base_is_not_installable() {
+    let pool = dev_branch_alias_pool(crate::package::Stability::Stable);
+    let policy = Policy::new();
+
+    let mut request = Request::new();
+    request.require("b", "*");
+
+    assert!(Solver::new(&pool, &policy).solve(&request).is_err());
+
+    let mut request = Request::new();
+    request.require("a", "1.0.0");
+    let failure = Solver::new(&pool, &policy).with_optimization(false).solve(&request).unwrap_err().to_string();
+    assert!(failure.contains("is an alias of a package that cannot be installed"), "{}", failure);
+}
+
 /// Port of Composer's testSolverInstallHonoursNotEqualOperator
 /// Tests multi-constraint handling with <=, <>, !=
 #[test]
@@ -1712,6 +1975,35 @@ fn test_install_provider() {
     assert!(result.is_err(), "Should fail because provider must be explicitly selected");
 }
 
+/// A requires B, Q provides B, and Q is required explicitly
+/// The requirement on B is satisfied purely by Q's provide
+#[test]
+fn test_install_provider_explicitly_required() {
+    let mut pool = Pool::new();
+
+    let mut pkg_a = Package::new("a", "1.0.0");
+    pkg_a.require.insert("b".to_string(), ">=1.0".to_string());
+    pool.add_package(pkg_a);
+
+    let mut pkg_q = Package::new("q", "1.0.0");
+    pkg_q.provide.insert("b".to_string(), "1.0.0".to_string());
+    pool.add_package(pkg_q);
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+    request.require("q", "*");
+
+    let result = solver.solve(&request).expect("provider satisfies the requirement");
+    let transaction = make_transaction(&result, &request);
+    let mut installed: Vec<_> = transaction.installs().map(|p| p.name.clone()).collect();
+    installed.sort();
+    assert_eq!(installed, vec!["a", "q"]);
+    assert!(pool.packages_by_name("b").is_empty());
+}
+
 /// Port of Composer's testSolverMultiPackageNameVersionResolutionDependsOnRequireOrder
 ///
 /// This test covers a particular behavior of the solver related to packages with the same
@@ -2227,6 +2519,46 @@ fn test_update_allowlist_allows_upgrade() {
     );
 }
 
+/// Test that locked packages outside the update allowlist keep their version.
+/// A partial update of A must not move B, so A stays at the version B allows.
+#[test]
+fn test_locked_packages_outside_update_allowlist_are_pinned() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("b", "^1.0")]));
+    pool.add_package(pkg_with_requires("a", "2.0.0", vec![("b", "^2.0")]));
+    pool.add_package(pkg("b", "1.0.0"));
+    pool.add_package(pkg("b", "2.0.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+    request.lock(pkg("b", "1.0.0"));
+    request.update(vec!["a".to_string()]);
+
+    let solver_result = solver.solve(&request).unwrap();
+    check_solver_result(&solver_result, &request, vec![
+        ("install", "a", "1.0.0"),
+    ]);
+    let b = solver_result.packages.iter().find(|p| p.name == "b").expect("b should stay installed");
+    assert_eq!(b.version, "1.0.0");
+
+    // When A can only be updated by moving B, the problem names the lock
+    let mut request = Request::new();
+    request.require("a", "^2.0");
+    request.lock(pkg("b", "1.0.0"));
+    request.update(vec!["a".to_string()]);
+
+    let failure = solver.solve(&request).unwrap_err().to_string();
+    assert!(
+        failure.contains("b is locked to version 1.0.0 and an update of this package was not requested, so b 2.0.0 cannot be installed"),
+        "{}",
+        failure
+    );
+    assert!(failure.contains("--with-dependencies"), "{}", failure);
+}
+
 /// Test that major version upgrades are blocked by caret constraint.
 /// Even without locked packages, ^1.11 should never select 2.0.0.
 #[test]
@@ -2485,3 +2817,125 @@ fn test_policy_repository_priority() {
     assert_eq!(selected_pkg.version, "1.1.0",
         "Should select highest version from highest priority repo");
 }
+
+/// Equal candidates are chosen by what they are, not by pool insertion order
+#[test]
+fn test_policy_tie_break_ignores_insertion_order() {
+    let mirrored = |url: &str| {
+        let mut p = pkg("acme/lib", "1.0.0");
+        p.dist = Some(crate::package::Dist::new("zip", url));
+        p
+    };
+    let sourced = |reference: &str| {
+        let mut p = pkg("acme/util", "2.0.0");
+        p.source = Some(crate::package::Source::git("https://example.com/util.git", reference));
+        p
+    };
+
+    let packages = [
+        (pkg_with_requires("acme/app", "1.0.0", vec![("acme/lib", "^1.0"), ("acme/util", "^2.0")]), "repo"),
+        (mirrored("https://b.example/lib.zip"), "mirror-b"),
+        (mirrored("https://a.example/lib.zip"), "mirror-a"),
+        (sourced("ffff"), "repo"),
+        (sourced("aaaa"), "repo"),
+    ];
+
+    let solve = |order: &[usize]| {
+        let mut pool = Pool::new();
+        for &i in order {
+            let (package, repo) = &packages[i];
+            pool.add_package_from_repo(package.clone(), Some(repo));
+        }
+        let policy = Policy::new();
+        let mut request = Request::new();
+        request.require("acme/app", "*");
+        let result = Solver::new(&pool, &policy).solve(&request).unwrap();
+
+        let mut solved: Vec<String> = result.packages.iter()
+            .map(|p| {
+                let dist = p.dist.as_ref().map(|d| d.url.as_str()).unwrap_or("");
+                let source = p.source.as_ref().map(|s| s.reference.as_str()).unwrap_or("");
+                format!("{} {} {}", p.name, dist, source)
+            })
+            .collect();
+        solved.sort();
+        solved
+    };
+
+    let expected = solve(&[0, 1, 2, 3, 4]);
+    assert_eq!(expected, vec![
+        "acme/app  ",
+        "acme/lib https://a.example/lib.zip ",
+        "acme/util  aaaa",
+    ]);
+    for order in [[4, 3, 2, 1, 0], [2, 4, 0, 3, 1], [3, 1, 4, 0, 2]] {
+        assert_eq!(solve(&order), expected, "insertion order {:?}", order);
+    }
+}
+
+// ============================================================================
+// Platform Provider Tests
+// ============================================================================
+
+fn polyfill(name: &str, provides: &str, version: &str) -> Package {
+    let mut p = pkg(name, "1.0.0");
+    p.provide.insert(provides.to_string(), version.to_string());
+    p
+}
+
+fn solved_names(solver_result: &SolverResult) -> Vec<String> {
+    let mut names: Vec<String> = solver_result.packages.iter().map(|p| p.name.clone()).collect();
+    names.sort();
+    names
+}
+
+/// A missing extension can be provided by a polyfill that is not required directly
+#[test]
+fn test_platform_requirement_satisfied_by_polyfill() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("ext-mbstring", "*")]));
+    pool.add_package(polyfill("symfony/polyfill-mbstring", "ext-mbstring", "*"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    let solver_result = solver.solve(&request).expect("polyfill should satisfy ext-mbstring");
+    assert_eq!(solved_names(&solver_result), vec!["a", "symfony/polyfill-mbstring"]);
+}
+
+/// An installed extension is used instead of a polyfill providing it
+#[test]
+fn test_platform_package_preferred_over_polyfill() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("ext-mbstring", "*")]));
+    pool.add_package(polyfill("symfony/polyfill-mbstring", "ext-mbstring", "*"));
+    pool.add_platform_package(pkg("ext-mbstring", "8.3.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    let solver_result = solver.solve(&request).expect("extension should satisfy ext-mbstring");
+    assert_eq!(solved_names(&solver_result), vec!["a", "ext-mbstring"]);
+}
+
+/// A polyfill only satisfies requirements matching the version it provides
+#[test]
+fn test_polyfill_version_must_match_requirement() {
+    let mut pool = Pool::new();
+    pool.add_package(pkg_with_requires("a", "1.0.0", vec![("ext-foo", "^2.0")]));
+    pool.add_package(polyfill("acme/polyfill-foo", "ext-foo", "1.5.0"));
+
+    let policy = Policy::new();
+    let solver = Solver::new(&pool, &policy);
+
+    let mut request = Request::new();
+    request.require("a", "*");
+
+    assert!(solver.solve(&request).is_err(), "ext-foo 1.5.0 must not satisfy ^2.0");
+}
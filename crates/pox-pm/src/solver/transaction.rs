@@ -1,6 +1,9 @@
 use std::sync::Arc;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use pox_semver::{Comparator, VersionParser};
+
+use crate::json::{ComposerLock, LockedPackage};
 use crate::package::{AliasPackage, Package};
 
 #[derive(Debug, Clone, Default)]
@@ -369,6 +372,152 @@ impl std::fmt::Display for TransactionSummary {
     }
 }
 
+/// A package whose locked version or reference differs from the solved one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockChange {
+    pub name: String,
+    /// Version in the lock file, `None` for added packages
+    pub from: Option<String>,
+    /// Solved version, `None` for removed packages
+    pub to: Option<String>,
+}
+
+/// How a solved set of packages differs from an existing lock file, see [`diff_against_lock`]
+///
+/// Unlike the operations of a [`Transaction`], which are relative to what is installed,
+/// this compares against the lock and is meant for changelogs and PR descriptions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockDiff {
+    pub added: Vec<LockChange>,
+    pub removed: Vec<LockChange>,
+    pub upgraded: Vec<LockChange>,
+    pub downgraded: Vec<LockChange>,
+    /// Packages kept at the same version whose commit changed, with the short references
+    /// next to the versions
+    pub updated_ref: Vec<LockChange>,
+}
+
+impl LockDiff {
+    /// Check if the solved packages match the lock
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.upgraded.is_empty()
+            && self.downgraded.is_empty()
+            && self.updated_ref.is_empty()
+    }
+}
+
+impl std::fmt::Display for LockDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No changes");
+        }
+
+        let sections = [
+            ("Upgraded", &self.upgraded),
+            ("Downgraded", &self.downgraded),
+            ("Updated", &self.updated_ref),
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+        ];
+        let mut lines = Vec::new();
+        for (label, changes) in sections {
+            for change in changes {
+                let versions = match (&change.from, &change.to) {
+                    (Some(from), Some(to)) => format!("{} => {}", from, to),
+                    (Some(version), None) | (None, Some(version)) => version.clone(),
+                    (None, None) => String::new(),
+                };
+                let suffix = if label == "Updated" { " (ref)" } else { "" };
+                lines.push(format!("  - {} {} ({}){}", label, change.name, versions, suffix));
+            }
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Compare the packages of a solution with an existing lock file
+///
+/// Packages are matched by name case-insensitively, and platform packages are ignored.
+/// Each list is sorted by package name.
+pub fn diff_against_lock(lock: &ComposerLock, packages: &[Package]) -> LockDiff {
+    let parser = VersionParser::new();
+    let normalize = |version: &str| parser.normalize(version).unwrap_or_else(|_| version.to_lowercase());
+
+    let locked: HashMap<String, &LockedPackage> = lock.packages.iter()
+        .chain(lock.packages_dev.iter())
+        .map(|pkg| (pkg.name.to_lowercase(), pkg))
+        .collect();
+    let solved: HashMap<String, &Package> = packages.iter()
+        .filter(|pkg| !crate::is_platform_package(&pkg.name))
+        .map(|pkg| (pkg.name.to_lowercase(), pkg))
+        .collect();
+
+    let mut diff = LockDiff::default();
+    for (name, pkg) in &solved {
+        let version = pkg.pretty_version.clone().unwrap_or_else(|| pkg.version.clone());
+        let Some(old) = locked.get(name) else {
+            diff.added.push(LockChange { name: pkg.name.clone(), from: None, to: Some(version) });
+            continue;
+        };
+
+        let (old_normalized, new_normalized) = (normalize(&old.version), normalize(&version));
+        if old_normalized == new_normalized {
+            let old_ref = locked_reference(old);
+            let new_ref = package_reference(pkg);
+            if let (Some(old_ref), Some(new_ref)) = (old_ref, new_ref) {
+                if old_ref != new_ref {
+                    diff.updated_ref.push(LockChange {
+                        name: pkg.name.clone(),
+                        from: Some(format!("{} {}", old.version, short_reference(old_ref))),
+                        to: Some(format!("{} {}", version, short_reference(new_ref))),
+                    });
+                }
+            }
+            continue;
+        }
+
+        let change = LockChange { name: pkg.name.clone(), from: Some(old.version.clone()), to: Some(version) };
+        if Comparator::compare(&new_normalized, "<", &old_normalized) {
+            diff.downgraded.push(change);
+        } else {
+            diff.upgraded.push(change);
+        }
+    }
+    for (name, old) in &locked {
+        if !solved.contains_key(name) {
+            diff.removed.push(LockChange { name: old.name.clone(), from: Some(old.version.clone()), to: None });
+        }
+    }
+
+    for changes in [&mut diff.added, &mut diff.removed, &mut diff.upgraded, &mut diff.downgraded, &mut diff.updated_ref] {
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    diff
+}
+
+fn locked_reference(pkg: &LockedPackage) -> Option<&str> {
+    pkg.source.as_ref().map(|source| source.reference.as_str())
+        .or_else(|| pkg.dist.as_ref().and_then(|dist| dist.reference.as_deref()))
+        .filter(|reference| !reference.is_empty())
+}
+
+fn package_reference(pkg: &Package) -> Option<&str> {
+    pkg.source.as_ref().map(|source| source.reference.as_str())
+        .or_else(|| pkg.dist.as_ref().and_then(|dist| dist.reference.as_deref()))
+        .filter(|reference| !reference.is_empty())
+}
+
+/// Shorten a commit hash like git does, leaving other references alone
+fn short_reference(reference: &str) -> &str {
+    if reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
+        &reference[..7]
+    } else {
+        reference
+    }
+}
+
 /// Sort operations using topological sort based on package dependencies.
 /// Dependencies are installed before the packages that depend on them.
 fn topological_sort_operations(operations: Vec<Operation>) -> Vec<Operation> {
@@ -451,6 +600,51 @@ fn topological_sort_operations(operations: Vec<Operation>) -> Vec<Operation> {
         .collect()
 }
 
+/// Find the packages of a solution that are needed without the root's dev requirements.
+///
+/// Starting from the root's non-dev requirements, `require` links are followed to the
+/// package of that name and to packages replacing or providing it. Packages that are never
+/// reached are only needed through `require-dev`; a package reached both ways is non-dev.
+/// Returns lowercase package names.
+pub fn non_dev_package_names<'a>(
+    packages: &[Package],
+    root_requires: impl IntoIterator<Item = &'a str>,
+) -> HashSet<String> {
+    let mut by_link: HashMap<String, Vec<&Package>> = HashMap::new();
+    for pkg in packages {
+        by_link.entry(pkg.name.to_lowercase()).or_default().push(pkg);
+        for name in pkg.replace.keys().chain(pkg.provide.keys()) {
+            by_link.entry(name.to_lowercase()).or_default().push(pkg);
+        }
+    }
+
+    let mut result: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = root_requires.into_iter()
+        .filter(|name| !crate::is_platform_package(name))
+        .map(|name| name.to_lowercase())
+        .collect();
+    let mut visited: HashSet<String> = HashSet::new();
+
+    while let Some(link) = queue.pop_front() {
+        if !visited.insert(link.clone()) {
+            continue;
+        }
+
+        for pkg in by_link.get(&link).into_iter().flatten() {
+            if !result.insert(pkg.name.to_lowercase()) {
+                continue;
+            }
+            for dep_name in pkg.require.keys() {
+                if !crate::is_platform_package(dep_name) {
+                    queue.push_back(dep_name.to_lowercase());
+                }
+            }
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -497,6 +691,51 @@ mod tests {
         assert_eq!(summary.updates, 0);
     }
 
+    #[test]
+    fn test_diff_against_lock() {
+        let lock: ComposerLock = serde_json::from_value(serde_json::json!({
+            "content-hash": "",
+            "packages": [
+                {"name": "acme/up", "version": "v1.0.0"},
+                {"name": "acme/down", "version": "2.1.0"},
+                {"name": "acme/same", "version": "1.0.0"},
+                {"name": "acme/gone", "version": "1.0.0"},
+                {"name": "acme/branch", "version": "dev-main", "source": {
+                    "type": "git", "url": "https://example.com/branch.git",
+                    "reference": "1111111111111111111111111111111111111111"
+                }}
+            ]
+        })).unwrap();
+
+        let mut branch = Package::new("acme/branch", "dev-main");
+        branch.source = Some(crate::package::Source::git("https://example.com/branch.git", "2222222222222222222222222222222222222222"));
+        let packages = vec![
+            Package::new("acme/up", "v1.2.0"),
+            Package::new("acme/down", "2.0.0"),
+            Package::new("acme/same", "1.0.0"),
+            Package::new("acme/new", "3.0.0"),
+            Package::new("php", "8.3.0"),
+            branch,
+        ];
+
+        let diff = diff_against_lock(&lock, &packages);
+        let names = |changes: &[LockChange]| changes.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&diff.upgraded), vec!["acme/up"]);
+        assert_eq!(names(&diff.downgraded), vec!["acme/down"]);
+        assert_eq!(names(&diff.added), vec!["acme/new"]);
+        assert_eq!(names(&diff.removed), vec!["acme/gone"]);
+        assert_eq!(names(&diff.updated_ref), vec!["acme/branch"]);
+
+        assert_eq!(diff.to_string(), [
+            "  - Upgraded acme/up (v1.0.0 => v1.2.0)",
+            "  - Downgraded acme/down (2.1.0 => 2.0.0)",
+            "  - Updated acme/branch (dev-main 1111111 => dev-main 2222222) (ref)",
+            "  - Added acme/new (3.0.0)",
+            "  - Removed acme/gone (1.0.0)",
+        ].join("\n"));
+        assert_eq!(diff_against_lock(&lock, &[]).removed.len(), 5);
+    }
+
     #[test]
     fn test_transaction_sort() {
         let mut tx = Transaction::new();
@@ -618,4 +857,33 @@ mod tests {
         assert_eq!(tx.updates().count(), 0);
         assert_eq!(tx.removals().count(), 1);
     }
+
+    #[test]
+    fn test_non_dev_package_names() {
+        let mut app = Package::new("acme/app-lib", "1.0.0");
+        app.require.insert("psr/log".to_string(), "^3.0".to_string());
+        app.require.insert("psr/log-implementation".to_string(), "*".to_string());
+        app.require.insert("php".to_string(), ">=8.1".to_string());
+
+        let mut phpunit = Package::new("phpunit/phpunit", "10.0.0");
+        phpunit.require.insert("psr/log".to_string(), "^3.0".to_string());
+        phpunit.require.insert("sebastian/diff".to_string(), "^5.0".to_string());
+
+        let mut monolog = Package::new("monolog/monolog", "3.0.0");
+        monolog.provide.insert("psr/log-implementation".to_string(), "3.0.0".to_string());
+
+        let packages = vec![
+            app,
+            phpunit,
+            monolog,
+            Package::new("psr/log", "3.0.0"),
+            Package::new("sebastian/diff", "5.0.0"),
+        ];
+
+        let names = non_dev_package_names(&packages, ["Acme/App-Lib", "php"]);
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort();
+        // psr/log is required by both a dev and a non-dev package, so it is non-dev
+        assert_eq!(names, vec!["acme/app-lib", "monolog/monolog", "psr/log"]);
+    }
 }
@@ -1,63 +1,131 @@
 //! Utility functions for the package manager.
 
+use std::collections::BTreeMap;
+
 use md5::{Md5, Digest};
 use serde_json::Value;
 
+/// Keys of composer.json that Composer includes in the content hash
+const CONTENT_HASH_KEYS: [&str; 11] = [
+    "name", "version", "require", "require-dev", "conflict",
+    "replace", "provide", "minimum-stability", "prefer-stable",
+    "repositories", "extra",
+];
+
 /// Compute the content hash for a composer.json file.
-/// This matches Composer's algorithm:
+/// This matches Composer's `Locker::getContentHash`:
 /// 1. Parse the JSON
-/// 2. Extract relevant keys (name, version, require, etc.)
+/// 2. Extract relevant keys (name, version, require, etc.) and `config.platform`
 /// 3. Sort keys alphabetically
-/// 4. JSON encode (compact, no pretty print)
+/// 4. JSON encode the way PHP's `json_encode` does
 /// 5. MD5 hash
 pub fn compute_content_hash(json_content: &str) -> String {
-    let json_value: Value = match serde_json::from_str(json_content) {
-        Ok(v) => v,
-        Err(_) => return "0".repeat(32),
-    };
-
-    let mut relevant_keys = vec![
-        "name", "version", "require", "require-dev", "conflict",
-        "replace", "provide", "minimum-stability", "prefer-stable",
-        "repositories", "extra"
-    ];
-    relevant_keys.sort();
+    match content_hash_input(json_content) {
+        Some(input) => {
+            let mut hasher = Md5::new();
+            hasher.update(input.as_bytes());
+            format!("{:x}", hasher.finalize())
+        }
+        None => "0".repeat(32),
+    }
+}
 
-    let mut relevant_map = serde_json::Map::new();
+/// Build the string Composer hashes for a composer.json file
+fn content_hash_input(json_content: &str) -> Option<String> {
+    let json_value: Value = serde_json::from_str(json_content).ok()?;
 
+    let mut relevant: BTreeMap<&str, Value> = BTreeMap::new();
     if let Some(obj) = json_value.as_object() {
-        for key in &relevant_keys {
-            if let Some(value) = obj.get(*key) {
-                relevant_map.insert(key.to_string(), value.clone());
+        for key in CONTENT_HASH_KEYS {
+            if let Some(value) = obj.get(key) {
+                relevant.insert(key, value.clone());
             }
         }
     }
 
-    // Include config.platform if present
-    if let Some(config) = json_value.get("config") {
-        if let Some(platform) = config.get("platform") {
-            if let Some(obj) = platform.as_object() {
-                if !obj.is_empty() {
-                    let mut config_obj = serde_json::Map::new();
-                    config_obj.insert("platform".to_string(), platform.clone());
-                    relevant_map.insert("config".to_string(), Value::Object(config_obj));
+    // Composer checks `isset($content['config']['platform'])`, so an empty platform counts
+    if let Some(platform) = json_value.get("config").and_then(|c| c.get("platform")) {
+        if !platform.is_null() {
+            let mut config = serde_json::Map::new();
+            config.insert("platform".to_string(), platform.clone());
+            relevant.insert("config", Value::Object(config));
+        }
+    }
+
+    let relevant: serde_json::Map<String, Value> = relevant
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+
+    let mut out = String::new();
+    encode_php_json(&Value::Object(relevant), &mut out);
+    Some(out)
+}
+
+/// Encode a value like PHP's `json_encode` with default flags encodes decoded JSON.
+///
+/// Composer decodes objects into PHP arrays, so empty objects and objects keyed `0..n`
+/// come back out as lists. Slashes and non-ASCII characters are escaped.
+fn encode_php_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) => encode_php_string(s, out),
+        Value::Array(items) => encode_php_list(items.iter(), out),
+        Value::Object(map) => {
+            if map.keys().enumerate().all(|(i, key)| *key == i.to_string()) {
+                encode_php_list(map.values(), out);
+                return;
+            }
+
+            out.push('{');
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
                 }
+                encode_php_string(key, out);
+                out.push(':');
+                encode_php_json(value, out);
             }
+            out.push('}');
         }
     }
+}
 
-    let json_output = match serde_json::to_string(&Value::Object(relevant_map)) {
-        Ok(s) => s,
-        Err(_) => return "0".repeat(32),
-    };
-
-    // Composer escapes forward slashes in JSON
-    let json_with_escaped_slashes = json_output.replace('/', "\\/");
+fn encode_php_list<'a>(items: impl Iterator<Item = &'a Value>, out: &mut String) {
+    out.push('[');
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        encode_php_json(item, out);
+    }
+    out.push(']');
+}
 
-    let mut hasher = Md5::new();
-    hasher.update(json_with_escaped_slashes.as_bytes());
-    let result = hasher.finalize();
-    format!("{:x}", result)
+fn encode_php_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '/' => out.push_str("\\/"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || !c.is_ascii() => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
 }
 
 /// Check if a package name represents a platform package.
@@ -103,6 +171,13 @@ pub fn is_platform_package(name: &str) -> bool {
         || name == "composer-plugin-api"
 }
 
+/// Check if network access was disabled through `COMPOSER_DISABLE_NETWORK`.
+///
+/// Like Composer, any value other than empty or `0` counts, including `prime`.
+pub fn is_network_disabled() -> bool {
+    std::env::var("COMPOSER_DISABLE_NETWORK").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +255,41 @@ mod tests {
         assert!(!is_platform_package("Ext-json"));
         assert!(!is_platform_package("COMPOSER"));
     }
+
+    #[test]
+    fn test_content_hash_known_composer_hash() {
+        // Hash computed by PHP for {"name":"vendor/test","require":{"symfony/console":"*"}}
+        let content = r#"{
+            "name": "vendor/test",
+            "description": "Not part of the hash",
+            "require": {
+                "symfony/console": "*"
+            },
+            "autoload": {"psr-4": {"Vendor\\Test\\": "src/"}}
+        }"#;
+        assert_eq!(compute_content_hash(content), "952f760ba9cfb2ca4a799c52d42099d4");
+    }
+
+    #[test]
+    fn test_content_hash_input_matches_php_encoding() {
+        let content = r#"{
+            "require": {},
+            "name": "acme/app",
+            "extra": {"branch": "main/ü", "list": {"0": "a", "1": "b"}, "tab": "a\tb"},
+            "config": {"platform": {}, "sort-packages": true},
+            "conflict": {"acme/old": "<1.0"}
+        }"#;
+        assert_eq!(
+            content_hash_input(content).unwrap(),
+            r#"{"config":{"platform":[]},"conflict":{"acme\/old":"<1.0"},"extra":{"branch":"main\/\u00fc","list":["a","b"],"tab":"a\tb"},"name":"acme\/app","require":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_content_hash_ignores_config_without_platform() {
+        let with_config = r#"{"name": "acme/app", "config": {"sort-packages": true}}"#;
+        let without_config = r#"{"name": "acme/app"}"#;
+        assert_eq!(compute_content_hash(with_config), compute_content_hash(without_config));
+        assert_eq!(compute_content_hash("not json"), "0".repeat(32));
+    }
 }
@@ -3,6 +3,8 @@
 use std::cmp::Ordering;
 use std::fmt;
 
+use super::constraint::compare_versions;
+
 /// Represents a bound (lower or upper) of a version constraint
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Bound {
@@ -55,6 +57,53 @@ impl Bound {
         }
     }
 
+    /// Order two lower bounds: the one admitting more versions sorts first.
+    ///
+    /// At the same version an inclusive bound (`>= 1.0`) starts before an exclusive one (`> 1.0`).
+    pub fn cmp_lower(&self, other: &Bound) -> Ordering {
+        compare_versions(&self.version, &other.version)
+            .then_with(|| other.is_inclusive.cmp(&self.is_inclusive))
+    }
+
+    /// Order two upper bounds: the one admitting fewer versions sorts first.
+    ///
+    /// At the same version an exclusive bound (`< 2.0`) ends before an inclusive one (`<= 2.0`).
+    pub fn cmp_upper(&self, other: &Bound) -> Ordering {
+        compare_versions(&self.version, &other.version)
+            .then_with(|| self.is_inclusive.cmp(&other.is_inclusive))
+    }
+
+    /// Check if a normalized version lies in the range from this lower bound to `upper`
+    pub fn contains(&self, upper: &Bound, version: &str) -> bool {
+        let above_lower = match compare_versions(version, &self.version) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.is_inclusive,
+            Ordering::Less => false,
+        };
+        let below_upper = match compare_versions(version, &upper.version) {
+            Ordering::Less => true,
+            Ordering::Equal => upper.is_inclusive,
+            Ordering::Greater => false,
+        };
+        above_lower && below_upper
+    }
+
+    /// Check if the range from this lower bound to `upper` shares a version with the range
+    /// from `other_lower` to `other_upper`.
+    ///
+    /// Ranges touching at a version only overlap when both include it: `< 2.0` and `>= 2.0`
+    /// are adjacent, while `<= 2.0` and `>= 2.0` overlap in `2.0`.
+    pub fn overlaps(&self, upper: &Bound, other_lower: &Bound, other_upper: &Bound) -> bool {
+        let lower = if self.cmp_lower(other_lower) == Ordering::Less { other_lower } else { self };
+        let upper = if upper.cmp_upper(other_upper) == Ordering::Greater { other_upper } else { upper };
+
+        match compare_versions(&lower.version, &upper.version) {
+            Ordering::Less => true,
+            Ordering::Equal => lower.is_inclusive && upper.is_inclusive,
+            Ordering::Greater => false,
+        }
+    }
+
     /// Compare this bound to another with a given operator
     pub fn compare_to(&self, other: &Bound, operator: &str) -> bool {
         if operator != "<" && operator != ">" {
@@ -232,6 +281,49 @@ mod tests {
         assert!(!inf.is_zero());
     }
 
+    fn bound(version: &str, is_inclusive: bool) -> Bound {
+        Bound::new(version.to_string(), is_inclusive)
+    }
+
+    #[test]
+    fn test_cmp_lower_and_upper() {
+        assert_eq!(bound("1.0.0.0", true).cmp_lower(&bound("1.0.0.0", false)), Ordering::Less);
+        assert_eq!(bound("1.0.0.0", false).cmp_lower(&bound("1.0.0.0", true)), Ordering::Greater);
+        assert_eq!(bound("1.0.0.0", true).cmp_upper(&bound("1.0.0.0", false)), Ordering::Greater);
+        assert_eq!(bound("1.0.0.0", false).cmp_upper(&bound("1.0.0.0", false)), Ordering::Equal);
+        assert_eq!(Bound::zero().cmp_lower(&bound("0.0.0.0", true)), Ordering::Less);
+        assert_eq!(bound("99.0.0.0", true).cmp_upper(&Bound::positive_infinity()), Ordering::Less);
+    }
+
+    #[test]
+    fn test_contains() {
+        let lower = bound("1.0.0.0-dev", true);
+        let upper = bound("2.0.0.0-dev", false);
+        assert!(lower.contains(&upper, "1.0.0.0"));
+        assert!(lower.contains(&upper, "1.0.0.0-dev"));
+        assert!(lower.contains(&upper, "1.9.9.9"));
+        assert!(!lower.contains(&upper, "2.0.0.0-dev"));
+        assert!(!lower.contains(&upper, "2.0.0.0"));
+        assert!(!lower.contains(&upper, "0.9.0.0"));
+
+        assert!(Bound::zero().contains(&Bound::positive_infinity(), "0.0.0.0-dev"));
+        assert!(Bound::zero().contains(&Bound::positive_infinity(), "999999.0.0.0"));
+        assert!(!bound("1.0.0.0", false).contains(&Bound::positive_infinity(), "1.0.0.0"));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let (lower, upper) = (bound("1.0.0.0", true), bound("2.0.0.0", false));
+        assert!(lower.overlaps(&upper, &bound("1.5.0.0", true), &bound("3.0.0.0", false)));
+        assert!(lower.overlaps(&upper, &Bound::zero(), &Bound::positive_infinity()));
+        assert!(!lower.overlaps(&upper, &bound("3.0.0.0", true), &Bound::positive_infinity()));
+
+        // Touching at 2.0: only overlapping when both sides include it
+        assert!(!lower.overlaps(&upper, &bound("2.0.0.0", true), &bound("3.0.0.0", false)));
+        assert!(!lower.overlaps(&bound("2.0.0.0", true), &bound("2.0.0.0", false), &bound("3.0.0.0", false)));
+        assert!(lower.overlaps(&bound("2.0.0.0", true), &bound("2.0.0.0", true), &bound("3.0.0.0", false)));
+    }
+
     #[test]
     fn test_compare_to() {
         let b1 = Bound::new("1.0.0.0".to_string(), true);
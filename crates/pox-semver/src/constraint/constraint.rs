@@ -52,6 +52,30 @@ impl Constraint {
         self.operator
     }
 
+    /// Format the constraint the way it would be written in composer.json
+    ///
+    /// Unlike [`Display`](fmt::Display) this omits the space after the operator, drops a
+    /// trailing fourth `.0` segment and prints `==` constraints as just the version, so
+    /// `== 1.2.0.0` becomes `1.2.0` and `>= 1.0.0.0-dev` becomes `>=1.0.0`. Parsing the
+    /// result yields the same constraint again.
+    pub fn canonical_string(&self) -> String {
+        let mut version = self.version.as_str();
+
+        // The parser appends `-dev` to the bounds of `>=` and `<` so pre-releases are
+        // included; a user never writes it, and parsing adds it back
+        if matches!(self.operator, Operator::GreaterThanOrEqual | Operator::LessThan) && !version.starts_with("dev-") {
+            if let Some(stripped) = version.strip_suffix("-dev") {
+                version = stripped;
+            }
+        }
+
+        let version = strip_fourth_segment(version);
+        match self.operator {
+            Operator::Equal => version,
+            operator => format!("{}{}", operator, version),
+        }
+    }
+
     /// Match against another single constraint
     pub fn match_specific(&self, provider: &Constraint, compare_branches: bool) -> bool {
         let is_equal_op = self.operator == Operator::Equal;
@@ -254,11 +278,37 @@ impl ConstraintInterface for Constraint {
         Box::new(self.clone())
     }
 
+    fn canonical_string(&self) -> String {
+        Constraint::canonical_string(self)
+    }
+
     fn as_constraint(&self) -> Option<(&Operator, &str)> {
         Some((&self.operator, &self.version))
     }
 }
 
+/// Drop the fourth segment of a normalized version if it is `0` (`1.2.3.0-beta1` -> `1.2.3-beta1`)
+fn strip_fourth_segment(version: &str) -> String {
+    if version.starts_with("dev-") {
+        return version.to_string();
+    }
+
+    let (numbers, suffix) = match version.find('-') {
+        Some(pos) => version.split_at(pos),
+        None => (version, ""),
+    };
+
+    let segments: Vec<&str> = numbers.split('.').collect();
+    if segments.len() == 4
+        && segments[3] == "0"
+        && segments.iter().all(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return format!("{}{}", segments[..3].join("."), suffix);
+    }
+
+    version.to_string()
+}
+
 impl fmt::Display for Constraint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} {}", self.operator, self.version)
@@ -266,6 +316,18 @@ impl fmt::Display for Constraint {
 }
 
 /// PHP-compatible version_compare
+///
+/// Matches PHP for the versions Composer produces, with a few intentional divergences:
+///
+/// - Missing numeric segments compare as `0`, so `1.0` equals `1.0.0` and `1.0-rc` equals
+///   `1.0-rc0`; PHP orders the longer version higher.
+/// - A number always sorts above a special form in the same position (`1.0.1 > 1.0.pl1`);
+///   PHP places `pl`/`p` above numbers.
+/// - Special forms are matched case-insensitively and exactly. PHP is case-sensitive, matches
+///   prefixes (`develop` is `dev`) and sorts unknown words below `dev`; here they sort like
+///   a missing suffix (`1.0-stable` equals `1.0`).
+///
+/// `tests/version_compare.rs` checks everything else against a port of PHP's implementation.
 pub fn php_version_compare(a: &str, b: &str, operator: &str) -> bool {
     let cmp = compare_versions(a, b);
 
@@ -281,28 +343,44 @@ pub fn php_version_compare(a: &str, b: &str, operator: &str) -> bool {
 }
 
 /// Compare two version strings (PHP version_compare compatible)
-fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+///
+/// Unlike PHP, a numeric segment is compared against `0` when the other version has no
+/// numeric segment at that position, so `1.2.3` equals `1.2.3.0` and `20230101` equals
+/// `20230101.0.0.0`. This keeps comparisons consistent with the 4-segment form produced
+/// by `VersionParser::normalize`.
+pub(crate) fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     let mut a_iter = PartIter::new(a);
     let mut b_iter = PartIter::new(b);
+    let mut a_pending: Option<Part<'_>> = None;
+    let mut b_pending: Option<Part<'_>> = None;
 
     loop {
-        let a_part = a_iter.next();
-        let b_part = b_iter.next();
-
-        if a_part.is_none() && b_part.is_none() {
-            return std::cmp::Ordering::Equal;
-        }
-
-        let a_part = match a_part {
-            Some(part) => part,
-            None => Part::empty(),
-        };
-        let b_part = match b_part {
-            Some(part) => part,
-            None => Part::empty(),
+        let a_part = a_pending.take().or_else(|| a_iter.next());
+        let b_part = b_pending.take().or_else(|| b_iter.next());
+
+        let cmp = match (a_part, b_part) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            // Pad the shorter numeric segment list with zeros
+            (Some(a_part), b_part)
+                if a_part.kind == PartKind::Digit
+                    && b_part.is_none_or(|p| p.kind == PartKind::Alpha) =>
+            {
+                b_pending = b_part;
+                compare_part(a_part, Part::zero())
+            }
+            (a_part, Some(b_part))
+                if b_part.kind == PartKind::Digit
+                    && a_part.is_none_or(|p| p.kind == PartKind::Alpha) =>
+            {
+                a_pending = a_part;
+                compare_part(Part::zero(), b_part)
+            }
+            (a_part, b_part) => compare_part(
+                a_part.unwrap_or_else(Part::empty),
+                b_part.unwrap_or_else(Part::empty),
+            ),
         };
 
-        let cmp = compare_part(a_part, b_part);
         if cmp != std::cmp::Ordering::Equal {
             return cmp;
         }
@@ -322,12 +400,19 @@ struct Part<'a> {
 }
 
 impl<'a> Part<'a> {
-    fn empty() -> Part<'static> {
+    fn empty() -> Self {
         Part {
             kind: PartKind::Alpha,
             text: "",
         }
     }
+
+    fn zero() -> Self {
+        Part {
+            kind: PartKind::Digit,
+            text: "0",
+        }
+    }
 }
 
 struct PartIter<'a> {
@@ -467,6 +552,23 @@ mod tests {
         assert!(!php_version_compare("1.0.0", "1.0.0", "!="));
     }
 
+    #[test]
+    fn test_version_compare_pads_missing_segments() {
+        assert!(php_version_compare("1.2.3", "1.2.3.0", "=="));
+        assert!(php_version_compare("1.2.3.4", "1.2.3", ">"));
+        assert!(php_version_compare("1.0.0-dev", "1.0.0.0-dev", "=="));
+        assert!(php_version_compare("20230102", "20230102.0.0.0-dev", ">"));
+        assert!(php_version_compare("20230101", "20230102.0.0.0-dev", "<"));
+    }
+
+    #[test]
+    fn test_version_compare_patch_suffix_ordering() {
+        assert!(php_version_compare("1.2.3.0-patch1", "1.2.3.0", ">"));
+        assert!(php_version_compare("1.2.3.0-patch1", "1.2.3.1", "<"));
+        assert!(php_version_compare("1.2.3.0-p1", "1.2.3.0-patch2", "<"));
+        assert!(php_version_compare("1.2.3.0-RC1", "1.2.3.0", "<"));
+    }
+
     #[test]
     fn test_dev_version_stability() {
         // Dev versions should be less than stable versions
@@ -798,4 +900,19 @@ mod tests {
         assert!(require.match_specific(&provide, false),
             ">=6.7.2.0 should match =6.7.9999999.9999999-dev");
     }
+
+    #[test]
+    fn test_canonical_string() {
+        let c = |op: Operator, v: &str| Constraint::new(op, v.to_string()).unwrap().canonical_string();
+
+        assert_eq!(c(Operator::Equal, "1.2.0.0"), "1.2.0");
+        assert_eq!(c(Operator::GreaterThanOrEqual, "1.0.0.0-dev"), ">=1.0.0");
+        assert_eq!(c(Operator::LessThan, "2.0.0.0-dev"), "<2.0.0");
+        assert_eq!(c(Operator::LessThanOrEqual, "1.0.0.0-dev"), "<=1.0.0-dev");
+        assert_eq!(c(Operator::NotEqual, "1.5.0.0"), "!=1.5.0");
+        assert_eq!(c(Operator::Equal, "1.0.0.0-beta1"), "1.0.0-beta1");
+        assert_eq!(c(Operator::Equal, "1.2.3.4"), "1.2.3.4");
+        assert_eq!(c(Operator::Equal, "dev-main"), "dev-main");
+        assert_eq!(c(Operator::GreaterThanOrEqual, "dev-main"), ">=dev-main");
+    }
 }
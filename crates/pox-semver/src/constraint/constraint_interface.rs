@@ -1,13 +1,28 @@
 //! Constraint interface trait
 
 use super::Bound;
+use super::Constraint;
 use super::Operator;
+use crate::VersionParser;
 
 /// Trait for all constraint types
 pub trait ConstraintInterface: std::fmt::Debug + std::fmt::Display + Send + Sync {
     /// Check if this constraint matches another constraint
     fn matches(&self, other: &dyn ConstraintInterface) -> bool;
 
+    /// Check if a single version, like `1.2.3` or `dev-main`, is allowed by this constraint.
+    ///
+    /// The version is normalized first; versions that cannot be normalized are compared as written.
+    fn allows(&self, version: &str) -> bool {
+        let normalized = VersionParser::new()
+            .normalize(version)
+            .unwrap_or_else(|_| version.to_string());
+        match Constraint::new(Operator::Equal, normalized) {
+            Ok(provided) => self.matches(&provided),
+            Err(_) => false,
+        }
+    }
+
     /// Get the lower bound of this constraint
     fn lower_bound(&self) -> Bound;
 
@@ -20,9 +35,22 @@ pub trait ConstraintInterface: std::fmt::Debug + std::fmt::Display + Send + Sync
     /// Set the pretty string representation
     fn set_pretty_string(&mut self, pretty: Option<String>);
 
+    /// Get the constraint as it would be written in composer.json (`>=1.0.0 <2.0.0`)
+    fn canonical_string(&self) -> String {
+        self.to_string()
+    }
+
     /// Clone this constraint into a boxed trait object
     fn clone_box(&self) -> Box<dyn ConstraintInterface>;
 
+    /// Compute the constraint matching exactly the versions both constraints match.
+    ///
+    /// Returns a [`MatchNoneConstraint`](super::MatchNoneConstraint) when the constraints
+    /// do not overlap. Dev branches (`dev-*`) only intersect with themselves.
+    fn intersect(&self, other: &dyn ConstraintInterface) -> Box<dyn ConstraintInterface> {
+        super::intersection::intersect(self.clone_box().as_ref(), other)
+    }
+
     /// Check if this is a Constraint (single version constraint)
     fn as_constraint(&self) -> Option<(&Operator, &str)> {
         None
@@ -49,3 +77,33 @@ impl Clone for Box<dyn ConstraintInterface> {
         self.clone_box()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::VersionParser;
+
+    #[test]
+    fn test_allows() {
+        let parser = VersionParser::new();
+
+        let constraint = parser.parse_constraints("^1.2").unwrap();
+        assert!(constraint.allows("1.2.0"));
+        assert!(constraint.allows("v1.9.3"));
+        assert!(constraint.allows("1.2.3.4"));
+        assert!(!constraint.allows("2.0.0"));
+        assert!(!constraint.allows("1.1.9.9"));
+        // Stability is not part of the constraint, only of the version selection
+        assert!(constraint.allows("1.3.0-beta1"));
+        assert!(constraint.allows("1.4.x-dev"));
+        assert!(!constraint.allows("2.0.x-dev"));
+
+        let constraint = parser.parse_constraints("dev-main").unwrap();
+        assert!(constraint.allows("dev-main"));
+        assert!(!constraint.allows("dev-feature"));
+        assert!(!constraint.allows("1.0.0"));
+
+        let constraint = parser.parse_constraints("*").unwrap();
+        assert!(constraint.allows("dev-main"));
+        assert!(constraint.allows("5.0.0"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Intersection of constraints
+//!
+//! Constraints are expanded into disjunctive normal form (an OR of ANDs of single
+//! constraints). Every pair of conjunctions from both sides is then reduced to its
+//! tightest lower/upper bound, and the non-empty results are joined back together.
+
+use std::cmp::Ordering;
+
+use super::constraint::compare_versions;
+use super::{Bound, Constraint, ConstraintInterface, MatchAllConstraint, MatchNoneConstraint, MultiConstraint, Operator};
+
+/// A conjunction of single constraints
+type Conjunction = Vec<Constraint>;
+
+/// Compute the constraint matching exactly the versions matched by both `a` and `b`
+pub(crate) fn intersect(a: &dyn ConstraintInterface, b: &dyn ConstraintInterface) -> Box<dyn ConstraintInterface> {
+    let left = to_dnf(a);
+    let right = to_dnf(b);
+
+    let mut alternatives: Vec<Conjunction> = Vec::new();
+    for x in &left {
+        for y in &right {
+            let combined: Conjunction = x.iter().chain(y.iter()).cloned().collect();
+            if let Some(simplified) = simplify(combined) {
+                if !alternatives.iter().any(|existing| same_conjunction(existing, &simplified)) {
+                    alternatives.push(simplified);
+                }
+            }
+        }
+    }
+
+    if alternatives.is_empty() {
+        return Box::new(MatchNoneConstraint::new());
+    }
+    if alternatives.iter().any(|c| c.is_empty()) {
+        return Box::new(MatchAllConstraint::new());
+    }
+
+    let groups: Vec<Box<dyn ConstraintInterface>> = alternatives
+        .into_iter()
+        .map(|conjunction| {
+            let constraints = conjunction
+                .into_iter()
+                .map(|c| Box::new(c) as Box<dyn ConstraintInterface>)
+                .collect();
+            build(constraints, true)
+        })
+        .collect();
+
+    build(groups, false)
+}
+
+fn build(constraints: Vec<Box<dyn ConstraintInterface>>, conjunctive: bool) -> Box<dyn ConstraintInterface> {
+    // `create` only fails for fewer than two constraints, which it handles itself
+    MultiConstraint::create(constraints, conjunctive).unwrap_or_else(|_| Box::new(MatchNoneConstraint::new()))
+}
+
+/// Expand a constraint into an OR of ANDs of single constraints
+fn to_dnf(constraint: &dyn ConstraintInterface) -> Vec<Conjunction> {
+    if constraint.is_match_none() {
+        return Vec::new();
+    }
+    if constraint.is_match_all() {
+        return vec![Vec::new()];
+    }
+    if let Some((operator, version)) = constraint.as_constraint() {
+        return match Constraint::new(*operator, version.to_string()) {
+            Ok(c) => vec![vec![c]],
+            Err(_) => Vec::new(),
+        };
+    }
+    if let Some((constraints, conjunctive)) = constraint.as_multi_constraint() {
+        if !conjunctive {
+            return constraints.iter().flat_map(|c| to_dnf(c.as_ref())).collect();
+        }
+
+        let mut result: Vec<Conjunction> = vec![Vec::new()];
+        for c in constraints {
+            let dnf = to_dnf(c.as_ref());
+            result = result
+                .iter()
+                .flat_map(|prefix| {
+                    dnf.iter().map(move |conjunction| {
+                        prefix.iter().chain(conjunction.iter()).cloned().collect::<Conjunction>()
+                    })
+                })
+                .collect();
+        }
+        return result;
+    }
+
+    // Unknown constraint types are treated as matching everything
+    vec![Vec::new()]
+}
+
+fn is_branch(version: &str) -> bool {
+    version.starts_with("dev-")
+}
+
+/// Reduce a conjunction to its tightest form, or `None` if nothing satisfies it
+fn simplify(conjunction: Conjunction) -> Option<Conjunction> {
+    // Dev branches only intersect with themselves
+    let branches: Vec<&str> = conjunction
+        .iter()
+        .filter(|c| c.operator() == Operator::Equal && is_branch(c.version()))
+        .map(|c| c.version())
+        .collect();
+
+    if let Some(branch) = branches.first() {
+        let compatible = conjunction.iter().all(|c| match c.operator() {
+            Operator::Equal => c.version() == *branch,
+            Operator::NotEqual => c.version() != *branch,
+            _ => false,
+        });
+        if !compatible {
+            return None;
+        }
+        return Some(vec![Constraint::new(Operator::Equal, branch.to_string()).ok()?]);
+    }
+
+    // `None` means unbounded
+    let mut lower: Option<Bound> = None;
+    let mut upper: Option<Bound> = None;
+    let mut excluded: Vec<String> = Vec::new();
+
+    for c in &conjunction {
+        let version = c.version().to_string();
+        if is_branch(&version) {
+            // Ranges never match a branch; `!= dev-x` has no effect on a numeric range
+            if c.operator() == Operator::NotEqual {
+                continue;
+            }
+            return None;
+        }
+
+        match c.operator() {
+            Operator::Equal => {
+                tighten_lower(&mut lower, Bound::new(version.clone(), true));
+                tighten_upper(&mut upper, Bound::new(version, true));
+            }
+            Operator::GreaterThan => tighten_lower(&mut lower, Bound::new(version, false)),
+            Operator::GreaterThanOrEqual => tighten_lower(&mut lower, Bound::new(version, true)),
+            Operator::LessThan => tighten_upper(&mut upper, Bound::new(version, false)),
+            Operator::LessThanOrEqual => tighten_upper(&mut upper, Bound::new(version, true)),
+            Operator::NotEqual => {
+                if !excluded.contains(&version) {
+                    excluded.push(version);
+                }
+            }
+        }
+    }
+
+    let range_lower = lower.clone().unwrap_or_else(Bound::zero);
+    let range_upper = upper.clone().unwrap_or_else(Bound::positive_infinity);
+    if !range_lower.overlaps(&range_upper, &range_lower, &range_upper) {
+        return None;
+    }
+    if let (Some(low), Some(high)) = (&lower, &upper) {
+        if compare_versions(low.version(), high.version()) == Ordering::Equal {
+            if excluded.iter().any(|v| compare_versions(v, low.version()) == Ordering::Equal) {
+                return None;
+            }
+            return Some(vec![Constraint::new(Operator::Equal, low.version().to_string()).ok()?]);
+        }
+    }
+
+    // Only keep exclusions that fall inside the range
+    excluded.retain(|v| range_lower.contains(&range_upper, v));
+
+    let mut result = Vec::new();
+    if let Some(bound) = lower {
+        let operator = if bound.is_inclusive() { Operator::GreaterThanOrEqual } else { Operator::GreaterThan };
+        result.push(Constraint::new(operator, bound.version().to_string()).ok()?);
+    }
+    if let Some(bound) = upper {
+        let operator = if bound.is_inclusive() { Operator::LessThanOrEqual } else { Operator::LessThan };
+        result.push(Constraint::new(operator, bound.version().to_string()).ok()?);
+    }
+    for version in excluded {
+        result.push(Constraint::new(Operator::NotEqual, version).ok()?);
+    }
+
+    Some(result)
+}
+
+fn tighten_lower(current: &mut Option<Bound>, bound: Bound) {
+    if current.as_ref().is_none_or(|existing| bound.cmp_lower(existing) == Ordering::Greater) {
+        *current = Some(bound);
+    }
+}
+
+fn tighten_upper(current: &mut Option<Bound>, bound: Bound) {
+    if current.as_ref().is_none_or(|existing| bound.cmp_upper(existing) == Ordering::Less) {
+        *current = Some(bound);
+    }
+}
+
+fn same_conjunction(a: &Conjunction, b: &Conjunction) -> bool {
+    a.len() == b.len()
+        && a
+            .iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.operator() == y.operator() && x.version() == y.version())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraint::ConstraintInterface;
+    use crate::VersionParser;
+
+    fn parse(constraint: &str) -> Box<dyn ConstraintInterface> {
+        VersionParser::new().parse_constraints(constraint).unwrap()
+    }
+
+    fn intersect(a: &str, b: &str) -> Box<dyn ConstraintInterface> {
+        parse(a).intersect(parse(b).as_ref())
+    }
+
+    fn matches(constraint: &dyn ConstraintInterface, version: &str) -> bool {
+        let normalized = VersionParser::new().normalize(version).unwrap();
+        let provider = crate::Constraint::new(crate::Operator::Equal, normalized).unwrap();
+        constraint.matches(&provider)
+    }
+
+    #[test]
+    fn test_intersect_overlapping_ranges() {
+        let result = intersect("^1.2", ">=1.5 <3.0");
+        assert_eq!(result.to_string(), "[>= 1.5.0.0-dev < 2.0.0.0-dev]");
+        assert!(matches(result.as_ref(), "1.5.0"));
+        assert!(matches(result.as_ref(), "1.9.9"));
+        assert!(!matches(result.as_ref(), "1.4.0"));
+        assert!(!matches(result.as_ref(), "2.0.0"));
+    }
+
+    #[test]
+    fn test_intersect_disjoint_ranges_is_match_none() {
+        assert!(intersect("^1.0", "^2.0").is_match_none());
+        assert!(intersect("<1.0", ">=1.0").is_match_none());
+        assert!(intersect(">1.0", "<=1.0").is_match_none());
+    }
+
+    #[test]
+    fn test_intersect_touching_bounds_is_exact_version() {
+        let result = intersect(">=1.0-dev", "<=1.0-dev");
+        assert_eq!(result.to_string(), "== 1.0.0.0-dev");
+
+        // `>=1.0` implies `>=1.0.0.0-dev`, so pre-releases of 1.0 remain
+        let result = intersect(">=1.0", "<=1.0");
+        assert_eq!(result.to_string(), "[>= 1.0.0.0-dev <= 1.0.0.0]");
+    }
+
+    #[test]
+    fn test_intersect_with_exact_version() {
+        let result = intersect("^1.0", "1.2.3");
+        assert_eq!(result.to_string(), "== 1.2.3.0");
+        assert!(intersect("^1.0", "2.0.0").is_match_none());
+        assert!(intersect("1.2.3", "!=1.2.3").is_match_none());
+    }
+
+    #[test]
+    fn test_intersect_distributes_over_unions() {
+        let result = intersect("^1.0 || ^3.0", ">=1.5 <4.0");
+        assert_eq!(
+            result.to_string(),
+            "[[>= 1.5.0.0-dev < 2.0.0.0-dev] || [>= 3.0.0.0-dev < 4.0.0.0-dev]]"
+        );
+        assert!(matches(result.as_ref(), "1.6.0"));
+        assert!(matches(result.as_ref(), "3.1.0"));
+        assert!(!matches(result.as_ref(), "2.5.0"));
+    }
+
+    #[test]
+    fn test_intersect_with_match_all_and_match_none() {
+        let result = intersect("*", "^1.0");
+        assert_eq!(result.to_string(), "[>= 1.0.0.0-dev < 2.0.0.0-dev]");
+
+        let none = crate::MatchNoneConstraint::new();
+        assert!(parse("^1.0").intersect(&none).is_match_none());
+        assert!(intersect("*", "*").is_match_all());
+    }
+
+    #[test]
+    fn test_intersect_dev_branches_only_match_themselves() {
+        assert_eq!(intersect("dev-main", "dev-main").to_string(), "== dev-main");
+        assert!(intersect("dev-main", "dev-feature").is_match_none());
+        assert!(intersect("dev-main", "^1.0").is_match_none());
+        assert!(intersect("dev-main", "!=dev-main").is_match_none());
+        assert_eq!(intersect("dev-main", "*").to_string(), "== dev-main");
+    }
+
+    #[test]
+    fn test_intersect_keeps_exclusions_inside_range() {
+        let result = intersect("^1.0", "!=1.5.0");
+        assert_eq!(result.to_string(), "[>= 1.0.0.0-dev < 2.0.0.0-dev != 1.5.0.0]");
+        assert!(!matches(result.as_ref(), "1.5.0"));
+
+        let result = intersect("^1.0", "!=2.5.0");
+        assert_eq!(result.to_string(), "[>= 1.0.0.0-dev < 2.0.0.0-dev]");
+    }
+}
@@ -0,0 +1,49 @@
+//! Source form of a parsed constraint
+
+use std::fmt;
+
+/// The form a constraint was written in before it was expanded into bounds
+///
+/// `^1.2`, `~1.2` and `1.2.*` all parse into a pair of `>=`/`<` constraints; the kind
+/// keeps track of which shorthand produced them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstraintKind {
+    /// An exact version or branch (`1.2.3`, `=1.2.3`, `dev-main`)
+    Exact,
+    /// A single comparison (`>=1.2`, `<2.0`, `!=1.5`)
+    Comparison,
+    /// Caret range (`^1.2`)
+    Caret,
+    /// Tilde range (`~1.2`)
+    Tilde,
+    /// Wildcard (`*`, `1.*`, `1.2.x`)
+    Wildcard,
+    /// Hyphen range (`1.0 - 2.0`)
+    Hyphen,
+    /// Several constraints that must all match (`>=1.0 <2.0`)
+    Range,
+    /// Alternatives separated by `||`
+    Union,
+}
+
+impl ConstraintKind {
+    /// Get the string representation of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConstraintKind::Exact => "exact",
+            ConstraintKind::Comparison => "comparison",
+            ConstraintKind::Caret => "caret",
+            ConstraintKind::Tilde => "tilde",
+            ConstraintKind::Wildcard => "wildcard",
+            ConstraintKind::Hyphen => "hyphen",
+            ConstraintKind::Range => "range",
+            ConstraintKind::Union => "union",
+        }
+    }
+}
+
+impl fmt::Display for ConstraintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
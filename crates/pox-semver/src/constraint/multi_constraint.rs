@@ -1,9 +1,11 @@
 //! MultiConstraint - compound constraint combining multiple constraints
 
+use std::cmp::Ordering;
 use std::fmt;
 use thiserror::Error;
 
-use super::{Bound, ConstraintInterface, MatchAllConstraint};
+use super::constraint::compare_versions;
+use super::{Bound, ConstraintInterface, MatchAllConstraint, Operator};
 
 #[derive(Error, Debug)]
 pub enum MultiConstraintError {
@@ -101,13 +103,67 @@ impl MultiConstraint {
     ) -> Option<(Vec<Box<dyn ConstraintInterface>>, bool)> {
         // Optimization for disjunctive constraints
         // [>= 1 < 2] || [>= 2 < 3] || [>= 3 < 4] => [>= 1 < 4]
-        if !conjunctive && constraints.len() >= 2 {
-            // Check if we can merge adjacent ranges
-            // This is a simplified version - full implementation would need more complex logic
+        if conjunctive {
             return None;
         }
 
-        None
+        let mut merged: Vec<Box<dyn ConstraintInterface>> = Vec::new();
+        let mut optimized = false;
+        let mut left = constraints[0].clone();
+        for right in &constraints[1..] {
+            if let Some(range) = Self::merge_adjacent_ranges(left.as_ref(), right.as_ref()) {
+                optimized = true;
+                left = range;
+            } else {
+                merged.push(left);
+                left = right.clone();
+            }
+        }
+
+        if !optimized {
+            return None;
+        }
+        merged.push(left);
+        Some((merged, false))
+    }
+
+    /// Join two `[lower upper]` ranges when the first ends where the second starts
+    fn merge_adjacent_ranges(
+        left: &dyn ConstraintInterface,
+        right: &dyn ConstraintInterface,
+    ) -> Option<Box<dyn ConstraintInterface>> {
+        let (left, true) = left.as_multi_constraint()? else {
+            return None;
+        };
+        let (right, true) = right.as_multi_constraint()? else {
+            return None;
+        };
+        if left.len() != 2 || right.len() != 2 {
+            return None;
+        }
+
+        let is_lower = |c: &dyn ConstraintInterface| {
+            matches!(c.as_constraint(), Some((Operator::GreaterThan | Operator::GreaterThanOrEqual, _)))
+        };
+        let is_upper = |c: &dyn ConstraintInterface| {
+            matches!(c.as_constraint(), Some((Operator::LessThan | Operator::LessThanOrEqual, _)))
+        };
+        if !(is_lower(left[0].as_ref()) && is_upper(left[1].as_ref())
+            && is_lower(right[0].as_ref()) && is_upper(right[1].as_ref()))
+        {
+            return None;
+        }
+
+        // `< 2.0` followed by `>= 2.0` touch without overlapping, but leave no gap either
+        let left_upper = left[1].upper_bound();
+        let right_lower = right[0].lower_bound();
+        let touching = compare_versions(left_upper.version(), right_lower.version()) == Ordering::Equal
+            && (left_upper.is_inclusive() || right_lower.is_inclusive());
+        if !touching {
+            return None;
+        }
+
+        Some(Box::new(MultiConstraint::new(vec![left[0].clone(), right[1].clone()], true).ok()?))
     }
 
     fn extract_bounds(&mut self) {
@@ -200,6 +256,15 @@ impl ConstraintInterface for MultiConstraint {
         self.pretty_string = pretty;
     }
 
+    fn canonical_string(&self) -> String {
+        let separator = if self.conjunctive { " " } else { " || " };
+        self.constraints
+            .iter()
+            .map(|c| c.canonical_string())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
     fn clone_box(&self) -> Box<dyn ConstraintInterface> {
         Box::new(self.clone())
     }
@@ -254,6 +319,36 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn range(lower: Operator, low: &str, upper: Operator, high: &str) -> Box<dyn ConstraintInterface> {
+        Box::new(MultiConstraint::new(vec![
+            Box::new(Constraint::new(lower, low.to_string()).unwrap()),
+            Box::new(Constraint::new(upper, high.to_string()).unwrap()),
+        ], true).unwrap())
+    }
+
+    #[test]
+    fn test_create_joins_adjacent_ranges() {
+        let result = MultiConstraint::create(vec![
+            range(Operator::GreaterThanOrEqual, "1.0.0.0-dev", Operator::LessThan, "2.0.0.0-dev"),
+            range(Operator::GreaterThanOrEqual, "2.0.0.0-dev", Operator::LessThan, "3.0.0.0-dev"),
+            range(Operator::GreaterThanOrEqual, "3.0.0.0-dev", Operator::LessThan, "4.0.0.0-dev"),
+        ], false).unwrap();
+        assert_eq!(result.to_string(), "[>= 1.0.0.0-dev < 4.0.0.0-dev]");
+
+        // Touching at an exclusive upper and exclusive lower bound leaves 2.0 out
+        let result = MultiConstraint::create(vec![
+            range(Operator::GreaterThanOrEqual, "1.0.0.0", Operator::LessThan, "2.0.0.0"),
+            range(Operator::GreaterThan, "2.0.0.0", Operator::LessThan, "3.0.0.0"),
+        ], false).unwrap();
+        assert_eq!(result.to_string(), "[[>= 1.0.0.0 < 2.0.0.0] || [> 2.0.0.0 < 3.0.0.0]]");
+
+        let result = MultiConstraint::create(vec![
+            range(Operator::GreaterThanOrEqual, "1.0.0.0", Operator::LessThanOrEqual, "2.0.0.0"),
+            range(Operator::GreaterThan, "2.0.0.0", Operator::LessThan, "3.0.0.0"),
+        ], false).unwrap();
+        assert_eq!(result.to_string(), "[>= 1.0.0.0 < 3.0.0.0]");
+    }
+
     #[test]
     fn test_create_single_constraint() {
         let c1: Box<dyn ConstraintInterface> = Box::new(Constraint::new(Operator::Equal, "1.0.0".to_string()).unwrap());
@@ -9,6 +9,6 @@ mod semver;
 mod version_parser;
 
 pub use comparator::Comparator;
-pub use constraint::{Bound, Constraint, ConstraintInterface, MatchAllConstraint, MatchNoneConstraint, MultiConstraint, Operator};
+pub use constraint::{Bound, Constraint, ConstraintInterface, ConstraintKind, MatchAllConstraint, MatchNoneConstraint, MultiConstraint, Operator};
 pub use semver::Semver;
 pub use version_parser::{ParsedConstraints, Stability, VersionParser, VersionParserError};
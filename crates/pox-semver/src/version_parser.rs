@@ -4,7 +4,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use thiserror::Error;
 
-use crate::constraint::{Constraint, ConstraintInterface, MatchAllConstraint, MultiConstraint, Operator};
+use crate::constraint::{Constraint, ConstraintInterface, ConstraintKind, MatchAllConstraint, MultiConstraint, Operator};
 
 /// Stability levels for versions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -122,6 +122,9 @@ lazy_static! {
     // Stability flag in constraint
     static ref CONSTRAINT_STABILITY_RE: Regex = Regex::new(&format!(r"(?i)^([^,\s]*?)@({})$", *STABILITIES_REGEX)).unwrap();
 
+    // Explicit stability flag on a root requirement, possibly after an alias
+    static ref EXPLICIT_STABILITY_RE: Regex = Regex::new(&format!(r"(?i)^[^@]*?@({})$", *STABILITIES_REGEX)).unwrap();
+
     // Reference on dev version
     static ref CONSTRAINT_REF_RE: Regex = Regex::new(r"(?i)^(dev-[^,\s@]+?|[^,\s@]+?\.x-dev)#.+$").unwrap();
 
@@ -861,30 +864,7 @@ impl VersionParser {
     }
 
     fn parse_constraint(&self, constraint: &str) -> Result<Vec<Box<dyn ConstraintInterface>>, VersionParserError> {
-        let constraint = constraint.trim();
-
-        // Strip off aliasing
-        let constraint = if let Some(caps) = ALIAS_RE.captures(constraint) {
-            caps.get(1).unwrap().as_str()
-        } else {
-            constraint
-        };
-
-        // Strip @stability flags
-        let (constraint, _stability_modifier) = if let Some(caps) = CONSTRAINT_STABILITY_RE.captures(constraint) {
-            let c = caps.get(1).map_or("*", |m| if m.as_str().is_empty() { "*" } else { m.as_str() });
-            let s = caps.get(2).map(|m| m.as_str());
-            (c, s)
-        } else {
-            (constraint, None)
-        };
-
-        // Strip #refs
-        let constraint = if let Some(caps) = CONSTRAINT_REF_RE.captures(constraint) {
-            caps.get(1).unwrap().as_str()
-        } else {
-            constraint
-        };
+        let (constraint, stability_modifier) = self.strip_constraint_modifiers(constraint);
 
         // Match any wildcard
         if WILDCARD_RE.is_match(constraint) {
@@ -961,8 +941,13 @@ impl VersionParser {
                 _ => return Err(VersionParserError::InvalidOperator(operator.to_string())),
             };
 
-            // Append -dev for < and >= operators on stable versions
-            let version = if (op == Operator::LessThan || op == Operator::GreaterThanOrEqual)
+            // A stability flag like `<=3.0@dev` widens a stable version to that stability,
+            // otherwise append -dev for < and >= operators on stable versions
+            let version = if let Some(stability) = stability_modifier.filter(|_| {
+                op != Operator::Equal && Self::parse_stability(&version) == Stability::Stable
+            }) {
+                format!("{}-{}", version, stability)
+            } else if (op == Operator::LessThan || op == Operator::GreaterThanOrEqual)
                 && !version.contains("-")
                 && !version.starts_with("dev-")
             {
@@ -980,6 +965,110 @@ impl VersionParser {
         })
     }
 
+    /// Extract the stability flag a root requirement sets for the required package
+    ///
+    /// Mirrors Composer's `RootPackageLoader::extractStabilityFlags`: explicit `@stability`
+    /// suffixes win, taking the least stable one across all alternatives. Without one, a
+    /// plain unstable version like `dev-main` or `2.0.0-beta1` implies its own stability,
+    /// but only when it is less stable than `minimum_stability`.
+    pub fn extract_stability_flag(&self, constraint: &str, minimum_stability: Stability) -> Option<Stability> {
+        let parts: Vec<&str> = OR_CONSTRAINT_RE
+            .split(constraint.trim())
+            .flat_map(|or_part| self.split_and_constraints(or_part))
+            .collect();
+
+        let explicit = parts
+            .iter()
+            .filter_map(|part| EXPLICIT_STABILITY_RE.captures(part.trim()))
+            .filter_map(|caps| Self::normalize_stability(&caps[1]).ok())
+            .min();
+        if explicit.is_some() {
+            return explicit;
+        }
+
+        parts
+            .iter()
+            .map(|part| {
+                let part = part.trim();
+                ALIAS_RE.captures(part).map_or(part, |caps| caps.get(1).unwrap().as_str())
+            })
+            .filter(|version| !version.is_empty() && !version.contains(|c: char| c == ',' || c == '@' || c.is_whitespace()))
+            .map(Self::parse_stability)
+            .filter(|stability| *stability != Stability::Stable && *stability < minimum_stability)
+            .min()
+    }
+
+    /// Strip aliases (`as 1.0`), stability flags (`@dev`) and references (`#abc`)
+    fn strip_constraint_modifiers<'a>(&self, constraint: &'a str) -> (&'a str, Option<&'a str>) {
+        let constraint = constraint.trim();
+
+        // Strip off aliasing
+        let constraint = if let Some(caps) = ALIAS_RE.captures(constraint) {
+            caps.get(1).unwrap().as_str()
+        } else {
+            constraint
+        };
+
+        // Strip @stability flags, keeping anything but @stable for the basic comparators
+        let (constraint, stability_modifier) = if let Some(caps) = CONSTRAINT_STABILITY_RE.captures(constraint) {
+            let stability = caps.get(2).unwrap().as_str();
+            (
+                caps.get(1).map_or("*", |m| if m.as_str().is_empty() { "*" } else { m.as_str() }),
+                (!stability.eq_ignore_ascii_case("stable")).then_some(stability),
+            )
+        } else {
+            (constraint, None)
+        };
+
+        // Strip #refs
+        let constraint = if let Some(caps) = CONSTRAINT_REF_RE.captures(constraint) {
+            caps.get(1).unwrap().as_str()
+        } else {
+            constraint
+        };
+
+        (constraint, stability_modifier)
+    }
+
+    /// Determine the form a constraint string was written in.
+    ///
+    /// The constraint is validated by parsing it first, so `Ok` is only returned for
+    /// constraints [`parse_constraints`](Self::parse_constraints) accepts.
+    pub fn parse_constraint_kind(&self, constraints: &str) -> Result<ConstraintKind, VersionParserError> {
+        self.parse_constraints(constraints)?;
+        Ok(self.classify_constraints(constraints))
+    }
+
+    /// Classify a constraint string that is known to parse
+    fn classify_constraints(&self, constraints: &str) -> ConstraintKind {
+        let or_constraints: Vec<&str> = OR_CONSTRAINT_RE.split(constraints.trim()).collect();
+        if or_constraints.len() > 1 {
+            return ConstraintKind::Union;
+        }
+
+        let and_constraints = self.split_and_constraints(or_constraints[0]);
+        if and_constraints.len() > 1 {
+            return ConstraintKind::Range;
+        }
+
+        let (constraint, _) = self.strip_constraint_modifiers(and_constraints[0]);
+
+        if WILDCARD_RE.is_match(constraint) || X_RANGE_RE.is_match(constraint) {
+            ConstraintKind::Wildcard
+        } else if TILDE_RE.is_match(constraint) {
+            ConstraintKind::Tilde
+        } else if CARET_RE.is_match(constraint) {
+            ConstraintKind::Caret
+        } else if HYPHEN_RE.is_match(constraint) {
+            ConstraintKind::Hyphen
+        } else {
+            match BASIC_COMPARATOR_RE.captures(constraint).and_then(|caps| caps.get(1)) {
+                Some(op) if !matches!(op.as_str(), "=" | "==") => ConstraintKind::Comparison,
+                _ => ConstraintKind::Exact,
+            }
+        }
+    }
+
     fn parse_tilde_constraint(
         &self,
         caps: &regex::Captures,
@@ -1182,16 +1271,30 @@ impl VersionParser {
     /// Parse constraints and return a reusable, pre-parsed representation.
     pub fn parse_constraints_cached(&self, constraints: &str) -> Result<ParsedConstraints, VersionParserError> {
         let parsed = self.parse_constraints(constraints)?;
-        Ok(ParsedConstraints { constraints: parsed })
+        Ok(ParsedConstraints {
+            constraints: parsed,
+            kind: self.classify_constraints(constraints),
+        })
     }
 }
 
 /// Reusable, pre-parsed constraints for repeated checks.
 pub struct ParsedConstraints {
     constraints: Box<dyn ConstraintInterface>,
+    kind: ConstraintKind,
 }
 
 impl ParsedConstraints {
+    /// The form the constraint was written in (`^`, `~`, `1.*`, a range, ...).
+    pub fn kind(&self) -> ConstraintKind {
+        self.kind
+    }
+
+    /// The constraint as originally written, e.g. `^1.0 || 2.0 - 3.0`.
+    pub fn pretty_string(&self) -> String {
+        self.constraints.pretty_string()
+    }
+
     /// Check a normalized version string against the parsed constraints.
     pub fn matches_normalized(&self, normalized_version: &str) -> bool {
         match Constraint::new(Operator::Equal, normalized_version.to_string()) {
@@ -1221,6 +1324,33 @@ impl Default for VersionParser {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_extract_stability_flag() {
+        let parser = VersionParser::new();
+        let flag = |c: &str| parser.extract_stability_flag(c, Stability::Stable);
+
+        assert_eq!(flag("^1.0"), None);
+        assert_eq!(flag("^1.0@dev"), Some(Stability::Dev));
+        assert_eq!(flag("^1.0@RC"), Some(Stability::RC));
+        assert_eq!(flag("^1.0@stable"), Some(Stability::Stable));
+        // The least stable flag across alternatives wins
+        assert_eq!(flag("^1.0@beta || ^2.0@alpha"), Some(Stability::Alpha));
+        assert_eq!(flag(">=1.0@beta, <2.0"), Some(Stability::Beta));
+        assert_eq!(flag("dev-feature as 1.0.x-dev@dev"), Some(Stability::Dev));
+        // Inferred from plain unstable versions
+        assert_eq!(flag("dev-main"), Some(Stability::Dev));
+        assert_eq!(flag("2.0.0-beta1"), Some(Stability::Beta));
+        assert_eq!(flag("dev-feature as 1.0.0"), Some(Stability::Dev));
+        assert_eq!(flag("^1.0 || 2.0.0-RC1"), Some(Stability::RC));
+        // An explicit flag takes precedence over inference
+        assert_eq!(flag("dev-main@beta"), Some(Stability::Beta));
+        // Inferred flags are only set when less stable than the minimum stability
+        assert_eq!(parser.extract_stability_flag("2.0.0-beta1", Stability::Alpha), None);
+        assert_eq!(parser.extract_stability_flag("2.0.0-beta1", Stability::Beta), None);
+        assert_eq!(parser.extract_stability_flag("2.0.0-beta1", Stability::RC), Some(Stability::Beta));
+        assert_eq!(parser.extract_stability_flag("^1.0@dev", Stability::Dev), Some(Stability::Dev));
+    }
+
     #[test]
     fn test_parse_stability() {
         // Full test suite from PHP
@@ -1507,16 +1637,66 @@ mod tests {
         assert_eq!(parser.parse_constraints("  > 2.0  ,  <=  3.0 ").unwrap().to_string(), expected);
     }
 
+    #[test]
+    fn test_parse_constraints_multi_with_stability_flags() {
+        let parser = VersionParser::new();
+
+        let expected = "[> 2.0.0.0 <= 3.0.0.0-dev]";
+        assert_eq!(parser.parse_constraints(">2.0@stable,<=3.0@dev").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints(">2.0@stable <=3.0@dev").unwrap().to_string(), expected);
+
+        assert_eq!(parser.parse_constraints(">=1.0@beta").unwrap().to_string(), ">= 1.0.0.0-beta");
+        assert_eq!(parser.parse_constraints("!=1.0@dev").unwrap().to_string(), "!= 1.0.0.0-dev");
+        assert_eq!(parser.parse_constraints(">=1.1.0-alpha4,<1.2.x-dev").unwrap().to_string(), "[>= 1.1.0.0-alpha4 < 1.2.9999999.9999999-dev]");
+        assert_eq!(parser.parse_constraints(">=1.1.0-alpha4,<1.2-beta2").unwrap().to_string(), "[>= 1.1.0.0-alpha4 < 1.2.0.0-beta2]");
+    }
+
+    #[test]
+    fn test_parse_constraints_not_equal() {
+        let parser = VersionParser::new();
+
+        for constraint in ["!=1.0", "<>1.0", "!= 1.0.0", "<> 1.0.0"] {
+            assert_eq!(parser.parse_constraints(constraint).unwrap().to_string(), "!= 1.0.0.0", "{}", constraint);
+        }
+        assert_eq!(parser.parse_constraints("<>dev-foo").unwrap().to_string(), "!= dev-foo");
+        assert_eq!(parser.parse_constraints(">=1.0, !=1.5, <2.0").unwrap().to_string(), "[>= 1.0.0.0-dev != 1.5.0.0 < 2.0.0.0-dev]");
+        assert_eq!(parser.parse_constraints("<>1.5 <> 1.6").unwrap().to_string(), "[!= 1.5.0.0 != 1.6.0.0]");
+
+        let constraint = parser.parse_constraints(">=1.0 !=1.5").unwrap();
+        assert!(constraint.allows("1.4.9"));
+        assert!(!constraint.allows("1.5.0"));
+        assert!(constraint.allows("1.5.1"));
+    }
+
     #[test]
     fn test_parse_constraints_multi_disjunctive() {
         let parser = VersionParser::new();
 
-        // OR has priority over AND
-        let result = parser.parse_constraints(">2.0,<2.0.5 | >2.0.6").unwrap().to_string();
-        assert!(result.contains("> 2.0.0.0"));
-        assert!(result.contains("< 2.0.5.0-dev"));
-        assert!(result.contains("> 2.0.6.0"));
-        assert!(result.contains("||"));
+        // AND binds tighter than OR
+        let expected = "[[> 2.0.0.0 < 2.0.5.0-dev] || > 2.0.6.0]";
+        assert_eq!(parser.parse_constraints(">2.0,<2.0.5 | >2.0.6").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints(">2.0,<2.0.5 || >2.0.6").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints("> 2.0 <2.0.5 ||>2.0.6").unwrap().to_string(), expected);
+
+        let expected = "[[>= 1.0.0.0-dev < 2.0.0.0-dev] || [>= 2.5.0.0-dev < 3.0.0.0-dev]]";
+        assert_eq!(parser.parse_constraints("^1 || >=2.5 <3").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints("^1 | >=2.5,<3").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints("^1||>=2.5, <3").unwrap().to_string(), expected);
+
+        // Ranges where one ends exactly where the next starts are joined, like Composer does
+        let expected = "[>= 1.0.0.0-dev < 3.0.0.0-dev]";
+        assert_eq!(parser.parse_constraints("^1 || >=2 <3").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints("^1 || ^2").unwrap().to_string(), expected);
+        assert_eq!(parser.parse_constraints("~1.0 || ~2.0 || ^3 || ^5").unwrap().to_string(), "[[>= 1.0.0.0-dev < 4.0.0.0-dev] || [>= 5.0.0.0-dev < 6.0.0.0-dev]]");
+
+        assert_eq!(
+            parser.parse_constraints(">=1.0,<1.1 || ^2 !=2.3").unwrap().to_string(),
+            "[[>= 1.0.0.0-dev < 1.1.0.0-dev] || [>= 2.0.0.0-dev < 3.0.0.0-dev != 2.3.0.0]]"
+        );
+        assert_eq!(
+            parser.parse_constraints("1.0.0 - 2.0.0 || ^3").unwrap().to_string(),
+            "[[>= 1.0.0.0-dev <= 2.0.0.0] || [>= 3.0.0.0-dev < 4.0.0.0-dev]]"
+        );
     }
 
     #[test]
@@ -1662,4 +1842,117 @@ mod tests {
         let v100 = Constraint::new(Operator::Equal, "1.0.0.0".to_string()).unwrap();
         assert!(!constraint.matches(&v100), "1.0.0.0 should NOT match ^2.3 || ^3.0");
     }
+
+    #[test]
+    fn test_parse_constraint_kind() {
+        let parser = VersionParser::new();
+        let kind = |c: &str| parser.parse_constraint_kind(c).unwrap();
+
+        assert_eq!(kind("1.2.3"), ConstraintKind::Exact);
+        assert_eq!(kind("==1.2.3"), ConstraintKind::Exact);
+        assert_eq!(kind("dev-main"), ConstraintKind::Exact);
+        assert_eq!(kind("dev-main#abc123"), ConstraintKind::Exact);
+        assert_eq!(kind(">=1.2"), ConstraintKind::Comparison);
+        assert_eq!(kind("!=1.5"), ConstraintKind::Comparison);
+        assert_eq!(kind("^1.2"), ConstraintKind::Caret);
+        assert_eq!(kind("^1.2@beta"), ConstraintKind::Caret);
+        assert_eq!(kind("~1.2.3"), ConstraintKind::Tilde);
+        assert_eq!(kind("*"), ConstraintKind::Wildcard);
+        assert_eq!(kind("1.*"), ConstraintKind::Wildcard);
+        assert_eq!(kind("1.2.x"), ConstraintKind::Wildcard);
+        assert_eq!(kind("1.0 - 2.0"), ConstraintKind::Hyphen);
+        assert_eq!(kind(">=1.0 <2.0"), ConstraintKind::Range);
+        assert_eq!(kind(">=1.0, <2.0"), ConstraintKind::Range);
+        assert_eq!(kind("^1.0 || ^2.0"), ConstraintKind::Union);
+        assert_eq!(kind("1.0 - 2.0 || ^3.0"), ConstraintKind::Union);
+
+        assert!(parser.parse_constraint_kind("~>1.2").is_err());
+        assert!(parser.parse_constraint_kind("").is_err());
+    }
+
+    #[test]
+    fn test_parsed_constraints_keep_source_form() {
+        let parser = VersionParser::new();
+
+        let parsed = parser.parse_constraints_cached("1.0 - 2.0 || ^3.0").unwrap();
+        assert_eq!(parsed.kind(), ConstraintKind::Union);
+        assert_eq!(parsed.pretty_string(), "1.0 - 2.0 || ^3.0");
+        assert!(parsed.satisfies("1.5.0"));
+        assert!(parsed.satisfies("3.1.0"));
+        assert!(!parsed.satisfies("2.1.0"));
+
+        let parsed = parser.parse_constraints_cached("^1.2").unwrap();
+        assert_eq!(parsed.kind(), ConstraintKind::Caret);
+        assert_eq!(parsed.pretty_string(), "^1.2");
+    }
+
+    #[test]
+    fn test_normalize_four_segment_and_date_versions() {
+        let parser = VersionParser::new();
+
+        assert_eq!(parser.normalize("1.2.3.4").unwrap(), "1.2.3.4");
+        assert_eq!(parser.normalize("v1.2.3.4").unwrap(), "1.2.3.4");
+        assert_eq!(parser.normalize("1.2.3.4-p1").unwrap(), "1.2.3.4-patch1");
+        assert_eq!(parser.normalize("20230101").unwrap(), "20230101");
+        assert_eq!(parser.normalize("2023.01.01").unwrap(), "2023.01.01.0");
+        assert_eq!(parser.normalize("2023-01-01").unwrap(), "2023.01.01");
+    }
+
+    #[test]
+    fn test_four_segment_constraints() {
+        use crate::Semver;
+
+        assert!(!Semver::satisfies("1.2.3", "1.2.3.4"));
+        assert!(Semver::satisfies("1.2.3.4", "1.2.3.4"));
+
+        assert!(!Semver::satisfies("1.2.3", "^1.2.3.4"));
+        assert!(!Semver::satisfies("1.2.3.3", "^1.2.3.4"));
+        assert!(Semver::satisfies("1.2.3.4", "^1.2.3.4"));
+        assert!(Semver::satisfies("1.2.3.4-p1", "^1.2.3.4"));
+        assert!(Semver::satisfies("1.3.0", "^1.2.3.4"));
+        assert!(!Semver::satisfies("2.0.0", "^1.2.3.4"));
+
+        assert!(Semver::satisfies("1.2.3.5", "~1.2.3.4"));
+        assert!(!Semver::satisfies("1.2.4", "~1.2.3.4"));
+    }
+
+    #[test]
+    fn test_date_version_constraints() {
+        use crate::Semver;
+
+        assert!(Semver::satisfies("20230101", "^20230101"));
+        assert!(!Semver::satisfies("20230102", "^20230101"));
+        assert!(Semver::satisfies("20230102", ">=20230101"));
+        assert!(Semver::satisfies("2023.01.02", ">=2023.01.01"));
+        assert!(!Semver::satisfies("2022.12.31", ">=2023.01.01"));
+    }
+
+    #[test]
+    fn test_canonical_string_round_trips() {
+        let parser = VersionParser::new();
+        let cases = [
+            ("1.2.0", "1.2.0"),
+            ("=1.2", "1.2.0"),
+            (">= 1.0", ">=1.0.0"),
+            ("^1.2", ">=1.2.0 <2.0.0"),
+            ("~1.2.3", ">=1.2.3 <1.3.0"),
+            ("1.0 - 2.0", ">=1.0.0 <2.1.0"),
+            ("^1.0 || ^2.0", ">=1.0.0 <3.0.0"),
+            ("^1.0 || ^3.0", ">=1.0.0 <2.0.0 || >=3.0.0 <4.0.0"),
+            ("!=1.5", "!=1.5.0"),
+            ("1.0.0-beta1", "1.0.0-beta1"),
+            ("1.2.3.4", "1.2.3.4"),
+            ("dev-main", "dev-main"),
+            ("*", "*"),
+        ];
+
+        for (input, expected) in cases {
+            let parsed = parser.parse_constraints(input).unwrap();
+            let canonical = parsed.canonical_string();
+            assert_eq!(canonical, expected, "canonical form of {}", input);
+
+            let reparsed = parser.parse_constraints(&canonical).unwrap();
+            assert_eq!(reparsed.to_string(), parsed.to_string(), "round trip of {}", input);
+        }
+    }
 }
@@ -0,0 +1,245 @@
+//! Property tests for `php_version_compare`
+//!
+//! Versions are generated from a grammar of numeric segments, separators and PHP's special
+//! forms, and compared against a port of PHP's `version_compare` (ext/standard/versioning.c).
+//! The grammar steers clear of the intentional divergences documented on
+//! `php_version_compare`; those are pinned by the table tests at the bottom instead.
+
+use std::cmp::Ordering;
+
+use pox_semver::constraint::php_version_compare;
+use proptest::prelude::*;
+
+/// Port of PHP's `php_canonicalize_version`
+fn php_canonicalize(version: &str) -> String {
+    let is_digit = |c: char| c.is_ascii_digit();
+    let is_non_digit = |c: char| !c.is_ascii_digit() && c != '.';
+
+    let mut chars = version.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+
+    let mut out = String::from(first);
+    let mut last = first;
+    for c in chars {
+        if matches!(c, '-' | '_' | '+') {
+            if !out.ends_with('.') {
+                out.push('.');
+            }
+        } else if (is_non_digit(last) && is_digit(c)) || (is_digit(last) && is_non_digit(c)) {
+            if !out.ends_with('.') {
+                out.push('.');
+            }
+            out.push(c);
+        } else if !c.is_ascii_alphanumeric() {
+            if !out.ends_with('.') {
+                out.push('.');
+            }
+        } else {
+            out.push(c);
+        }
+        last = c;
+    }
+    out
+}
+
+/// Port of PHP's `compare_special_version_forms`, including its prefix matching
+fn php_special_order(form: &str) -> i32 {
+    const FORMS: [(&str, i32); 10] = [
+        ("dev", 0),
+        ("alpha", 1),
+        ("a", 1),
+        ("beta", 2),
+        ("b", 2),
+        ("RC", 3),
+        ("rc", 3),
+        ("#", 4),
+        ("pl", 5),
+        ("p", 5),
+    ];
+    FORMS
+        .iter()
+        .find(|(name, _)| form.starts_with(name))
+        .map(|(_, order)| *order)
+        .unwrap_or(-1)
+}
+
+fn php_compare_parts(a: &str, b: &str) -> Ordering {
+    let a_digit = a.starts_with(|c: char| c.is_ascii_digit());
+    let b_digit = b.starts_with(|c: char| c.is_ascii_digit());
+    match (a_digit, b_digit) {
+        (true, true) => a.parse::<u64>().unwrap().cmp(&b.parse::<u64>().unwrap()),
+        (false, false) => php_special_order(a).cmp(&php_special_order(b)),
+        (true, false) => php_special_order("#N#").cmp(&php_special_order(b)),
+        (false, true) => php_special_order(a).cmp(&php_special_order("#N#")),
+    }
+}
+
+/// Port of PHP's `php_version_compare`
+fn php_reference_compare(a: &str, b: &str) -> Ordering {
+    if a.is_empty() || b.is_empty() {
+        return (!a.is_empty()).cmp(&!b.is_empty());
+    }
+
+    let a = php_canonicalize(a);
+    let b = php_canonicalize(b);
+    let a_parts: Vec<&str> = a.split('.').collect();
+    let b_parts: Vec<&str> = b.split('.').collect();
+
+    for (x, y) in a_parts.iter().zip(b_parts.iter()) {
+        let cmp = php_compare_parts(x, y);
+        if cmp != Ordering::Equal {
+            return cmp;
+        }
+    }
+
+    match a_parts.len().cmp(&b_parts.len()) {
+        Ordering::Equal => Ordering::Equal,
+        Ordering::Greater => {
+            let rest = a_parts[b_parts.len()..].join(".");
+            if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                Ordering::Greater
+            } else {
+                php_reference_compare(&rest, "#N#")
+            }
+        }
+        Ordering::Less => {
+            let rest = b_parts[a_parts.len()..].join(".");
+            if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                Ordering::Less
+            } else {
+                php_reference_compare("#N#", &rest)
+            }
+        }
+    }
+}
+
+fn ordering(a: &str, b: &str) -> Ordering {
+    if php_version_compare(a, b, "<") {
+        Ordering::Less
+    } else if php_version_compare(a, b, ">") {
+        Ordering::Greater
+    } else {
+        assert!(php_version_compare(a, b, "=="), "{} and {} are neither <, > nor ==", a, b);
+        Ordering::Equal
+    }
+}
+
+/// A numeric release part with `segments` segments (`1.2.3`)
+fn release(segments: usize) -> impl Strategy<Value = String> {
+    prop::collection::vec(0u32..30, segments).prop_map(|parts| {
+        parts
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    })
+}
+
+/// An optional stability suffix (`-rc1`, `beta`, `.pl2`, `+p3`)
+fn suffix() -> impl Strategy<Value = String> {
+    let form = prop::sample::select(vec!["dev", "alpha", "a", "beta", "b", "RC", "rc", "pl", "p", "patch"]);
+    let separator = prop::sample::select(vec!["", ".", "-", "_", "+"]);
+    // Numbers start at 1: PHP orders `rc0` above `rc`, while zero-padding makes them equal
+    let number = prop::option::of(1u32..20);
+
+    prop::option::of((separator, form, number)).prop_map(|suffix| match suffix {
+        None => String::new(),
+        Some((separator, form, number)) => {
+            let number = number.map(|n| n.to_string()).unwrap_or_default();
+            format!("{}{}{}", separator, form, number)
+        }
+    })
+}
+
+/// Two versions with the same number of release segments
+fn version_pair() -> impl Strategy<Value = (String, String)> {
+    (1usize..=4).prop_flat_map(|segments| {
+        (
+            (release(segments), suffix()).prop_map(|(r, s)| r + &s),
+            (release(segments), suffix()).prop_map(|(r, s)| r + &s),
+        )
+    })
+}
+
+fn any_version() -> impl Strategy<Value = String> {
+    (1usize..=4).prop_flat_map(|segments| (release(segments), suffix()).prop_map(|(r, s)| r + &s))
+}
+
+proptest! {
+    #[test]
+    fn matches_php_version_compare((a, b) in version_pair()) {
+        prop_assert_eq!(ordering(&a, &b), php_reference_compare(&a, &b), "comparing {} and {}", a, b);
+    }
+
+    #[test]
+    fn is_antisymmetric(a in any_version(), b in any_version()) {
+        prop_assert_eq!(ordering(&a, &b), ordering(&b, &a).reverse());
+    }
+
+    #[test]
+    fn is_transitive(a in any_version(), b in any_version(), c in any_version()) {
+        if ordering(&a, &b) != Ordering::Greater && ordering(&b, &c) != Ordering::Greater {
+            prop_assert_ne!(ordering(&a, &c), Ordering::Greater, "{} <= {} <= {}", a, b, c);
+        }
+    }
+
+    #[test]
+    fn never_panics(a in "\\PC{0,12}", b in "\\PC{0,12}") {
+        let _ = php_version_compare(&a, &b, "<");
+    }
+}
+
+#[test]
+fn test_known_php_results() {
+    // (a, b, PHP's version_compare(a, b))
+    let cases = [
+        ("1.0.0-rc1", "1.0.0", -1),
+        ("1.0.0-p1", "1.0.0", 1),
+        ("1.0.0-pl1", "1.0.0-p2", -1),
+        ("1.0.0-patch1", "1.0.0-p1", 0),
+        ("1.0.0-dev", "1.0.0-alpha", -1),
+        ("1.0.0-alpha", "1.0.0-a", 0),
+        ("1.0.0-alpha2", "1.0.0-beta1", -1),
+        ("1.0.0-b1", "1.0.0-beta1", 0),
+        ("1.0.0-beta3", "1.0.0-RC1", -1),
+        ("1.0.0-RC1", "1.0.0-rc1", 0),
+        ("1.0.0rc1", "1.0.0-rc1", 0),
+        ("1.0.0_rc1", "1.0.0+rc1", 0),
+        ("1.0.0-rc10", "1.0.0-rc9", 1),
+        ("1.0.0-dev", "1.0.0", -1),
+        ("1.0.1-dev", "1.0.0", 1),
+        ("1.0.0.0-beta2", "1.0.0.0-beta10", -1),
+        ("2.0a1", "2.0", -1),
+        ("2.0b", "2.0a", 1),
+        ("1.10", "1.9", 1),
+    ];
+
+    for (a, b, expected) in cases {
+        let expected = expected.cmp(&0);
+        assert_eq!(php_reference_compare(a, b), expected, "reference for {} vs {}", a, b);
+        assert_eq!(ordering(a, b), expected, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn test_intentional_divergences() {
+    // Missing numeric segments count as zero; PHP orders the longer version higher
+    assert_eq!(php_reference_compare("1.0", "1.0.0"), Ordering::Less);
+    assert_eq!(ordering("1.0", "1.0.0"), Ordering::Equal);
+    assert_eq!(ordering("1.0.0-rc", "1.0.0-rc0"), Ordering::Equal);
+
+    // A number always sorts above a special form in the same position; PHP puts `pl`/`p` above it
+    assert_eq!(php_reference_compare("1.0.1", "1.0.pl1"), Ordering::Less);
+    assert_eq!(ordering("1.0.1", "1.0.pl1"), Ordering::Greater);
+
+    // Special forms match case-insensitively and exactly; PHP is case-sensitive and
+    // matches prefixes, sorting unknown words below `dev`
+    assert_eq!(php_reference_compare("1.0-ALPHA", "1.0-dev"), Ordering::Less);
+    assert_eq!(ordering("1.0-ALPHA", "1.0-dev"), Ordering::Greater);
+    assert_eq!(php_reference_compare("1.0-stable", "1.0"), Ordering::Less);
+    assert_eq!(ordering("1.0-stable", "1.0"), Ordering::Equal);
+    assert_eq!(php_reference_compare("1.0-develop", "1.0-dev"), Ordering::Equal);
+    assert_eq!(ordering("1.0-develop", "1.0-dev"), Ordering::Greater);
+}
@@ -0,0 +1,133 @@
+/// A parsed SPDX license expression.
+///
+/// Identifiers are kept as written, including a trailing `+`. `WITH` exceptions are kept
+/// on the license they apply to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpression {
+    License {
+        identifier: String,
+        exception: Option<String>,
+    },
+    And(Box<LicenseExpression>, Box<LicenseExpression>),
+    Or(Box<LicenseExpression>, Box<LicenseExpression>),
+}
+
+impl LicenseExpression {
+    /// Parses an expression like `(MIT OR GPL-2.0-only WITH Classpath-exception-2.0)`.
+    ///
+    /// Identifiers are not validated; use [`SpdxLicenses::validate`](crate::SpdxLicenses::validate)
+    /// for that. Returns `None` for empty operands or unbalanced parentheses.
+    pub fn parse(expr: &str) -> Option<Self> {
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return None;
+        }
+
+        if let Some((left, right)) = split_by_operator(expr, "OR") {
+            return Some(Self::Or(Box::new(Self::parse(left)?), Box::new(Self::parse(right)?)));
+        }
+
+        if let Some((left, right)) = split_by_operator(expr, "AND") {
+            return Some(Self::And(Box::new(Self::parse(left)?), Box::new(Self::parse(right)?)));
+        }
+
+        if expr.starts_with('(') && expr.ends_with(')') {
+            return Self::parse(&expr[1..expr.len() - 1]);
+        }
+
+        let (identifier, exception) = match split_by_with(expr) {
+            Some((license, exception)) => (license, Some(exception.to_string())),
+            None => (expr, None),
+        };
+
+        if identifier.contains(['(', ')', ' ']) {
+            return None;
+        }
+
+        Some(Self::License {
+            identifier: identifier.to_string(),
+            exception,
+        })
+    }
+
+    /// Parses a package's `license` field, where several entries mean the package may be
+    /// used under any one of them.
+    pub fn parse_any(licenses: &[&str]) -> Option<Self> {
+        licenses
+            .iter()
+            .map(|license| Self::parse(license))
+            .reduce(|acc, next| Some(Self::Or(Box::new(acc?), Box::new(next?))))?
+    }
+
+    /// Checks whether the terms can be met using only licenses accepted by `permitted`.
+    ///
+    /// An `OR` needs one permitted side, an `AND` needs both.
+    pub fn is_satisfied_by(&self, permitted: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Self::License { identifier, .. } => permitted(identifier),
+            Self::And(left, right) => left.is_satisfied_by(permitted) && right.is_satisfied_by(permitted),
+            Self::Or(left, right) => left.is_satisfied_by(permitted) || right.is_satisfied_by(permitted),
+        }
+    }
+
+    /// Returns all license identifiers in the expression.
+    pub fn identifiers(&self) -> Vec<&str> {
+        match self {
+            Self::License { identifier, .. } => vec![identifier.as_str()],
+            Self::And(left, right) | Self::Or(left, right) => {
+                let mut identifiers = left.identifiers();
+                identifiers.extend(right.identifiers());
+                identifiers
+            }
+        }
+    }
+}
+
+/// Splits an expression at the first top-level occurrence of ` <op> ` (case-insensitive).
+pub(crate) fn split_by_operator<'a>(expr: &'a str, op: &str) -> Option<(&'a str, &'a str)> {
+    let mut depth = 0;
+    let op_pattern = format!(" {} ", op);
+
+    let bytes = expr.as_bytes();
+    let mut i = 0;
+
+    while i < expr.len() {
+        let c = bytes[i] as char;
+        if !expr.is_char_boundary(i) {
+            i += 1;
+            continue;
+        }
+
+        if c == '(' {
+            depth += 1;
+        } else if c == ')' {
+            depth -= 1;
+        } else if depth == 0 && i + op_pattern.len() <= expr.len() && expr.is_char_boundary(i + op_pattern.len()) {
+            let slice = &expr[i..i + op_pattern.len()];
+            if slice.eq_ignore_ascii_case(&op_pattern) {
+                let left = expr[..i].trim();
+                let right = expr[i + op_pattern.len()..].trim();
+                if !left.is_empty() && !right.is_empty() {
+                    return Some((left, right));
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+/// Splits `<license> WITH <exception>` (case-insensitive).
+pub(crate) fn split_by_with(expr: &str) -> Option<(&str, &str)> {
+    let lower = expr.to_lowercase();
+    if let Some(pos) = lower.find(" with ") {
+        let license = expr[..pos].trim();
+        let exception = expr[pos + 6..].trim();
+        if !license.is_empty() && !exception.is_empty() {
+            return Some((license, exception));
+        }
+    }
+    None
+}
@@ -3,6 +3,11 @@ use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
 
+mod expression;
+
+use expression::{split_by_operator, split_by_with};
+pub use expression::LicenseExpression;
+
 const LICENSES_JSON: &str = include_str!("../res/spdx-licenses.json");
 const EXCEPTIONS_JSON: &str = include_str!("../res/spdx-exceptions.json");
 
@@ -191,12 +196,12 @@ impl SpdxLicenses {
         }
 
         // Try to split by OR (lowest precedence)
-        if let Some((left, right)) = self.split_by_operator(expr, "OR") {
+        if let Some((left, right)) = split_by_operator(expr, "OR") {
             return self.parse_compound_expression(left) && self.parse_compound_expression(right);
         }
 
         // Try to split by AND
-        if let Some((left, right)) = self.split_by_operator(expr, "AND") {
+        if let Some((left, right)) = split_by_operator(expr, "AND") {
             return self.parse_compound_expression(left) && self.parse_compound_expression(right);
         }
 
@@ -206,7 +211,7 @@ impl SpdxLicenses {
         }
 
         // Check for simple expression with WITH clause
-        if let Some((license, exception)) = self.split_by_with(expr) {
+        if let Some((license, exception)) = split_by_with(expr) {
             return self.is_valid_simple_expression(license) && self.is_valid_exception(exception);
         }
 
@@ -214,50 +219,6 @@ impl SpdxLicenses {
         self.is_valid_simple_expression(expr)
     }
 
-    fn split_by_operator<'a>(&self, expr: &'a str, op: &str) -> Option<(&'a str, &'a str)> {
-        let mut depth = 0;
-        let op_pattern = format!(" {} ", op);
-
-        let bytes = expr.as_bytes();
-        let mut i = 0;
-
-        while i < expr.len() {
-            let c = bytes[i] as char;
-
-            if c == '(' {
-                depth += 1;
-            } else if c == ')' {
-                depth -= 1;
-            } else if depth == 0 && i + op_pattern.len() <= expr.len() {
-                let slice = &expr[i..i + op_pattern.len()];
-                if slice.eq_ignore_ascii_case(&op_pattern) {
-                    let left = expr[..i].trim();
-                    let right = expr[i + op_pattern.len()..].trim();
-                    if !left.is_empty() && !right.is_empty() {
-                        return Some((left, right));
-                    }
-                }
-            }
-
-            i += 1;
-        }
-
-        None
-    }
-
-    fn split_by_with<'a>(&self, expr: &'a str) -> Option<(&'a str, &'a str)> {
-        // Find " WITH " (case insensitive)
-        let lower = expr.to_lowercase();
-        if let Some(pos) = lower.find(" with ") {
-            let license = expr[..pos].trim();
-            let exception = expr[pos + 6..].trim();
-            if !license.is_empty() && !exception.is_empty() {
-                return Some((license, exception));
-            }
-        }
-        None
-    }
-
     fn is_valid_simple_expression(&self, expr: &str) -> bool {
         let expr = expr.trim();
 
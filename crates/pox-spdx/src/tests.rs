@@ -322,3 +322,48 @@ fn test_case_insensitivity_operators() {
     assert!(spdx.validate("(MIT and GPL-3.0-only)"));
     assert!(spdx.validate("(MIT AND GPL-3.0-only)"));
 }
+
+// Test expression parsing
+#[test]
+fn test_expression_parse_or() {
+    let expr = LicenseExpression::parse("(MIT OR GPL-2.0-only)").unwrap();
+    assert_eq!(expr.identifiers(), vec!["MIT", "GPL-2.0-only"]);
+    assert!(matches!(expr, LicenseExpression::Or(_, _)));
+}
+
+#[test]
+fn test_expression_and_binds_tighter_than_or() {
+    let expr = LicenseExpression::parse("MIT AND BSD-3-Clause OR Apache-2.0").unwrap();
+    assert!(expr.is_satisfied_by(&|l| l == "Apache-2.0"));
+    assert!(expr.is_satisfied_by(&|l| l == "MIT" || l == "BSD-3-Clause"));
+    assert!(!expr.is_satisfied_by(&|l| l == "MIT"));
+}
+
+#[test]
+fn test_expression_with_exception() {
+    let expr = LicenseExpression::parse("GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+    assert_eq!(
+        expr,
+        LicenseExpression::License {
+            identifier: "GPL-2.0-only".to_string(),
+            exception: Some("Classpath-exception-2.0".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_expression_parse_any() {
+    let expr = LicenseExpression::parse_any(&["MIT", "(GPL-3.0-only AND LGPL-3.0-only)"]).unwrap();
+    assert_eq!(expr.identifiers(), vec!["MIT", "GPL-3.0-only", "LGPL-3.0-only"]);
+    assert!(expr.is_satisfied_by(&|l| l == "MIT"));
+    assert!(!expr.is_satisfied_by(&|l| l == "GPL-3.0-only"));
+    assert!(LicenseExpression::parse_any(&[]).is_none());
+}
+
+#[test]
+fn test_expression_invalid() {
+    assert!(LicenseExpression::parse("").is_none());
+    assert!(LicenseExpression::parse("MIT OR").is_none());
+    assert!(LicenseExpression::parse("(MIT").is_none());
+    assert!(LicenseExpression::parse("MIT GPL").is_none());
+}